@@ -296,7 +296,9 @@ pub struct NativeOptions {
 
     /// Try to detect and follow the system preferred setting for dark vs light mode.
     ///
-    /// The theme will automatically change when the dark vs light mode preference is changed.
+    /// The style will automatically switch between the dark and light style (see [`egui::Context::style_of`])
+    /// when the dark vs light mode preference is changed.
+    /// Use [`egui::Context::set_style_of`] to customize the two styles.
     ///
     /// Does not work on Linux (see <https://github.com/rust-windowing/winit/issues/1549>).
     ///
@@ -362,6 +364,25 @@ pub struct NativeOptions {
     /// Controls whether or not the native window position and size will be
     /// persisted (only if the "persistence" feature is enabled).
     pub persist_window: bool,
+
+    /// Record all input to this file, so that the session can be replayed with [`egui::InputRecording::replay`].
+    ///
+    /// Each frame is written to the file as soon as it is recorded, so the recording survives a crash.
+    /// This can also be set with the `EFRAME_RECORD_INPUT_TO` environment variable,
+    /// which lets users record a bug for you without recompiling the app.
+    ///
+    /// Requires the `persistence` feature. Without it, a warning is logged and nothing is recorded.
+    pub record_input_to: Option<std::path::PathBuf>,
+
+    /// Load the style from this theme file, and reload it whenever the file changes.
+    ///
+    /// See [`egui::Style::from_theme`] for the format.
+    /// While a theme file is used, [`Self::follow_system_theme`] is ignored.
+    ///
+    /// This can also be set with the `EFRAME_THEME_FILE` environment variable,
+    /// so that you can tweak the theme of any app without recompiling it.
+    #[cfg(feature = "theme_files")]
+    pub theme_file: Option<std::path::PathBuf>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -379,6 +400,11 @@ impl Clone for NativeOptions {
             #[cfg(feature = "wgpu")]
             wgpu_options: self.wgpu_options.clone(),
 
+            record_input_to: self.record_input_to.clone(),
+
+            #[cfg(feature = "theme_files")]
+            theme_file: self.theme_file.clone(),
+
             ..*self
         }
     }
@@ -418,6 +444,11 @@ impl Default for NativeOptions {
             wgpu_options: egui_wgpu::WgpuConfiguration::default(),
 
             persist_window: true,
+
+            record_input_to: None,
+
+            #[cfg(feature = "theme_files")]
+            theme_file: None,
         }
     }
 }
@@ -429,6 +460,10 @@ impl Default for NativeOptions {
 pub struct WebOptions {
     /// Try to detect and follow the system preferred setting for dark vs light mode.
     ///
+    /// The style will automatically switch between the dark and light style (see [`egui::Context::style_of`])
+    /// when the `prefers-color-scheme` of the browser changes.
+    /// Use [`egui::Context::set_style_of`] to customize the two styles.
+    ///
     /// See also [`Self::default_theme`].
     ///
     /// Default: `true`.
@@ -483,28 +518,7 @@ impl Default for WebOptions {
 
 // ----------------------------------------------------------------------------
 
-/// Dark or Light theme.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
-pub enum Theme {
-    /// Dark mode: light text on a dark background.
-    Dark,
-
-    /// Light mode: dark text on a light background.
-    Light,
-}
-
-impl Theme {
-    /// Get the egui visuals corresponding to this theme.
-    ///
-    /// Use with [`egui::Context::set_visuals`].
-    pub fn egui_visuals(self) -> egui::Visuals {
-        match self {
-            Self::Dark => egui::Visuals::dark(),
-            Self::Light => egui::Visuals::light(),
-        }
-    }
-}
+pub use egui::Theme;
 
 // ----------------------------------------------------------------------------
 
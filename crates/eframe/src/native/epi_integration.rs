@@ -147,6 +147,8 @@ pub struct EpiIntegration {
     follow_system_theme: bool,
     #[cfg(feature = "persistence")]
     persist_window: bool,
+    #[cfg(feature = "theme_files")]
+    _theme_watcher: Option<super::theme_watcher::ThemeWatcher>,
     app_icon_setter: super::app_icon::AppTitleIconSetter,
 }
 
@@ -196,6 +198,40 @@ impl EpiIntegration {
             Some(icon),
         );
 
+        let record_input_to = native_options
+            .record_input_to
+            .clone()
+            .or_else(|| std::env::var_os("EFRAME_RECORD_INPUT_TO").map(std::path::PathBuf::from));
+        if let Some(path) = record_input_to {
+            #[cfg(feature = "persistence")]
+            match egui_ctx.start_recording_input_to(&path) {
+                Ok(()) => log::info!("Recording input to {path:?}"),
+                Err(err) => log::error!("Failed to record input to {path:?}: {err}"),
+            }
+            #[cfg(not(feature = "persistence"))]
+            log::warn!(
+                "Not recording input to {path:?}: that requires the `persistence` feature of eframe"
+            );
+        }
+
+        #[cfg(feature = "theme_files")]
+        let theme_file = native_options
+            .theme_file
+            .clone()
+            .or_else(|| std::env::var_os("EFRAME_THEME_FILE").map(std::path::PathBuf::from));
+        #[cfg(feature = "theme_files")]
+        let follow_system_theme = native_options.follow_system_theme && theme_file.is_none();
+        #[cfg(not(feature = "theme_files"))]
+        let follow_system_theme = native_options.follow_system_theme;
+
+        let theme = system_theme.unwrap_or(native_options.default_theme);
+        egui_ctx.set_style(egui_ctx.style_of(theme));
+        egui_ctx.options_mut(|o| o.follow_system_theme = follow_system_theme);
+
+        #[cfg(feature = "theme_files")]
+        let theme_watcher =
+            theme_file.map(|path| super::theme_watcher::ThemeWatcher::new(egui_ctx.clone(), path));
+
         Self {
             frame,
             last_auto_save: Instant::now(),
@@ -203,9 +239,11 @@ impl EpiIntegration {
             pending_full_output: Default::default(),
             close: false,
             can_drag_window: false,
-            follow_system_theme: native_options.follow_system_theme,
+            follow_system_theme,
             #[cfg(feature = "persistence")]
             persist_window: native_options.persist_window,
+            #[cfg(feature = "theme_files")]
+            _theme_watcher: theme_watcher,
             app_icon_setter,
             beginning: Instant::now(),
             is_first_frame: true,
@@ -252,9 +290,8 @@ impl EpiIntegration {
                 ..
             } => self.can_drag_window = true,
             WindowEvent::ThemeChanged(winit_theme) if self.follow_system_theme => {
-                let theme = theme_from_winit_theme(*winit_theme);
-                self.frame.info.system_theme = Some(theme);
-                self.egui_ctx.set_visuals(theme.egui_visuals());
+                // egui switches the style itself, see `egui::Options::follow_system_theme`.
+                self.frame.info.system_theme = Some(theme_from_winit_theme(*winit_theme));
             }
             _ => {}
         }
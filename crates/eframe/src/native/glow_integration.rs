@@ -11,7 +11,9 @@
 #![allow(clippy::arc_with_non_send_sync)]
 #![allow(clippy::undocumented_unsafe_blocks)]
 
-use std::{cell::RefCell, num::NonZeroU32, rc::Rc, sync::Arc, time::Instant};
+use std::{
+    cell::RefCell, collections::VecDeque, num::NonZeroU32, rc::Rc, sync::Arc, time::Instant,
+};
 
 use egui_winit::ActionRequested;
 use glutin::{
@@ -119,6 +121,9 @@ struct Viewport {
     gl_surface: Option<glutin::surface::Surface<glutin::surface::WindowSurface>>,
     window: Option<Arc<Window>>,
     egui_winit: Option<egui_winit::State>,
+
+    /// The [`egui::FullOutput::damage`] of the last few frames painted in this viewport, newest first.
+    recent_damage: VecDeque<Option<egui::Rect>>,
 }
 
 // ----------------------------------------------------------------------------
@@ -269,9 +274,6 @@ impl GlowWinitApp {
             }
         }
 
-        let theme = system_theme.unwrap_or(self.native_options.default_theme);
-        integration.egui_ctx.set_visuals(theme.egui_visuals());
-
         if self
             .native_options
             .viewport
@@ -566,7 +568,13 @@ impl GlowWinitRunning {
             .clear_color(&self.integration.egui_ctx.style().visuals);
 
         let has_many_viewports = self.glutin.borrow().viewports.len() > 1;
-        let clear_before_update = !has_many_viewports; // HACK: for some reason, an early clear doesn't "take" on Mac with multiple viewports.
+        // HACK: for some reason, an early clear doesn't "take" on Mac with multiple viewports.
+        // When tracking damage we may keep most of the last frame, so we only know what to clear after the update.
+        let clear_before_update = !has_many_viewports
+            && !self
+                .integration
+                .egui_ctx
+                .options(|options| options.track_damage);
 
         if clear_before_update {
             // clear before we call update, so users can paint between clear-color and egui windows:
@@ -623,6 +631,7 @@ impl GlowWinitRunning {
             platform_output,
             textures_delta,
             shapes,
+            damage,
             pixels_per_point,
             viewport_output,
         } = full_output;
@@ -657,16 +666,34 @@ impl GlowWinitRunning {
 
         let screen_size_in_pixels: [u32; 2] = window.inner_size().into();
 
-        if !clear_before_update {
-            painter.clear(screen_size_in_pixels, clear_color);
-        }
+        let buffer_age = if damage.is_some() {
+            gl_surface.buffer_age()
+        } else {
+            0
+        };
+        let repaint_region = region_to_repaint(&mut viewport.recent_damage, damage, buffer_age);
+
+        if let Some(region) = repaint_region {
+            painter.clear_damage(screen_size_in_pixels, pixels_per_point, region, clear_color);
+            painter.paint_and_update_textures_in_damage(
+                screen_size_in_pixels,
+                pixels_per_point,
+                &clipped_primitives,
+                &textures_delta,
+                region,
+            );
+        } else {
+            if !clear_before_update {
+                painter.clear(screen_size_in_pixels, clear_color);
+            }
 
-        painter.paint_and_update_textures(
-            screen_size_in_pixels,
-            pixels_per_point,
-            &clipped_primitives,
-            &textures_delta,
-        );
+            painter.paint_and_update_textures(
+                screen_size_in_pixels,
+                pixels_per_point,
+                &clipped_primitives,
+                &textures_delta,
+            );
+        }
 
         {
             for action in viewport.actions_requested.drain() {
@@ -708,10 +735,14 @@ impl GlowWinitRunning {
             // vsync - don't count as frame-time:
             frame_timer.pause();
             crate::profile_scope!("swap_buffers");
-            if let Err(err) = gl_surface.swap_buffers(
+            if let Err(err) = swap_buffers(
+                gl_surface,
                 current_gl_context
                     .as_ref()
                     .expect("failed to get current context to swap buffers"),
+                repaint_region,
+                screen_size_in_pixels,
+                pixels_per_point,
             ) {
                 log::error!("swap_buffers failed: {err}");
             }
@@ -1033,6 +1064,7 @@ impl GlutinWindowContext {
                 gl_surface: None,
                 window: window.map(Arc::new),
                 egui_winit: None,
+                recent_damage: Default::default(),
             },
         );
 
@@ -1336,6 +1368,7 @@ fn initialize_or_update_viewport(
                 window: None,
                 egui_winit: None,
                 gl_surface: None,
+                recent_damage: Default::default(),
             })
         }
 
@@ -1434,6 +1467,7 @@ fn render_immediate_viewport(
         platform_output,
         textures_delta,
         shapes,
+        damage,
         pixels_per_point,
         viewport_output,
     } = egui_ctx.run(input, |ctx| {
@@ -1465,6 +1499,7 @@ fn render_immediate_viewport(
     ) else {
         return;
     };
+    let recent_damage = &mut viewport.recent_damage;
 
     let screen_size_in_pixels: [u32; 2] = window.inner_size().into();
 
@@ -1480,22 +1515,44 @@ fn render_immediate_viewport(
         );
     }
 
-    egui_glow::painter::clear(
-        painter.borrow().gl(),
-        screen_size_in_pixels,
-        [0.0, 0.0, 0.0, 0.0],
-    );
+    let buffer_age = if damage.is_some() {
+        gl_surface.buffer_age()
+    } else {
+        0
+    };
+    let repaint_region = region_to_repaint(recent_damage, damage, buffer_age);
 
-    painter.borrow_mut().paint_and_update_textures(
-        screen_size_in_pixels,
-        pixels_per_point,
-        &clipped_primitives,
-        &textures_delta,
-    );
+    let clear_color = [0.0, 0.0, 0.0, 0.0];
+    if let Some(region) = repaint_region {
+        let mut painter = painter.borrow_mut();
+        painter.clear_damage(screen_size_in_pixels, pixels_per_point, region, clear_color);
+        painter.paint_and_update_textures_in_damage(
+            screen_size_in_pixels,
+            pixels_per_point,
+            &clipped_primitives,
+            &textures_delta,
+            region,
+        );
+    } else {
+        egui_glow::painter::clear(painter.borrow().gl(), screen_size_in_pixels, clear_color);
+
+        painter.borrow_mut().paint_and_update_textures(
+            screen_size_in_pixels,
+            pixels_per_point,
+            &clipped_primitives,
+            &textures_delta,
+        );
+    }
 
     {
         crate::profile_scope!("swap_buffers");
-        if let Err(err) = gl_surface.swap_buffers(current_gl_context) {
+        if let Err(err) = swap_buffers(
+            gl_surface,
+            current_gl_context,
+            repaint_region,
+            screen_size_in_pixels,
+            pixels_per_point,
+        ) {
             log::error!("swap_buffers failed: {err}");
         }
     }
@@ -1505,6 +1562,66 @@ fn render_immediate_viewport(
     glutin.handle_viewport_output(event_loop, egui_ctx, &viewport_output);
 }
 
+/// The part of the window (in points) that needs repainting to show the new frame,
+/// or `None` if all of it does.
+///
+/// `buffer_age` is how many frames ago the back buffer we are painting into was painted,
+/// or 0 if we don't know (see [`GlSurface::buffer_age`]).
+/// It is then missing the `damage` of this frame and of the frames after that one.
+fn region_to_repaint(
+    recent_damage: &mut VecDeque<Option<egui::Rect>>,
+    damage: Option<egui::Rect>,
+    buffer_age: u32,
+) -> Option<egui::Rect> {
+    /// Older back buffers are rare, even with triple buffering.
+    const MAX_BUFFER_AGE: usize = 4;
+
+    recent_damage.push_front(damage);
+    recent_damage.truncate(MAX_BUFFER_AGE);
+
+    let buffer_age = buffer_age as usize;
+    if buffer_age == 0 || recent_damage.len() < buffer_age {
+        return None;
+    }
+    recent_damage
+        .iter()
+        .take(buffer_age)
+        .try_fold(egui::Rect::NOTHING, |region, damage| {
+            Some(region.union((*damage)?))
+        })
+}
+
+/// Show the back buffer, telling the compositor that only `repaint_region` (in points) changed, if it can use that.
+fn swap_buffers(
+    gl_surface: &glutin::surface::Surface<glutin::surface::WindowSurface>,
+    gl_context: &glutin::context::PossiblyCurrentContext,
+    repaint_region: Option<egui::Rect>,
+    screen_size_in_pixels: [u32; 2],
+    pixels_per_point: f32,
+) -> glutin::error::Result<()> {
+    // Only EGL can swap with damage. Elsewhere the whole window is presented,
+    // but we still only painted what changed.
+    #[cfg(all(any(windows, unix), not(any(target_os = "macos", target_os = "ios"))))]
+    if let (
+        Some(region),
+        glutin::surface::Surface::Egl(gl_surface),
+        glutin::context::PossiblyCurrentContext::Egl(gl_context),
+    ) = (repaint_region, gl_surface, gl_context)
+    {
+        if region.is_positive() {
+            let [x, y, width, height] =
+                egui_glow::painter::scissor_rect(screen_size_in_pixels, pixels_per_point, region);
+            let damage = glutin::surface::Rect::new(x, y, width, height);
+            return gl_surface.swap_buffers_with_damage(gl_context, &[damage]);
+        }
+    }
+
+    #[cfg(not(all(any(windows, unix), not(any(target_os = "macos", target_os = "ios")))))]
+    let _ = (repaint_region, screen_size_in_pixels, pixels_per_point);
+
+    gl_surface.swap_buffers(gl_context)
+}
+
 #[cfg(feature = "__screenshot")]
 fn save_screenshot_and_exit(
     path: &str,
@@ -8,6 +8,9 @@ pub mod file_storage;
 
 pub(crate) mod winit_integration;
 
+#[cfg(feature = "theme_files")]
+mod theme_watcher;
+
 #[cfg(feature = "glow")]
 mod glow_integration;
 
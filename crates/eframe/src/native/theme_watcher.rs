@@ -0,0 +1,69 @@
+//! Hot reloading of [`crate::NativeOptions::theme_file`].
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+/// How often we check if the theme file has changed.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Loads a theme file into an [`egui::Context`], and loads it again whenever the file changes.
+///
+/// The file is watched by a background thread, which stops when this is dropped.
+pub struct ThemeWatcher {
+    stop: Arc<AtomicBool>,
+}
+
+impl ThemeWatcher {
+    pub fn new(egui_ctx: egui::Context, path: PathBuf) -> Self {
+        let mut last_modified = modified(&path);
+        load(&egui_ctx, &path);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let result = std::thread::Builder::new()
+            .name("eframe_theme_watcher".to_owned())
+            .spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(POLL_INTERVAL);
+
+                    // Editors may briefly remove the file while saving it:
+                    let Some(modified) = modified(&path) else {
+                        continue;
+                    };
+                    if last_modified != Some(modified) {
+                        last_modified = Some(modified);
+                        load(&egui_ctx, &path);
+                        egui_ctx.request_repaint();
+                    }
+                }
+            });
+        if let Err(err) = result {
+            log::warn!("Failed to spawn thread to watch the theme file: {err}");
+        }
+
+        Self { stop }
+    }
+}
+
+impl Drop for ThemeWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn load(egui_ctx: &egui::Context, path: &Path) {
+    match egui_ctx.load_theme_file(path) {
+        Ok(()) => log::debug!("Loaded theme from {path:?}"),
+        Err(err) => log::warn!("{path:?}: {err}"),
+    }
+}
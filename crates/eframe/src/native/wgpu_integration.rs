@@ -247,8 +247,6 @@ impl WgpuWinitApp {
             let event_loop_proxy = self.repaint_proxy.lock().clone();
             integration.init_accesskit(&mut egui_winit, &window, event_loop_proxy);
         }
-        let theme = system_theme.unwrap_or(self.native_options.default_theme);
-        egui_ctx.set_visuals(theme.egui_visuals());
 
         let app_creator = std::mem::take(&mut self.app_creator)
             .expect("Single-use AppCreator has unexpectedly already been taken");
@@ -633,6 +631,7 @@ impl WgpuWinitRunning {
             platform_output,
             textures_delta,
             shapes,
+            damage: _, // wgpu surface textures don't keep the last frame, so we repaint all of it
             pixels_per_point,
             viewport_output,
         } = full_output;
@@ -974,6 +973,7 @@ fn render_immediate_viewport(
         platform_output,
         textures_delta,
         shapes,
+        damage: _, // wgpu surface textures don't keep the last frame, so we repaint all of it
         pixels_per_point,
         viewport_output,
     } = egui_ctx.run(input, |ctx| {
@@ -68,7 +68,8 @@ impl AppRunner {
         });
 
         let theme = system_theme.unwrap_or(web_options.default_theme);
-        egui_ctx.set_visuals(theme.egui_visuals());
+        egui_ctx.set_style(egui_ctx.style_of(theme));
+        egui_ctx.options_mut(|o| o.follow_system_theme = web_options.follow_system_theme);
 
         let app = app_creator(&epi::CreationContext {
             egui_ctx: egui_ctx.clone(),
@@ -124,13 +125,14 @@ impl AppRunner {
         };
 
         runner.input.raw.max_texture_side = Some(runner.painter.max_texture_side());
-        runner
+        let info = runner
             .input
             .raw
             .viewports
             .entry(egui::ViewportId::ROOT)
-            .or_default()
-            .native_pixels_per_point = Some(super::native_pixels_per_point());
+            .or_default();
+        info.native_pixels_per_point = Some(super::native_pixels_per_point());
+        info.system_theme = super::system_theme();
 
         Ok(runner)
     }
@@ -195,6 +197,7 @@ impl AppRunner {
             platform_output,
             textures_delta,
             shapes,
+            damage: _, // The canvas is cleared once it has been shown, so we repaint all of it
             pixels_per_point,
             viewport_output,
         } = full_output;
@@ -33,6 +33,12 @@ impl WebInput {
             .entry(egui::ViewportId::ROOT)
             .or_default()
             .native_pixels_per_point = Some(super::native_pixels_per_point());
+
+        // Viewport events should only be sent once:
+        if let Some(info) = self.raw.viewports.get_mut(&egui::ViewportId::ROOT) {
+            info.events.clear();
+        }
+
         raw_input
     }
 
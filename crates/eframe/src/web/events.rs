@@ -271,9 +271,17 @@ pub(crate) fn install_color_scheme_change_event(runner_ref: &WebRunner) -> Resul
             &media_query_list,
             "change",
             |event, runner| {
-                let theme = theme_from_dark_mode(event.matches());
+                // egui switches the style itself, see `egui::Options::follow_system_theme`.
+                let theme = Theme::from_dark_mode(event.matches());
                 runner.frame.info.system_theme = Some(theme);
-                runner.egui_ctx().set_visuals(theme.egui_visuals());
+                let info = runner
+                    .input
+                    .raw
+                    .viewports
+                    .entry(egui::ViewportId::ROOT)
+                    .or_default();
+                info.system_theme = Some(theme);
+                info.events.push(egui::ViewportEvent::ThemeChanged(theme));
                 runner.needs_repaint.repaint_asap();
             },
         )?;
@@ -63,8 +63,6 @@ impl WebRunner {
     ) -> Result<(), JsValue> {
         self.destroy();
 
-        let follow_system_theme = web_options.follow_system_theme;
-
         let runner = AppRunner::new(canvas_id, web_options, app_creator).await?;
         self.runner.replace(Some(runner));
 
@@ -73,10 +71,7 @@ impl WebRunner {
             events::install_document_events(self)?;
             events::install_window_events(self)?;
             super::text_agent::install_text_agent(self)?;
-
-            if follow_system_theme {
-                events::install_color_scheme_change_event(self)?;
-            }
+            events::install_color_scheme_change_event(self)?;
 
             self.request_animation_frame()?;
         }
@@ -89,3 +89,79 @@ fn fs_main_gamma_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
     let out_color_gamma = in.color * tex_gamma;
     return out_color_gamma;
 }
+
+// Coverage from a signed distance field, where the outline is at 0.5. See `epaint::sdf`.
+fn sdf_coverage(distance: f32) -> f32 {
+    // Anti-alias over one pixel, however much the texture is scaled:
+    let half_width = max(0.5 * length(vec2<f32>(dpdx(distance), dpdy(distance))), 0.0001);
+    // Same gamma as `epaint::FontImage::srgba_pixels` applies to coverage:
+    return pow(smoothstep(0.5 - half_width, 0.5 + half_width, distance), 0.55);
+}
+
+// For font textures where the alpha channel is a signed distance field.
+@fragment
+fn fs_main_sdf_linear_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_linear = textureSample(r_tex_color, r_tex_sampler, in.tex_coord);
+    let out_color_gamma = in.color * sdf_coverage(tex_linear.a);
+    return vec4<f32>(linear_from_gamma_rgb(out_color_gamma.rgb), out_color_gamma.a);
+}
+
+// For font textures where the alpha channel is a signed distance field.
+@fragment
+fn fs_main_sdf_gamma_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_linear = textureSample(r_tex_color, r_tex_sampler, in.tex_coord);
+    return in.color * sdf_coverage(tex_linear.a);
+}
+
+// Gaussian blur, see `epaint::BlurPrimitive`.
+// Blurs in one direction, reading and writing premultiplied gamma colors.
+
+struct BlurLocals {
+    step: vec2<f32>, // uv offset between two samples
+    uv_min: vec2<f32>, // the part of the texture to sample from
+    uv_max: vec2<f32>,
+    sigma: f32, // standard deviation, in samples
+    _padding: f32,
+};
+@group(2) @binding(0) var<uniform> r_blur: BlurLocals;
+
+fn blur(in: VertexOutput) -> vec4<f32> {
+    let sigma = max(r_blur.sigma, 0.01);
+    let radius = i32(ceil(3.0 * sigma));
+    var sum = vec4<f32>(0.0);
+    var total = 0.0;
+    for (var i = -radius; i <= radius; i++) {
+        let x = f32(i);
+        let weight = exp(-0.5 * x * x / (sigma * sigma));
+        let tex_coord = clamp(in.tex_coord + x * r_blur.step, r_blur.uv_min, r_blur.uv_max);
+        sum += weight * textureSampleLevel(r_tex_color, r_tex_sampler, tex_coord, 0.0);
+        total += weight;
+    }
+    return in.color * (sum / total);
+}
+
+@fragment
+fn fs_blur_gamma_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
+    return blur(in);
+}
+
+@fragment
+fn fs_blur_linear_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
+    let out_color_gamma = blur(in);
+    return vec4<f32>(linear_from_gamma_rgb(out_color_gamma.rgb), out_color_gamma.a);
+}
+
+// Clearing part of the framebuffer, see `Renderer::clear_damage`.
+// The color comes from the blend constant, so no bindings are needed.
+
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    // One triangle that covers the whole viewport:
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_clear() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0);
+}
@@ -2,7 +2,11 @@
 
 use std::{borrow::Cow, num::NonZeroU64, ops::Range};
 
-use epaint::{ahash::HashMap, emath::NumExt, PaintCallbackInfo, Primitive, Vertex};
+use epaint::{
+    ahash::{HashMap, HashSet},
+    emath::NumExt,
+    BlurPrimitive, BlurSource, Mesh, PaintCallbackInfo, Primitive, Vertex,
+};
 
 use wgpu::util::DeviceExt as _;
 
@@ -149,10 +153,137 @@ struct SlicedBuffer {
     capacity: wgpu::BufferAddress,
 }
 
+/// Uniform buffer used when blurring, see `BlurLocals` in `egui.wgsl`.
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct BlurUniformBuffer {
+    step: [f32; 2],
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+    sigma: f32,
+    _padding: u32,
+}
+
+/// The largest number of samples on either side of a pixel when blurring.
+///
+/// Wider blurs skip pixels, and let the linear filtering fill in between.
+const MAX_BLUR_RADIUS: f32 = 48.0;
+
+/// Pipelines for painting [`BlurPrimitive`]s.
+struct BlurPipelines {
+    /// For painting the meshes of a shadow into a texture, to be blurred.
+    source: wgpu::RenderPipeline,
+    source_sdf: wgpu::RenderPipeline,
+
+    /// For blurring horizontally from one texture into the other.
+    horizontal: wgpu::RenderPipeline,
+
+    /// For blurring vertically onto the screen.
+    vertical: wgpu::RenderPipeline,
+
+    uniform_bind_group_layout: wgpu::BindGroupLayout,
+    blur_bind_group_layout: wgpu::BindGroupLayout,
+
+    /// The format of the textures we blur in.
+    format: wgpu::TextureFormat,
+
+    /// Can we copy the framebuffer into a texture, to blur a backdrop?
+    supports_backdrop: bool,
+}
+
+/// Two textures to blur back and forth between.
+///
+/// These are reused between frames, and grown as needed.
+struct BlurTarget {
+    size: [u32; 2],
+    textures: [wgpu::Texture; 2],
+    views: [wgpu::TextureView; 2],
+    bind_groups: [wgpu::BindGroup; 2],
+}
+
+/// Where a mesh is in the vertex and index buffers.
+#[derive(Clone)]
+struct MeshSlice {
+    vertices: Range<usize>,
+    indices: Range<usize>,
+}
+
+/// A region of the screen blurred into a [`BlurTarget`], ready to be painted.
+struct BlurredRegion {
+    /// Index into [`Renderer::blur_targets`].
+    target: usize,
+
+    /// Position and size of the region on the screen, in physical pixels.
+    /// The textures hold it in their top left corner.
+    region: [u32; 4],
+
+    /// Covers the region, for the horizontal pass.
+    region_mesh: MeshSlice,
+
+    /// The [`BlurPrimitive::mesh`], for the vertical pass.
+    mesh: MeshSlice,
+
+    /// The screen size for painting into the textures.
+    offscreen_uniform_bind_group: wgpu::BindGroup,
+    horizontal_bind_group: wgpu::BindGroup,
+    vertical_bind_group: wgpu::BindGroup,
+}
+
+/// What [`Renderer::update_buffers`] prepared for a [`BlurPrimitive`].
+enum PreparedBlur {
+    /// Nothing to paint, e.g. because it is off screen.
+    Nothing,
+
+    /// A shadow, already blurred horizontally.
+    Shadow(BlurredRegion),
+
+    /// A backdrop blur, which needs [`Renderer::render_with_backdrops`].
+    ///
+    /// Otherwise we paint the fallback meshes.
+    Backdrop(BlurredRegion, Vec<(epaint::TextureId, MeshSlice)>),
+
+    /// We can't blur this, so we paint the fallback meshes.
+    Fallback(Vec<(epaint::TextureId, MeshSlice)>),
+}
+
+/// What [`plan_blur`] decided to do with a [`BlurPrimitive`],
+/// before its meshes are uploaded.
+///
+/// The mesh indices are into the meshes made for blurs.
+enum PlannedBlur {
+    Nothing,
+
+    Blur {
+        target: usize,
+        region: [u32; 4],
+        sigma_in_pixels: f32,
+
+        /// `None` for backdrops.
+        source: Option<Range<usize>>,
+        region_mesh: usize,
+        mesh: usize,
+        fallback: Range<usize>,
+    },
+
+    Fallback(Range<usize>),
+}
+
 /// Renderer for a egui based GUI.
 pub struct Renderer {
     pipeline: wgpu::RenderPipeline,
 
+    /// Used for font textures containing signed distance fields.
+    sdf_pipeline: wgpu::RenderPipeline,
+
+    /// Used by [`Self::clear_damage`].
+    clear_pipeline: wgpu::RenderPipeline,
+
+    blur_pipelines: BlurPipelines,
+    blur_targets: Vec<BlurTarget>,
+
+    /// One for each [`Primitive::Blur`] passed to [`Self::update_buffers`].
+    prepared_blurs: Vec<PreparedBlur>,
+
     index_buffer: SlicedBuffer,
     vertex_buffer: SlicedBuffer,
 
@@ -165,6 +296,10 @@ pub struct Renderer {
     /// sampler). The texture may be None if the `TextureId` is just a handle to a user-provided
     /// sampler.
     textures: HashMap<epaint::TextureId, (Option<wgpu::Texture>, wgpu::BindGroup)>,
+
+    /// Font textures containing signed distance fields, see [`egui::GlyphRasterization::Sdf`].
+    sdf_textures: HashSet<epaint::TextureId>,
+
     next_user_texture_id: u64,
     samplers: HashMap<epaint::textures::TextureOptions, wgpu::Sampler>,
 
@@ -179,6 +314,10 @@ impl Renderer {
     ///
     /// `output_color_format` should preferably be [`wgpu::TextureFormat::Rgba8Unorm`] or
     /// [`wgpu::TextureFormat::Bgra8Unorm`], i.e. in gamma-space.
+    ///
+    /// Backdrop blurs ([`BlurSource::Backdrop`]) need such a gamma-space format,
+    /// since we blur in gamma space and copy the framebuffer to blur it.
+    /// With an sRGB format they are not painted at all.
     pub fn new(
         device: &wgpu::Device,
         output_color_format: wgpu::TextureFormat,
@@ -277,11 +416,85 @@ impl Renderer {
             bias: wgpu::DepthBiasState::default(),
         });
 
-        let pipeline = {
+        let (fs_main, fs_main_sdf, fs_blur) = if output_color_format.is_srgb() {
+            log::warn!("Detected a linear (sRGBA aware) framebuffer {output_color_format:?}. egui prefers Rgba8Unorm or Bgra8Unorm");
+            (
+                "fs_main_linear_framebuffer",
+                "fs_main_sdf_linear_framebuffer",
+                "fs_blur_linear_framebuffer",
+            )
+        } else {
+            (
+                "fs_main_gamma_framebuffer",
+                "fs_main_sdf_gamma_framebuffer",
+                "fs_blur_gamma_framebuffer",
+            ) // this is what we prefer
+        };
+
+        // We blur in gamma space, like we blend.
+        // To blur a backdrop we copy the framebuffer, so we then need the same format.
+        let supports_backdrop = !output_color_format.is_srgb();
+        if !supports_backdrop {
+            log::warn!("Backdrop blurs are not supported with the framebuffer format {output_color_format:?}, and will not be painted");
+        }
+        let blur_format = if supports_backdrop {
+            output_color_format
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+
+        let blur_bind_group_layout = {
+            crate::profile_scope!("create_bind_group_layout");
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("egui_blur_bind_group_layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(
+                            std::mem::size_of::<BlurUniformBuffer>() as _
+                        ),
+                        ty: wgpu::BufferBindingType::Uniform,
+                    },
+                    count: None,
+                }],
+            })
+        };
+
+        let blur_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("egui_blur_pipeline_layout"),
+            bind_group_layouts: &[
+                &uniform_bind_group_layout,
+                &texture_bind_group_layout,
+                &blur_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let premultiplied_alpha_blending = wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+        };
+
+        // Offscreen pipelines paint into textures to blur, without depth or multisampling.
+        let create_pipeline = |label,
+                               layout,
+                               fragment_entry_point,
+                               offscreen: bool,
+                               blend: Option<wgpu::BlendState>| {
             crate::profile_scope!("create_render_pipeline");
             device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("egui_pipeline"),
-                layout: Some(&pipeline_layout),
+                label: Some(label),
+                layout: Some(layout),
                 vertex: wgpu::VertexState {
                     entry_point: "vs_main",
                     module: &module,
@@ -304,35 +517,19 @@ impl Renderer {
                     polygon_mode: wgpu::PolygonMode::default(),
                     strip_index_format: None,
                 },
-                depth_stencil,
+                depth_stencil: if offscreen { None } else { depth_stencil.clone() },
                 multisample: wgpu::MultisampleState {
                     alpha_to_coverage_enabled: false,
-                    count: msaa_samples,
+                    count: if offscreen { 1 } else { msaa_samples },
                     mask: !0,
                 },
 
                 fragment: Some(wgpu::FragmentState {
                     module: &module,
-                    entry_point: if output_color_format.is_srgb() {
-                        log::warn!("Detected a linear (sRGBA aware) framebuffer {:?}. egui prefers Rgba8Unorm or Bgra8Unorm", output_color_format);
-                        "fs_main_linear_framebuffer"
-                    } else {
-                        "fs_main_gamma_framebuffer" // this is what we prefer
-                    },
+                    entry_point: fragment_entry_point,
                     targets: &[Some(wgpu::ColorTargetState {
-                        format: output_color_format,
-                        blend: Some(wgpu::BlendState {
-                            color: wgpu::BlendComponent {
-                                src_factor: wgpu::BlendFactor::One,
-                                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                                operation: wgpu::BlendOperation::Add,
-                            },
-                            alpha: wgpu::BlendComponent {
-                                src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
-                                dst_factor: wgpu::BlendFactor::One,
-                                operation: wgpu::BlendOperation::Add,
-                            },
-                        }),
+                        format: if offscreen { blur_format } else { output_color_format },
+                        blend,
                         write_mask: wgpu::ColorWrites::ALL,
                     })],
                     compilation_options: wgpu::PipelineCompilationOptions::default()
@@ -341,6 +538,96 @@ impl Renderer {
             }
         )
         };
+        let blending = Some(premultiplied_alpha_blending);
+        let pipeline = create_pipeline("egui_pipeline", &pipeline_layout, fs_main, false, blending);
+        let sdf_pipeline = create_pipeline(
+            "egui_sdf_pipeline",
+            &pipeline_layout,
+            fs_main_sdf,
+            false,
+            blending,
+        );
+        let blur_pipelines = BlurPipelines {
+            source: create_pipeline(
+                "egui_blur_source_pipeline",
+                &pipeline_layout,
+                "fs_main_gamma_framebuffer",
+                true,
+                blending,
+            ),
+            source_sdf: create_pipeline(
+                "egui_blur_source_sdf_pipeline",
+                &pipeline_layout,
+                "fs_main_sdf_gamma_framebuffer",
+                true,
+                blending,
+            ),
+            horizontal: create_pipeline(
+                "egui_blur_horizontal_pipeline",
+                &blur_pipeline_layout,
+                "fs_blur_gamma_framebuffer",
+                true,
+                None,
+            ),
+            vertical: create_pipeline(
+                "egui_blur_vertical_pipeline",
+                &blur_pipeline_layout,
+                fs_blur,
+                false,
+                blending,
+            ),
+            uniform_bind_group_layout,
+            blur_bind_group_layout,
+            format: blur_format,
+            supports_backdrop,
+        };
+
+        // Fills the viewport with the blend constant, so that we can clear within a scissor rectangle.
+        let clear_pipeline = {
+            crate::profile_scope!("create_render_pipeline");
+            let fill_with_constant = wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::Constant,
+                dst_factor: wgpu::BlendFactor::Zero,
+                operation: wgpu::BlendOperation::Add,
+            };
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("egui_clear_pipeline"),
+                layout: Some(
+                    &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: Some("egui_clear_pipeline_layout"),
+                        bind_group_layouts: &[],
+                        push_constant_ranges: &[],
+                    }),
+                ),
+                vertex: wgpu::VertexState {
+                    entry_point: "vs_fullscreen",
+                    module: &module,
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: depth_stencil.clone(),
+                multisample: wgpu::MultisampleState {
+                    alpha_to_coverage_enabled: false,
+                    count: msaa_samples,
+                    mask: !0,
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: "fs_clear",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: output_color_format,
+                        blend: Some(wgpu::BlendState {
+                            color: fill_with_constant,
+                            alpha: fill_with_constant,
+                        }),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                multiview: None,
+            })
+        };
 
         const VERTEX_BUFFER_START_CAPACITY: wgpu::BufferAddress =
             (std::mem::size_of::<Vertex>() * 1024) as _;
@@ -349,6 +636,11 @@ impl Renderer {
 
         Self {
             pipeline,
+            sdf_pipeline,
+            clear_pipeline,
+            blur_pipelines,
+            blur_targets: Vec::new(),
+            prepared_blurs: Vec::new(),
             vertex_buffer: SlicedBuffer {
                 buffer: create_vertex_buffer(device, VERTEX_BUFFER_START_CAPACITY),
                 slices: Vec::with_capacity(64),
@@ -368,6 +660,7 @@ impl Renderer {
             uniform_bind_group,
             texture_bind_group_layout,
             textures: HashMap::default(),
+            sdf_textures: HashSet::default(),
             next_user_texture_id: 0,
             samplers: HashMap::default(),
             callback_resources: CallbackResources::default(),
@@ -375,6 +668,10 @@ impl Renderer {
     }
 
     /// Executes the egui renderer onto an existing wgpu renderpass.
+    ///
+    /// Backdrop blurs ([`BlurSource::Backdrop`]) need more than one render pass,
+    /// so they are painted with their fallback.
+    /// Use [`Self::render_with_backdrops`] to blur them.
     pub fn render<'rp>(
         &'rp self,
         render_pass: &mut wgpu::RenderPass<'rp>,
@@ -382,22 +679,259 @@ impl Renderer {
         screen_descriptor: &ScreenDescriptor,
     ) {
         crate::profile_function!();
+        self.render_range(
+            render_pass,
+            paint_jobs,
+            0..paint_jobs.len(),
+            screen_descriptor,
+            false,
+            epaint::Rect::EVERYTHING,
+        );
+    }
+
+    /// Like [`Self::render`], but only repaints the `damage` rectangle (in points),
+    /// and leaves the rest of the render target as it is.
+    ///
+    /// Use this with [`egui::FullOutput::damage`] when the render target still contains the previous frame,
+    /// i.e. the render pass uses [`wgpu::LoadOp::Load`] on a texture you painted last frame.
+    /// Call [`Self::clear_damage`] before this.
+    pub fn render_in_damage<'rp>(
+        &'rp self,
+        render_pass: &mut wgpu::RenderPass<'rp>,
+        paint_jobs: &'rp [epaint::ClippedPrimitive],
+        screen_descriptor: &ScreenDescriptor,
+        damage: epaint::Rect,
+    ) {
+        crate::profile_function!();
+        self.render_range(
+            render_pass,
+            paint_jobs,
+            0..paint_jobs.len(),
+            screen_descriptor,
+            false,
+            damage,
+        );
+    }
 
+    /// Clears the `damage` rectangle (in points) to `clear_color`, leaving the rest of the render target as it is.
+    ///
+    /// See [`Self::render_in_damage`].
+    pub fn clear_damage<'rp>(
+        &'rp self,
+        render_pass: &mut wgpu::RenderPass<'rp>,
+        screen_descriptor: &ScreenDescriptor,
+        damage: epaint::Rect,
+        clear_color: wgpu::Color,
+    ) {
+        crate::profile_function!();
+        let size_in_pixels = screen_descriptor.size_in_pixels;
+        let rect = ScissorRect::new(&damage, screen_descriptor.pixels_per_point, size_in_pixels);
+        if rect.width == 0 || rect.height == 0 {
+            return;
+        }
+
+        render_pass.set_viewport(
+            0.0,
+            0.0,
+            size_in_pixels[0] as f32,
+            size_in_pixels[1] as f32,
+            0.0,
+            1.0,
+        );
+        render_pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+        render_pass.set_pipeline(&self.clear_pipeline);
+        render_pass.set_blend_constant(clear_color);
+        render_pass.draw(0..3, 0..1);
+        render_pass.set_scissor_rect(0, 0, size_in_pixels[0], size_in_pixels[1]);
+    }
+
+    /// Did the last call to [`Self::update_buffers`] include any backdrop blurs?
+    ///
+    /// If so, you may want to use [`Self::render_with_backdrops`].
+    pub fn has_backdrop_blurs(&self) -> bool {
+        self.prepared_blurs
+            .iter()
+            .any(|blur| matches!(blur, PreparedBlur::Backdrop(..)))
+    }
+
+    /// Executes the egui renderer in as many render passes as needed to blur backdrops.
+    ///
+    /// Each backdrop blur ([`BlurSource::Backdrop`]) copies what has been painted so far,
+    /// so it ends the current render pass.
+    /// This needs a gamma-space `output_color_format`, see [`Self::new`].
+    ///
+    /// * `render_pass_descriptor`: describes the first render pass.
+    ///   The later ones are the same, except that they load what was painted before.
+    /// * `target`: the texture that is painted to (the resolve target, if using multisampling).
+    ///   It must have the format given to [`Self::new`] and [`wgpu::TextureUsages::COPY_SRC`].
+    pub fn render_with_backdrops(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        render_pass_descriptor: &wgpu::RenderPassDescriptor<'_, '_>,
+        target: &wgpu::Texture,
+        paint_jobs: &[epaint::ClippedPrimitive],
+        screen_descriptor: &ScreenDescriptor,
+    ) {
+        crate::profile_function!();
+
+        fn continued<V>(
+            ops: wgpu::Operations<V>,
+            is_first: bool,
+            is_last: bool,
+        ) -> wgpu::Operations<V> {
+            wgpu::Operations {
+                load: if is_first {
+                    ops.load
+                } else {
+                    wgpu::LoadOp::Load
+                },
+                store: if is_last {
+                    ops.store
+                } else {
+                    wgpu::StoreOp::Store
+                },
+            }
+        }
+
+        let backdrops: Vec<(usize, &BlurredRegion)> = paint_jobs
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| matches!(job.primitive, Primitive::Blur(_)))
+            .zip(&self.prepared_blurs)
+            .filter_map(|((index, _), blur)| match blur {
+                PreparedBlur::Backdrop(blurred, _) => Some((index, blurred)),
+                _ => None,
+            })
+            .collect();
+
+        let mut start = 0;
+        for pass in 0..=backdrops.len() {
+            let is_first = pass == 0;
+            let is_last = pass == backdrops.len();
+            let end = backdrops
+                .get(pass)
+                .map_or(paint_jobs.len(), |(index, _)| *index);
+
+            // Everything but the first pass continues where the previous one left off:
+            let color_attachments: Vec<_> = render_pass_descriptor
+                .color_attachments
+                .iter()
+                .map(|attachment| {
+                    attachment
+                        .as_ref()
+                        .map(|attachment| wgpu::RenderPassColorAttachment {
+                            view: attachment.view,
+                            resolve_target: attachment.resolve_target,
+                            ops: continued(attachment.ops, is_first, is_last),
+                        })
+                })
+                .collect();
+            let depth_stencil_attachment = render_pass_descriptor
+                .depth_stencil_attachment
+                .as_ref()
+                .map(|attachment| wgpu::RenderPassDepthStencilAttachment {
+                    view: attachment.view,
+                    depth_ops: attachment
+                        .depth_ops
+                        .map(|ops| continued(ops, is_first, is_last)),
+                    stencil_ops: attachment
+                        .stencil_ops
+                        .map(|ops| continued(ops, is_first, is_last)),
+                });
+
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: render_pass_descriptor.label,
+                    color_attachments: &color_attachments,
+                    depth_stencil_attachment,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                self.render_range(
+                    &mut render_pass,
+                    paint_jobs,
+                    start..end,
+                    screen_descriptor,
+                    true,
+                    epaint::Rect::EVERYTHING,
+                );
+            }
+
+            if let Some((_, blurred)) = backdrops.get(pass) {
+                let [x, y, width, height] = blurred.region;
+                encoder.copy_texture_to_texture(
+                    wgpu::ImageCopyTexture {
+                        texture: target,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d { x, y, z: 0 },
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    wgpu::ImageCopyTexture {
+                        texture: &self.blur_targets[blurred.target].textures[0],
+                        mip_level: 0,
+                        origin: wgpu::Origin3d::ZERO,
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                );
+                self.blur_horizontally(encoder, blurred);
+            }
+
+            start = end;
+        }
+    }
+
+    /// Paint some of the paint jobs.
+    ///
+    /// The buffers are set up for all of them, so we need to know about all of them.
+    /// Nothing outside of `damage` is painted.
+    fn render_range<'rp>(
+        &'rp self,
+        render_pass: &mut wgpu::RenderPass<'rp>,
+        paint_jobs: &'rp [epaint::ClippedPrimitive],
+        range: Range<usize>,
+        screen_descriptor: &ScreenDescriptor,
+        paint_backdrops: bool,
+        damage: epaint::Rect,
+    ) {
         let pixels_per_point = screen_descriptor.pixels_per_point;
         let size_in_pixels = screen_descriptor.size_in_pixels;
 
         // Whether or not we need to reset the render pass because a paint callback has just
         // run.
         let mut needs_reset = true;
+        let mut is_sdf_pipeline_set = false;
 
         let mut index_buffer_slices = self.index_buffer.slices.iter();
         let mut vertex_buffer_slices = self.vertex_buffer.slices.iter();
+        let mut prepared_blurs = self.prepared_blurs.iter();
 
-        for epaint::ClippedPrimitive {
-            clip_rect,
-            primitive,
-        } in paint_jobs
+        for (
+            index,
+            epaint::ClippedPrimitive {
+                clip_rect,
+                primitive,
+            },
+        ) in paint_jobs.iter().enumerate()
         {
+            let prepared_blur = match primitive {
+                Primitive::Blur(_) => prepared_blurs.next(),
+                Primitive::Mesh(_) | Primitive::Callback(_) => None,
+            };
+
+            if !range.contains(&index) {
+                if let Primitive::Mesh(_) = primitive {
+                    // We need to advance the index and vertex buffer iterators:
+                    index_buffer_slices.next().unwrap();
+                    vertex_buffer_slices.next().unwrap();
+                }
+                continue;
+            }
+
             if needs_reset {
                 render_pass.set_viewport(
                     0.0,
@@ -410,10 +944,12 @@ impl Renderer {
                 render_pass.set_pipeline(&self.pipeline);
                 render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
                 needs_reset = false;
+                is_sdf_pipeline_set = false;
             }
 
+            let clip_rect = clip_rect.intersect(damage);
             {
-                let rect = ScissorRect::new(clip_rect, pixels_per_point, size_in_pixels);
+                let rect = ScissorRect::new(&clip_rect, pixels_per_point, size_in_pixels);
 
                 if rect.width == 0 || rect.height == 0 {
                     // Skip rendering zero-sized clip areas.
@@ -430,26 +966,41 @@ impl Renderer {
 
             match primitive {
                 Primitive::Mesh(mesh) => {
-                    let index_buffer_slice = index_buffer_slices.next().unwrap();
-                    let vertex_buffer_slice = vertex_buffer_slices.next().unwrap();
-
-                    if let Some((_texture, bind_group)) = self.textures.get(&mesh.texture_id) {
-                        render_pass.set_bind_group(1, bind_group, &[]);
-                        render_pass.set_index_buffer(
-                            self.index_buffer.buffer.slice(
-                                index_buffer_slice.start as u64..index_buffer_slice.end as u64,
-                            ),
-                            wgpu::IndexFormat::Uint32,
-                        );
-                        render_pass.set_vertex_buffer(
-                            0,
-                            self.vertex_buffer.buffer.slice(
-                                vertex_buffer_slice.start as u64..vertex_buffer_slice.end as u64,
-                            ),
-                        );
-                        render_pass.draw_indexed(0..mesh.indices.len() as u32, 0, 0..1);
-                    } else {
-                        log::warn!("Missing texture: {:?}", mesh.texture_id);
+                    let slice = MeshSlice {
+                        indices: index_buffer_slices.next().unwrap().clone(),
+                        vertices: vertex_buffer_slices.next().unwrap().clone(),
+                    };
+                    self.draw_mesh(
+                        render_pass,
+                        mesh.texture_id,
+                        &slice,
+                        &mut is_sdf_pipeline_set,
+                    );
+                }
+                Primitive::Blur(_) => {
+                    let fallback = match prepared_blur {
+                        None | Some(PreparedBlur::Nothing) => None,
+                        Some(PreparedBlur::Shadow(blurred)) => {
+                            self.blur_vertically(render_pass, blurred);
+                            None
+                        }
+                        Some(PreparedBlur::Backdrop(blurred, fallback)) => {
+                            if paint_backdrops {
+                                self.blur_vertically(render_pass, blurred);
+                                None
+                            } else {
+                                Some(fallback)
+                            }
+                        }
+                        Some(PreparedBlur::Fallback(fallback)) => Some(fallback),
+                    };
+
+                    // Restore state:
+                    render_pass.set_pipeline(&self.pipeline);
+                    is_sdf_pipeline_set = false;
+
+                    for (texture_id, slice) in fallback.into_iter().flatten() {
+                        self.draw_mesh(render_pass, *texture_id, slice, &mut is_sdf_pipeline_set);
                     }
                 }
                 Primitive::Callback(callback) => {
@@ -460,7 +1011,7 @@ impl Renderer {
 
                     let info = PaintCallbackInfo {
                         viewport: callback.rect,
-                        clip_rect: *clip_rect,
+                        clip_rect,
                         pixels_per_point,
                         screen_size_px: size_in_pixels,
                     };
@@ -497,6 +1048,87 @@ impl Renderer {
         render_pass.set_scissor_rect(0, 0, size_in_pixels[0], size_in_pixels[1]);
     }
 
+    fn draw_mesh<'rp>(
+        &'rp self,
+        render_pass: &mut wgpu::RenderPass<'rp>,
+        texture_id: epaint::TextureId,
+        slice: &MeshSlice,
+        is_sdf_pipeline_set: &mut bool,
+    ) {
+        if let Some((_texture, bind_group)) = self.textures.get(&texture_id) {
+            let is_sdf = self.sdf_textures.contains(&texture_id);
+            if is_sdf != *is_sdf_pipeline_set {
+                render_pass.set_pipeline(if is_sdf {
+                    &self.sdf_pipeline
+                } else {
+                    &self.pipeline
+                });
+                *is_sdf_pipeline_set = is_sdf;
+            }
+            render_pass.set_bind_group(1, bind_group, &[]);
+            self.draw_slice(render_pass, slice);
+        } else {
+            log::warn!("Missing texture: {:?}", texture_id);
+        }
+    }
+
+    /// Draw a mesh with whatever pipeline and bind groups are set.
+    fn draw_slice<'rp>(&'rp self, render_pass: &mut wgpu::RenderPass<'rp>, slice: &MeshSlice) {
+        render_pass.set_index_buffer(
+            self.index_buffer
+                .buffer
+                .slice(slice.indices.start as u64..slice.indices.end as u64),
+            wgpu::IndexFormat::Uint32,
+        );
+        render_pass.set_vertex_buffer(
+            0,
+            self.vertex_buffer
+                .buffer
+                .slice(slice.vertices.start as u64..slice.vertices.end as u64),
+        );
+        let index_count = slice.indices.len() / std::mem::size_of::<u32>();
+        render_pass.draw_indexed(0..index_count as u32, 0, 0..1);
+    }
+
+    /// Blur from the first texture of a [`BlurTarget`] into the second one.
+    fn blur_horizontally(&self, encoder: &mut wgpu::CommandEncoder, blurred: &BlurredRegion) {
+        let target = &self.blur_targets[blurred.target];
+        let [_, _, width, height] = blurred.region;
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("egui_blur_horizontal"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.views[1],
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_viewport(0.0, 0.0, width as f32, height as f32, 0.0, 1.0);
+        render_pass.set_pipeline(&self.blur_pipelines.horizontal);
+        render_pass.set_bind_group(0, &blurred.offscreen_uniform_bind_group, &[]);
+        render_pass.set_bind_group(1, &target.bind_groups[0], &[]);
+        render_pass.set_bind_group(2, &blurred.horizontal_bind_group, &[]);
+        self.draw_slice(&mut render_pass, &blurred.region_mesh);
+    }
+
+    /// Blur from the second texture of a [`BlurTarget`] onto the screen.
+    fn blur_vertically<'rp>(
+        &'rp self,
+        render_pass: &mut wgpu::RenderPass<'rp>,
+        blurred: &'rp BlurredRegion,
+    ) {
+        render_pass.set_pipeline(&self.blur_pipelines.vertical);
+        render_pass.set_bind_group(1, &self.blur_targets[blurred.target].bind_groups[1], &[]);
+        render_pass.set_bind_group(2, &blurred.vertical_bind_group, &[]);
+        self.draw_slice(render_pass, &blurred.mesh);
+    }
+
     /// Should be called before `render()`.
     pub fn update_texture(
         &mut self,
@@ -523,6 +1155,7 @@ impl Renderer {
                     image.pixels.len(),
                     "Mismatch between texture size and texel count"
                 );
+                self.sdf_textures.remove(&id);
                 Cow::Borrowed(&image.pixels)
             }
             epaint::ImageData::Font(image) => {
@@ -531,6 +1164,11 @@ impl Renderer {
                     image.pixels.len(),
                     "Mismatch between texture size and texel count"
                 );
+                if image.sdf {
+                    self.sdf_textures.insert(id);
+                } else {
+                    self.sdf_textures.remove(&id);
+                }
                 crate::profile_scope!("font -> sRGBA");
                 Cow::Owned(image.srgba_pixels(None).collect::<Vec<egui::Color32>>())
             }
@@ -617,6 +1255,7 @@ impl Renderer {
 
     pub fn free_texture(&mut self, id: &epaint::TextureId) {
         self.textures.remove(id);
+        self.sdf_textures.remove(id);
     }
 
     /// Get the WGPU texture and bind group associated to a texture that has been allocated by egui.
@@ -794,15 +1433,42 @@ impl Renderer {
             self.previous_uniform_buffer_content = uniform_buffer_content;
         }
 
+        // Blurs need some more meshes, which we put after those of the paint jobs:
+        let mut blur_meshes = Vec::new();
+        let mut planned_blurs = Vec::new();
+        {
+            crate::profile_scope!("plan_blurs");
+            let mut num_targets = 0;
+            for clipped_primitive in paint_jobs {
+                if let Primitive::Blur(blur) = &clipped_primitive.primitive {
+                    planned_blurs.push(plan_blur(
+                        blur,
+                        screen_descriptor,
+                        self.blur_pipelines.supports_backdrop,
+                        |size| {
+                            let target = num_targets;
+                            num_targets += 1;
+                            (target, self.ensure_blur_target(device, target, size))
+                        },
+                        &mut blur_meshes,
+                    ));
+                }
+            }
+        }
+
         // Determine how many vertices & indices need to be rendered, and gather prepare callbacks
         let mut callbacks = Vec::new();
         let (vertex_count, index_count) = {
             crate::profile_scope!("count_vertices_indices");
-            paint_jobs.iter().fold((0, 0), |acc, clipped_primitive| {
+            let counts = blur_meshes.iter().fold((0, 0), |acc, mesh: &Mesh| {
+                (acc.0 + mesh.vertices.len(), acc.1 + mesh.indices.len())
+            });
+            paint_jobs.iter().fold(counts, |acc, clipped_primitive| {
                 match &clipped_primitive.primitive {
                     Primitive::Mesh(mesh) => {
                         (acc.0 + mesh.vertices.len(), acc.1 + mesh.indices.len())
                     }
+                    Primitive::Blur(_) => acc,
                     Primitive::Callback(callback) => {
                         if let Some(c) = callback.callback.downcast_ref::<Callback>() {
                             callbacks.push(c.0.as_ref());
@@ -814,6 +1480,15 @@ impl Renderer {
                 }
             })
         };
+        let paint_job_meshes =
+            paint_jobs
+                .iter()
+                .filter_map(|clipped_primitive| match &clipped_primitive.primitive {
+                    Primitive::Mesh(mesh) => Some(mesh),
+                    Primitive::Blur(_) | Primitive::Callback(_) => None,
+                });
+        let num_paint_job_meshes = paint_job_meshes.clone().count();
+        let all_meshes = paint_job_meshes.chain(&blur_meshes);
 
         if index_count > 0 {
             crate::profile_scope!("indices", index_count.to_string());
@@ -839,18 +1514,13 @@ impl Renderer {
             };
 
             let mut index_offset = 0;
-            for epaint::ClippedPrimitive { primitive, .. } in paint_jobs {
-                match primitive {
-                    Primitive::Mesh(mesh) => {
-                        let size = mesh.indices.len() * std::mem::size_of::<u32>();
-                        let slice = index_offset..(size + index_offset);
-                        index_buffer_staging[slice.clone()]
-                            .copy_from_slice(bytemuck::cast_slice(&mesh.indices));
-                        self.index_buffer.slices.push(slice);
-                        index_offset += size;
-                    }
-                    Primitive::Callback(_) => {}
-                }
+            for mesh in all_meshes.clone() {
+                let size = mesh.indices.len() * std::mem::size_of::<u32>();
+                let slice = index_offset..(size + index_offset);
+                index_buffer_staging[slice.clone()]
+                    .copy_from_slice(bytemuck::cast_slice(&mesh.indices));
+                self.index_buffer.slices.push(slice);
+                index_offset += size;
             }
         }
         if vertex_count > 0 {
@@ -878,21 +1548,44 @@ impl Renderer {
             };
 
             let mut vertex_offset = 0;
-            for epaint::ClippedPrimitive { primitive, .. } in paint_jobs {
-                match primitive {
-                    Primitive::Mesh(mesh) => {
-                        let size = mesh.vertices.len() * std::mem::size_of::<Vertex>();
-                        let slice = vertex_offset..(size + vertex_offset);
-                        vertex_buffer_staging[slice.clone()]
-                            .copy_from_slice(bytemuck::cast_slice(&mesh.vertices));
-                        self.vertex_buffer.slices.push(slice);
-                        vertex_offset += size;
-                    }
-                    Primitive::Callback(_) => {}
-                }
+            for mesh in all_meshes {
+                let size = mesh.vertices.len() * std::mem::size_of::<Vertex>();
+                let slice = vertex_offset..(size + vertex_offset);
+                vertex_buffer_staging[slice.clone()]
+                    .copy_from_slice(bytemuck::cast_slice(&mesh.vertices));
+                self.vertex_buffer.slices.push(slice);
+                vertex_offset += size;
             }
         }
 
+        {
+            crate::profile_scope!("prepare_blurs");
+            let blur_mesh_slices: Vec<(epaint::TextureId, MeshSlice)> = blur_meshes
+                .iter()
+                .enumerate()
+                .map(|(i, mesh)| {
+                    let i = num_paint_job_meshes + i;
+                    let slice = MeshSlice {
+                        vertices: self.vertex_buffer.slices[i].clone(),
+                        indices: self.index_buffer.slices[i].clone(),
+                    };
+                    (mesh.texture_id, slice)
+                })
+                .collect();
+            self.prepared_blurs = planned_blurs
+                .into_iter()
+                .map(|planned| {
+                    self.prepare_blur(
+                        device,
+                        encoder,
+                        planned,
+                        &blur_mesh_slices,
+                        screen_descriptor,
+                    )
+                })
+                .collect();
+        }
+
         let mut user_cmd_bufs = Vec::new();
         {
             crate::profile_scope!("prepare callbacks");
@@ -920,6 +1613,311 @@ impl Renderer {
 
         user_cmd_bufs
     }
+
+    /// Make sure there is a [`BlurTarget`] at `index` that is at least `size` large.
+    ///
+    /// Returns the size of the target.
+    fn ensure_blur_target(
+        &mut self,
+        device: &wgpu::Device,
+        index: usize,
+        size: [u32; 2],
+    ) -> [u32; 2] {
+        let old_size = self.blur_targets.get(index).map(|target| target.size);
+        let size = match old_size {
+            Some(old_size) if size[0] <= old_size[0] && size[1] <= old_size[1] => {
+                return old_size;
+            }
+            Some(old_size) => [size[0].max(old_size[0]), size[1].max(old_size[1])],
+            None => size,
+        };
+
+        let sampler = self
+            .samplers
+            .entry(epaint::textures::TextureOptions::LINEAR)
+            .or_insert_with(|| create_sampler(epaint::textures::TextureOptions::LINEAR, device));
+        let create_texture = || {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("egui_blur_texture"),
+                size: wgpu::Extent3d {
+                    width: size[0],
+                    height: size[1],
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.blur_pipelines.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            })
+        };
+        let textures = [create_texture(), create_texture()];
+        let views = [
+            textures[0].create_view(&wgpu::TextureViewDescriptor::default()),
+            textures[1].create_view(&wgpu::TextureViewDescriptor::default()),
+        ];
+        let create_bind_group = |view| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("egui_blur_texture_bind_group"),
+                layout: &self.texture_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(sampler),
+                    },
+                ],
+            })
+        };
+        let bind_groups = [create_bind_group(&views[0]), create_bind_group(&views[1])];
+
+        let target = BlurTarget {
+            size,
+            textures,
+            views,
+            bind_groups,
+        };
+        if index < self.blur_targets.len() {
+            self.blur_targets[index] = target;
+        } else {
+            self.blur_targets.push(target);
+        }
+        size
+    }
+
+    /// Set up the uniforms for a planned blur.
+    ///
+    /// Shadows are painted into their [`BlurTarget`] and blurred horizontally right away.
+    fn prepare_blur(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        planned: PlannedBlur,
+        blur_mesh_slices: &[(epaint::TextureId, MeshSlice)],
+        screen_descriptor: &ScreenDescriptor,
+    ) -> PreparedBlur {
+        let (target, region, sigma_in_pixels, source, region_mesh, mesh, fallback) = match planned {
+            PlannedBlur::Nothing => return PreparedBlur::Nothing,
+            PlannedBlur::Fallback(fallback) => {
+                return PreparedBlur::Fallback(blur_mesh_slices[fallback].to_vec());
+            }
+            PlannedBlur::Blur {
+                target,
+                region,
+                sigma_in_pixels,
+                source,
+                region_mesh,
+                mesh,
+                fallback,
+            } => (
+                target,
+                region,
+                sigma_in_pixels,
+                source,
+                region_mesh,
+                mesh,
+                fallback,
+            ),
+        };
+
+        let pixels_per_point = screen_descriptor.pixels_per_point;
+        let [_, _, width, height] = region;
+        let [texture_width, texture_height] =
+            self.blur_targets[target].size.map(|side| side as f32);
+
+        let offscreen_uniform_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("egui_blur_uniform_buffer"),
+                contents: bytemuck::cast_slice(&[UniformBuffer {
+                    screen_size_in_points: [
+                        width as f32 / pixels_per_point,
+                        height as f32 / pixels_per_point,
+                    ],
+                    _padding: Default::default(),
+                }]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+        let offscreen_uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("egui_blur_uniform_bind_group"),
+            layout: &self.blur_pipelines.uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: offscreen_uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        // Skip pixels for very wide blurs, and let the linear filtering fill in between:
+        let stride = (3.0 * sigma_in_pixels / MAX_BLUR_RADIUS).ceil().max(1.0);
+        let create_blur_bind_group = |[x, y]: [f32; 2]| {
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("egui_blur_locals_buffer"),
+                contents: bytemuck::cast_slice(&[BlurUniformBuffer {
+                    step: [stride * x / texture_width, stride * y / texture_height],
+                    uv_min: [0.5 / texture_width, 0.5 / texture_height],
+                    uv_max: [
+                        (width as f32 - 0.5) / texture_width,
+                        (height as f32 - 0.5) / texture_height,
+                    ],
+                    sigma: (sigma_in_pixels / stride).max(0.01),
+                    _padding: 0,
+                }]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("egui_blur_locals_bind_group"),
+                layout: &self.blur_pipelines.blur_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }],
+            })
+        };
+
+        let blurred = BlurredRegion {
+            target,
+            region,
+            region_mesh: blur_mesh_slices[region_mesh].1.clone(),
+            mesh: blur_mesh_slices[mesh].1.clone(),
+            offscreen_uniform_bind_group,
+            horizontal_bind_group: create_blur_bind_group([1.0, 0.0]),
+            vertical_bind_group: create_blur_bind_group([0.0, 1.0]),
+        };
+
+        let Some(source) = source else {
+            // A backdrop, which we can only blur once it has been painted.
+            return PreparedBlur::Backdrop(blurred, blur_mesh_slices[fallback].to_vec());
+        };
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("egui_blur_source"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.blur_targets[target].views[0],
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_viewport(0.0, 0.0, width as f32, height as f32, 0.0, 1.0);
+            render_pass.set_bind_group(0, &blurred.offscreen_uniform_bind_group, &[]);
+            for (texture_id, slice) in &blur_mesh_slices[source] {
+                if let Some((_texture, bind_group)) = self.textures.get(texture_id) {
+                    render_pass.set_pipeline(if self.sdf_textures.contains(texture_id) {
+                        &self.blur_pipelines.source_sdf
+                    } else {
+                        &self.blur_pipelines.source
+                    });
+                    render_pass.set_bind_group(1, bind_group, &[]);
+                    self.draw_slice(&mut render_pass, slice);
+                } else {
+                    log::warn!("Missing texture: {texture_id:?}");
+                }
+            }
+        }
+        self.blur_horizontally(encoder, &blurred);
+
+        PreparedBlur::Shadow(blurred)
+    }
+}
+
+/// Decide how to paint a [`BlurPrimitive`], and make the meshes needed for it.
+///
+/// `blur_target` is given the size of the blurred region in pixels,
+/// and returns the index and size of a [`BlurTarget`] at least that large.
+fn plan_blur(
+    blur: &BlurPrimitive,
+    screen_descriptor: &ScreenDescriptor,
+    supports_backdrop: bool,
+    blur_target: impl FnOnce([u32; 2]) -> (usize, [u32; 2]),
+    blur_meshes: &mut Vec<Mesh>,
+) -> PlannedBlur {
+    fn push_meshes(
+        blur_meshes: &mut Vec<Mesh>,
+        meshes: impl Iterator<Item = Mesh>,
+    ) -> Range<usize> {
+        let start = blur_meshes.len();
+        blur_meshes.extend(meshes.filter(|mesh| !mesh.is_empty()));
+        start..blur_meshes.len()
+    }
+
+    let is_backdrop = matches!(blur.source, BlurSource::Backdrop);
+    if is_backdrop && !supports_backdrop {
+        let fallback = push_meshes(blur_meshes, blur.fallback.iter().cloned());
+        return PlannedBlur::Fallback(fallback);
+    }
+    if blur.mesh.is_empty() {
+        return PlannedBlur::Nothing;
+    }
+
+    // The region in physical pixels, shrunk to the screen:
+    let pixels_per_point = screen_descriptor.pixels_per_point;
+    let [screen_width, screen_height] = screen_descriptor.size_in_pixels;
+    let to_pixels = |points: f32, max: u32| ((points * pixels_per_point).round() as u32).min(max);
+    let x = to_pixels(blur.rect.min.x, screen_width);
+    let y = to_pixels(blur.rect.min.y, screen_height);
+    let width = to_pixels(blur.rect.max.x, screen_width).saturating_sub(x);
+    let height = to_pixels(blur.rect.max.y, screen_height).saturating_sub(y);
+    if width == 0 || height == 0 {
+        return PlannedBlur::Nothing;
+    }
+
+    let (target, [texture_width, texture_height]) = blur_target([width, height]);
+    let texture_size = epaint::vec2(texture_width as f32, texture_height as f32);
+    let region_min = epaint::vec2(x as f32, y as f32);
+    let region_size = epaint::vec2(width as f32, height as f32);
+
+    let source = match &blur.source {
+        BlurSource::Meshes(meshes) => {
+            let offset = -region_min / pixels_per_point;
+            let meshes = meshes.iter().map(|mesh| {
+                let mut mesh = mesh.clone();
+                mesh.translate(offset);
+                mesh
+            });
+            Some(push_meshes(blur_meshes, meshes))
+        }
+        BlurSource::Backdrop => None,
+    };
+
+    let mut region_mesh = Mesh::default();
+    region_mesh.add_rect_with_uv(
+        epaint::Rect::from_min_size(epaint::Pos2::ZERO, region_size / pixels_per_point),
+        epaint::Rect::from_min_size(epaint::Pos2::ZERO, region_size / texture_size),
+        epaint::Color32::WHITE,
+    );
+    blur_meshes.push(region_mesh);
+    let region_mesh = blur_meshes.len() - 1;
+
+    let mut mesh = blur.mesh.clone();
+    for vertex in &mut mesh.vertices {
+        vertex.uv =
+            ((vertex.pos.to_vec2() * pixels_per_point - region_min) / texture_size).to_pos2();
+    }
+    blur_meshes.push(mesh);
+    let mesh = blur_meshes.len() - 1;
+
+    let fallback = push_meshes(blur_meshes, blur.fallback.iter().cloned());
+
+    PlannedBlur::Blur {
+        target,
+        region: [x, y, width, height],
+        sigma_in_pixels: blur.sigma * pixels_per_point,
+        source,
+        region_mesh,
+        mesh,
+        fallback,
+    }
 }
 
 fn create_sampler(
@@ -1013,3 +2011,155 @@ fn renderer_impl_send_sync() {
     fn assert_send_sync<T: Send + Sync>() {}
     assert_send_sync::<Renderer>();
 }
+
+#[test]
+fn scissor_rect_in_damage() {
+    use epaint::{pos2, Rect};
+
+    let target_size = [200, 100];
+    let clip_rect = Rect::from_min_max(pos2(10.0, 10.0), pos2(50.0, 40.0));
+    let scissor = |damage: Rect| {
+        let rect = ScissorRect::new(&clip_rect.intersect(damage), 2.0, target_size);
+        [rect.x, rect.y, rect.width, rect.height]
+    };
+
+    assert_eq!(scissor(Rect::EVERYTHING), [20, 20, 80, 60]);
+
+    let damage = Rect::from_min_max(pos2(40.0, 0.0), pos2(100.0, 20.0));
+    assert_eq!(scissor(damage), [80, 20, 20, 20]);
+
+    let damage = Rect::from_min_max(pos2(60.0, 60.0), pos2(70.0, 70.0));
+    let [_, _, width, height] = scissor(damage);
+    assert!(width == 0 || height == 0, "outside of the damage");
+
+    let [_, _, width, height] = scissor(Rect::NOTHING);
+    assert!(width == 0 || height == 0, "no damage");
+}
+
+#[test]
+fn plan_tessellated_blurs() {
+    use epaint::{pos2, vec2, ClippedShape, Color32, Rect, Shadow, Shape};
+
+    let screen_descriptor = ScreenDescriptor {
+        size_in_pixels: [200, 100],
+        pixels_per_point: 2.0,
+    };
+    let shadow = Shadow {
+        offset: vec2(2.0, 2.0),
+        blur: 8.0,
+        spread: 0.0,
+        color: Color32::BLACK,
+    };
+    let shadow_of =
+        |rect: Rect| Shape::shadow(Shape::rect_filled(rect, 0.0, Color32::WHITE), shadow);
+    let shapes = vec![
+        shadow_of(Rect::from_min_size(pos2(40.0, 20.0), vec2(10.0, 10.0))),
+        // Partly outside of the screen:
+        shadow_of(Rect::from_min_size(pos2(95.0, 0.0), vec2(10.0, 10.0))),
+        Shape::backdrop_blur(
+            Rect::from_min_size(pos2(10.0, 10.0), vec2(20.0, 20.0)),
+            0.0,
+            8.0,
+        ),
+    ];
+    let screen_rect = Rect::from_min_size(pos2(0.0, 0.0), vec2(100.0, 50.0));
+    let shapes = shapes
+        .into_iter()
+        .map(|shape| ClippedShape {
+            clip_rect: screen_rect,
+            shape,
+        })
+        .collect();
+    let primitives =
+        epaint::Tessellator::new(2.0, Default::default(), [1, 1], vec![]).tessellate_shapes(shapes);
+    let blurs: Vec<&BlurPrimitive> = primitives
+        .iter()
+        .filter_map(|clipped| match &clipped.primitive {
+            Primitive::Blur(blur) => Some(blur),
+            _ => None,
+        })
+        .collect();
+    let [shadow, clipped_shadow, backdrop] = blurs[..] else {
+        panic!("expected three blurs, got {}", blurs.len());
+    };
+
+    let mut blur_meshes = vec![];
+    let plan = |blur: &BlurPrimitive, supports_backdrop: bool, blur_meshes: &mut Vec<Mesh>| {
+        // Blur targets exactly as large as the region:
+        plan_blur(
+            blur,
+            &screen_descriptor,
+            supports_backdrop,
+            |size| (7, size),
+            blur_meshes,
+        )
+    };
+
+    let PlannedBlur::Blur {
+        target,
+        region,
+        sigma_in_pixels,
+        source: Some(source),
+        region_mesh,
+        mesh,
+        fallback,
+    } = plan(shadow, false, &mut blur_meshes)
+    else {
+        panic!("a shadow should be blurred");
+    };
+    assert_eq!(target, 7);
+    let rect = shadow.rect;
+    assert_eq!(
+        region,
+        [rect.min.x, rect.min.y, rect.width(), rect.height()].map(|points| (points * 2.0) as u32)
+    );
+    assert_eq!(sigma_in_pixels, shadow.sigma * 2.0);
+    assert_eq!(fallback.len(), shadow.fallback.len());
+    // The silhouette is painted into the region, moved by the offset of the shadow:
+    let silhouette = blur_meshes[source]
+        .iter()
+        .fold(Rect::NOTHING, |bounds, mesh| {
+            bounds.union(mesh.calc_bounds())
+        });
+    let expected =
+        Rect::from_min_size(pos2(42.0, 22.0), vec2(10.0, 10.0)).translate(-rect.min.to_vec2());
+    assert!(
+        (silhouette.center() - expected.center()).length() < 0.01,
+        "{silhouette:?} vs {expected:?}"
+    );
+    assert_eq!(
+        blur_meshes[region_mesh].calc_bounds(),
+        Rect::from_min_size(pos2(0.0, 0.0), rect.size())
+    );
+    for vertex in &blur_meshes[mesh].vertices {
+        let uv = vertex.uv;
+        assert!(
+            (0.0..=1.0).contains(&uv.x) && (0.0..=1.0).contains(&uv.y),
+            "{uv:?}"
+        );
+    }
+
+    // Only the part on the screen is blurred:
+    let PlannedBlur::Blur { region, .. } = plan(clipped_shadow, false, &mut blur_meshes) else {
+        panic!("a shadow on the screen should be blurred");
+    };
+    assert_eq!(region[0] + region[2], 200);
+    assert_eq!(region[1], 0);
+
+    let mut off_screen = shadow.clone();
+    off_screen.rect = Rect::from_min_size(pos2(120.0, 10.0), vec2(10.0, 10.0));
+    assert!(matches!(
+        plan(&off_screen, true, &mut blur_meshes),
+        PlannedBlur::Nothing
+    ));
+
+    // A backdrop can only be blurred with a gamma-space framebuffer:
+    assert!(matches!(
+        plan(backdrop, true, &mut blur_meshes),
+        PlannedBlur::Blur { source: None, .. }
+    ));
+    assert!(
+        matches!(plan(backdrop, false, &mut blur_meshes), PlannedBlur::Fallback(fallback) if fallback.is_empty()),
+        "nothing to paint instead of a backdrop blur"
+    );
+}
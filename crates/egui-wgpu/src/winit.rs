@@ -13,6 +13,9 @@ struct SurfaceState {
     width: u32,
     height: u32,
     supports_screenshot: bool,
+
+    /// Can we copy from the surface texture, to blur backdrops?
+    supports_copy_src: bool,
 }
 
 /// A texture and a buffer for reading the rendered frame back to the cpu.
@@ -149,11 +152,14 @@ impl Painter {
     ) {
         crate::profile_function!();
 
-        let usage = if surface_state.supports_screenshot {
+        let mut usage = if surface_state.supports_screenshot {
             wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST
         } else {
             wgpu::TextureUsages::RENDER_ATTACHMENT
         };
+        if surface_state.supports_copy_src {
+            usage |= wgpu::TextureUsages::COPY_SRC;
+        }
 
         let width = surface_state.width;
         let height = surface_state.height;
@@ -285,6 +291,10 @@ impl Painter {
         };
         let supports_screenshot =
             !matches!(render_state.adapter.get_info().backend, wgpu::Backend::Gl);
+        let supports_copy_src = surface
+            .get_capabilities(&render_state.adapter)
+            .usages
+            .contains(wgpu::TextureUsages::COPY_SRC);
         self.surfaces.insert(
             viewport_id,
             SurfaceState {
@@ -293,6 +303,7 @@ impl Painter {
                 height: size.height,
                 alpha_mode,
                 supports_screenshot,
+                supports_copy_src,
             },
         );
         let Some(width) = NonZeroU32::new(size.width) else {
@@ -597,24 +608,20 @@ impl Painter {
 
         {
             let renderer = render_state.renderer.read();
-            let frame_view = if capture {
+            let frame_texture = if capture {
                 Self::update_capture_state(
                     &mut self.screen_capture_state,
                     &output_frame,
                     render_state,
                 );
-                self.screen_capture_state
-                    .as_ref()
-                    .map_or_else(
-                        || &output_frame.texture,
-                        |capture_state| &capture_state.texture,
-                    )
-                    .create_view(&wgpu::TextureViewDescriptor::default())
+                self.screen_capture_state.as_ref().map_or_else(
+                    || &output_frame.texture,
+                    |capture_state| &capture_state.texture,
+                )
             } else {
-                output_frame
-                    .texture
-                    .create_view(&wgpu::TextureViewDescriptor::default())
+                &output_frame.texture
             };
+            let frame_view = frame_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
             let (view, resolve_target) = (self.msaa_samples > 1)
                 .then_some(self.msaa_texture_view.get(&viewport_id))
@@ -623,7 +630,7 @@ impl Painter {
                     (texture_view, Some(&frame_view))
                 });
 
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            let render_pass_descriptor = wgpu::RenderPassDescriptor {
                 label: Some("egui_render"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view,
@@ -652,9 +659,22 @@ impl Painter {
                 }),
                 timestamp_writes: None,
                 occlusion_query_set: None,
-            });
+            };
 
-            renderer.render(&mut render_pass, clipped_primitives, &screen_descriptor);
+            // Blurring backdrops needs to copy from the texture we paint to:
+            let can_copy_frame = capture || surface_state.supports_copy_src;
+            if renderer.has_backdrop_blurs() && can_copy_frame {
+                renderer.render_with_backdrops(
+                    &mut encoder,
+                    &render_pass_descriptor,
+                    frame_texture,
+                    clipped_primitives,
+                    &screen_descriptor,
+                );
+            } else {
+                let mut render_pass = encoder.begin_render_pass(&render_pass_descriptor);
+                renderer.render(&mut render_pass, clipped_primitives, &screen_descriptor);
+            }
         }
 
         {
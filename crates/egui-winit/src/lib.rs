@@ -977,6 +977,19 @@ pub fn update_viewport_info(
 
     viewport_info.fullscreen = Some(window.fullscreen().is_some());
     viewport_info.focused = Some(window.has_focus());
+
+    let system_theme = window.theme().map(|theme| match theme {
+        winit::window::Theme::Dark => egui::Theme::Dark,
+        winit::window::Theme::Light => egui::Theme::Light,
+    });
+    if let Some(theme) = system_theme {
+        if !is_init && viewport_info.system_theme.is_some_and(|old| old != theme) {
+            viewport_info
+                .events
+                .push(egui::ViewportEvent::ThemeChanged(theme));
+        }
+    }
+    viewport_info.system_theme = system_theme;
 }
 
 fn open_url_in_browser(_url: &str) {
@@ -0,0 +1,221 @@
+//! Reusing what a region of a [`Ui`] painted last frame, see [`Ui::cached`].
+
+use std::{hash::Hash, sync::Arc};
+
+use epaint::{mutex::Mutex, ClippedShape, TextureAtlas};
+
+use crate::{util::cache::FrameCache, *};
+
+/// What a cached region painted, and the circumstances under which it did so.
+#[derive(Clone)]
+pub(crate) struct CachedRegion {
+    /// Hash of the user-supplied key.
+    key: u64,
+
+    layer_id: LayerId,
+    max_rect: Rect,
+    clip_rect: Rect,
+    enabled: bool,
+    style: Arc<Style>,
+
+    /// The galleys we painted refer to this font atlas.
+    font_atlas: Arc<Mutex<TextureAtlas>>,
+
+    /// The space the region used.
+    rect: Rect,
+
+    shapes: Vec<ClippedShape>,
+    widgets: Vec<WidgetRect>,
+
+    /// Was the region hovered, or did it request a repaint (e.g. for an animation)?
+    ///
+    /// Then it will likely look different next frame, so we shouldn't reuse it.
+    unsettled: bool,
+}
+
+type RegionCache = FrameCache<CachedRegion, ()>;
+
+/// What we need to know to decide if a [`CachedRegion`] can be reused.
+struct Circumstances {
+    key: u64,
+    layer_id: LayerId,
+    max_rect: Rect,
+    clip_rect: Rect,
+    enabled: bool,
+    style: Arc<Style>,
+    font_atlas: Arc<Mutex<TextureAtlas>>,
+    hover_pos: Option<Pos2>,
+    interact_radius: f32,
+
+    /// Has anything but pointer movement happened?
+    has_events: bool,
+
+    focused: Option<Id>,
+    dragged: Option<Id>,
+}
+
+impl Circumstances {
+    fn new(ui: &Ui, key: u64, max_rect: Rect) -> Self {
+        let ctx = ui.ctx();
+        let (hover_pos, has_events) = ctx.input(|i| {
+            let has_events = i
+                .events
+                .iter()
+                .any(|event| !matches!(event, Event::PointerMoved(_) | Event::MouseMoved(_)));
+            (i.pointer.hover_pos(), has_events)
+        });
+        Self {
+            key,
+            layer_id: ui.layer_id(),
+            max_rect,
+            clip_rect: ui.clip_rect(),
+            enabled: ui.is_enabled(),
+            style: ui.style().clone(),
+            font_atlas: ctx.fonts(|fonts| fonts.texture_atlas()),
+            hover_pos,
+            interact_radius: ui.style().interaction.interact_radius,
+            has_events,
+            focused: ctx.memory(|mem| mem.focused()),
+            dragged: ctx.dragged_id(),
+        }
+    }
+
+    fn is_hovered(&self, rect: Rect) -> bool {
+        self.hover_pos.map_or(false, |pos| {
+            rect.expand(self.interact_radius).contains(pos)
+                && self.clip_rect.expand(self.interact_radius).contains(pos)
+        })
+    }
+
+    fn can_reuse(&self, cached: &CachedRegion) -> bool {
+        let same_circumstances = cached.key == self.key
+            && cached.layer_id == self.layer_id
+            && cached.max_rect == self.max_rect
+            && cached.clip_rect == self.clip_rect
+            && cached.enabled == self.enabled
+            && (Arc::ptr_eq(&cached.style, &self.style) || cached.style == self.style)
+            && Arc::ptr_eq(&cached.font_atlas, &self.font_atlas);
+
+        let is_interacted_with = self.has_events
+            || self.is_hovered(cached.rect)
+            || cached
+                .widgets
+                .iter()
+                .any(|widget| Some(widget.id) == self.focused || Some(widget.id) == self.dragged);
+
+        same_circumstances && !cached.unsettled && !is_interacted_with
+    }
+}
+
+/// See [`Ui::cached`].
+pub(crate) fn show_cached<R>(
+    ui: &mut Ui,
+    id_source: impl Hash,
+    key: impl Hash,
+    add_contents: impl FnOnce(&mut Ui) -> R,
+) -> InnerResponse<Option<R>> {
+    let id = ui.id().with(&id_source);
+    let max_rect = ui.available_rect_before_wrap();
+    let circumstances = Circumstances::new(ui, crate::util::hash(key), max_rect);
+
+    let cached = ui.ctx().memory_mut(|mem| {
+        mem.caches
+            .cache::<RegionCache>()
+            .get_cached(id)
+            .filter(|cached| circumstances.can_reuse(cached))
+            .map(|cached| (cached.rect, cached.shapes.clone(), cached.widgets.clone()))
+    });
+
+    if let Some((rect, shapes, widgets)) = cached {
+        crate::profile_scope!("reuse cached region");
+        ui.ctx().graphics_mut(|graphics| {
+            let paint_list = graphics.entry(circumstances.layer_id);
+            for ClippedShape { clip_rect, shape } in shapes {
+                paint_list.add(clip_rect, shape);
+            }
+        });
+        ui.ctx().recreate_widgets(&widgets);
+        let response = ui.allocate_rect(rect, Sense::hover());
+        return InnerResponse::new(None, response);
+    }
+
+    let layer_id = circumstances.layer_id;
+    let shapes_start = ui.ctx().graphics(|graphics| {
+        graphics
+            .get(layer_id)
+            .map_or(0, |list| list.all_entries().len())
+    });
+    let widgets_start = ui.ctx().num_widgets_in_layer(layer_id);
+    let repaints_start = ui.ctx().num_repaint_requests();
+
+    let InnerResponse { inner, response } = ui.push_id(id_source, add_contents);
+
+    let ctx = ui.ctx();
+    let shapes = ctx.graphics(|graphics| {
+        graphics.get(layer_id).map_or_else(Vec::new, |list| {
+            list.all_entries().skip(shapes_start).cloned().collect()
+        })
+    });
+    let widgets = ctx.widgets_in_layer_from(layer_id, widgets_start);
+    let unsettled =
+        circumstances.is_hovered(response.rect) || repaints_start < ctx.num_repaint_requests();
+
+    let Circumstances {
+        key,
+        layer_id,
+        max_rect,
+        clip_rect,
+        enabled,
+        style,
+        font_atlas,
+        ..
+    } = circumstances;
+    let region = CachedRegion {
+        key,
+        layer_id,
+        max_rect,
+        clip_rect,
+        enabled,
+        style,
+        font_atlas,
+        rect: response.rect,
+        shapes,
+        widgets,
+        unsettled,
+    };
+    ctx.memory_mut(|mem| mem.caches.cache::<RegionCache>().insert(id, region));
+
+    InnerResponse::new(Some(inner), response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_static_region() {
+        let ctx = Context::default();
+        let mut runs = 0;
+        let mut shapes = vec![];
+        for frame in 0..4 {
+            let mut input = RawInput::default();
+            if frame == 3 {
+                input.events.push(Event::Text("a".to_owned()));
+            }
+            let output = ctx.run(input, |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.cached("report", 42, |ui| {
+                        runs += 1;
+                        ui.label("A static report");
+                        ui.separator();
+                    });
+                });
+            });
+            shapes.push(output.shapes);
+        }
+
+        // Run on the first frame, reused on the next two, and run again because of the input:
+        assert_eq!(runs, 2);
+        assert!(shapes.iter().all(|frame_shapes| frame_shapes == &shapes[0]));
+    }
+}
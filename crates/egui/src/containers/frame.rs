@@ -65,6 +65,13 @@ pub struct Frame {
 
     pub shadow: Shadow,
 
+    /// Blur what is behind the frame, by this much (see [`Shadow::blur`]).
+    ///
+    /// Use with a translucent [`Self::fill`] for a frosted glass look.
+    /// Renderers that can't blur will ignore this.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backdrop_blur: f32,
+
     pub fill: Color32,
 
     pub stroke: Stroke,
@@ -196,6 +203,13 @@ impl Frame {
         self
     }
 
+    /// Blur what is behind the frame. See [`Self::backdrop_blur`].
+    #[inline]
+    pub fn backdrop_blur(mut self, backdrop_blur: f32) -> Self {
+        self.backdrop_blur = backdrop_blur;
+        self
+    }
+
     /// Opacity multiplier in gamma space.
     ///
     /// For instance, multiplying with `0.5`
@@ -286,18 +300,26 @@ impl Frame {
             outer_margin: _,
             rounding,
             shadow,
+            backdrop_blur,
             fill,
             stroke,
         } = *self;
 
         let frame_shape = Shape::Rect(epaint::RectShape::new(outer_rect, rounding, fill, stroke));
 
-        if shadow == Default::default() {
-            frame_shape
-        } else {
-            let shadow = shadow.as_shape(outer_rect, rounding);
-            Shape::Vec(vec![Shape::from(shadow), frame_shape])
+        if shadow == Default::default() && backdrop_blur <= 0.0 {
+            return frame_shape;
+        }
+
+        let mut shapes = Vec::with_capacity(3);
+        if shadow != Default::default() {
+            shapes.push(Shape::from(shadow.as_shape(outer_rect, rounding)));
+        }
+        if 0.0 < backdrop_blur {
+            shapes.push(Shape::backdrop_blur(outer_rect, rounding, backdrop_blur));
         }
+        shapes.push(frame_shape);
+        Shape::Vec(shapes)
     }
 }
 
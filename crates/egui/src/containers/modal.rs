@@ -0,0 +1,191 @@
+//! A modal dialog, that blocks interaction with everything behind it.
+
+use crate::*;
+
+/// A modal dialog.
+///
+/// A modal is shown on top of everything else, with a dimmed backdrop covering the rest of the screen.
+/// While a modal is open, nothing behind it can be hovered, clicked or focused.
+///
+/// Modals can be nested: a modal opened from within another modal is put on top of it,
+/// and only the top-most one can be interacted with.
+///
+/// Whether or not the modal is open is up to you: show it every frame while it is open,
+/// and close it when [`ModalResponse::should_close`] returns `true`.
+///
+/// ```
+/// # egui::__run_test_ctx(|ctx| {
+/// # let mut show_modal = true;
+/// if show_modal {
+///     let modal = egui::Modal::new(egui::Id::new("confirm_delete")).show(ctx, |ui| {
+///         ui.label("Delete this file?");
+///         ui.horizontal(|ui| {
+///             if ui.button("Delete").clicked() {
+///                 // …
+///                 show_modal = false;
+///             }
+///             if ui.button("Cancel").clicked() {
+///                 show_modal = false;
+///             }
+///         });
+///     });
+///     if modal.should_close() {
+///         show_modal = false;
+///     }
+/// }
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+#[derive(Clone, Copy, Debug)]
+pub struct Modal {
+    area: Area,
+    backdrop_color: Color32,
+    frame: Option<Frame>,
+    close_on_escape: bool,
+    close_on_backdrop_click: bool,
+}
+
+impl Modal {
+    /// The `id` must be globally unique.
+    pub fn new(id: Id) -> Self {
+        Self {
+            area: Self::default_area(id),
+            backdrop_color: Color32::from_black_alpha(100),
+            frame: None,
+            close_on_escape: true,
+            close_on_backdrop_click: true,
+        }
+    }
+
+    /// The default [`Area`] of a modal: in the [`Order::Foreground`], centered on the screen.
+    pub fn default_area(id: Id) -> Area {
+        Area::new(id)
+            .order(Order::Foreground)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+    }
+
+    /// Use a custom [`Area`], e.g. to change where the modal is placed.
+    ///
+    /// The [`Order`] of the area should be [`Order::Foreground`], or the modal may end up behind popups.
+    #[inline]
+    pub fn area(mut self, area: Area) -> Self {
+        self.area = area;
+        self
+    }
+
+    /// The color the screen behind the modal is dimmed with.
+    ///
+    /// Default: black with an alpha of 100.
+    #[inline]
+    pub fn backdrop_color(mut self, backdrop_color: Color32) -> Self {
+        self.backdrop_color = backdrop_color;
+        self
+    }
+
+    /// Change the frame around the contents.
+    ///
+    /// Default: [`Frame::popup`].
+    #[inline]
+    pub fn frame(mut self, frame: Frame) -> Self {
+        self.frame = Some(frame);
+        self
+    }
+
+    /// Should pressing Escape make [`ModalResponse::should_close`] return `true`?
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn close_on_escape(mut self, close_on_escape: bool) -> Self {
+        self.close_on_escape = close_on_escape;
+        self
+    }
+
+    /// Should clicking the backdrop make [`ModalResponse::should_close`] return `true`?
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn close_on_backdrop_click(mut self, close_on_backdrop_click: bool) -> Self {
+        self.close_on_backdrop_click = close_on_backdrop_click;
+        self
+    }
+
+    /// Show the modal.
+    pub fn show<R>(
+        self,
+        ctx: &Context,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> ModalResponse<R> {
+        let Self {
+            area,
+            backdrop_color,
+            frame,
+            close_on_escape,
+            close_on_backdrop_click,
+        } = self;
+
+        let layer_id = area.layer();
+        let is_top_modal = ctx.memory_mut(|mem| {
+            mem.areas_mut().set_modal_layer(layer_id);
+            mem.top_modal_layer() == Some(layer_id)
+        });
+
+        // The backdrop is added before the contents, so that it is below them,
+        // both when painting and when checking what is under the pointer.
+        let screen_rect = ctx.screen_rect();
+        ctx.layer_painter(layer_id)
+            .rect_filled(screen_rect, 0.0, backdrop_color);
+        let backdrop_response = ctx.create_widget(WidgetRect {
+            id: area.id.with("backdrop"),
+            layer_id,
+            rect: screen_rect,
+            interact_rect: screen_rect,
+            sense: Sense::click(),
+            enabled: true,
+        });
+
+        let frame = frame.unwrap_or_else(|| Frame::popup(&ctx.style()));
+        let InnerResponse { inner, response } =
+            area.show(ctx, |ui| frame.show(ui, add_contents).inner);
+
+        let escape_pressed =
+            close_on_escape && is_top_modal && ctx.input(|i| i.key_pressed(Key::Escape));
+        let backdrop_clicked = close_on_backdrop_click && backdrop_response.clicked();
+
+        ModalResponse {
+            response,
+            backdrop_response,
+            inner,
+            is_top_modal,
+            should_close: escape_pressed || backdrop_clicked,
+        }
+    }
+}
+
+/// The response of showing a [`Modal`].
+pub struct ModalResponse<T> {
+    /// The response of the modal contents.
+    pub response: Response,
+
+    /// The response of the backdrop covering the rest of the screen.
+    pub backdrop_response: Response,
+
+    /// What the contents closure returned.
+    pub inner: T,
+
+    /// Is this the top-most modal, i.e. the one that can be interacted with?
+    ///
+    /// This is `false` on the first frame a modal is shown.
+    pub is_top_modal: bool,
+
+    should_close: bool,
+}
+
+impl<T> ModalResponse<T> {
+    /// Should the modal be closed?
+    ///
+    /// `true` if the user pressed Escape or clicked the backdrop (unless disabled with
+    /// [`Modal::close_on_escape`] or [`Modal::close_on_backdrop_click`]).
+    pub fn should_close(&self) -> bool {
+        self.should_close
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
     animation_manager::AnimationManager,
     data::output::PlatformOutput,
     frame_state::FrameState,
+    input_recording::InputRecorder,
     input_state::*,
     layers::GraphicLayers,
     load::{Bytes, Loaders, SizedTexture},
@@ -249,6 +250,17 @@ struct ViewportState {
     // Most of the things in `PlatformOutput` are not actually viewport dependent.
     output: PlatformOutput,
     commands: Vec<ViewportCommand>,
+
+    // ----------------------
+    // Used for `FullOutput::damage`:
+    //
+    damage_tracker: DamageTracker,
+
+    /// The screen rect and `pixels_per_point` when we last tracked the damage.
+    damage_screen: Option<(Rect, f32)>,
+
+    /// Textures whose contents changed since we last tracked the damage.
+    changed_textures: Vec<TextureId>,
 }
 
 /// What called [`Context::request_repaint`]?
@@ -379,6 +391,18 @@ struct ContextImpl {
 
     paint_stats: PaintStats,
 
+    /// Used if [`Options::cache_tessellation`] is on.
+    tessellation_caches: ViewportIdMap<epaint::TessellationCache>,
+
+    /// See [`Context::start_recording_input`].
+    input_recording: Option<InputRecorder>,
+
+    /// The system theme we last switched the style to, see [`Options::follow_system_theme`].
+    followed_system_theme: Option<Theme>,
+
+    /// See [`Context::set_style_class`].
+    style_classes: ahash::HashMap<String, StyleOverride>,
+
     request_repaint_callback: Option<Box<dyn Fn(RequestRepaintInfo) + Send + Sync>>,
 
     viewport_parents: ViewportIdMap<ViewportId>,
@@ -393,6 +417,28 @@ struct ContextImpl {
 }
 
 impl ContextImpl {
+    /// Switch to the style of the system theme if it changed, see [`Options::follow_system_theme`].
+    fn follow_system_theme(&mut self, system_theme: Option<Theme>) {
+        let options = &mut self.memory.options;
+        if !options.follow_system_theme {
+            self.followed_system_theme = None;
+            return;
+        }
+        let Some(system_theme) = system_theme else {
+            return;
+        };
+        let current_theme = Theme::from_dark_mode(options.style.visuals.dark_mode);
+        let is_change = match self.followed_system_theme {
+            Some(followed) => followed != system_theme,
+            // Just started following. Keep a style that already matches, e.g. one set by the app at startup.
+            None => current_theme != system_theme,
+        };
+        self.followed_system_theme = Some(system_theme);
+        if is_change {
+            options.style = options.style_of(system_theme).clone();
+        }
+    }
+
     fn begin_frame_mut(&mut self, mut new_raw_input: RawInput) {
         let viewport_id = new_raw_input.viewport_id;
         let parent_id = new_raw_input
@@ -423,6 +469,8 @@ impl ContextImpl {
                 // We should really scale everything else in the input too,
                 // but the `screen_rect` is the most important part.
             }
+
+            self.follow_system_theme(new_raw_input.viewport().system_theme);
         }
         let native_pixels_per_point = new_raw_input
             .viewport()
@@ -436,12 +484,25 @@ impl ContextImpl {
 
         self.memory.begin_frame(&new_raw_input, &all_viewport_ids);
 
+        let recorded_input = self
+            .input_recording
+            .is_some()
+            .then(|| new_raw_input.clone());
+
         viewport.input = std::mem::take(&mut viewport.input).begin_frame(
             new_raw_input,
             viewport.repaint.requested_immediate_repaint_prev_frame(),
             pixels_per_point,
         );
 
+        if let (Some(recorder), Some(raw_input)) = (&mut self.input_recording, recorded_input) {
+            if let Err(_err) = recorder.begin_frame(viewport.input.time, raw_input) {
+                #[cfg(feature = "log")]
+                log::error!("Stopped recording input: {_err}");
+                self.input_recording = None;
+            }
+        }
+
         let screen_rect = viewport.input.screen_rect;
 
         viewport.frame_state.begin_frame(screen_rect);
@@ -449,7 +510,11 @@ impl ContextImpl {
         {
             let area_order = self.memory.areas().order_map();
 
-            let mut layers: Vec<LayerId> = viewport.widgets_prev_frame.layer_ids().collect();
+            let mut layers: Vec<LayerId> = viewport
+                .widgets_prev_frame
+                .layer_ids()
+                .filter(|layer_id| self.memory.allows_interaction(*layer_id))
+                .collect();
 
             layers.sort_by(|a, b| {
                 if a.order == b.order {
@@ -784,6 +849,63 @@ impl Context {
     }
 }
 
+/// ## Recording input
+impl Context {
+    /// Start recording the [`RawInput`] and [`PlatformOutput`] of every frame,
+    /// so that the session can be replayed with [`InputRecording::replay`].
+    ///
+    /// This restarts any recording in progress.
+    pub fn start_recording_input(&self) {
+        self.write(|ctx| {
+            ctx.input_recording = Some(InputRecorder::Memory(InputRecording::default()));
+        });
+    }
+
+    /// Like [`Self::start_recording_input`], but write each frame to `path` as soon as it is recorded,
+    /// so that the recording survives a crash and doesn't grow in memory.
+    ///
+    /// Read it back with [`InputRecording::load`].
+    /// If writing to the file fails, the recording stops.
+    ///
+    /// # Errors
+    /// If the file can't be created.
+    #[cfg(feature = "persistence")]
+    pub fn start_recording_input_to(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        self.start_recording_input_stream(Box::new(std::io::BufWriter::new(file)));
+        Ok(())
+    }
+
+    #[cfg(feature = "persistence")]
+    pub(crate) fn start_recording_input_stream(
+        &self,
+        writer: Box<dyn std::io::Write + Send + Sync>,
+    ) {
+        let stream = crate::input_recording::RecordingStream::new(writer);
+        self.write(|ctx| ctx.input_recording = Some(InputRecorder::Stream(stream)));
+    }
+
+    /// Stop recording, and return what was recorded since [`Self::start_recording_input`].
+    ///
+    /// Returns `None` if we weren't recording,
+    /// or were writing the recording to a file with [`Self::start_recording_input_to`].
+    pub fn stop_recording_input(&self) -> Option<InputRecording> {
+        match self.write(|ctx| ctx.input_recording.take())? {
+            InputRecorder::Memory(recording) => Some(recording),
+            #[cfg(feature = "persistence")]
+            InputRecorder::Stream(_) => None, // Already written
+        }
+    }
+
+    /// Are we recording input? See [`Self::start_recording_input`].
+    pub fn is_recording_input(&self) -> bool {
+        self.read(|ctx| ctx.input_recording.is_some())
+    }
+}
+
 /// ## Borrows parts of [`Context`]
 /// These functions all lock the [`Context`].
 /// Please see the documentation of [`Context`] for how locking works!
@@ -1037,12 +1159,16 @@ impl Context {
             // but also to know when we have reached the widget we are checking for cover.
             viewport.widgets_this_frame.insert(w.layer_id, w);
 
-            if w.sense.focusable {
+            if w.sense.focusable && ctx.memory.allows_interaction(w.layer_id) {
                 ctx.memory.interested_in_focus(w.id);
             }
         });
 
-        if !w.enabled || !w.sense.focusable || !w.layer_id.allow_interaction() {
+        if !w.enabled
+            || !w.sense.focusable
+            || !w.layer_id.allow_interaction()
+            || !self.memory(|mem| mem.allows_interaction(w.layer_id))
+        {
             // Not interested or allowed input:
             self.memory_mut(|mem| mem.surrender_focus(w.id));
         }
@@ -1065,6 +1191,43 @@ impl Context {
         res
     }
 
+    /// How many widgets have been created in this layer so far this frame?
+    pub(crate) fn num_widgets_in_layer(&self, layer_id: LayerId) -> usize {
+        self.write(|ctx| {
+            ctx.viewport()
+                .widgets_this_frame
+                .get_layer(layer_id)
+                .count()
+        })
+    }
+
+    /// The widgets created in this layer this frame, skipping the first `start` of them.
+    pub(crate) fn widgets_in_layer_from(&self, layer_id: LayerId, start: usize) -> Vec<WidgetRect> {
+        self.write(|ctx| {
+            ctx.viewport()
+                .widgets_this_frame
+                .get_layer(layer_id)
+                .skip(start)
+                .copied()
+                .collect()
+        })
+    }
+
+    /// Recreate widgets that were created on an earlier frame, without checking for interaction.
+    ///
+    /// Used to keep the widgets of a cached region (see [`Ui::cached`]) around for hit testing.
+    pub(crate) fn recreate_widgets(&self, widgets: &[WidgetRect]) {
+        self.write(|ctx| {
+            for &w in widgets {
+                ctx.viewport().widgets_this_frame.insert(w.layer_id, w);
+
+                if w.sense.focusable && ctx.memory.allows_interaction(w.layer_id) {
+                    ctx.memory.interested_in_focus(w.id);
+                }
+            }
+        });
+    }
+
     /// Read the response of some widget, which may be called _before_ creating the widget (!).
     ///
     /// This is because widget interaction happens at the start of the frame, using the previous frame's widgets.
@@ -1494,6 +1657,15 @@ impl Context {
         self.read(|ctx| ctx.has_requested_repaint(viewport_id))
     }
 
+    /// How many times has a repaint of the current viewport been requested this frame?
+    pub(crate) fn num_repaint_requests(&self) -> usize {
+        self.read(|ctx| {
+            ctx.viewports
+                .get(&ctx.viewport_id())
+                .map_or(0, |v| v.repaint.causes.len())
+        })
+    }
+
     /// Why are we repainting?
     ///
     /// This can be helpful in debugging why egui is constantly repainting.
@@ -1617,6 +1789,80 @@ impl Context {
         self.options_mut(|opt| std::sync::Arc::make_mut(&mut opt.style).visuals = visuals);
     }
 
+    /// Is the current [`Style`] dark or light?
+    ///
+    /// This is based on [`crate::Visuals::dark_mode`].
+    pub fn theme(&self) -> Theme {
+        self.options(|opt| Theme::from_dark_mode(opt.style.visuals.dark_mode))
+    }
+
+    /// The theme of the operating system (or browser), if the backend knows it.
+    ///
+    /// See [`crate::ViewportInfo::system_theme`].
+    pub fn system_theme(&self) -> Option<Theme> {
+        self.input(|i| i.viewport().system_theme)
+    }
+
+    /// The [`Style`] that is used for the given theme when [`Options::follow_system_theme`] is on.
+    pub fn style_of(&self, theme: Theme) -> Arc<Style> {
+        self.options(|opt| opt.style_of(theme).clone())
+    }
+
+    /// Set the [`Style`] to use for the given theme when [`Options::follow_system_theme`] is on.
+    ///
+    /// If we are currently following the system theme, and it is `theme`,
+    /// the style is also used right away.
+    ///
+    /// Example:
+    /// ```
+    /// # let mut ctx = egui::Context::default();
+    /// let mut dark = egui::Theme::Dark.default_style();
+    /// dark.visuals.hyperlink_color = egui::Color32::LIGHT_BLUE;
+    /// ctx.set_style_of(egui::Theme::Dark, dark);
+    /// ctx.options_mut(|o| o.follow_system_theme = true);
+    /// ```
+    pub fn set_style_of(&self, theme: Theme, style: impl Into<Arc<Style>>) {
+        let style = style.into();
+        self.write(|ctx| {
+            let options = &mut ctx.memory.options;
+            if options.follow_system_theme && ctx.followed_system_theme == Some(theme) {
+                options.style = style.clone();
+            }
+            match theme {
+                Theme::Dark => options.dark_style = style,
+                Theme::Light => options.light_style = style,
+            }
+        });
+    }
+
+    /// Give a name to a [`StyleOverride`], so that widgets can opt into it.
+    ///
+    /// See [`Ui::push_style_class`], [`crate::Button::class`] and [`crate::Label::class`].
+    ///
+    /// Example:
+    /// ```
+    /// # let mut ctx = egui::Context::default();
+    /// let red = egui::Color32::from_rgb(200, 40, 40);
+    /// ctx.set_style_class(
+    ///     "danger",
+    ///     egui::StyleOverride::new(("danger", red), move |style| {
+    ///         style.visuals.widgets.inactive.weak_bg_fill = red;
+    ///         style.visuals.widgets.hovered.weak_bg_fill = red.gamma_multiply(1.2);
+    ///     }),
+    /// );
+    /// # egui::__run_test_ui(|ui| {
+    /// ui.add(egui::Button::new("Delete everything").class("danger"));
+    /// # });
+    /// ```
+    pub fn set_style_class(&self, class: impl Into<String>, style_override: StyleOverride) {
+        self.write(|ctx| ctx.style_classes.insert(class.into(), style_override));
+    }
+
+    /// The [`StyleOverride`] with this name, see [`Self::set_style_class`].
+    pub fn style_class(&self, class: &str) -> Option<StyleOverride> {
+        self.read(|ctx| ctx.style_classes.get(class).cloned())
+    }
+
     /// The number of physical pixels for each logical point.
     ///
     /// This is calculated as [`Self::zoom_factor`] * [`Self::native_pixels_per_point`]
@@ -2025,6 +2271,40 @@ impl ContextImpl {
             .graphics
             .drain(self.memory.areas().order(), &self.memory.layer_transforms);
 
+        // All viewports share the same textures:
+        let track_damage = self.memory.options.track_damage;
+        let font_atlas_replaced = textures_delta
+            .set
+            .iter()
+            .any(|(id, delta)| *id == TextureId::default() && delta.is_whole());
+        let changed_textures: Vec<TextureId> = textures_delta
+            .set
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| *id != TextureId::default()) // Only new glyphs are added to the font atlas
+            .collect();
+
+        let damage = if track_damage {
+            crate::profile_scope!("damage");
+            let screen_rect = viewport.input.screen_rect();
+            if viewport.damage_screen != Some((screen_rect, pixels_per_point))
+                || font_atlas_replaced
+            {
+                viewport.damage_tracker.clear();
+                viewport.damage_screen = Some((screen_rect, pixels_per_point));
+            }
+            viewport.changed_textures.extend(&changed_textures);
+            let damage = viewport
+                .damage_tracker
+                .damage(&shapes, &viewport.changed_textures);
+            viewport.changed_textures.clear();
+            damage.map(|damage| damage_in_whole_pixels(damage, screen_rect, pixels_per_point))
+        } else {
+            viewport.damage_tracker.clear();
+            viewport.damage_screen = None;
+            None
+        };
+
         let mut repaint_needed = false;
 
         {
@@ -2046,6 +2326,17 @@ impl ContextImpl {
             self.request_repaint(ended_viewport_id, RepaintCause::new());
         }
 
+        if track_damage {
+            for (id, viewport) in &mut self.viewports {
+                if *id != ended_viewport_id {
+                    if font_atlas_replaced {
+                        viewport.damage_tracker.clear();
+                    }
+                    viewport.changed_textures.extend(&changed_textures);
+                }
+            }
+        }
+
         //  -------------------
 
         let all_viewport_ids = self.all_viewport_ids();
@@ -2148,16 +2439,44 @@ impl ContextImpl {
             }
         });
 
+        if let Some(recorder) = &mut self.input_recording {
+            if let Err(_err) = recorder.end_frame(ended_viewport_id, &platform_output) {
+                #[cfg(feature = "log")]
+                log::error!("Stopped recording input: {_err}");
+                self.input_recording = None;
+            }
+        }
+
         FullOutput {
             platform_output,
             textures_delta,
             shapes,
+            damage,
             pixels_per_point,
             viewport_output,
         }
     }
 }
 
+/// Include the anti-aliasing around the damage, and round it outwards to whole physical pixels,
+/// so that it can be used as a scissor rectangle.
+fn damage_in_whole_pixels(damage: Rect, screen_rect: Rect, pixels_per_point: f32) -> Rect {
+    if !damage.is_positive() {
+        return Rect::NOTHING;
+    }
+    let damage = damage.expand(1.0 / pixels_per_point);
+    let damage = Rect::from_min_max(
+        (damage.min.to_vec2() * pixels_per_point).floor().to_pos2() / pixels_per_point,
+        (damage.max.to_vec2() * pixels_per_point).ceil().to_pos2() / pixels_per_point,
+    )
+    .intersect(screen_rect);
+    if damage.is_positive() {
+        damage
+    } else {
+        Rect::NOTHING
+    }
+}
+
 impl Context {
     /// Tessellate the given shapes into triangle meshes.
     ///
@@ -2171,12 +2490,13 @@ impl Context {
     ) -> Vec<ClippedPrimitive> {
         crate::profile_function!();
 
-        // A tempting optimization is to reuse the tessellation from last frame if the
-        // shapes are the same, but just comparing the shapes takes about 50% of the time
-        // it takes to tessellate them, so it is not a worth optimization.
+        // Reusing the tessellation from last frame for the shapes that are the same
+        // is opt-in (`Options::cache_tessellation`), because just hashing the shapes takes
+        // a good fraction of the time it takes to tessellate them.
 
         self.write(|ctx| {
             let tessellation_options = ctx.memory.options.tessellation_options;
+            let cache_tessellation = ctx.memory.options.cache_tessellation;
             let texture_atlas = ctx
                 .fonts
                 .get(&pixels_per_point.into())
@@ -2188,16 +2508,29 @@ impl Context {
                 (atlas.size(), atlas.prepared_discs())
             };
 
-            let paint_stats = PaintStats::from_shapes(&shapes);
+            let mut paint_stats = PaintStats::from_shapes(&shapes);
             let clipped_primitives = {
                 crate::profile_scope!("tessellator::tessellate_shapes");
-                tessellator::Tessellator::new(
+                let mut tessellator = tessellator::Tessellator::new(
                     pixels_per_point,
                     tessellation_options,
                     font_tex_size,
                     prepared_discs,
-                )
-                .tessellate_shapes(shapes)
+                );
+                if cache_tessellation {
+                    // We are called after the frame of the viewport we are tessellating for:
+                    let viewport_id = ctx.last_viewport;
+                    let viewports = &ctx.viewports;
+                    ctx.tessellation_caches
+                        .retain(|id, _| *id == viewport_id || viewports.contains_key(id));
+                    let cache = ctx.tessellation_caches.entry(viewport_id).or_default();
+                    let clipped_primitives = tessellator.tessellate_shapes_cached(shapes, cache);
+                    paint_stats = paint_stats.with_tessellation_cache(cache);
+                    clipped_primitives
+                } else {
+                    ctx.tessellation_caches.clear();
+                    tessellator.tessellate_shapes(shapes)
+                }
             };
             ctx.paint_stats = paint_stats.with_clipped_primitives(&clipped_primitives);
             clipped_primitives
@@ -3386,3 +3719,80 @@ fn context_impl_send_sync() {
     fn assert_send_sync<T: Send + Sync>() {}
     assert_send_sync::<Context>();
 }
+
+#[test]
+fn follows_system_theme() {
+    let ctx = Context::default();
+    let frame = |system_theme: Theme| {
+        let mut input = RawInput::default();
+        input
+            .viewports
+            .entry(ViewportId::ROOT)
+            .or_default()
+            .system_theme = Some(system_theme);
+        let _ = ctx.run(input, |_| {});
+    };
+
+    frame(Theme::Light);
+    assert_eq!(ctx.theme(), Theme::Dark, "Not following by default");
+
+    ctx.options_mut(|o| o.follow_system_theme = true);
+    frame(Theme::Light);
+    assert_eq!(ctx.theme(), Theme::Light);
+    assert_eq!(ctx.system_theme(), Some(Theme::Light));
+
+    // The style can be changed between theme changes:
+    ctx.set_visuals(Visuals::dark());
+    frame(Theme::Light);
+    assert_eq!(ctx.theme(), Theme::Dark);
+
+    let mut dark = Theme::Dark.default_style();
+    dark.spacing.item_spacing = vec2(10.0, 10.0);
+    ctx.set_style_of(Theme::Dark, dark.clone());
+    frame(Theme::Dark);
+    assert_eq!(*ctx.style(), dark);
+}
+
+#[test]
+fn modal_blocks_scrolling_behind_it() {
+    let scroll_offset = |with_modal: bool| {
+        let ctx = Context::default();
+        let mut offset = 0.0;
+        for frame in 0..20 {
+            let events = match frame {
+                0 => vec![Event::PointerMoved(pos2(50.0, 50.0))],
+                2 => vec![Event::Scroll(vec2(0.0, -100.0))],
+                _ => vec![],
+            };
+            let input = RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(400.0, 300.0))),
+                events,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    offset = ScrollArea::vertical()
+                        .show(ui, |ui| {
+                            for row in 0..100 {
+                                ui.label(format!("Row {row}"));
+                            }
+                        })
+                        .state
+                        .offset
+                        .y;
+                });
+                if with_modal {
+                    let _ = Modal::new(Id::new("modal")).show(ctx, |ui| ui.label("Modal"));
+                }
+            });
+        }
+        offset
+    };
+
+    assert!(scroll_offset(false) > 0.0);
+    assert_eq!(
+        scroll_offset(true),
+        0.0,
+        "The backdrop covers the scroll area"
+    );
+}
@@ -165,6 +165,11 @@ pub enum ViewportEvent {
     ///
     /// This even will wake up both the child and parent viewport.
     Close,
+
+    /// The theme of the operating system (or browser) changed.
+    ///
+    /// See also [`ViewportInfo::system_theme`].
+    ThemeChanged(crate::Theme),
 }
 
 /// Information about the current viewport, given as input each frame.
@@ -218,6 +223,12 @@ pub struct ViewportInfo {
     ///
     /// This should be the same as [`RawInput::focused`].
     pub focused: Option<bool>,
+
+    /// The current theme of the operating system (or browser), if known.
+    ///
+    /// When this changes, the backend also sends a [`ViewportEvent::ThemeChanged`].
+    /// See [`crate::Options::follow_system_theme`].
+    pub system_theme: Option<crate::Theme>,
 }
 
 impl ViewportInfo {
@@ -248,6 +259,7 @@ impl ViewportInfo {
             maximized,
             fullscreen,
             focused,
+            system_theme,
         } = self;
 
         crate::Grid::new("viewport_info").show(ui, |ui| {
@@ -295,6 +307,10 @@ impl ViewportInfo {
             ui.label(opt_as_str(focused));
             ui.end_row();
 
+            ui.label("System theme:");
+            ui.label(opt_as_str(system_theme));
+            ui.end_row();
+
             fn opt_rect_as_string(v: &Option<Rect>) -> String {
                 v.as_ref().map_or(String::new(), |r| {
                     format!("Pos: {:?}, size: {:?}", r.min, r.size())
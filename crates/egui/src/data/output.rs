@@ -23,6 +23,18 @@ pub struct FullOutput {
     /// You can use [`crate::Context::tessellate`] to turn this into triangles.
     pub shapes: Vec<epaint::ClippedShape>,
 
+    /// The part of the viewport (in points) where [`Self::shapes`] differ from the shapes of the previous frame.
+    ///
+    /// If the previous frame is still on screen, you only need to repaint this part of it,
+    /// e.g. by using it as a scissor rectangle.
+    /// It is rounded outwards to whole physical pixels.
+    ///
+    /// [`crate::Rect::NOTHING`] means nothing changed.
+    /// `None` means everything may have changed, e.g. on the first frame or after a resize.
+    ///
+    /// This is only computed if [`crate::Options::track_damage`] is on, and is `None` otherwise.
+    pub damage: Option<crate::Rect>,
+
     /// The number of physical pixels per logical ui point, for the viewport that was updated.
     ///
     /// You can pass this to [`crate::Context::tessellate`] together with [`Self::shapes`].
@@ -42,6 +54,7 @@ impl FullOutput {
             platform_output,
             textures_delta,
             shapes,
+            damage,
             pixels_per_point,
             viewport_output: viewports,
         } = newer;
@@ -49,6 +62,7 @@ impl FullOutput {
         self.platform_output.append(platform_output);
         self.textures_delta.append(textures_delta);
         self.shapes = shapes; // Only paint the latest
+        self.damage = self.damage.zip(damage).map(|(a, b)| a.union(b)); // The older frame was never painted
         self.pixels_per_point = pixels_per_point; // Use latest
 
         for (id, new_viewport) in viewports {
@@ -0,0 +1,418 @@
+//! Recording the input of a session, and replaying it to reproduce bugs.
+
+use crate::{Context, PlatformOutput, RawInput, ViewportId};
+
+/// One frame of an [`InputRecording`].
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct RecordedFrame {
+    /// The time of the frame, in seconds, as seen by [`crate::InputState::time`].
+    pub time: f64,
+
+    /// What was given to [`Context::run`].
+    pub raw_input: RawInput,
+
+    /// What egui output this frame.
+    ///
+    /// `None` if the frame never ended.
+    pub platform_output: Option<PlatformOutput>,
+}
+
+/// All the input given to a [`Context`], one frame at a time.
+///
+/// Record a session with [`Context::start_recording_input`] and [`Context::stop_recording_input`],
+/// then feed it to another [`Context`] with [`Self::replay`] to see exactly what the user saw.
+///
+/// With the `persistence` feature you can [`Self::save`] it to a file, e.g. to attach it to a bug report,
+/// or write it to a file as it is recorded with [`Context::start_recording_input_to`].
+/// `eframe` can do this for you, see `NativeOptions::record_input_to`.
+///
+/// ```
+/// # let mut my_app = |ctx: &egui::Context| {};
+/// let ctx = egui::Context::default();
+/// ctx.start_recording_input();
+/// for _ in 0..3 {
+///     let _ = ctx.run(egui::RawInput::default(), &mut my_app);
+/// }
+/// let recording = ctx.stop_recording_input().unwrap();
+///
+/// // Later, maybe on another computer:
+/// let mismatches = recording.replay_and_compare(&egui::Context::default(), &mut my_app);
+/// assert!(mismatches.is_empty());
+/// ```
+#[derive(Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct InputRecording {
+    /// In the order they were run, including the frames of immediate and deferred viewports.
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// A frame where replaying an [`InputRecording`] gave a different [`PlatformOutput`] than what was recorded.
+///
+/// See [`InputRecording::replay_and_compare`].
+#[derive(Clone, PartialEq)]
+pub struct OutputMismatch {
+    /// Index into [`InputRecording::frames`].
+    pub frame: usize,
+
+    pub recorded: PlatformOutput,
+    pub replayed: PlatformOutput,
+}
+
+impl InputRecording {
+    /// Run `run_ui` once for each recorded frame, with the same input as when it was recorded.
+    ///
+    /// For the replay to be faithful, `ctx` should be a fresh [`Context`] set up like the original one
+    /// (same fonts, style and options), and `run_ui` should behave like the recorded app.
+    ///
+    /// Only the frames of the root viewport are replayed.
+    /// Other viewports are embedded in it, as if the backend didn't support multiple viewports.
+    pub fn replay(&self, ctx: &Context, run_ui: impl FnMut(&Context)) {
+        self.replay_frames(ctx, run_ui, |_, _| {});
+    }
+
+    /// Like [`Self::replay`], but also compares the [`PlatformOutput`] of each frame to what was recorded.
+    ///
+    /// This tells you if the replay went off track, e.g. because the app behaves differently now.
+    /// Frames without a recorded output are not compared.
+    pub fn replay_and_compare(
+        &self,
+        ctx: &Context,
+        run_ui: impl FnMut(&Context),
+    ) -> Vec<OutputMismatch> {
+        let mut mismatches = vec![];
+        self.replay_frames(ctx, run_ui, |index, replayed| {
+            if let Some(recorded) = &self.frames[index].platform_output {
+                if recorded != &replayed {
+                    mismatches.push(OutputMismatch {
+                        frame: index,
+                        recorded: recorded.clone(),
+                        replayed,
+                    });
+                }
+            }
+        });
+        mismatches
+    }
+
+    fn replay_frames(
+        &self,
+        ctx: &Context,
+        mut run_ui: impl FnMut(&Context),
+        mut on_output: impl FnMut(usize, PlatformOutput),
+    ) {
+        crate::profile_function!();
+        ctx.set_embed_viewports(true);
+        for (index, frame) in self.frames.iter().enumerate() {
+            if frame.raw_input.viewport_id != ViewportId::ROOT {
+                continue;
+            }
+            let mut raw_input = frame.raw_input.clone();
+            raw_input.time = Some(frame.time);
+            let output = ctx.run(raw_input, &mut run_ui);
+            on_output(index, output.platform_output);
+        }
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl InputRecording {
+    /// Write the recording to a file, in [RON](https://github.com/ron-rs/ron) format.
+    ///
+    /// This is the same format that [`Context::start_recording_input_to`] writes.
+    ///
+    /// # Errors
+    /// If the file can't be written.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        crate::profile_function!();
+        let file = std::fs::File::create(path)?;
+        let mut stream = RecordingStream::new(Box::new(std::io::BufWriter::new(file)));
+        for frame in &self.frames {
+            stream.begin_frame(frame.time, &frame.raw_input)?;
+            if let Some(platform_output) = &frame.platform_output {
+                stream.end_frame(frame.raw_input.viewport_id, platform_output)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a recording written by [`Self::save`] or [`Context::start_recording_input_to`].
+    ///
+    /// If the app crashed while recording, the last line may be cut short. It is ignored.
+    ///
+    /// # Errors
+    /// If the file can't be read, or doesn't contain a recording.
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        crate::profile_function!();
+        Self::from_lines(&std::fs::read_to_string(path)?)
+    }
+
+    fn from_lines(text: &str) -> std::io::Result<Self> {
+        let invalid_data = |err: Box<dyn std::error::Error + Send + Sync>| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+        };
+
+        let mut frames: Vec<RecordedFrame> = vec![];
+        let mut lines = text.lines().peekable();
+        while let Some(line) = lines.next() {
+            match ron::from_str::<RecordingLine<RawInput, PlatformOutput>>(line) {
+                Ok(RecordingLine::Begin { time, raw_input }) => frames.push(RecordedFrame {
+                    time,
+                    raw_input,
+                    platform_output: None,
+                }),
+                Ok(RecordingLine::End {
+                    frame,
+                    platform_output,
+                }) => {
+                    let frame = frames.get_mut(frame).ok_or_else(|| {
+                        invalid_data(format!("Frame {frame} ended before it began").into())
+                    })?;
+                    frame.platform_output = Some(platform_output);
+                }
+                Err(_) if lines.peek().is_none() => {
+                    // Cut short by a crash
+                }
+                Err(err) => return Err(invalid_data(err.into())),
+            }
+        }
+        Ok(Self { frames })
+    }
+}
+
+/// One line of a saved [`InputRecording`].
+///
+/// A frame is written both when it begins and when it ends,
+/// so that a recording written while the app runs has the input of the frame it crashed in.
+#[cfg(feature = "persistence")]
+#[derive(serde::Deserialize, serde::Serialize)]
+enum RecordingLine<Input, Output> {
+    Begin {
+        time: f64,
+        raw_input: Input,
+    },
+    End {
+        frame: usize,
+        platform_output: Output,
+    },
+}
+
+/// Writes an [`InputRecording`] one line at a time, as it is recorded.
+#[cfg(feature = "persistence")]
+pub(crate) struct RecordingStream {
+    writer: Box<dyn std::io::Write + Send + Sync>,
+    num_frames: usize,
+
+    /// The index and viewport of the frames that have begun but not ended.
+    unfinished: Vec<(usize, ViewportId)>,
+}
+
+#[cfg(feature = "persistence")]
+impl RecordingStream {
+    pub(crate) fn new(writer: Box<dyn std::io::Write + Send + Sync>) -> Self {
+        Self {
+            writer,
+            num_frames: 0,
+            unfinished: vec![],
+        }
+    }
+
+    fn begin_frame(&mut self, time: f64, raw_input: &RawInput) -> std::io::Result<()> {
+        self.write_line(&RecordingLine::<_, ()>::Begin { time, raw_input })?;
+        self.unfinished
+            .push((self.num_frames, raw_input.viewport_id));
+        self.num_frames += 1;
+        Ok(())
+    }
+
+    fn end_frame(
+        &mut self,
+        viewport_id: ViewportId,
+        platform_output: &PlatformOutput,
+    ) -> std::io::Result<()> {
+        // Immediate viewports run within their parent, so the frame that just ended may not be the last one:
+        if let Some(index) = self
+            .unfinished
+            .iter()
+            .rposition(|(_, id)| *id == viewport_id)
+        {
+            let (frame, _) = self.unfinished.remove(index);
+            self.write_line(&RecordingLine::<(), _>::End {
+                frame,
+                platform_output,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &impl serde::Serialize) -> std::io::Result<()> {
+        use std::io::Write as _;
+        let ron = ron::to_string(line)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        writeln!(self.writer, "{ron}")?;
+        self.writer.flush()
+    }
+}
+
+/// Where a [`Context`] puts what it records.
+pub(crate) enum InputRecorder {
+    /// Kept until [`Context::stop_recording_input`].
+    Memory(InputRecording),
+
+    /// Written out as it is recorded, see [`Context::start_recording_input_to`].
+    #[cfg(feature = "persistence")]
+    Stream(RecordingStream),
+}
+
+impl InputRecorder {
+    #[allow(clippy::unnecessary_wraps)] // Only writing to a stream can fail
+    pub(crate) fn begin_frame(&mut self, time: f64, raw_input: RawInput) -> std::io::Result<()> {
+        match self {
+            Self::Memory(recording) => {
+                recording.frames.push(RecordedFrame {
+                    time,
+                    raw_input,
+                    platform_output: None,
+                });
+                Ok(())
+            }
+            #[cfg(feature = "persistence")]
+            Self::Stream(stream) => stream.begin_frame(time, &raw_input),
+        }
+    }
+
+    #[allow(clippy::unnecessary_wraps)] // Only writing to a stream can fail
+    pub(crate) fn end_frame(
+        &mut self,
+        viewport_id: ViewportId,
+        platform_output: &PlatformOutput,
+    ) -> std::io::Result<()> {
+        match self {
+            Self::Memory(recording) => {
+                // Immediate viewports run within their parent, so the frame that just ended may not be the last one:
+                if let Some(frame) = recording.frames.iter_mut().rev().find(|frame| {
+                    frame.raw_input.viewport_id == viewport_id && frame.platform_output.is_none()
+                }) {
+                    frame.platform_output = Some(platform_output.clone());
+                }
+                Ok(())
+            }
+            #[cfg(feature = "persistence")]
+            Self::Stream(stream) => stream.end_frame(viewport_id, platform_output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn click_at(pos: Pos2) -> Vec<RawInput> {
+        let button = |pressed| Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Modifiers::default(),
+        };
+        vec![
+            RawInput {
+                events: vec![Event::PointerMoved(pos)],
+                ..Default::default()
+            },
+            RawInput {
+                events: vec![button(true)],
+                ..Default::default()
+            },
+            RawInput {
+                events: vec![button(false)],
+                ..Default::default()
+            },
+            RawInput::default(),
+        ]
+    }
+
+    #[test]
+    fn replay_reproduces_output() {
+        let app = |text: &'static str| {
+            move |ctx: &Context| {
+                CentralPanel::default().show(ctx, |ui| {
+                    if ui.button("Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = text.to_owned());
+                    }
+                });
+            }
+        };
+
+        let ctx = Context::default();
+        ctx.start_recording_input();
+        let _ = ctx.run(RawInput::default(), app("hello"));
+        for input in click_at(pos2(20.0, 16.0)) {
+            let _ = ctx.run(input, app("hello"));
+        }
+        let recording = ctx.stop_recording_input().unwrap();
+        assert!(!ctx.is_recording_input());
+        assert_eq!(recording.frames.len(), 5);
+        assert!(recording.frames.iter().any(|frame| frame
+            .platform_output
+            .as_ref()
+            .unwrap()
+            .copied_text
+            == "hello"));
+
+        let mismatches = recording.replay_and_compare(&Context::default(), app("hello"));
+        assert!(mismatches.is_empty());
+
+        let mismatches = recording.replay_and_compare(&Context::default(), app("goodbye"));
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].replayed.copied_text, "goodbye");
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn streamed_recording_survives_crash() {
+        #[derive(Clone, Default)]
+        struct SharedBuffer(std::sync::Arc<mutex::Mutex<Vec<u8>>>);
+
+        impl std::io::Write for SharedBuffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let app = |ctx: &Context| {
+            CentralPanel::default().show(ctx, |ui| {
+                if ui.button("Copy").clicked() {
+                    ui.output_mut(|o| o.copied_text = "hello".to_owned());
+                }
+            });
+        };
+        let record = |ctx: &Context| {
+            for input in click_at(pos2(20.0, 16.0)) {
+                let _ = ctx.run(input, app);
+            }
+            // Crash in the middle of a frame:
+            ctx.begin_frame(RawInput::default());
+        };
+
+        let ctx = Context::default();
+        ctx.start_recording_input();
+        record(&ctx);
+        let in_memory = ctx.stop_recording_input().unwrap();
+
+        let buffer = SharedBuffer::default();
+        let ctx = Context::default();
+        ctx.start_recording_input_stream(Box::new(buffer.clone()));
+        record(&ctx);
+        let mut text = String::from_utf8(buffer.0.lock().clone()).unwrap();
+        text.push_str("End(frame: 4, platfo");
+
+        let streamed = InputRecording::from_lines(&text).unwrap();
+        assert_eq!(streamed.frames.len(), 5);
+        assert!(streamed.frames[4].platform_output.is_none());
+        assert!(streamed == in_memory);
+    }
+}
@@ -94,6 +94,8 @@ impl Widget for &epaint::stats::PaintStats {
                 clipped_primitives,
                 vertices,
                 indices,
+                tessellation_cache_hits,
+                tessellation_cache_misses,
             } = self;
 
             ui.label("Intermediate:");
@@ -121,6 +123,17 @@ impl Widget for &epaint::stats::PaintStats {
             label(ui, indices, "indices").on_hover_text("Three 32-bit indices per triangles");
             ui.add_space(10.0);
 
+            let cached_shapes = tessellation_cache_hits + tessellation_cache_misses;
+            if 0 < cached_shapes {
+                ui.label("Tessellation cache:");
+                ui.label(format!(
+                    "{tessellation_cache_hits:6} hits   {tessellation_cache_misses:6} misses   {:3.0}% hit rate",
+                    100.0 * *tessellation_cache_hits as f32 / cached_shapes as f32
+                ))
+                .on_hover_text("Shapes whose mesh was reused from last frame");
+                ui.add_space(10.0);
+            }
+
             // ui.label("Total:");
             // ui.label(self.total().format(""));
         })
@@ -375,6 +375,7 @@
 #![cfg_attr(not(feature = "puffin"), forbid(unsafe_code))]
 
 mod animation_manager;
+mod cached_region;
 pub mod containers;
 mod context;
 mod data;
@@ -385,6 +386,7 @@ pub(crate) mod grid;
 pub mod gui_zoom;
 mod hit_test;
 mod id;
+mod input_recording;
 mod input_state;
 mod interaction;
 pub mod introspection;
@@ -399,6 +401,7 @@ pub(crate) mod placer;
 mod response;
 mod sense;
 pub mod style;
+mod style_override;
 pub mod text_selection;
 mod ui;
 pub mod util;
@@ -411,9 +414,15 @@ pub mod widgets;
 #[cfg(debug_assertions)]
 mod callstack;
 
+#[cfg(feature = "theme_files")]
+mod theme_file;
+
 #[cfg(feature = "accesskit")]
 pub use accesskit;
 
+#[cfg(feature = "theme_files")]
+pub use theme_file::ThemeError;
+
 pub use ahash;
 
 pub use epaint;
@@ -428,9 +437,11 @@ pub use emath::{
 };
 pub use epaint::{
     mutex,
-    text::{FontData, FontDefinitions, FontFamily, FontId, FontTweak},
+    text::{
+        FontData, FontDefinitions, FontFamily, FontId, FontTweak, GlyphRasterization, SystemFonts,
+    },
     textures::{TextureFilter, TextureOptions, TextureWrapMode, TexturesDelta},
-    ClippedPrimitive, ColorImage, FontImage, ImageData, Margin, Mesh, PaintCallback,
+    ClippedPrimitive, ColorImage, FontImage, Gradient, ImageData, Margin, Mesh, PaintCallback,
     PaintCallbackInfo, Rounding, Shadow, Shape, Stroke, TextureHandle, TextureId,
 };
 
@@ -455,6 +466,7 @@ pub use {
     drag_and_drop::DragAndDrop,
     grid::Grid,
     id::{Id, IdMap},
+    input_recording::{InputRecording, OutputMismatch, RecordedFrame},
     input_state::{InputState, MultiTouchInfo, PointerState},
     layers::{LayerId, Order},
     layout::*,
@@ -463,7 +475,8 @@ pub use {
     painter::Painter,
     response::{InnerResponse, Response},
     sense::Sense,
-    style::{FontSelection, Style, TextStyle, Visuals},
+    style::{FontSelection, Style, TextStyle, Theme, Visuals},
+    style_override::StyleOverride,
     text::{Galley, TextFormat},
     ui::Ui,
     viewport::*,
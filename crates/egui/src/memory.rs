@@ -4,8 +4,8 @@ use ahash::HashMap;
 use epaint::emath::TSTransform;
 
 use crate::{
-    area, vec2, EventFilter, Id, IdMap, LayerId, Order, Pos2, Rangef, RawInput, Rect, Style, Vec2,
-    ViewportId, ViewportIdMap, ViewportIdSet,
+    area, vec2, EventFilter, Id, IdMap, LayerId, Order, Pos2, Rangef, RawInput, Rect, Style, Theme,
+    Vec2, ViewportId, ViewportIdMap, ViewportIdSet,
 };
 
 // ----------------------------------------------------------------------------
@@ -169,6 +169,28 @@ pub struct Options {
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) style: std::sync::Arc<Style>,
 
+    /// The style to switch to when the system theme becomes [`Theme::Dark`].
+    ///
+    /// See [`Self::follow_system_theme`] and [`crate::Context::set_style_of`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) dark_style: std::sync::Arc<Style>,
+
+    /// The style to switch to when the system theme becomes [`Theme::Light`].
+    ///
+    /// See [`Self::follow_system_theme`] and [`crate::Context::set_style_of`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) light_style: std::sync::Arc<Style>,
+
+    /// If `true`, egui switches between the dark and light style
+    /// whenever the backend reports a new [`crate::ViewportInfo::system_theme`].
+    ///
+    /// The switch only happens when the system theme changes,
+    /// so you can still call [`crate::Context::set_style`] in between.
+    /// When you turn this on, the current style is replaced only if it doesn't match the system theme.
+    ///
+    /// Default: `false`.
+    pub follow_system_theme: bool,
+
     /// Global zoom factor of the UI.
     ///
     /// This is used to calculate the `pixels_per_point`
@@ -197,6 +219,27 @@ pub struct Options {
     /// Controls the tessellator.
     pub tessellation_options: epaint::TessellationOptions,
 
+    /// If `true`, [`crate::Context::tessellate`] reuses the meshes of shapes that didn't change since last frame.
+    ///
+    /// This saves CPU time when most of the screen stays the same (e.g. a dashboard),
+    /// at the cost of hashing every shape and keeping last frame's meshes in memory.
+    /// See [`epaint::TessellationCache`].
+    ///
+    /// The hit rate is shown in [`crate::Context::inspection_ui`].
+    ///
+    /// Default: `false`.
+    pub cache_tessellation: bool,
+
+    /// If `true`, egui compares the shapes of each frame with the previous frame
+    /// to find what part of the viewport changed, see [`crate::FullOutput::damage`].
+    ///
+    /// A renderer can use this to only repaint what changed, saving power.
+    /// `eframe` does this with the glow renderer when the platform reports the age of the back buffer,
+    /// but then clears the window after `eframe::App::update` instead of before it.
+    ///
+    /// Default: `false`.
+    pub track_damage: bool,
+
     /// If any widget moves or changes id, repaint everything.
     ///
     /// It is recommended you keep this OFF, because
@@ -233,9 +276,14 @@ impl Default for Options {
     fn default() -> Self {
         Self {
             style: Default::default(),
+            dark_style: std::sync::Arc::new(Theme::Dark.default_style()),
+            light_style: std::sync::Arc::new(Theme::Light.default_style()),
+            follow_system_theme: false,
             zoom_factor: 1.0,
             zoom_with_keyboard: true,
             tessellation_options: Default::default(),
+            cache_tessellation: false,
+            track_damage: false,
             repaint_on_widget_change: false,
             screen_reader: false,
             preload_font_glyphs: true,
@@ -245,13 +293,26 @@ impl Default for Options {
 }
 
 impl Options {
+    /// The style to use for the given theme, see [`Self::follow_system_theme`].
+    pub(crate) fn style_of(&self, theme: Theme) -> &std::sync::Arc<Style> {
+        match theme {
+            Theme::Dark => &self.dark_style,
+            Theme::Light => &self.light_style,
+        }
+    }
+
     /// Show the options in the ui.
     pub fn ui(&mut self, ui: &mut crate::Ui) {
         let Self {
             style,          // covered above
+            dark_style: _,  // only the style in use is shown
+            light_style: _, // only the style in use is shown
+            follow_system_theme,
             zoom_factor: _, // TODO(emilk)
             zoom_with_keyboard,
             tessellation_options,
+            cache_tessellation,
+            track_damage,
             repaint_on_widget_change,
             screen_reader: _, // needs to come from the integration
             preload_font_glyphs: _,
@@ -274,6 +335,11 @@ impl Options {
                 );
 
                 ui.checkbox(warn_on_id_clash, "Warn if two widgets have the same Id");
+
+                ui.checkbox(
+                    follow_system_theme,
+                    "Follow the dark/light theme of the system",
+                );
             });
 
         use crate::containers::*;
@@ -287,6 +353,10 @@ impl Options {
             .default_open(false)
             .show(ui, |ui| {
                 tessellation_options.ui(ui);
+                ui.checkbox(cache_tessellation, "Reuse the meshes of unchanged shapes")
+                    .on_hover_text("Cache the tessellation of shapes from one frame to the next");
+                ui.checkbox(track_damage, "Track what changed since last frame")
+                    .on_hover_text("So that renderers can repaint only that part of the screen");
                 ui.vertical_centered(|ui| {
                     crate::reset_button(ui, tessellation_options, "Reset paint settings");
                 });
@@ -637,6 +707,19 @@ impl Memory {
         self.areas().layer_id_at(pos, &self.layer_transforms)
     }
 
+    /// The top-most modal layer of the previous frame, if any.
+    ///
+    /// See [`crate::Modal`].
+    pub fn top_modal_layer(&self) -> Option<LayerId> {
+        self.areas().top_modal_layer()
+    }
+
+    /// Can the user interact with widgets in this layer,
+    /// or is it blocked by a [`crate::Modal`] on top of it?
+    pub fn allows_interaction(&self, layer_id: LayerId) -> bool {
+        self.areas().allows_interaction(layer_id)
+    }
+
     /// An iterator over all layers. Back-to-front. Top is last.
     pub fn layer_ids(&self) -> impl ExactSizeIterator<Item = LayerId> + '_ {
         self.areas().order().iter().copied()
@@ -887,6 +970,15 @@ pub struct Areas {
     /// So if you close three windows and then reopen them all in one frame,
     /// they will all be sent to the top, but keep their previous internal order.
     wants_to_be_on_top: ahash::HashSet<LayerId>,
+
+    /// Layers of [`crate::Modal`]s shown this frame.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    modal_layers_this_frame: Vec<LayerId>,
+
+    /// The top-most [`crate::Modal`] layer of the previous frame.
+    /// Only this layer, and the layers above it, may be interacted with.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    top_modal_layer: Option<LayerId>,
 }
 
 impl Areas {
@@ -921,12 +1013,18 @@ impl Areas {
     }
 
     /// Top-most layer at the given position.
+    ///
+    /// While a modal layer is open, its backdrop covers everything below it,
+    /// so that is where the top modal layer is found.
     pub fn layer_id_at(
         &self,
         pos: Pos2,
         layer_transforms: &HashMap<LayerId, TSTransform>,
     ) -> Option<LayerId> {
         for layer in self.order.iter().rev() {
+            if !self.allows_interaction(*layer) {
+                break; // Behind the backdrop of the top modal layer
+            }
             if self.is_visible(layer) {
                 if let Some(state) = self.areas.get(&layer.id) {
                     let mut rect = state.rect();
@@ -942,7 +1040,7 @@ impl Areas {
                 }
             }
         }
-        None
+        self.top_modal_layer
     }
 
     pub fn visible_last_frame(&self, layer_id: &LayerId) -> bool {
@@ -986,12 +1084,40 @@ impl Areas {
             .copied()
     }
 
+    /// Mark this layer as modal for the current frame.
+    ///
+    /// From the next frame on, layers below the top-most modal layer can not be interacted with.
+    pub fn set_modal_layer(&mut self, layer_id: LayerId) {
+        self.modal_layers_this_frame.push(layer_id);
+    }
+
+    /// The top-most modal layer of the previous frame, if any.
+    pub fn top_modal_layer(&self) -> Option<LayerId> {
+        self.top_modal_layer
+    }
+
+    /// Can the user interact with widgets in this layer,
+    /// or is it blocked by a modal layer on top of it?
+    pub fn allows_interaction(&self, layer_id: LayerId) -> bool {
+        let Some(modal_layer) = self.top_modal_layer else {
+            return true;
+        };
+        let index_of = |layer_id: LayerId| self.order.iter().position(|x| *x == layer_id);
+        match (index_of(layer_id), index_of(modal_layer)) {
+            (Some(layer_index), Some(modal_index)) => modal_index <= layer_index,
+            // e.g. a panel, which is not an area:
+            _ => layer_id == modal_layer || modal_layer.order < layer_id.order,
+        }
+    }
+
     pub(crate) fn end_frame(&mut self) {
         let Self {
             visible_last_frame,
             visible_current_frame,
             order,
             wants_to_be_on_top,
+            modal_layers_this_frame,
+            top_modal_layer,
             ..
         } = self;
 
@@ -999,6 +1125,10 @@ impl Areas {
         visible_current_frame.clear();
         order.sort_by_key(|layer| (layer.order, wants_to_be_on_top.contains(layer)));
         wants_to_be_on_top.clear();
+
+        *top_modal_layer = modal_layers_this_frame
+            .drain(..)
+            .max_by_key(|layer| order.iter().position(|x| x == layer));
     }
 }
 
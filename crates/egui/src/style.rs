@@ -680,6 +680,48 @@ impl Default for TextCursorStyle {
     }
 }
 
+/// Dark or Light theme.
+///
+/// See [`crate::Options::follow_system_theme`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Theme {
+    /// Dark mode: light text on a dark background.
+    Dark,
+
+    /// Light mode: dark text on a light background.
+    Light,
+}
+
+impl Theme {
+    /// Get the egui visuals corresponding to this theme.
+    ///
+    /// Use with [`crate::Context::set_visuals`].
+    pub fn egui_visuals(self) -> Visuals {
+        match self {
+            Self::Dark => Visuals::dark(),
+            Self::Light => Visuals::light(),
+        }
+    }
+
+    /// The default [`Style`] of this theme.
+    pub fn default_style(self) -> Style {
+        Style {
+            visuals: self.egui_visuals(),
+            ..Default::default()
+        }
+    }
+
+    /// [`Self::Dark`] if `dark_mode` is `true`, otherwise [`Self::Light`].
+    pub fn from_dark_mode(dark_mode: bool) -> Self {
+        if dark_mode {
+            Self::Dark
+        } else {
+            Self::Light
+        }
+    }
+}
+
 /// Controls the visual style (colors etc) of egui.
 ///
 /// You can change the visuals of a [`Ui`] with [`Ui::visuals_mut`]
@@ -2276,6 +2318,7 @@ impl Widget for &mut crate::Frame {
             outer_margin,
             rounding,
             shadow,
+            backdrop_blur,
             fill,
             stroke,
         } = self;
@@ -2301,6 +2344,14 @@ impl Widget for &mut crate::Frame {
                 ui.add(shadow);
                 ui.end_row();
 
+                ui.label("Backdrop blur");
+                ui.add(
+                    DragValue::new(backdrop_blur)
+                        .speed(1.0)
+                        .clamp_range(0.0..=100.0),
+                );
+                ui.end_row();
+
                 ui.label("Fill");
                 ui.color_edit_button_srgba(fill);
                 ui.end_row();
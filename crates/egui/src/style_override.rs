@@ -0,0 +1,171 @@
+//! Changing parts of the [`Style`] of a [`crate::Ui`] and its children, see [`crate::Ui::push_style_override`].
+
+use std::{hash::Hash, sync::Arc};
+
+use crate::{util::cache::FrameCache, Context, Style, TextStyle};
+
+/// A change to part of a [`Style`], e.g. a single color or the size of one [`TextStyle`].
+///
+/// Push it onto a [`Ui`](crate::Ui) with [`Ui::push_style_override`](crate::Ui::push_style_override)
+/// and it applies to that [`Ui`](crate::Ui) and all its children.
+/// Overrides pushed later are applied on top of earlier ones, like in CSS.
+///
+/// You can also give an override a name with [`Context::set_style_class`],
+/// and then opt into it with e.g. [`crate::Button::class`] or [`crate::Label::class`].
+///
+/// Each override has a `key` that identifies what it does.
+/// egui uses it to reuse the resulting [`Style`] from one frame to the next,
+/// so two overrides with the same key must make the same change,
+/// e.g. by including any color or size they set in the key.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// let red = egui::Color32::from_rgb(200, 40, 40);
+/// ui.push_style_override(&egui::StyleOverride::new(("inactive_bg_fill", red), move |style| {
+///     style.visuals.widgets.inactive.weak_bg_fill = red;
+/// }));
+/// ui.button("This button is red, and so is any button in a child ui");
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct StyleOverride {
+    key: u64,
+    apply: Arc<dyn Fn(&mut Style) + Send + Sync>,
+}
+
+impl std::fmt::Debug for StyleOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StyleOverride")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StyleOverride {
+    /// Change the style with `apply`.
+    ///
+    /// `key` must identify the change, including any values it sets.
+    pub fn new(key: impl Hash, apply: impl Fn(&mut Style) + Send + Sync + 'static) -> Self {
+        Self {
+            key: crate::util::hash(key),
+            apply: Arc::new(apply),
+        }
+    }
+
+    /// Change the font size of one [`TextStyle`].
+    ///
+    /// Does nothing if the style has no font for that [`TextStyle`].
+    pub fn text_size(text_style: TextStyle, size: f32) -> Self {
+        let key = ("text_size", text_style.clone(), size.to_bits());
+        Self::new(key, move |style| {
+            if let Some(font_id) = style.text_styles.get_mut(&text_style) {
+                font_id.size = size;
+            }
+        })
+    }
+
+    /// First apply this override, then `other`.
+    pub fn then(self, other: Self) -> Self {
+        Self {
+            key: crate::util::hash((self.key, other.key)),
+            apply: Arc::new(move |style| {
+                self.apply(style);
+                other.apply(style);
+            }),
+        }
+    }
+
+    /// Make the change to `style`.
+    pub fn apply(&self, style: &mut Style) {
+        (self.apply)(style);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A [`Style`] with a [`StyleOverride`] applied to it.
+#[derive(Clone)]
+struct OverriddenStyle {
+    /// We look up styles by the address of the base style,
+    /// so we keep it alive to make sure no other style can get the same address.
+    base: Arc<Style>,
+    style: Arc<Style>,
+}
+
+type OverriddenStyleCache = FrameCache<OverriddenStyle, ()>;
+
+/// `base` with `style_override` applied to it.
+///
+/// The result is cached, so that the same [`Ui`](crate::Ui) gets the same [`Arc<Style>`] each frame,
+/// and its children can share it without copying the [`Style`].
+pub(crate) fn apply_override(
+    ctx: &Context,
+    base: &Arc<Style>,
+    style_override: &StyleOverride,
+) -> Arc<Style> {
+    let key = (Arc::as_ptr(base) as usize, style_override.key);
+    ctx.memory_mut(|mem| {
+        let cache = mem.caches.cache::<OverriddenStyleCache>();
+        if let Some(cached) = cache.get_cached(key) {
+            if Arc::ptr_eq(&cached.base, base) {
+                return cached.style.clone();
+            }
+        }
+
+        let mut style = (**base).clone();
+        style_override.apply(&mut style);
+        let style = Arc::new(style);
+        cache.insert(
+            key,
+            OverriddenStyle {
+                base: base.clone(),
+                style: style.clone(),
+            },
+        );
+        style
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn overrides_cascade_without_copying() {
+        let ctx = Context::default();
+        ctx.set_style_class(
+            "big",
+            StyleOverride::text_size(TextStyle::Body, 30.0)
+                .then(StyleOverride::text_size(TextStyle::Button, 30.0)),
+        );
+        let red = StyleOverride::new("red", |style| {
+            style.visuals.widgets.inactive.weak_bg_fill = Color32::RED;
+        });
+
+        let mut styles = vec![];
+        for _ in 0..2 {
+            let _ = ctx.run(RawInput::default(), |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.push_style_override(&red);
+                    ui.scope(|ui| {
+                        assert_eq!(ui.visuals().widgets.inactive.weak_bg_fill, Color32::RED);
+                        ui.push_style_class("big");
+                        assert_eq!(ui.style().text_styles[&TextStyle::Body].size, 30.0);
+                        assert_eq!(ui.visuals().widgets.inactive.weak_bg_fill, Color32::RED);
+                        styles.push(ui.style().clone());
+                    });
+                    assert_eq!(ui.style().text_styles[&TextStyle::Body].size, 12.5);
+
+                    let button = ui.add(Button::new("Big").class("big"));
+                    assert!(button.rect.height() > 30.0);
+                    assert_eq!(ui.style().text_styles[&TextStyle::Button].size, 12.5);
+                });
+            });
+        }
+
+        assert!(
+            std::sync::Arc::ptr_eq(&styles[0], &styles[1]),
+            "The resolved style should be reused from last frame"
+        );
+    }
+}
@@ -24,6 +24,26 @@ pub fn paint_text_selection(
 
     for ri in min.row..=max.row {
         let row = &galley.rows[ri];
+
+        if row.has_rtl() {
+            // The selected characters may be spread out over the row:
+            let start = if ri == min.row { min.column } else { 0 };
+            let end = if ri == max.row {
+                max.column
+            } else {
+                row.char_count_excluding_newline()
+            };
+            for x_range in selected_x_ranges(row, start..end) {
+                let rect = Rect::from_x_y_ranges(x_range, row.min_y()..=row.max_y())
+                    .translate(galley_pos.to_vec2());
+                let shape_idx = painter.rect_filled(rect, 0.0, color);
+                if let Some(out_shaped_idx) = &mut out_shaped_idx {
+                    out_shaped_idx.push(shape_idx);
+                }
+            }
+            continue;
+        }
+
         let left = if ri == min.row {
             row.x_offset(min.column)
         } else {
@@ -50,6 +70,28 @@ pub fn paint_text_selection(
     }
 }
 
+/// The x ranges covered by the given characters of a row with right-to-left text, left to right.
+fn selected_x_ranges(row: &epaint::text::Row, columns: std::ops::Range<usize>) -> Vec<Rangef> {
+    let mut x_ranges: Vec<Rangef> = row
+        .glyphs
+        .get(columns)
+        .unwrap_or_default()
+        .iter()
+        .map(|glyph| Rangef::new(glyph.pos.x, glyph.max_x()))
+        .collect();
+    x_ranges.sort_by(|a, b| a.min.total_cmp(&b.min));
+
+    // Merge adjacent ranges:
+    let mut merged: Vec<Rangef> = vec![];
+    for x_range in x_ranges {
+        match merged.last_mut() {
+            Some(last) if x_range.min <= last.max + 0.5 => last.max = last.max.max(x_range.max),
+            _ => merged.push(x_range),
+        }
+    }
+    merged
+}
+
 /// Paint one end of the selection, e.g. the primary cursor.
 ///
 /// This will never blink.
@@ -105,3 +147,49 @@ pub fn paint_text_cursor(
         paint_cursor_end(painter, ui.visuals(), primary_cursor_rect);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use epaint::text::{Glyph, Row};
+
+    use super::*;
+
+    #[test]
+    fn selection_of_right_to_left_text() {
+        // "ab" followed by the Hebrew "אבג", which is shown right-to-left as "abגבא":
+        let glyphs = [
+            ('a', 0, 0.0),
+            ('b', 0, 10.0),
+            ('א', 1, 40.0),
+            ('ב', 1, 30.0),
+            ('ג', 1, 20.0),
+        ]
+        .map(|(chr, bidi_level, x)| Glyph {
+            chr,
+            pos: pos2(x, 0.0),
+            ascent: 10.0,
+            size: vec2(10.0, 10.0),
+            uv_rect: Default::default(),
+            section_index: 0,
+            bidi_level,
+        })
+        .to_vec();
+        let row = Row {
+            section_index_at_start: 0,
+            glyphs,
+            shaped_glyphs: vec![],
+            rect: Rect::from_min_max(pos2(0.0, 0.0), pos2(50.0, 10.0)),
+            visuals: Default::default(),
+            ends_with_newline: false,
+        };
+
+        // "bא" is shown in two places, with "גב" in between:
+        assert_eq!(
+            selected_x_ranges(&row, 1..3),
+            [Rangef::new(10.0, 20.0), Rangef::new(40.0, 50.0)]
+        );
+        assert_eq!(selected_x_ranges(&row, 2..4), [Rangef::new(30.0, 50.0)]);
+        assert_eq!(selected_x_ranges(&row, 0..5), [Rangef::new(0.0, 50.0)]);
+        assert!(selected_x_ranges(&row, 2..2).is_empty());
+    }
+}
@@ -0,0 +1,648 @@
+//! Loading a [`Style`] from a theme file, see [`Style::from_theme`].
+//!
+//! A theme only lists what differs from [`Visuals::dark`] or [`Visuals::light`].
+//! We deserialize it straight into a [`Style`], and fill in whatever it leaves out from the base style.
+//! The base style is kept as a [`serde_json::Value`], because unlike [`ron::Value`] it remembers enum variants.
+
+use std::fmt::Display;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, Visitor};
+
+use crate::{Context, Style, Visuals};
+
+/// Why a theme could not be loaded, see [`Style::from_theme`].
+#[derive(Debug)]
+pub enum ThemeError {
+    /// The theme file could not be read.
+    Io(std::io::Error),
+
+    /// The theme is not valid RON, or doesn't describe a [`Style`].
+    Parse(String),
+}
+
+impl Display for ThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Failed to read theme: {err}"),
+            Self::Parse(err) => write!(f, "Failed to parse theme: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ThemeError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// What a theme starts from.
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+enum Base {
+    #[default]
+    Dark,
+    Light,
+}
+
+#[derive(serde::Deserialize)]
+struct Header {
+    #[serde(default)]
+    base: Base,
+}
+
+impl Style {
+    /// Parse a theme in [RON](https://github.com/ron-rs/ron) format.
+    ///
+    /// A theme starts from the default [`Style`] with either [`Visuals::dark`] (the default) or [`Visuals::light`],
+    /// and changes any of its fields, at any depth:
+    ///
+    /// ```
+    /// let style = egui::Style::from_theme(r#"
+    ///     (
+    ///         base: Light,
+    ///         style: (
+    ///             spacing: (item_spacing: (x: 10.0, y: 6.0)),
+    ///             visuals: (
+    ///                 hyperlink_color: ((0, 90, 200, 255)),
+    ///                 widgets: (hovered: (expansion: 2.0)),
+    ///             ),
+    ///         ),
+    ///     )
+    /// "#).unwrap();
+    ///
+    /// assert_eq!(style.spacing.item_spacing, egui::vec2(10.0, 6.0));
+    /// assert_eq!(style.visuals.widgets.hovered.expansion, 2.0);
+    /// assert_eq!(style.visuals.panel_fill, egui::Visuals::light().panel_fill);
+    /// ```
+    ///
+    /// Maps (like [`Style::text_styles`]) are merged entry by entry, so a theme can change one text style
+    /// and keep the others. Enums are replaced as a whole.
+    ///
+    /// # Errors
+    /// [`ThemeError::Parse`] if the theme isn't valid, including if it has a misspelled field.
+    pub fn from_theme(ron: &str) -> Result<Self, ThemeError> {
+        crate::profile_function!();
+
+        let Header { base } = ron::from_str(ron).map_err(parse_error)?;
+        let base = Self {
+            visuals: match base {
+                Base::Dark => Visuals::dark(),
+                Base::Light => Visuals::light(),
+            },
+            ..Default::default()
+        };
+        let base = serde_json::to_value(&base).map_err(parse_error)?;
+
+        let mut deserializer = ron::Deserializer::from_str(ron).map_err(parse_error)?;
+        let style = deserializer
+            .deserialize_struct("Theme", &["base", "style"], ThemeVisitor { base })
+            .and_then(|style| deserializer.end().map(|()| style))
+            .map_err(|err| parse_error(deserializer.span_error(err)))?;
+        Ok(style)
+    }
+}
+
+impl Context {
+    /// Use the [`Style`] described by a theme, see [`Style::from_theme`].
+    ///
+    /// # Errors
+    /// [`ThemeError::Parse`] if the theme isn't valid, in which case the style is left unchanged.
+    pub fn load_theme(&self, ron: &str) -> Result<(), ThemeError> {
+        self.set_style(Style::from_theme(ron)?);
+        Ok(())
+    }
+
+    /// Use the [`Style`] described by a theme file, see [`Style::from_theme`].
+    ///
+    /// # Errors
+    /// If the file can't be read or isn't a valid theme, in which case the style is left unchanged.
+    pub fn load_theme_file(&self, path: impl AsRef<std::path::Path>) -> Result<(), ThemeError> {
+        self.load_theme(&std::fs::read_to_string(path)?)
+    }
+}
+
+fn parse_error(err: impl Display) -> ThemeError {
+    ThemeError::Parse(err.to_string())
+}
+
+// ----------------------------------------------------------------------------
+
+/// Visits the top level of a theme file.
+struct ThemeVisitor {
+    base: serde_json::Value,
+}
+
+impl<'de> Visitor<'de> for ThemeVisitor {
+    type Value = Style;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a theme")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Style, A::Error> {
+        let mut style = None;
+        while let Some(key) = map.next_key_seed(FieldName)? {
+            match key.as_str() {
+                "base" => {
+                    map.next_value::<de::IgnoredAny>()?; // Already read
+                }
+                "style" => {
+                    style = Some(map.next_value_seed(Merge {
+                        seed: std::marker::PhantomData::<Style>,
+                        base: Some(&self.base),
+                    })?);
+                }
+                _ => return Err(de::Error::unknown_field(&key, &["base", "style"])),
+            }
+        }
+        match style {
+            Some(style) => Ok(style),
+            None => serde_json::from_value(self.base).map_err(de::Error::custom),
+        }
+    }
+}
+
+/// Reads the name of a struct field.
+struct FieldName;
+
+impl<'de> DeserializeSeed<'de> for FieldName {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<String, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldName {
+    type Value = String;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a field name")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<String, E> {
+        Ok(name.to_owned())
+    }
+}
+
+/// Deserializes `seed`, taking the fields of structs and the entries of maps that are missing from `base`.
+struct Merge<'b, S> {
+    seed: S,
+    base: Option<&'b serde_json::Value>,
+}
+
+impl<'de, 'b, S: DeserializeSeed<'de>> DeserializeSeed<'de> for Merge<'b, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.seed.deserialize(MergeDeserializer {
+            deserializer,
+            base: self.base,
+        })
+    }
+}
+
+/// Passes everything on to `deserializer`, except that structs and maps are merged with `base`.
+struct MergeDeserializer<'b, D> {
+    deserializer: D,
+    base: Option<&'b serde_json::Value>,
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+                self.deserializer.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'b, D: Deserializer<'de>> Deserializer<'de> for MergeDeserializer<'b, D> {
+    type Error = D::Error;
+
+    forward!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    );
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        match self.base {
+            Some(serde_json::Value::Object(base)) => {
+                self.deserializer.deserialize_map(StructVisitor {
+                    visitor,
+                    base,
+                    fields: None,
+                })
+            }
+            _ => self.deserializer.deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        match self.base {
+            Some(serde_json::Value::Object(base)) => self.deserializer.deserialize_struct(
+                name,
+                fields,
+                StructVisitor {
+                    visitor,
+                    base,
+                    fields: Some(fields),
+                },
+            ),
+            _ => self.deserializer.deserialize_struct(name, fields, visitor),
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
+    }
+}
+
+/// Visits a struct (with its `fields`) or a map (without).
+struct StructVisitor<'b, V> {
+    visitor: V,
+    base: &'b serde_json::Map<String, serde_json::Value>,
+    fields: Option<&'static [&'static str]>,
+}
+
+impl<'de, 'b, V: Visitor<'de>> Visitor<'de> for StructVisitor<'b, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_map(StructMap {
+            map,
+            base: self.base,
+            fields: self.fields,
+            given: vec![],
+            missing: None,
+            value: None,
+        })
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_seq(seq)
+    }
+}
+
+/// The fields of a struct in the theme, followed by the fields it left out, taken from the base.
+///
+/// Likewise for the entries of a map.
+struct StructMap<'b, A> {
+    map: A,
+    base: &'b serde_json::Map<String, serde_json::Value>,
+
+    /// The fields of a struct, or `None` for a map.
+    fields: Option<&'static [&'static str]>,
+
+    /// The fields (or keys) given in the theme.
+    given: Vec<String>,
+
+    /// Once we've read all fields given in the theme: the ones left to take from the base.
+    missing: Option<Vec<&'b str>>,
+
+    /// The base value of the field whose value is next.
+    value: Option<&'b serde_json::Value>,
+}
+
+impl<'de, 'b, A: MapAccess<'de>> MapAccess<'de> for StructMap<'b, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        let mut seed = Some(seed);
+        if self.missing.is_none() {
+            if let Some(fields) = self.fields {
+                if let Some(name) = self.map.next_key_seed(FieldName)? {
+                    if !fields.contains(&name.as_str()) {
+                        return Err(de::Error::unknown_field(&name, fields));
+                    }
+                    self.value = self.base.get(&name);
+                    self.given.push(name.clone());
+                    return take_seed(&mut seed)
+                        .deserialize(de::value::StringDeserializer::new(name))
+                        .map(Some);
+                }
+            } else {
+                // The key of a map entry is deserialized as it is, but we need its name to find it in the base:
+                let mut name = None;
+                let key = self.map.next_key_seed(RecordName {
+                    seed: TakeSeed(&mut seed),
+                    name: &mut name,
+                })?;
+                if let Some(key) = key {
+                    self.value = name.as_ref().and_then(|name| self.base.get(name));
+                    self.given.extend(name);
+                    return Ok(Some(key));
+                }
+            }
+
+            let given = &self.given;
+            self.missing = Some(
+                self.base
+                    .keys()
+                    .map(String::as_str)
+                    .filter(|name| !given.iter().any(|given| given == name))
+                    .collect(),
+            );
+        }
+
+        let missing = self.missing.as_mut().expect("Set above");
+        if let Some(name) = missing.pop() {
+            self.value = self.base.get(name);
+            take_seed(&mut seed)
+                .deserialize(de::value::StrDeserializer::new(name))
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, A::Error> {
+        let base = self.value.take();
+        if self.missing.is_none() {
+            self.map.next_value_seed(Merge { seed, base })
+        } else {
+            let base = base.cloned().unwrap_or_default();
+            seed.deserialize(base).map_err(de::Error::custom)
+        }
+    }
+}
+
+fn take_seed<S>(seed: &mut Option<S>) -> S {
+    seed.take().expect("A seed is only used once")
+}
+
+/// Deserializes the seed in the slot, leaving it there if it isn't used.
+struct TakeSeed<'s, S>(&'s mut Option<S>);
+
+impl<'de, 's, S: DeserializeSeed<'de>> DeserializeSeed<'de> for TakeSeed<'s, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        take_seed(self.0).deserialize(deserializer)
+    }
+}
+
+/// Deserializes `seed`, noting the name of the string, identifier or enum variant it reads.
+///
+/// Also wraps the [`Deserializer`], [`Visitor`] and [`de::EnumAccess`] on the way to that name.
+struct RecordName<'n, T> {
+    seed: T,
+    name: &'n mut Option<String>,
+}
+
+impl<'de, 'n, S: DeserializeSeed<'de>> DeserializeSeed<'de> for RecordName<'n, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.seed.deserialize(RecordNameDeserializer {
+            deserializer,
+            name: self.name,
+        })
+    }
+}
+
+impl<'de, 'n, V: Visitor<'de>> Visitor<'de> for RecordName<'n, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.seed.expecting(f)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<V::Value, E> {
+        *self.name = Some(v.to_owned());
+        self.seed.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<V::Value, E> {
+        *self.name = Some(v.to_owned());
+        self.seed.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<V::Value, E> {
+        *self.name = Some(v.clone());
+        self.seed.visit_string(v)
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.seed.visit_enum(RecordName {
+            seed: data,
+            name: self.name,
+        })
+    }
+}
+
+impl<'de, 'n, A: de::EnumAccess<'de>> de::EnumAccess<'de> for RecordName<'n, A> {
+    type Error = A::Error;
+    type Variant = A::Variant;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, A::Variant), A::Error> {
+        self.seed.variant_seed(RecordName {
+            seed,
+            name: self.name,
+        })
+    }
+}
+
+struct RecordNameDeserializer<'n, D> {
+    deserializer: D,
+    name: &'n mut Option<String>,
+}
+
+impl<'de, 'n, D: Deserializer<'de>> Deserializer<'de> for RecordNameDeserializer<'n, D> {
+    type Error = D::Error;
+
+    forward!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_ignored_any(),
+    );
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        let name = self.name;
+        self.deserializer.deserialize_str(RecordName {
+            seed: visitor,
+            name,
+        })
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        let name = self.name;
+        self.deserializer.deserialize_string(RecordName {
+            seed: visitor,
+            name,
+        })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        let name = self.name;
+        self.deserializer.deserialize_identifier(RecordName {
+            seed: visitor,
+            name,
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        enum_name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let name = self.name;
+        self.deserializer.deserialize_enum(
+            enum_name,
+            variants,
+            RecordName {
+                seed: visitor,
+                name,
+            },
+        )
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn theme_overrides_base() {
+        let style = Style::from_theme("()").unwrap();
+        assert_eq!(style, Style::default());
+
+        let style = Style::from_theme(
+            "(
+                style: (
+                    visuals: (
+                        window_rounding: (nw: 2.0),
+                        widgets: (hovered: (bg_stroke: (width: 3.0))),
+                    ),
+                    text_styles: { Body: (size: 20.0, family: Monospace) },
+                ),
+                base: Light,
+            )",
+        )
+        .unwrap();
+        let mut expected = Style {
+            visuals: Visuals::light(),
+            ..Default::default()
+        };
+        expected.visuals.window_rounding.nw = 2.0;
+        expected.visuals.widgets.hovered.bg_stroke.width = 3.0;
+        expected
+            .text_styles
+            .insert(TextStyle::Body, FontId::monospace(20.0));
+        assert_eq!(style, expected);
+
+        let err = Style::from_theme("(style: (visuals: (hyperlink_colour: ((0, 0, 0, 0)))))");
+        assert!(matches!(err, Err(ThemeError::Parse(_))));
+    }
+
+    #[test]
+    fn theme_merges_maps() {
+        let style = Style::from_theme(
+            r#"(
+                style: (
+                    text_styles: {
+                        Body: (size: 20.0),
+                        Name("Subheading"): (size: 16.0, family: Proportional),
+                    },
+                ),
+            )"#,
+        )
+        .unwrap();
+
+        let default = Style::default();
+        assert_eq!(
+            TextStyle::Body.resolve(&style),
+            FontId::proportional(20.0),
+            "Entries keep the fields they leave out"
+        );
+        assert_eq!(
+            TextStyle::Heading.resolve(&style),
+            TextStyle::Heading.resolve(&default)
+        );
+        assert_eq!(
+            TextStyle::Name("Subheading".into()).resolve(&style),
+            FontId::proportional(16.0)
+        );
+        assert_eq!(style.text_styles.len(), default.text_styles.len() + 1);
+    }
+}
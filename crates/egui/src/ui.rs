@@ -181,6 +181,40 @@ impl Ui {
         self.style = self.ctx().style();
     }
 
+    /// Change part of the style of this [`Ui`] and its subsequent children.
+    ///
+    /// Unlike [`Self::style_mut`], this doesn't copy the [`Style`] each frame:
+    /// the result is shared by all children and reused next frame.
+    /// See [`StyleOverride`].
+    pub fn push_style_override(&mut self, style_override: &StyleOverride) {
+        self.style = crate::style_override::apply_override(self.ctx(), &self.style, style_override);
+    }
+
+    /// Apply the [`StyleOverride`] with this name to this [`Ui`] and its subsequent children.
+    ///
+    /// Does nothing if there is no such class, see [`Context::set_style_class`].
+    pub fn push_style_class(&mut self, class: &str) {
+        if let Some(style_override) = self.ctx().style_class(class) {
+            self.push_style_override(&style_override);
+        }
+    }
+
+    /// Apply the [`StyleOverride`] with this name while adding some contents,
+    /// then go back to the previous style.
+    ///
+    /// Unlike [`Self::scope`], this doesn't create a child [`Ui`].
+    pub fn with_style_class<R>(
+        &mut self,
+        class: &str,
+        add_contents: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let style = self.style.clone();
+        self.push_style_class(class);
+        let result = add_contents(self);
+        self.style = style;
+        result
+    }
+
     /// The current spacing options for this [`Ui`].
     /// Short for `ui.style().spacing`.
     #[inline]
@@ -1779,6 +1813,45 @@ impl Ui {
         self.scope_dyn(Box::new(add_contents), Id::new("child"))
     }
 
+    /// Create a child Ui whose painted shapes are reused on the next frame,
+    /// without calling `add_contents`, as long as nothing has changed.
+    ///
+    /// This can save a lot of CPU for large regions that rarely change, like a long static report.
+    ///
+    /// `add_contents` is called again when:
+    /// * `key` changes. Hash everything the contents depends on into it.
+    /// * The region moves or changes clip rectangle, or the [`Style`] changes.
+    /// * The pointer is over the region, a widget in it has focus or is being dragged,
+    ///   or there is any input other than pointer movement (key presses, clicks, scrolling, …).
+    /// * The contents requested a repaint last time, e.g. because of an animation.
+    ///
+    /// Widgets in a reused region are still there for hit testing,
+    /// but they are not shown to screen readers.
+    /// Only shapes painted to the layer of this [`Ui`] are reused,
+    /// so don't show any popups or tooltips in the region.
+    ///
+    /// Returns `None` if the contents was reused.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// let report = vec!["A lot".to_owned(), "of rows".to_owned()];
+    /// let report_version = 1;
+    /// ui.cached("report", report_version, |ui| {
+    ///     for row in &report {
+    ///         ui.label(row);
+    ///     }
+    /// });
+    /// # });
+    /// ```
+    pub fn cached<R>(
+        &mut self,
+        id_source: impl Hash,
+        key: impl Hash,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> InnerResponse<Option<R>> {
+        crate::cached_region::show_cached(self, id_source, key, add_contents)
+    }
+
     fn scope_dyn<'c, R>(
         &mut self,
         add_contents: Box<dyn FnOnce(&mut Ui) -> R + 'c>,
@@ -74,6 +74,32 @@ impl<Value, Computer> FrameCache<Value, Computer> {
     }
 }
 
+impl<Value, Computer> FrameCache<Value, Computer> {
+    /// Get from cache (if the same key was used last frame or this frame),
+    /// without computing anything.
+    ///
+    /// Use this together with [`Self::insert`] for values that are computed elsewhere.
+    pub fn get_cached<Key>(&mut self, key: Key) -> Option<&Value>
+    where
+        Key: std::hash::Hash,
+    {
+        let hash = crate::util::hash(key);
+        let cached = self.cache.get_mut(&hash)?;
+        cached.0 = self.generation;
+        Some(&cached.1)
+    }
+
+    /// Store a value that was computed elsewhere,
+    /// replacing any value already in the cache for the same key.
+    pub fn insert<Key>(&mut self, key: Key, value: Value)
+    where
+        Key: std::hash::Hash,
+    {
+        let hash = crate::util::hash(key);
+        self.cache.insert(hash, (self.generation, value));
+    }
+}
+
 #[allow(clippy::len_without_is_empty)]
 pub trait CacheTrait: 'static + Send + Sync {
     /// Call once per frame to evict cache.
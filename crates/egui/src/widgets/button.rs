@@ -34,6 +34,7 @@ pub struct Button<'a> {
     min_size: Vec2,
     rounding: Option<Rounding>,
     selected: bool,
+    class: Option<String>,
 }
 
 impl<'a> Button<'a> {
@@ -67,6 +68,7 @@ impl<'a> Button<'a> {
             min_size: Vec2::ZERO,
             rounding: None,
             selected: false,
+            class: None,
         }
     }
 
@@ -157,10 +159,23 @@ impl<'a> Button<'a> {
         self.selected = selected;
         self
     }
+
+    /// Style the button with the [`crate::StyleOverride`] of this name.
+    ///
+    /// See [`crate::Context::set_style_class`].
+    #[inline]
+    pub fn class(mut self, class: impl Into<String>) -> Self {
+        self.class = Some(class.into());
+        self
+    }
 }
 
 impl Widget for Button<'_> {
-    fn ui(self, ui: &mut Ui) -> Response {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        if let Some(class) = self.class.take() {
+            return ui.with_style_class(&class, |ui| self.ui(ui));
+        }
+
         let Button {
             text,
             image,
@@ -174,6 +189,7 @@ impl Widget for Button<'_> {
             min_size,
             rounding,
             selected,
+            class: _,
         } = self;
 
         let frame = frame.unwrap_or_else(|| ui.visuals().button_frame);
@@ -26,6 +26,7 @@ pub struct Label {
     truncate: bool,
     sense: Option<Sense>,
     selectable: Option<bool>,
+    class: Option<String>,
 }
 
 impl Label {
@@ -36,6 +37,7 @@ impl Label {
             truncate: false,
             sense: None,
             selectable: None,
+            class: None,
         }
     }
 
@@ -105,6 +107,15 @@ impl Label {
         self.sense = Some(sense);
         self
     }
+
+    /// Style the label with the [`crate::StyleOverride`] of this name.
+    ///
+    /// See [`crate::Context::set_style_class`].
+    #[inline]
+    pub fn class(mut self, class: impl Into<String>) -> Self {
+        self.class = Some(class.into());
+        self
+    }
 }
 
 impl Label {
@@ -224,7 +235,11 @@ impl Label {
 }
 
 impl Widget for Label {
-    fn ui(self, ui: &mut Ui) -> Response {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        if let Some(class) = self.class.take() {
+            return ui.with_style_class(&class, |ui| self.ui(ui));
+        }
+
         // Interactive = the uses asked to sense interaction.
         // We DON'T want to have the color respond just because the text is selectable;
         // the cursor is enough to communicate that.
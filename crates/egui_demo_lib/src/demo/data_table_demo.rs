@@ -0,0 +1,123 @@
+use egui_extras::{Column, DataColumn, DataTable, SelectionMode, TableData};
+
+struct File {
+    name: String,
+    kind: &'static str,
+    size: u64,
+}
+
+/// Shows off the [`DataTable`] of `egui_extras`.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DataTableDemo {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    files: Vec<File>,
+    selection_mode: SelectionMode,
+    striped: bool,
+    last_activated: Option<String>,
+}
+
+impl Default for DataTableDemo {
+    fn default() -> Self {
+        let kinds = ["rs", "toml", "md", "png", "ttf"];
+        let files = (0..10_000_u64)
+            .map(|i| {
+                let kind = kinds[(i * 7 % 5) as usize];
+                File {
+                    name: format!("file_{i:05}.{kind}"),
+                    kind,
+                    size: i * 7919 % 100_003,
+                }
+            })
+            .collect();
+        Self {
+            files,
+            selection_mode: SelectionMode::Multiple,
+            striped: true,
+            last_activated: None,
+        }
+    }
+}
+
+impl TableData for DataTableDemo {
+    fn num_rows(&self) -> usize {
+        self.files.len()
+    }
+
+    fn cell_text(&self, row: usize, column: usize) -> String {
+        let file = &self.files[row];
+        match column {
+            0 => file.name.clone(),
+            1 => file.kind.to_owned(),
+            _ => format!("{} bytes", file.size),
+        }
+    }
+
+    fn compare(&self, column: usize, a: usize, b: usize) -> std::cmp::Ordering {
+        let (a, b) = (&self.files[a], &self.files[b]);
+        match column {
+            0 => a.name.cmp(&b.name),
+            1 => a.kind.cmp(b.kind),
+            _ => a.size.cmp(&b.size),
+        }
+    }
+}
+
+impl super::Demo for DataTableDemo {
+    fn name(&self) -> &'static str {
+        "☰ Data Table"
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(self.name())
+            .open(open)
+            .default_size([480.0, 400.0])
+            .show(ctx, |ui| {
+                use super::View as _;
+                self.ui(ui);
+            });
+    }
+}
+
+impl super::View for DataTableDemo {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.files.is_empty() {
+            // Not stored, so regenerate it after a restore:
+            self.files = Self::default().files;
+        }
+
+        ui.vertical_centered(|ui| {
+            ui.add(crate::egui_github_link_file!());
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Selection:");
+            ui.selectable_value(&mut self.selection_mode, SelectionMode::None, "None");
+            ui.selectable_value(&mut self.selection_mode, SelectionMode::Single, "Single");
+            ui.selectable_value(
+                &mut self.selection_mode,
+                SelectionMode::Multiple,
+                "Multiple",
+            );
+        });
+        ui.checkbox(&mut self.striped, "Striped");
+        ui.label("Click a header to sort, shift-click to sort by several columns.");
+        ui.label("Click a row and use the arrow keys to move around. Press enter to open a file.");
+        if let Some(name) = &self.last_activated {
+            ui.label(format!("Opened {name}"));
+        }
+        ui.separator();
+
+        let table = DataTable::new("demo_data_table")
+            .column(DataColumn::new("Name", Column::initial(160.0)).filterable(true))
+            .column(DataColumn::new("Kind", Column::initial(80.0)).filterable(true))
+            .column(DataColumn::new("Size", Column::remainder()))
+            .selection_mode(self.selection_mode)
+            .striped(self.striped);
+        let response = table.show(ui, self);
+
+        if let Some(row) = response.activated {
+            self.last_activated = Some(self.files[row].name.clone());
+        }
+    }
+}
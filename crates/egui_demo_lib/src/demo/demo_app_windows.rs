@@ -26,11 +26,14 @@ impl Default for Demos {
             Box::<super::code_example::CodeExample>::default(),
             Box::<super::context_menu::ContextMenus>::default(),
             Box::<super::dancing_strings::DancingStrings>::default(),
+            Box::<super::data_table_demo::DataTableDemo>::default(),
+            Box::<super::dock_demo::DockDemo>::default(),
             Box::<super::drag_and_drop::DragAndDropDemo>::default(),
             Box::<super::extra_viewport::ExtraViewport>::default(),
             Box::<super::font_book::FontBook>::default(),
             Box::<super::frame_demo::FrameDemo>::default(),
             Box::<super::MiscDemoWindow>::default(),
+            Box::<super::modals::Modals>::default(),
             Box::<super::multi_touch::MultiTouch>::default(),
             Box::<super::painting::Painting>::default(),
             Box::<super::pan_zoom::PanZoom>::default(),
@@ -42,6 +45,7 @@ impl Default for Demos {
             Box::<super::table_demo::TableDemo>::default(),
             Box::<super::text_edit::TextEditDemo>::default(),
             Box::<super::text_layout::TextLayoutDemo>::default(),
+            Box::<super::tree_demo::TreeDemo>::default(),
             Box::<super::widget_gallery::WidgetGallery>::default(),
             Box::<super::window_options::WindowOptions>::default(),
             Box::<super::tests::WindowResizeTest>::default(),
@@ -373,3 +377,48 @@ fn file_menu_button(ui: &mut Ui) {
         }
     });
 }
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use egui_harness::Harness;
+
+    use super::Demo;
+
+    /// `"🗖 Window Options"` -> `"window_options"`
+    fn snapshot_name(demo: &dyn Demo) -> String {
+        demo.name()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == ' ')
+            .collect::<String>()
+            .trim()
+            .to_lowercase()
+            .replace(' ', "_")
+    }
+
+    #[test]
+    fn demos_should_match_snapshots() {
+        let demos: Vec<Box<dyn Demo>> = vec![
+            Box::<super::super::window_options::WindowOptions>::default(),
+            Box::<super::super::table_demo::TableDemo>::default(),
+            Box::<super::super::plot_demo::PlotDemo>::default(),
+        ];
+
+        let mut errors = vec![];
+        for mut demo in demos {
+            let name = format!("demos/{}", snapshot_name(demo.as_ref()));
+
+            let mut harness = Harness::builder()
+                .with_size(egui::vec2(600.0, 600.0))
+                .build(|ctx| demo.show(ctx, &mut true));
+            harness.run();
+
+            if let Err(err) = harness.try_snapshot(&name) {
+                errors.push(err.to_string());
+            }
+        }
+
+        assert!(errors.is_empty(), "{}", errors.join("\n"));
+    }
+}
@@ -0,0 +1,82 @@
+use egui_extras::{DockArea, DockNode, DockState, SplitDirection, TabViewer};
+
+/// Shows off the [`DockArea`] of `egui_extras`.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DockDemo {
+    state: DockState<String>,
+    allow_detach: bool,
+    next_tab: usize,
+}
+
+impl Default for DockDemo {
+    fn default() -> Self {
+        let files = DockNode::tabs(vec!["Files".to_owned(), "Search".to_owned()]);
+        let editor = DockNode::tabs(vec!["main.rs".to_owned(), "lib.rs".to_owned()]);
+        let console = DockNode::tabs(vec!["Console".to_owned()]);
+        let right = DockNode::split(SplitDirection::Vertical, 0.7, editor, console);
+        Self {
+            state: DockState::from_root(DockNode::split(
+                SplitDirection::Horizontal,
+                0.3,
+                files,
+                right,
+            )),
+            allow_detach: true,
+            next_tab: 1,
+        }
+    }
+}
+
+struct Tabs;
+
+impl TabViewer for Tabs {
+    type Tab = String;
+
+    fn title(&mut self, tab: &mut String) -> egui::WidgetText {
+        tab.as_str().into()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, tab: &mut String) {
+        ui.heading(tab.as_str());
+        ui.label("Drag the tab onto another tab bar, or onto the edge of a pane to split it.");
+        ui.label("Drop it outside the dock area to open it in a window of its own.");
+    }
+}
+
+impl super::Demo for DockDemo {
+    fn name(&self) -> &'static str {
+        "🗖 Dock Area"
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(self.name())
+            .open(open)
+            .default_size([640.0, 400.0])
+            .show(ctx, |ui| {
+                use super::View as _;
+                self.ui(ui);
+            });
+    }
+}
+
+impl super::View for DockDemo {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            ui.add(crate::egui_github_link_file!());
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Add tab").clicked() {
+                self.state.add_tab(format!("Tab {}", self.next_tab));
+                self.next_tab += 1;
+            }
+            ui.checkbox(&mut self.allow_detach, "Allow detaching tabs");
+        });
+        ui.separator();
+
+        DockArea::new(&mut self.state)
+            .allow_detach(self.allow_detach)
+            .show_inside(ui, &mut Tabs);
+    }
+}
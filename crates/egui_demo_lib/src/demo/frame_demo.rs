@@ -17,6 +17,7 @@ impl Default for FrameDemo {
                     spread: 0.0,
                     color: egui::Color32::from_black_alpha(180),
                 },
+                backdrop_blur: 0.0,
                 fill: egui::Color32::from_rgba_unmultiplied(97, 0, 255, 128),
                 stroke: egui::Stroke::new(1.0, egui::Color32::GRAY),
             },
@@ -9,7 +9,9 @@ pub mod code_editor;
 pub mod code_example;
 pub mod context_menu;
 pub mod dancing_strings;
+pub mod data_table_demo;
 pub mod demo_app_windows;
+pub mod dock_demo;
 pub mod drag_and_drop;
 pub mod extra_viewport;
 pub mod font_book;
@@ -17,6 +19,7 @@ pub mod frame_demo;
 pub mod highlighting;
 pub mod layout_test;
 pub mod misc_demo_window;
+pub mod modals;
 pub mod multi_touch;
 pub mod paint_bezier;
 pub mod painting;
@@ -32,6 +35,7 @@ pub mod tests;
 pub mod text_edit;
 pub mod text_layout;
 pub mod toggle_switch;
+pub mod tree_demo;
 pub mod widget_gallery;
 pub mod window_options;
 
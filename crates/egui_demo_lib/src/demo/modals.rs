@@ -0,0 +1,175 @@
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Modals {
+    save_modal_open: bool,
+    confirm_modal_open: bool,
+    close_on_backdrop_click: bool,
+    save_count: usize,
+}
+
+impl super::Demo for Modals {
+    fn name(&self) -> &'static str {
+        "🗖 Modals"
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(self.name())
+            .default_width(320.0)
+            .open(open)
+            .show(ctx, |ui| {
+                use super::View as _;
+                self.ui(ui);
+            });
+
+        self.modals(ctx);
+    }
+}
+
+impl super::View for Modals {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            ui.add(crate::egui_github_link_file!());
+        });
+
+        ui.label("A modal blocks interaction with everything behind it until it is closed.");
+        ui.checkbox(
+            &mut self.close_on_backdrop_click,
+            "Close when clicking the backdrop",
+        );
+        ui.horizontal(|ui| {
+            if ui.button("Save…").clicked() {
+                self.save_modal_open = true;
+            }
+            ui.label(format!("Saved {} times", self.save_count));
+        });
+    }
+}
+
+impl Modals {
+    fn modals(&mut self, ctx: &egui::Context) {
+        if self.save_modal_open {
+            let modal = egui::Modal::new(egui::Id::new("demo_save_modal"))
+                .close_on_backdrop_click(self.close_on_backdrop_click)
+                .show(ctx, |ui| {
+                    ui.heading("Save changes?");
+                    ui.label("Press Escape to close this modal.");
+                    ui.horizontal(|ui| {
+                        if ui.button("Save").clicked() {
+                            self.confirm_modal_open = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.save_modal_open = false;
+                        }
+                    });
+                });
+            if modal.should_close() {
+                self.save_modal_open = false;
+            }
+        }
+
+        // Opened from within the first modal, and shown on top of it:
+        if self.confirm_modal_open {
+            let modal = egui::Modal::new(egui::Id::new("demo_confirm_modal"))
+                .close_on_backdrop_click(self.close_on_backdrop_click)
+                .show(ctx, |ui| {
+                    ui.label("This will overwrite the file. Are you sure?");
+                    ui.horizontal(|ui| {
+                        if ui.button("Overwrite").clicked() {
+                            self.save_count += 1;
+                            self.confirm_modal_open = false;
+                            self.save_modal_open = false;
+                        }
+                        if ui.button("Back").clicked() {
+                            self.confirm_modal_open = false;
+                        }
+                    });
+                });
+            if modal.should_close() {
+                self.confirm_modal_open = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Key;
+    use egui_harness::Harness;
+
+    use super::super::Demo as _;
+    use super::Modals;
+
+    fn harness() -> Harness<'static, Modals> {
+        let mut harness = Harness::new_state(
+            |ctx, modals: &mut Modals| modals.show(ctx, &mut true),
+            Modals::default(),
+        );
+        harness.run();
+        harness
+    }
+
+    #[test]
+    fn modal_blocks_input_behind_it() {
+        let mut harness = harness();
+        let save = harness.get_by_label("Save…");
+        harness.click(&save);
+        harness.run();
+        assert!(harness.state().save_modal_open);
+
+        // The checkbox is behind the modal:
+        let checkbox = harness.get_by_label("Close when clicking the backdrop");
+        harness.click(&checkbox);
+        harness.run();
+        assert!(!harness.state().close_on_backdrop_click);
+        assert!(harness.state().save_modal_open);
+    }
+
+    #[test]
+    fn backdrop_click_closes_modal() {
+        let mut harness = harness();
+        harness.state_mut().close_on_backdrop_click = true;
+        let save = harness.get_by_label("Save…");
+        harness.click(&save);
+        harness.run();
+        assert!(harness.state().save_modal_open);
+
+        harness.click_at(egui::pos2(1.0, 1.0));
+        harness.run();
+        assert!(!harness.state().save_modal_open);
+    }
+
+    #[test]
+    fn nested_modals() {
+        let mut harness = harness();
+        let save = harness.get_by_label("Save…");
+        harness.click(&save);
+        harness.run();
+
+        let save = harness.get_by_label("Save");
+        harness.click(&save);
+        harness.run();
+        assert!(harness.state().confirm_modal_open);
+
+        // Only the top modal can be interacted with:
+        let cancel = harness.get_by_label("Cancel");
+        harness.click(&cancel);
+        harness.run();
+        assert!(harness.state().save_modal_open);
+
+        // Escape only closes the top modal:
+        harness.press_key(Key::Escape);
+        harness.run();
+        assert!(!harness.state().confirm_modal_open);
+        assert!(harness.state().save_modal_open);
+
+        let save = harness.get_by_label("Save");
+        harness.click(&save);
+        harness.run();
+        let overwrite = harness.get_by_label("Overwrite");
+        harness.click(&overwrite);
+        harness.run();
+        assert_eq!(harness.state().save_count, 1);
+        assert!(!harness.state().save_modal_open);
+    }
+}
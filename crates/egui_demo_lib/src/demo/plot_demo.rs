@@ -1,12 +1,14 @@
 use std::f64::consts::TAU;
 use std::ops::RangeInclusive;
+use std::sync::Arc;
 
 use egui::*;
 
 use egui_plot::{
-    Arrows, AxisHints, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, CoordinatesFormatter, Corner,
-    GridInput, GridMark, HLine, Legend, Line, LineStyle, MarkerShape, Plot, PlotImage, PlotPoint,
-    PlotPoints, PlotResponse, Points, Polygon, Text, VLine,
+    Arrows, AxisHints, Band, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, Colormap, Contour,
+    CoordinatesFormatter, Corner, FilledContour, GridInput, GridMark, HLine, Heatmap, Legend, Line,
+    LineStyle, MarkerShape, Plot, PlotImage, PlotPoint, PlotPoints, PlotResponse, PlotRingBuffer,
+    Points, Polygon, Text, VLine,
 };
 
 // ----------------------------------------------------------------------------
@@ -21,6 +23,10 @@ enum Panel {
     Interaction,
     CustomAxes,
     LinkedAxes,
+    Streaming,
+    Heatmap,
+    Contour,
+    Uncertainty,
 }
 
 impl Default for Panel {
@@ -41,6 +47,10 @@ pub struct PlotDemo {
     interaction_demo: InteractionDemo,
     custom_axes_demo: CustomAxesDemo,
     linked_axes_demo: LinkedAxesDemo,
+    streaming_demo: StreamingDemo,
+    heatmap_demo: HeatmapDemo,
+    contour_demo: ContourDemo,
+    uncertainty_demo: UncertaintyDemo,
     open_panel: Panel,
 }
 
@@ -78,7 +88,7 @@ impl super::View for PlotDemo {
             });
         });
         ui.separator();
-        ui.horizontal(|ui| {
+        ui.horizontal_wrapped(|ui| {
             ui.selectable_value(&mut self.open_panel, Panel::Lines, "Lines");
             ui.selectable_value(&mut self.open_panel, Panel::Markers, "Markers");
             ui.selectable_value(&mut self.open_panel, Panel::Legend, "Legend");
@@ -87,6 +97,10 @@ impl super::View for PlotDemo {
             ui.selectable_value(&mut self.open_panel, Panel::Interaction, "Interaction");
             ui.selectable_value(&mut self.open_panel, Panel::CustomAxes, "Custom Axes");
             ui.selectable_value(&mut self.open_panel, Panel::LinkedAxes, "Linked Axes");
+            ui.selectable_value(&mut self.open_panel, Panel::Streaming, "Streaming");
+            ui.selectable_value(&mut self.open_panel, Panel::Heatmap, "Heatmap");
+            ui.selectable_value(&mut self.open_panel, Panel::Contour, "Contour");
+            ui.selectable_value(&mut self.open_panel, Panel::Uncertainty, "Uncertainty");
         });
         ui.separator();
 
@@ -115,6 +129,18 @@ impl super::View for PlotDemo {
             Panel::LinkedAxes => {
                 self.linked_axes_demo.ui(ui);
             }
+            Panel::Streaming => {
+                self.streaming_demo.ui(ui);
+            }
+            Panel::Heatmap => {
+                self.heatmap_demo.ui(ui);
+            }
+            Panel::Contour => {
+                self.contour_demo.ui(ui);
+            }
+            Panel::Uncertainty => {
+                self.uncertainty_demo.ui(ui);
+            }
         }
     }
 }
@@ -694,6 +720,298 @@ impl LinkedAxesDemo {
 
 // ----------------------------------------------------------------------------
 
+#[derive(PartialEq)]
+struct StreamingDemo {
+    /// The plot shows these without copying them.
+    samples: Arc<PlotRingBuffer>,
+    paused: bool,
+    follow_latest: bool,
+    window: f64,
+}
+
+impl Default for StreamingDemo {
+    fn default() -> Self {
+        Self {
+            samples: Arc::new(PlotRingBuffer::new(2_000)),
+            paused: false,
+            follow_latest: true,
+            window: 10.0,
+        }
+    }
+}
+
+impl StreamingDemo {
+    fn ui(&mut self, ui: &mut Ui) -> Response {
+        if !self.paused {
+            let time = ui.input(|i| i.time);
+            let value = (2.0 * time).sin() + 0.3 * (13.0 * time).sin() * (0.7 * time).cos();
+            // The plot let go of the buffer last frame, so this doesn't copy it:
+            Arc::make_mut(&mut self.samples).push([time, value]);
+            ui.ctx().request_repaint();
+        }
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.paused, "Paused");
+            ui.checkbox(&mut self.follow_latest, "Follow latest");
+            ui.add_enabled(
+                self.follow_latest,
+                egui::DragValue::new(&mut self.window)
+                    .speed(0.1)
+                    .clamp_range(1.0..=60.0)
+                    .prefix("window: ")
+                    .suffix(" s"),
+            );
+        });
+        ui.label(format!(
+            "Keeping the latest {} of at most {} samples. Double-click to follow again after panning.",
+            self.samples.len(),
+            self.samples.capacity()
+        ));
+
+        let mut plot = Plot::new("streaming_demo").x_axis_label("time (s)");
+        if self.follow_latest {
+            plot = plot.follow_latest(self.window);
+        }
+        plot.show(ui, |plot_ui| {
+            plot_ui.line(Line::new(PlotPoints::shared(&self.samples)).name("signal"));
+        })
+        .response
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(PartialEq)]
+struct HeatmapDemo {
+    colormap: Colormap,
+    resolution: usize,
+    colorbar: bool,
+}
+
+impl Default for HeatmapDemo {
+    fn default() -> Self {
+        Self {
+            colormap: Colormap::Viridis,
+            resolution: 64,
+            colorbar: true,
+        }
+    }
+}
+
+impl HeatmapDemo {
+    /// Two bumps and a dip.
+    fn field(x: f64, y: f64) -> f64 {
+        let bump = |cx: f64, cy: f64, r: f64| (-((x - cx).powi(2) + (y - cy).powi(2)) / r).exp();
+        bump(-1.0, 0.5, 0.8) + 0.7 * bump(1.2, -0.6, 0.5) - 0.9 * bump(0.4, 1.2, 0.3)
+    }
+
+    fn ui(&mut self, ui: &mut Ui) -> Response {
+        ui.horizontal(|ui| {
+            ComboBox::from_label("Colormap")
+                .selected_text(self.colormap.to_string())
+                .show_ui(ui, |ui| {
+                    for colormap in Colormap::all() {
+                        ui.selectable_value(&mut self.colormap, colormap, colormap.to_string());
+                    }
+                });
+            ui.add(
+                egui::DragValue::new(&mut self.resolution)
+                    .speed(1.0)
+                    .clamp_range(4..=512)
+                    .prefix("resolution: "),
+            );
+            ui.checkbox(&mut self.colorbar, "Colorbar");
+        });
+        ui.label("Hover a cell to see its value.");
+
+        let n = self.resolution;
+        let step = 6.0 / n as f64;
+        let values: Vec<f64> = (0..n * n)
+            .map(|i| {
+                let x = -3.0 + ((i % n) as f64 + 0.5) * step;
+                let y = -3.0 + ((i / n) as f64 + 0.5) * step;
+                Self::field(x, y)
+            })
+            .collect();
+        let heatmap = Heatmap::new(values, n)
+            .x_range(-3.0..=3.0)
+            .y_range(-3.0..=3.0)
+            .colormap(self.colormap)
+            .colorbar(self.colorbar)
+            .name("height");
+
+        Plot::new("heatmap_demo")
+            .data_aspect(1.0)
+            .show(ui, |plot_ui| plot_ui.heatmap(heatmap))
+            .response
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(PartialEq)]
+struct ContourDemo {
+    num_levels: usize,
+    filled: bool,
+    lines: bool,
+    labels: bool,
+}
+
+impl Default for ContourDemo {
+    fn default() -> Self {
+        Self {
+            num_levels: 6,
+            filled: true,
+            lines: true,
+            labels: true,
+        }
+    }
+}
+
+impl ContourDemo {
+    fn ui(&mut self, ui: &mut Ui) -> Response {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut self.num_levels)
+                    .speed(0.1)
+                    .clamp_range(1..=20)
+                    .prefix("levels: "),
+            );
+            ui.checkbox(&mut self.filled, "Filled");
+            ui.checkbox(&mut self.lines, "Lines");
+            ui.checkbox(&mut self.labels, "Labels");
+        });
+        ui.label("Click a level in the legend to hide it.");
+
+        let n = 60;
+        let step = 6.0 / n as f64;
+        let values: Vec<f64> = (0..n * n)
+            .map(|i| {
+                let x = -3.0 + ((i % n) as f64 + 0.5) * step;
+                let y = -3.0 + ((i / n) as f64 + 0.5) * step;
+                HeatmapDemo::field(x, y)
+            })
+            .collect();
+
+        let filled_contour = self.filled.then(|| {
+            FilledContour::new(values.clone(), n)
+                .x_range(-3.0..=3.0)
+                .y_range(-3.0..=3.0)
+                .num_levels(self.num_levels)
+                .colormap(Colormap::Magma)
+        });
+        let contour = self.lines.then(|| {
+            let contour = Contour::new(values, n)
+                .x_range(-3.0..=3.0)
+                .y_range(-3.0..=3.0)
+                .num_levels(self.num_levels)
+                .labels(self.labels)
+                .width(1.5)
+                .name("height");
+            if self.filled {
+                contour.color(Color32::WHITE)
+            } else {
+                contour
+            }
+        });
+
+        Plot::new("contour_demo")
+            .data_aspect(1.0)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                if let Some(filled_contour) = filled_contour {
+                    plot_ui.filled_contour(filled_contour);
+                }
+                if let Some(contour) = contour {
+                    plot_ui.contour(contour);
+                }
+            })
+            .response
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(PartialEq)]
+struct UncertaintyDemo {
+    sigmas: f64,
+    asymmetric: bool,
+    x_errors: bool,
+}
+
+impl Default for UncertaintyDemo {
+    fn default() -> Self {
+        Self {
+            sigmas: 1.0,
+            asymmetric: false,
+            x_errors: true,
+        }
+    }
+}
+
+impl UncertaintyDemo {
+    fn ui(&mut self, ui: &mut Ui) -> Response {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut self.sigmas)
+                    .speed(0.05)
+                    .clamp_range(0.0..=3.0)
+                    .prefix("band: ±")
+                    .suffix(" σ"),
+            );
+            ui.checkbox(&mut self.asymmetric, "Asymmetric y errors");
+            ui.checkbox(&mut self.x_errors, "x errors");
+        });
+
+        // A model with its uncertainty, and measurements with theirs:
+        let model: Vec<[f64; 2]> = (0..=200)
+            .map(|i| {
+                let x = i as f64 / 20.0;
+                [x, (x / 2.0).sin() * (1.0 + x / 5.0)]
+            })
+            .collect();
+        let sigma: Vec<f64> = model
+            .iter()
+            .map(|[x, _]| self.sigmas * (0.1 + x / 40.0))
+            .collect();
+        let color = Color32::from_rgb(100, 150, 250);
+        let band = Band::around(model.clone(), sigma)
+            .color(color)
+            .name("model");
+        let line = Line::new(model).color(color).name("model");
+
+        let measurements: Vec<[f64; 2]> = (0..10)
+            .map(|i| {
+                let x = 0.5 + i as f64;
+                let noise = ((i * 7919) % 13) as f64 / 13.0 - 0.5;
+                [x, (x / 2.0).sin() * (1.0 + x / 5.0) + 0.3 * noise]
+            })
+            .collect();
+        let below: Vec<f64> = (0..10).map(|i| 0.15 + 0.02 * i as f64).collect();
+        let mut points = Points::new(measurements).radius(3.0).name("measurements");
+        points = if self.asymmetric {
+            let above: Vec<f64> = below.iter().map(|error| 2.0 * error).collect();
+            points.y_errors_asymmetric(below, above)
+        } else {
+            points.y_errors(below)
+        };
+        if self.x_errors {
+            points = points.x_errors(vec![0.2; 10]);
+        }
+
+        Plot::new("uncertainty_demo")
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                plot_ui.band(band);
+                plot_ui.line(line);
+                plot_ui.points(points);
+            })
+            .response
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 #[derive(PartialEq, Default)]
 struct ItemsDemo {
     texture: Option<egui::TextureHandle>,
@@ -0,0 +1,173 @@
+use egui_extras::{SelectionMode, TreeDrop, TreeNodes, TreeView};
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct Node {
+    name: String,
+    children: Vec<usize>,
+}
+
+/// Shows off the [`TreeView`] of `egui_extras`.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TreeDemo {
+    nodes: Vec<Node>,
+    roots: Vec<usize>,
+    selection_mode: SelectionMode,
+    drag_and_drop: bool,
+    last_activated: Option<String>,
+}
+
+impl Default for TreeDemo {
+    fn default() -> Self {
+        let mut demo = Self {
+            nodes: vec![],
+            roots: vec![],
+            selection_mode: SelectionMode::Multiple,
+            drag_and_drop: true,
+            last_activated: None,
+        };
+
+        let src = demo.add("src", None);
+        for name in ["lib.rs", "main.rs", "tree.rs"] {
+            demo.add(name, Some(src));
+        }
+        let widgets = demo.add("widgets", Some(src));
+        for name in ["button.rs", "label.rs", "slider.rs"] {
+            demo.add(name, Some(widgets));
+        }
+        let assets = demo.add("assets", None);
+        for name in ["icon.png", "font.ttf"] {
+            demo.add(name, Some(assets));
+        }
+        let many = demo.add("10 000 files", None);
+        for i in 0..10_000 {
+            demo.add(&format!("file_{i:05}.txt"), Some(many));
+        }
+        demo.add("README.md", None);
+        demo
+    }
+}
+
+impl TreeDemo {
+    fn add(&mut self, name: &str, parent: Option<usize>) -> usize {
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_owned(),
+            children: vec![],
+        });
+        self.siblings_mut(parent).push(node);
+        node
+    }
+
+    fn siblings_mut(&mut self, parent: Option<usize>) -> &mut Vec<usize> {
+        match parent {
+            Some(parent) => &mut self.nodes[parent].children,
+            None => &mut self.roots,
+        }
+    }
+
+    fn move_nodes(&mut self, drop: TreeDrop<usize>) {
+        let TreeDrop {
+            nodes,
+            parent,
+            index,
+        } = drop;
+
+        // The moved nodes are removed first, so they no longer count:
+        let siblings = self.siblings_mut(parent);
+        let index = index.min(siblings.len());
+        let index = index
+            - siblings[..index]
+                .iter()
+                .filter(|n| nodes.contains(n))
+                .count();
+
+        self.roots.retain(|n| !nodes.contains(n));
+        for node in &mut self.nodes {
+            node.children.retain(|n| !nodes.contains(n));
+        }
+        self.siblings_mut(parent).splice(index..index, nodes);
+    }
+}
+
+impl TreeNodes for TreeDemo {
+    type NodeId = usize;
+
+    fn roots(&mut self) -> Vec<usize> {
+        self.roots.clone()
+    }
+
+    fn has_children(&mut self, node: &usize) -> bool {
+        !self.nodes[*node].children.is_empty()
+    }
+
+    fn children(&mut self, node: &usize) -> Vec<usize> {
+        self.nodes[*node].children.clone()
+    }
+
+    fn row_ui(&mut self, ui: &mut egui::Ui, node: &usize) {
+        let node = &self.nodes[*node];
+        let icon = if node.children.is_empty() {
+            "🗋"
+        } else {
+            "🗀"
+        };
+        ui.add(egui::Label::new(format!("{icon} {}", node.name)).selectable(false));
+    }
+}
+
+impl super::Demo for TreeDemo {
+    fn name(&self) -> &'static str {
+        "🌲 Tree View"
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(self.name())
+            .open(open)
+            .default_size([320.0, 400.0])
+            .show(ctx, |ui| {
+                use super::View as _;
+                self.ui(ui);
+            });
+    }
+}
+
+impl super::View for TreeDemo {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            ui.add(crate::egui_github_link_file!());
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Selection:");
+            ui.selectable_value(&mut self.selection_mode, SelectionMode::None, "None");
+            ui.selectable_value(&mut self.selection_mode, SelectionMode::Single, "Single");
+            ui.selectable_value(
+                &mut self.selection_mode,
+                SelectionMode::Multiple,
+                "Multiple",
+            );
+        });
+        ui.checkbox(&mut self.drag_and_drop, "Drag and drop");
+        ui.label(
+            "Click the tree and use the arrow keys to move around. Press enter to open a file.",
+        );
+        if let Some(name) = &self.last_activated {
+            ui.label(format!("Opened {name}"));
+        }
+        ui.separator();
+
+        let tree = TreeView::new("demo_tree")
+            .selection_mode(self.selection_mode)
+            .drag_and_drop(self.drag_and_drop);
+        let response = tree.show(ui, self);
+
+        if let Some(node) = response.activated {
+            self.last_activated = Some(self.nodes[node].name.clone());
+        }
+        if let Some(drop) = response.dropped {
+            self.move_nodes(drop);
+        }
+    }
+}
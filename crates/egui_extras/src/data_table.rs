@@ -0,0 +1,680 @@
+//! A [`Table`](crate::Table) that manages sorting, filtering and selection for you.
+//!
+//! The rows come from your implementation of [`TableData`],
+//! and the state (sort order, filters and selection) is stored in [`egui::Memory`].
+
+use std::{cmp::Ordering, hash::Hash, sync::Arc};
+
+use egui::{Align, Context, EventFilter, Id, Label, Layout, Response, Sense, Ui, WidgetText};
+
+use crate::{selection::Selection, Column, SelectionMode, TableBuilder};
+
+/// The rows shown by a [`DataTable`].
+///
+/// Rows and columns are identified by their index in your data,
+/// which stays the same however the table is sorted or filtered.
+pub trait TableData {
+    /// The number of rows, before filtering.
+    fn num_rows(&self) -> usize;
+
+    /// The text of a cell.
+    ///
+    /// By default this is what is shown in the cell, sorted by, and filtered on.
+    fn cell_text(&self, row: usize, column: usize) -> String;
+
+    /// Show the contents of a cell.
+    ///
+    /// Clicks on the cell select its row, so the default label isn't selectable;
+    /// widgets that sense clicks themselves keep them from selecting the row.
+    fn cell_ui(&mut self, ui: &mut Ui, row: usize, column: usize) {
+        ui.add(Label::new(self.cell_text(row, column)).selectable(false));
+    }
+
+    /// Compare two rows by the given column, for sorting in ascending order.
+    ///
+    /// By default this compares the [`Self::cell_text`] of the rows.
+    /// Override it to e.g. sort numbers by value.
+    fn compare(&self, column: usize, a: usize, b: usize) -> Ordering {
+        self.cell_text(a, column).cmp(&self.cell_text(b, column))
+    }
+
+    /// Should the row be shown, given the filter the user typed for this column?
+    ///
+    /// Only called for non-empty filters.
+    /// By default this checks if [`Self::cell_text`] contains the filter, ignoring case.
+    fn matches_filter(&self, row: usize, column: usize, filter: &str) -> bool {
+        self.cell_text(row, column)
+            .to_lowercase()
+            .contains(&filter.to_lowercase())
+    }
+}
+
+/// Which way a column of a [`DataTable`] is sorted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// A column of a [`DataTable`].
+pub struct DataColumn {
+    title: WidgetText,
+    column: Column,
+    sortable: bool,
+    filterable: bool,
+}
+
+impl DataColumn {
+    /// A column with this title in the header, sized like `column`.
+    pub fn new(title: impl Into<WidgetText>, column: Column) -> Self {
+        Self {
+            title: title.into(),
+            column,
+            sortable: true,
+            filterable: false,
+        }
+    }
+
+    /// Can the user sort by this column by clicking its header? Default: `true`.
+    #[inline]
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    /// Is there a filter input under the title of this column? Default: `false`.
+    #[inline]
+    pub fn filterable(mut self, filterable: bool) -> Self {
+        self.filterable = filterable;
+        self
+    }
+}
+
+/// What happened in a [`DataTable`] this frame.
+pub struct DataTableResponse {
+    /// The table as a whole.
+    ///
+    /// Its id is where the state of the table is stored, see [`DataTableState::load`].
+    pub response: Response,
+
+    /// The rows that passed the filters, in the order they are shown.
+    pub visible_rows: Arc<Vec<usize>>,
+
+    /// The selected rows, in ascending order.
+    pub selected: Vec<usize>,
+
+    /// Did the user change the selection this frame?
+    pub selection_changed: bool,
+
+    /// A row the user double-clicked, or pressed enter on.
+    pub activated: Option<usize>,
+}
+
+// ----------------------------------------------------------------------------
+
+/// How a [`DataTable`] is sorted and filtered, and which of its rows are selected.
+///
+/// This is stored in [`egui::Memory`] for you,
+/// but you can load and change it to e.g. select a row from code:
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let table_id = egui::Id::new("table");
+/// use egui_extras::{DataTableState, SortDirection};
+/// let mut state = DataTableState::load(ui.ctx(), table_id).unwrap_or_default();
+/// state.set_sort(vec![(1, SortDirection::Descending)]);
+/// state.set_selected([3]);
+/// state.store(ui.ctx(), table_id);
+/// # });
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DataTableState {
+    /// Which columns to sort by, most significant first.
+    sort: Vec<(usize, SortDirection)>,
+
+    /// The filter of each column, empty if the column isn't filtered.
+    filters: Vec<String>,
+
+    selection: Selection<usize>,
+
+    /// The visible rows, until the sorting, the filters or the number of rows change.
+    cached_rows: Option<CachedRows>,
+}
+
+/// The visible rows of a [`DataTable`], and what they were computed from.
+#[derive(Clone, Debug)]
+struct CachedRows {
+    sort: Vec<(usize, SortDirection)>,
+    filters: Vec<String>,
+    num_rows: usize,
+    rows: Arc<Vec<usize>>,
+}
+
+impl DataTableState {
+    /// Load the state of the table with this id, see [`DataTableResponse::response`].
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_temp(id))
+    }
+
+    /// Store the state of the table with this id.
+    pub fn store(self, ctx: &Context, id: Id) {
+        ctx.data_mut(|d| d.insert_temp(id, self));
+    }
+
+    /// The columns the rows are sorted by, most significant first.
+    pub fn sort(&self) -> &[(usize, SortDirection)] {
+        &self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: Vec<(usize, SortDirection)>) {
+        self.sort = sort;
+    }
+
+    /// The filter of this column, empty if the column isn't filtered.
+    pub fn filter(&self, column: usize) -> &str {
+        self.filters.get(column).map_or("", String::as_str)
+    }
+
+    pub fn set_filter(&mut self, column: usize, filter: impl Into<String>) {
+        if self.filters.len() <= column {
+            self.filters.resize(column + 1, String::new());
+        }
+        self.filters[column] = filter.into();
+    }
+
+    /// The selected rows, in the order they were selected.
+    pub fn selected(&self) -> &[usize] {
+        self.selection.selected()
+    }
+
+    pub fn is_selected(&self, row: usize) -> bool {
+        self.selection.is_selected(&row)
+    }
+
+    /// Select these rows, and only these.
+    pub fn set_selected(&mut self, selected: impl IntoIterator<Item = usize>) {
+        self.selection.set_selected(selected);
+    }
+
+    /// The rows that pass the filters, in sorted order.
+    pub fn visible_rows(&self, data: &impl TableData) -> Vec<usize> {
+        crate::profile_function!();
+
+        let mut rows: Vec<usize> = (0..data.num_rows())
+            .filter(|&row| {
+                self.filters.iter().enumerate().all(|(column, filter)| {
+                    filter.is_empty() || data.matches_filter(row, column, filter)
+                })
+            })
+            .collect();
+
+        if !self.sort.is_empty() {
+            // Stable, so rows that compare equal keep their order:
+            rows.sort_by(|&a, &b| {
+                self.sort
+                    .iter()
+                    .map(|&(column, direction)| match direction {
+                        SortDirection::Ascending => data.compare(column, a, b),
+                        SortDirection::Descending => data.compare(column, b, a),
+                    })
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+        rows
+    }
+
+    /// Sort and filter the rows again the next frame.
+    ///
+    /// A [`DataTable`] only does so by itself when the sorting, the filters or the number of rows change,
+    /// so call this when you change the contents of rows.
+    pub fn refresh_rows(&mut self) {
+        self.cached_rows = None;
+    }
+
+    /// [`Self::visible_rows`], reusing the ones from the previous frame if nothing changed.
+    fn cached_visible_rows(&mut self, data: &impl TableData) -> Arc<Vec<usize>> {
+        let num_rows = data.num_rows();
+        if let Some(cached) = &self.cached_rows {
+            if cached.num_rows == num_rows
+                && cached.sort == self.sort
+                && cached.filters == self.filters
+            {
+                return cached.rows.clone();
+            }
+        }
+
+        let rows = Arc::new(self.visible_rows(data));
+        self.cached_rows = Some(CachedRows {
+            sort: self.sort.clone(),
+            filters: self.filters.clone(),
+            num_rows,
+            rows: rows.clone(),
+        });
+        rows
+    }
+
+    /// A click on the header of a column.
+    ///
+    /// Cycles the column through ascending, descending and unsorted.
+    /// With `add` the column is added to the columns already sorted by, otherwise it replaces them.
+    fn toggle_sort(&mut self, column: usize, add: bool) {
+        let position = self.sort.iter().position(|&(c, _)| c == column);
+        if !add && position != Some(0) {
+            self.sort = vec![(column, SortDirection::Ascending)];
+            return;
+        }
+        if !add {
+            self.sort.truncate(1);
+        }
+        match position {
+            Some(i) => match self.sort[i].1 {
+                SortDirection::Ascending => self.sort[i].1 = SortDirection::Descending,
+                SortDirection::Descending => {
+                    self.sort.remove(i);
+                }
+            },
+            None => self.sort.push((column, SortDirection::Ascending)),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A [`Table`](crate::Table) of rows from a [`TableData`], with sorting, filtering and selection.
+///
+/// Click the header of a column to sort by it (click again to reverse, and again to stop sorting by it).
+/// Shift-click more headers to sort by several columns.
+/// Filterable columns have a text input under their title.
+///
+/// When the table has keyboard focus (after a click on a row),
+/// up/down/home/end moves between rows (with shift to select a range) and enter activates a row.
+///
+/// Only the rows in view are shown.
+/// All rows are filtered and sorted when the sorting, the filters or the number of rows change:
+/// call [`DataTableState::refresh_rows`] when you change the contents of rows.
+///
+/// ```
+/// use egui_extras::{Column, DataColumn, DataTable, SelectionMode, TableData};
+///
+/// struct Planets(Vec<(&'static str, f64)>);
+///
+/// impl TableData for Planets {
+///     fn num_rows(&self) -> usize {
+///         self.0.len()
+///     }
+///
+///     fn cell_text(&self, row: usize, column: usize) -> String {
+///         let (name, radius) = self.0[row];
+///         match column {
+///             0 => name.to_owned(),
+///             _ => format!("{radius} km"),
+///         }
+///     }
+///
+///     fn compare(&self, column: usize, a: usize, b: usize) -> std::cmp::Ordering {
+///         match column {
+///             0 => self.0[a].0.cmp(self.0[b].0),
+///             _ => self.0[a].1.total_cmp(&self.0[b].1),
+///         }
+///     }
+/// }
+///
+/// # egui::__run_test_ui(|ui| {
+/// let mut planets = Planets(vec![("Mercury", 2439.7), ("Venus", 6051.8), ("Earth", 6371.0)]);
+/// let response = DataTable::new("planets")
+///     .column(DataColumn::new("Name", Column::auto()).filterable(true))
+///     .column(DataColumn::new("Radius", Column::remainder()))
+///     .selection_mode(SelectionMode::Multiple)
+///     .show(ui, &mut planets);
+/// if response.selection_changed {
+///     println!("Selected {:?}", response.selected);
+/// }
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+pub struct DataTable {
+    id_source: Id,
+    columns: Vec<DataColumn>,
+    row_height: Option<f32>,
+    striped: Option<bool>,
+    resizable: bool,
+    selection_mode: SelectionMode,
+}
+
+impl DataTable {
+    pub fn new(id_source: impl Hash) -> Self {
+        Self {
+            id_source: Id::new(id_source),
+            columns: Vec::new(),
+            row_height: None,
+            striped: None,
+            resizable: true,
+            selection_mode: SelectionMode::default(),
+        }
+    }
+
+    /// Add a column.
+    #[inline]
+    pub fn column(mut self, column: DataColumn) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// The height of each row.
+    ///
+    /// Default: [`egui::style::Spacing::interact_size`]`.y`.
+    #[inline]
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = Some(row_height);
+        self
+    }
+
+    /// See [`TableBuilder::striped`].
+    #[inline]
+    pub fn striped(mut self, striped: bool) -> Self {
+        self.striped = Some(striped);
+        self
+    }
+
+    /// See [`TableBuilder::resizable`]. Default: `true`.
+    #[inline]
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Default: [`SelectionMode::Single`].
+    #[inline]
+    pub fn selection_mode(mut self, selection_mode: SelectionMode) -> Self {
+        self.selection_mode = selection_mode;
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui, data: &mut impl TableData) -> DataTableResponse {
+        crate::profile_function!();
+
+        let Self {
+            id_source,
+            columns,
+            row_height,
+            striped,
+            resizable,
+            selection_mode,
+        } = self;
+
+        let id = ui.make_persistent_id(id_source);
+        let mut state = DataTableState::load(ui.ctx(), id).unwrap_or_default();
+        state.filters.resize(columns.len(), String::new());
+        state.sort.retain(|&(column, _)| column < columns.len());
+        let selected_before = state.selection.clone();
+        let mut rows = state.cached_visible_rows(data);
+
+        ui.memory_mut(|mem| {
+            mem.interested_in_focus(id);
+            let event_filter = EventFilter {
+                vertical_arrows: true,
+                ..Default::default()
+            };
+            mem.set_focus_lock_filter(id, event_filter);
+        });
+        let has_focus = ui.memory(|mem| mem.has_focus(id));
+        let (scroll_to_row, mut activated) = if has_focus {
+            state
+                .selection
+                .handle_keyboard(ui, &rows[..], selection_mode)
+        } else {
+            (None, None)
+        };
+
+        let row_height = row_height.unwrap_or_else(|| ui.spacing().interact_size.y);
+        let header_height = if columns.iter().any(|column| column.filterable) {
+            2.0 * ui.spacing().interact_size.y + ui.spacing().item_spacing.y
+        } else {
+            ui.spacing().interact_size.y
+        };
+
+        let mut clicked = None;
+        let table_rect = ui
+            .push_id(id, |ui| {
+                let mut builder = TableBuilder::new(ui)
+                    .resizable(resizable)
+                    .cell_layout(Layout::left_to_right(Align::Center))
+                    .sense(Sense {
+                        focusable: false, // The table as a whole has the keyboard focus.
+                        ..Sense::click()
+                    });
+                if let Some(striped) = striped {
+                    builder = builder.striped(striped);
+                }
+                if let Some(index) = scroll_to_row {
+                    builder = builder.scroll_to_row(index, None);
+                }
+                for column in &columns {
+                    builder = builder.column(column.column);
+                }
+
+                let mut sort_or_filter_changed = false;
+                let table = builder.header(header_height, |mut header| {
+                    for (index, column) in columns.iter().enumerate() {
+                        header.col(|ui| {
+                            sort_or_filter_changed |= header_ui(ui, &mut state, column, index);
+                        });
+                    }
+                });
+                if sort_or_filter_changed {
+                    rows = state.cached_visible_rows(data);
+                }
+
+                table.body(|body| {
+                    body.rows(row_height, rows.len(), |mut row| {
+                        let index = row.index();
+                        let data_row = rows[index];
+                        row.set_selected(state.is_selected(data_row));
+                        for column in 0..columns.len() {
+                            let (_, response) = row.col(|ui| data.cell_ui(ui, data_row, column));
+                            if response.clicked() {
+                                clicked = Some(index);
+                            }
+                            if response.double_clicked() {
+                                activated = Some(data_row);
+                            }
+                        }
+                    });
+                });
+            })
+            .response
+            .rect;
+
+        if let Some(index) = clicked {
+            let modifiers = ui.input(|i| i.modifiers);
+            state
+                .selection
+                .click(&rows[..], index, modifiers, selection_mode);
+            ui.memory_mut(|mem| mem.request_focus(id));
+        }
+
+        // Gives the table keyboard focus, without covering the rows:
+        let response = ui.interact(table_rect, id, Sense::focusable_noninteractive());
+
+        let selection_changed = !state.selection.same_as(&selected_before);
+        let mut selected = state.selected().to_vec();
+        selected.sort_unstable();
+        state.store(ui.ctx(), id);
+
+        DataTableResponse {
+            response,
+            visible_rows: rows,
+            selected,
+            selection_changed,
+            activated,
+        }
+    }
+}
+
+/// The title of a column, with its sort order and filter input.
+///
+/// Returns `true` if the sorting or filtering changed.
+fn header_ui(ui: &mut Ui, state: &mut DataTableState, column: &DataColumn, index: usize) -> bool {
+    let mut changed = false;
+    ui.vertical(|ui| {
+        ui.horizontal(|ui| {
+            let title =
+                Label::new(column.title.clone())
+                    .selectable(false)
+                    .sense(if column.sortable {
+                        Sense::click()
+                    } else {
+                        Sense::hover()
+                    });
+            let response = ui.add(title);
+            if response.clicked() {
+                let add = ui.input(|i| i.modifiers.shift);
+                state.toggle_sort(index, add);
+                changed = true;
+            }
+            if column.sortable {
+                let response =
+                    response.on_hover_text("Click to sort, shift-click to sort by several columns");
+                if response.hovered() {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                }
+            }
+
+            if let Some(rank) = state.sort.iter().position(|&(c, _)| c == index) {
+                let arrow = match state.sort[rank].1 {
+                    SortDirection::Ascending => "⏶",
+                    SortDirection::Descending => "⏷",
+                };
+                if state.sort.len() > 1 {
+                    ui.weak(format!("{arrow}{}", rank + 1));
+                } else {
+                    ui.weak(arrow);
+                }
+            }
+        });
+
+        if column.filterable {
+            let filter = &mut state.filters[index];
+            let edit = egui::TextEdit::singleline(filter)
+                .hint_text("Filter")
+                .desired_width(f32::INFINITY);
+            changed |= ui.add(edit).changed();
+        }
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Modifiers;
+
+    use super::*;
+
+    struct Rows(Vec<[&'static str; 2]>);
+
+    impl TableData for Rows {
+        fn num_rows(&self) -> usize {
+            self.0.len()
+        }
+
+        fn cell_text(&self, row: usize, column: usize) -> String {
+            self.0[row][column].to_owned()
+        }
+    }
+
+    fn rows() -> Rows {
+        Rows(vec![["b", "1"], ["a", "2"], ["b", "0"], ["c", "1"]])
+    }
+
+    #[test]
+    fn clicking_headers_cycles_sorting() {
+        use SortDirection::{Ascending, Descending};
+
+        let mut state = DataTableState::default();
+        state.toggle_sort(0, false);
+        assert_eq!(state.sort(), &[(0, Ascending)]);
+        state.toggle_sort(0, false);
+        assert_eq!(state.sort(), &[(0, Descending)]);
+
+        state.toggle_sort(1, true);
+        assert_eq!(state.sort(), &[(0, Descending), (1, Ascending)]);
+        state.toggle_sort(1, true);
+        assert_eq!(state.sort(), &[(0, Descending), (1, Descending)]);
+        state.toggle_sort(1, true);
+        assert_eq!(state.sort(), &[(0, Descending)]);
+
+        state.toggle_sort(1, true);
+        state.toggle_sort(0, false);
+        assert_eq!(state.sort(), &[]);
+
+        state.toggle_sort(0, true);
+        state.toggle_sort(1, false);
+        assert_eq!(state.sort(), &[(1, Ascending)]);
+    }
+
+    #[test]
+    fn sorting_by_several_columns_and_filtering() {
+        use SortDirection::{Ascending, Descending};
+
+        let data = rows();
+        let mut state = DataTableState::default();
+        assert_eq!(state.visible_rows(&data), vec![0, 1, 2, 3]);
+
+        state.set_sort(vec![(0, Ascending), (1, Descending)]);
+        assert_eq!(state.visible_rows(&data), vec![1, 0, 2, 3]);
+
+        state.set_sort(vec![(1, Ascending)]);
+        assert_eq!(state.visible_rows(&data), vec![2, 0, 3, 1]);
+
+        state.set_filter(0, "B");
+        assert_eq!(state.visible_rows(&data), vec![2, 0]);
+        state.set_filter(1, "1");
+        assert_eq!(state.visible_rows(&data), vec![0]);
+    }
+
+    #[test]
+    fn visible_rows_are_cached_until_something_changes() {
+        let mut data = rows();
+        let mut state = DataTableState::default();
+        state.set_sort(vec![(0, SortDirection::Ascending)]);
+        let rows = state.cached_visible_rows(&data);
+        assert_eq!(*rows, vec![1, 0, 2, 3]);
+
+        data.0[1][0] = "d";
+        assert!(Arc::ptr_eq(&state.cached_visible_rows(&data), &rows));
+        state.refresh_rows();
+        assert_eq!(*state.cached_visible_rows(&data), vec![0, 2, 3, 1]);
+
+        data.0.push(["a", "3"]);
+        assert_eq!(*state.cached_visible_rows(&data), vec![4, 0, 2, 3, 1]);
+
+        state.set_filter(1, "3");
+        assert_eq!(*state.cached_visible_rows(&data), vec![4]);
+    }
+
+    #[test]
+    fn range_selection_follows_the_shown_order() {
+        let mode = SelectionMode::Multiple;
+        let shown = [3, 1, 0, 2];
+        let mut state = DataTableState::default();
+        let selection = &mut state.selection;
+
+        selection.click(&shown[..], 1, Modifiers::NONE, mode);
+        selection.click(&shown[..], 3, Modifiers::SHIFT, mode);
+        assert_eq!(selection.selected(), &[1, 0, 2]);
+
+        selection.click(&shown[..], 2, Modifiers::COMMAND, mode);
+        assert_eq!(selection.selected(), &[1, 2]);
+
+        // The anchor moved to the ctrl-clicked row:
+        selection.click(&shown[..], 0, Modifiers::SHIFT | Modifiers::COMMAND, mode);
+        assert_eq!(selection.selected(), &[1, 2, 3, 0]);
+
+        selection.move_cursor(&shown[..], 1, false, mode);
+        assert_eq!(selection.selected(), &[1]);
+        selection.move_cursor(&shown[..], 2, true, mode);
+        assert_eq!(selection.selected(), &[1, 0]);
+        assert!(!selection.same_as(&Default::default()));
+    }
+}
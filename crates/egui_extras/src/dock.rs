@@ -0,0 +1,1005 @@
+//! A dock area, where tabs can be dragged around, split into panes and detached into their own windows.
+//!
+//! The layout is a tree of [`DockNode`]s stored in a [`DockState`], which you own and can serialize.
+
+use std::hash::Hash;
+
+use egui::{
+    pos2, vec2, Align, CentralPanel, Context, CursorIcon, DragAndDrop, Id, Layout, Pos2, Rect,
+    Response, Sense, TextStyle, TopBottomPanel, Ui, Vec2, ViewportBuilder, ViewportClass,
+    ViewportId, WidgetText, Window,
+};
+
+/// Shows the tabs of a [`DockArea`].
+pub trait TabViewer {
+    /// The tabs in the [`DockState`].
+    type Tab;
+
+    /// The text on the tab, and the title of the window if it is detached.
+    fn title(&mut self, tab: &mut Self::Tab) -> WidgetText;
+
+    /// Show the contents of the tab.
+    fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab);
+
+    /// Identifies the tab.
+    ///
+    /// Must be unique within the [`DockState`], and stay the same when the tab is moved.
+    /// By default this is the title of the tab.
+    fn id(&mut self, tab: &mut Self::Tab) -> Id {
+        Id::new(self.title(tab).text())
+    }
+
+    /// Should the tab have a close button?
+    fn closeable(&mut self, _tab: &mut Self::Tab) -> bool {
+        true
+    }
+
+    /// Called when the user closes the tab.
+    ///
+    /// Return `false` to keep the tab open, e.g. to first ask about unsaved changes.
+    fn on_close(&mut self, _tab: &mut Self::Tab) -> bool {
+        true
+    }
+}
+
+/// How the two halves of a [`DockNode::Split`] are placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum SplitDirection {
+    /// Side by side, the first to the left.
+    Horizontal,
+
+    /// On top of each other, the first at the top.
+    Vertical,
+}
+
+/// A node in the layout tree of a [`DockState`].
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub enum DockNode<Tab> {
+    /// A group of tabs, of which the active one is shown.
+    Tabs { tabs: Vec<Tab>, active: usize },
+
+    /// Two nodes sharing the space.
+    Split {
+        direction: SplitDirection,
+
+        /// How much of the space goes to the first node, in `0..=1`.
+        fraction: f32,
+
+        children: Box<[DockNode<Tab>; 2]>,
+    },
+}
+
+impl<Tab> DockNode<Tab> {
+    /// A group of tabs, with the first one active.
+    pub fn tabs(tabs: Vec<Tab>) -> Self {
+        Self::Tabs { tabs, active: 0 }
+    }
+
+    /// Split the space between `first` and `second`, giving `fraction` of it to `first`.
+    pub fn split(direction: SplitDirection, fraction: f32, first: Self, second: Self) -> Self {
+        Self::Split {
+            direction,
+            fraction,
+            children: Box::new([first, second]),
+        }
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut Self> {
+        match (path.split_first(), self) {
+            (None, node) => Some(node),
+            (Some((&i, rest)), Self::Split { children, .. }) => children.get_mut(i)?.node_mut(rest),
+            (Some(_), Self::Tabs { .. }) => None,
+        }
+    }
+
+    fn first_tabs_mut(&mut self) -> (&mut Vec<Tab>, &mut usize) {
+        match self {
+            Self::Tabs { tabs, active } => (tabs, active),
+            Self::Split { children, .. } => children[0].first_tabs_mut(),
+        }
+    }
+
+    fn collect_tabs<'a>(&'a self, out: &mut Vec<&'a Tab>) {
+        match self {
+            Self::Tabs { tabs, .. } => out.extend(tabs),
+            Self::Split { children, .. } => {
+                for child in children.iter() {
+                    child.collect_tabs(out);
+                }
+            }
+        }
+    }
+
+    /// Remove empty tab groups, and splits with only one side left.
+    fn without_empty(self) -> Option<Self> {
+        match self {
+            Self::Tabs { tabs, .. } if tabs.is_empty() => None,
+            Self::Tabs { tabs, active } => Some(Self::Tabs {
+                active: active.min(tabs.len() - 1),
+                tabs,
+            }),
+            Self::Split {
+                direction,
+                fraction,
+                children,
+            } => {
+                let [first, second] = *children;
+                match (first.without_empty(), second.without_empty()) {
+                    (Some(first), Some(second)) => {
+                        Some(Self::split(direction, fraction, first, second))
+                    }
+                    (first, second) => first.or(second),
+                }
+            }
+        }
+    }
+}
+
+/// A tab that has been detached into its own window.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct DetachedTab<Tab> {
+    pub tab: Tab,
+
+    /// Where the window was opened, in screen coordinates (points).
+    pub position: Option<Pos2>,
+
+    /// The size the window was opened with.
+    pub size: Vec2,
+}
+
+/// The layout of a [`DockArea`]: a tree of split panes with groups of tabs,
+/// plus the tabs that have been detached into their own windows.
+///
+/// Store it in your app (it can be serialized with `serde`) and show it with [`DockArea`].
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct DockState<Tab> {
+    root: Option<DockNode<Tab>>,
+    detached: Vec<DetachedTab<Tab>>,
+}
+
+impl<Tab> Default for DockState<Tab> {
+    fn default() -> Self {
+        Self {
+            root: None,
+            detached: vec![],
+        }
+    }
+}
+
+impl<Tab> DockState<Tab> {
+    /// All tabs in one group.
+    pub fn new(tabs: Vec<Tab>) -> Self {
+        Self::from_root(DockNode::tabs(tabs))
+    }
+
+    /// Start with the given layout.
+    pub fn from_root(root: DockNode<Tab>) -> Self {
+        let mut state = Self {
+            root: Some(root),
+            detached: vec![],
+        };
+        state.remove_empty();
+        state
+    }
+
+    /// The layout of the docked tabs, if there are any.
+    pub fn root(&self) -> Option<&DockNode<Tab>> {
+        self.root.as_ref()
+    }
+
+    /// The layout of the docked tabs, if there are any.
+    ///
+    /// Empty tab groups are removed the next time the [`DockArea`] is shown.
+    pub fn root_mut(&mut self) -> Option<&mut DockNode<Tab>> {
+        self.root.as_mut()
+    }
+
+    /// The tabs that are shown in their own windows.
+    pub fn detached(&self) -> &[DetachedTab<Tab>] {
+        &self.detached
+    }
+
+    /// Add a tab to the first tab group, and make it active.
+    pub fn add_tab(&mut self, tab: Tab) {
+        match &mut self.root {
+            Some(root) => {
+                let (tabs, active) = root.first_tabs_mut();
+                tabs.push(tab);
+                *active = tabs.len() - 1;
+            }
+            None => self.root = Some(DockNode::tabs(vec![tab])),
+        }
+    }
+
+    /// All tabs, docked and detached.
+    pub fn tabs(&self) -> impl Iterator<Item = &Tab> {
+        let mut tabs = vec![];
+        if let Some(root) = &self.root {
+            root.collect_tabs(&mut tabs);
+        }
+        tabs.extend(self.detached.iter().map(|window| &window.tab));
+        tabs.into_iter()
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut DockNode<Tab>> {
+        self.root.as_mut()?.node_mut(path)
+    }
+
+    fn tab_mut(&mut self, at: &TabPath) -> Option<&mut Tab> {
+        match self.node_mut(&at.path)? {
+            DockNode::Tabs { tabs, .. } => tabs.get_mut(at.index),
+            DockNode::Split { .. } => None,
+        }
+    }
+
+    /// Remove a tab, leaving its group in place even if it becomes empty,
+    /// so that all paths stay valid until [`Self::remove_empty`].
+    fn take_tab(&mut self, at: &TabPath) -> Option<Tab> {
+        let DockNode::Tabs { tabs, active } = self.node_mut(&at.path)? else {
+            return None;
+        };
+        if at.index >= tabs.len() {
+            return None;
+        }
+        let tab = tabs.remove(at.index);
+        if at.index < *active || tabs.len() <= *active {
+            *active = active.saturating_sub(1);
+        }
+        Some(tab)
+    }
+
+    fn insert_tab(&mut self, target: &DropTarget, tab: Tab) {
+        let Some(node) = self.node_mut(&target.path) else {
+            self.add_tab(tab);
+            return;
+        };
+
+        let (direction, new_first) = match target.zone {
+            DropZone::Tab(index) => {
+                let (tabs, active) = node.first_tabs_mut();
+                let index = index.min(tabs.len());
+                tabs.insert(index, tab);
+                *active = index;
+                return;
+            }
+            DropZone::Center => {
+                let (tabs, active) = node.first_tabs_mut();
+                tabs.push(tab);
+                *active = tabs.len() - 1;
+                return;
+            }
+            DropZone::Left => (SplitDirection::Horizontal, true),
+            DropZone::Right => (SplitDirection::Horizontal, false),
+            DropZone::Top => (SplitDirection::Vertical, true),
+            DropZone::Bottom => (SplitDirection::Vertical, false),
+        };
+
+        let old = std::mem::replace(node, DockNode::tabs(vec![]));
+        let new = DockNode::tabs(vec![tab]);
+        *node = if new_first {
+            DockNode::split(direction, 0.5, new, old)
+        } else {
+            DockNode::split(direction, 0.5, old, new)
+        };
+    }
+
+    fn move_tab(&mut self, from: &TabPath, mut to: DropTarget) {
+        if to.path == from.path {
+            if let DropZone::Tab(index) = &mut to.zone {
+                if from.index < *index {
+                    *index -= 1;
+                }
+            }
+        }
+        if let Some(tab) = self.take_tab(from) {
+            self.insert_tab(&to, tab);
+        }
+    }
+
+    fn remove_empty(&mut self) {
+        self.root = self.root.take().and_then(DockNode::without_empty);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Where a tab is: the path of child indices from the root to its group, and its index in the group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TabPath {
+    path: Vec<usize>,
+    index: usize,
+}
+
+/// Where in a tab group a dragged tab is dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DropZone {
+    /// Into the tab bar, before the tab with this index.
+    Tab(usize),
+
+    /// Last in the group.
+    Center,
+
+    /// Split the group, putting the tab on this side of it.
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DropTarget {
+    path: Vec<usize>,
+    zone: DropZone,
+}
+
+/// The drag-and-drop payload of a dragged tab.
+#[derive(Clone, Debug)]
+struct TabDrag {
+    area_id: Id,
+    from: TabPath,
+
+    /// The size of the group it is dragged from, used if it is detached.
+    size: Vec2,
+}
+
+/// What the user did this frame.
+enum Action {
+    Activate(TabPath),
+    Close(TabPath),
+    Detach {
+        at: TabPath,
+        position: Option<Pos2>,
+        size: Vec2,
+    },
+    Move {
+        from: TabPath,
+        to: DropTarget,
+    },
+    Redock(usize),
+}
+
+// ----------------------------------------------------------------------------
+
+/// Shows a [`DockState`]: groups of tabs in resizable panes.
+///
+/// Drag a tab to another tab bar to move it there,
+/// or onto the edge of a pane to split that pane.
+/// Dropping a tab outside the dock area (or picking "Undock" from its context menu)
+/// opens it in a window of its own, using a new viewport if the backend supports it.
+/// Closing that window docks the tab again.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// struct Viewer;
+///
+/// impl egui_extras::TabViewer for Viewer {
+///     type Tab = String;
+///
+///     fn title(&mut self, tab: &mut String) -> egui::WidgetText {
+///         tab.as_str().into()
+///     }
+///
+///     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut String) {
+///         ui.label(format!("This is {tab}"));
+///     }
+/// }
+///
+/// # let mut state = egui_extras::DockState::new(vec!["Files".to_owned(), "Editor".to_owned()]);
+/// // Store the state in your app:
+/// // let mut state = DockState::new(vec!["Files".to_owned(), "Editor".to_owned()]);
+/// egui_extras::DockArea::new(&mut state).show_inside(ui, &mut Viewer);
+/// # });
+/// ```
+#[must_use = "You should call .show_inside()"]
+pub struct DockArea<'a, Tab> {
+    state: &'a mut DockState<Tab>,
+    id_source: Id,
+    allow_detach: bool,
+}
+
+impl<'a, Tab> DockArea<'a, Tab> {
+    pub fn new(state: &'a mut DockState<Tab>) -> Self {
+        Self {
+            state,
+            id_source: Id::new("dock_area"),
+            allow_detach: true,
+        }
+    }
+
+    /// Must be set if you have more than one dock area in the same [`Ui`].
+    #[inline]
+    pub fn id_source(mut self, id_source: impl Hash) -> Self {
+        self.id_source = Id::new(id_source);
+        self
+    }
+
+    /// Can tabs be detached into windows of their own? Default: `true`.
+    #[inline]
+    pub fn allow_detach(mut self, allow_detach: bool) -> Self {
+        self.allow_detach = allow_detach;
+        self
+    }
+
+    /// Fill the central panel with the dock area.
+    pub fn show(self, ctx: &Context, viewer: &mut impl TabViewer<Tab = Tab>) {
+        CentralPanel::default().show(ctx, |ui| {
+            self.show_inside(ui, viewer);
+        });
+    }
+
+    /// Fill the available space of the [`Ui`] with the dock area.
+    pub fn show_inside(self, ui: &mut Ui, viewer: &mut impl TabViewer<Tab = Tab>) -> Response {
+        crate::profile_function!();
+
+        let Self {
+            state,
+            id_source,
+            allow_detach,
+        } = self;
+
+        let rect = ui.available_rect_before_wrap();
+        let response = ui.allocate_rect(rect, Sense::hover());
+
+        let mut frame = DockFrame {
+            id: ui.make_persistent_id(id_source),
+            allow_detach,
+            action: None,
+            drop_preview: None,
+        };
+
+        if let Some(root) = &mut state.root {
+            frame.node_ui(ui, viewer, root, &mut vec![], rect);
+        }
+        if let Some(preview) = frame.drop_preview {
+            let visuals = &ui.visuals().selection;
+            ui.painter().rect(
+                preview,
+                2.0,
+                visuals.bg_fill.gamma_multiply(0.3),
+                visuals.stroke,
+            );
+        }
+        if allow_detach {
+            frame.drop_outside(ui, rect);
+        }
+        frame.detached_ui(ui.ctx(), viewer, &mut state.detached);
+
+        match frame.action {
+            Some(Action::Activate(at)) => {
+                if let Some(DockNode::Tabs { active, .. }) = state.node_mut(&at.path) {
+                    *active = at.index;
+                }
+            }
+            Some(Action::Close(at))
+                if state.tab_mut(&at).is_some_and(|tab| viewer.on_close(tab)) =>
+            {
+                state.take_tab(&at);
+            }
+            Some(Action::Detach { at, position, size }) => {
+                if let Some(tab) = state.take_tab(&at) {
+                    state.detached.push(DetachedTab {
+                        tab,
+                        position,
+                        size,
+                    });
+                }
+            }
+            Some(Action::Move { from, to }) => state.move_tab(&from, to),
+            Some(Action::Redock(index)) => {
+                let window = state.detached.remove(index);
+                state.add_tab(window.tab);
+            }
+            Some(Action::Close(_)) | None => {}
+        }
+        state.remove_empty();
+
+        response
+    }
+}
+
+/// The state of a [`DockArea`] while it is being shown.
+struct DockFrame {
+    id: Id,
+    allow_detach: bool,
+    action: Option<Action>,
+    drop_preview: Option<Rect>,
+}
+
+impl DockFrame {
+    fn node_ui<V: TabViewer>(
+        &mut self,
+        ui: &mut Ui,
+        viewer: &mut V,
+        node: &mut DockNode<V::Tab>,
+        path: &mut Vec<usize>,
+        rect: Rect,
+    ) {
+        match node {
+            DockNode::Tabs { tabs, active } => self.tabs_ui(ui, viewer, tabs, active, path, rect),
+            DockNode::Split {
+                direction,
+                fraction,
+                children,
+            } => {
+                let gap = ui.spacing().item_spacing.x;
+                let (split, rects) = match direction {
+                    SplitDirection::Horizontal => {
+                        let x = rect.left() + rect.width() * *fraction;
+                        let first = rect.with_max_x(x - 0.5 * gap);
+                        let second = rect.with_min_x(x + 0.5 * gap);
+                        (x, [first, second])
+                    }
+                    SplitDirection::Vertical => {
+                        let y = rect.top() + rect.height() * *fraction;
+                        let first = rect.with_max_y(y - 0.5 * gap);
+                        let second = rect.with_min_y(y + 0.5 * gap);
+                        (y, [first, second])
+                    }
+                };
+
+                for (i, (child, rect)) in children.iter_mut().zip(rects).enumerate() {
+                    path.push(i);
+                    self.node_ui(ui, viewer, child, path, rect);
+                    path.pop();
+                }
+
+                // After the children, so it is on top of them:
+                self.separator_ui(ui, *direction, fraction, split, path, rect);
+            }
+        }
+    }
+
+    fn separator_ui(
+        &self,
+        ui: &Ui,
+        direction: SplitDirection,
+        fraction: &mut f32,
+        split: f32,
+        path: &[usize],
+        rect: Rect,
+    ) {
+        let grab = ui.style().interaction.resize_grab_radius_side;
+        let (grab_rect, cursor) = match direction {
+            SplitDirection::Horizontal => (
+                Rect::from_x_y_ranges(split - grab..=split + grab, rect.y_range()),
+                CursorIcon::ResizeHorizontal,
+            ),
+            SplitDirection::Vertical => (
+                Rect::from_x_y_ranges(rect.x_range(), split - grab..=split + grab),
+                CursorIcon::ResizeVertical,
+            ),
+        };
+
+        let response = ui.interact(grab_rect, self.id.with(("split", path)), Sense::drag());
+        if let Some(pointer) = response
+            .interact_pointer_pos()
+            .filter(|_| response.dragged())
+        {
+            let t = match direction {
+                SplitDirection::Horizontal => (pointer.x - rect.left()) / rect.width(),
+                SplitDirection::Vertical => (pointer.y - rect.top()) / rect.height(),
+            };
+            *fraction = t.clamp(0.1, 0.9);
+        }
+
+        let stroke = if response.hovered() || response.dragged() {
+            ui.ctx().set_cursor_icon(cursor);
+            ui.visuals().widgets.hovered.fg_stroke
+        } else {
+            ui.visuals().widgets.noninteractive.bg_stroke
+        };
+        let painter = ui.painter();
+        match direction {
+            SplitDirection::Horizontal => painter.vline(split, rect.y_range(), stroke),
+            SplitDirection::Vertical => painter.hline(rect.x_range(), split, stroke),
+        };
+    }
+
+    fn tabs_ui<V: TabViewer>(
+        &mut self,
+        ui: &mut Ui,
+        viewer: &mut V,
+        tabs: &mut [V::Tab],
+        active: &mut usize,
+        path: &[usize],
+        rect: Rect,
+    ) {
+        let bar_height =
+            ui.text_style_height(&TextStyle::Button) + 2.0 * ui.spacing().button_padding.y + 2.0;
+        let (bar_rect, content_rect) = rect.split_top_bottom_at_y(rect.top() + bar_height);
+        ui.painter()
+            .rect_filled(bar_rect, 0.0, ui.visuals().extreme_bg_color);
+        ui.painter().hline(
+            bar_rect.x_range(),
+            bar_rect.bottom(),
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+
+        *active = (*active).min(tabs.len().saturating_sub(1));
+
+        let mut bar_ui = ui.child_ui(bar_rect, Layout::left_to_right(Align::Max));
+        bar_ui.set_clip_rect(bar_rect.intersect(ui.clip_rect()));
+        bar_ui.spacing_mut().item_spacing.x = 0.0;
+        let mut tab_rects = Vec::with_capacity(tabs.len());
+        for (index, tab) in tabs.iter_mut().enumerate() {
+            let at = TabPath {
+                path: path.to_vec(),
+                index,
+            };
+            let is_active = index == *active;
+            tab_rects.push(self.tab_ui(&mut bar_ui, viewer, tab, at, is_active, rect.size()));
+        }
+
+        if let Some(tab) = tabs.get_mut(*active) {
+            let tab_id = viewer.id(tab);
+            let mut content_ui = ui.child_ui_with_id_source(
+                content_rect - ui.spacing().window_margin,
+                Layout::top_down(Align::Min),
+                (self.id, tab_id),
+            );
+            content_ui.set_clip_rect(content_rect.intersect(ui.clip_rect()));
+            viewer.ui(&mut content_ui, tab);
+        }
+
+        self.drop_zones(
+            ui,
+            path,
+            tabs.len(),
+            rect,
+            bar_rect,
+            content_rect,
+            &tab_rects,
+        );
+    }
+
+    /// Returns the rectangle of the tab.
+    fn tab_ui<V: TabViewer>(
+        &mut self,
+        ui: &mut Ui,
+        viewer: &mut V,
+        tab: &mut V::Tab,
+        at: TabPath,
+        is_active: bool,
+        group_size: Vec2,
+    ) -> Rect {
+        let id = self.id.with(viewer.id(tab));
+        let closeable = viewer.closeable(tab);
+        let galley =
+            viewer
+                .title(tab)
+                .into_galley(ui, Some(false), f32::INFINITY, TextStyle::Button);
+
+        let padding = ui.spacing().button_padding;
+        let close_size = galley.size().y;
+        let mut width = galley.size().x + 2.0 * padding.x;
+        if closeable {
+            width += close_size + padding.x;
+        }
+        let height = ui.available_height() - 2.0;
+        let (rect, _) = ui.allocate_exact_size(vec2(width, height), Sense::hover());
+        let response = ui.interact(rect, id, Sense::click_and_drag());
+
+        if response.clicked() {
+            self.action = Some(Action::Activate(at.clone()));
+        }
+        if response.drag_started() {
+            response.dnd_set_drag_payload(TabDrag {
+                area_id: self.id,
+                from: at.clone(),
+                size: group_size,
+            });
+        }
+        response.context_menu(|ui| {
+            if closeable && ui.button("Close").clicked() {
+                self.action = Some(Action::Close(at.clone()));
+                ui.close_menu();
+            }
+            if self.allow_detach && ui.button("Undock").clicked() {
+                self.action = Some(Action::Detach {
+                    at: at.clone(),
+                    position: None,
+                    size: group_size,
+                });
+                ui.close_menu();
+            }
+        });
+
+        let visuals = ui.visuals();
+        let rounding = egui::Rounding {
+            nw: 4.0,
+            ne: 4.0,
+            ..Default::default()
+        };
+        if is_active {
+            ui.painter().rect(
+                rect,
+                rounding,
+                visuals.panel_fill,
+                visuals.widgets.noninteractive.bg_stroke,
+            );
+        } else if response.hovered() {
+            ui.painter()
+                .rect_filled(rect, rounding, visuals.widgets.hovered.weak_bg_fill);
+        }
+        let text_color = if is_active {
+            visuals.strong_text_color()
+        } else {
+            visuals.text_color()
+        };
+        let text_pos = pos2(
+            rect.left() + padding.x,
+            rect.center().y - 0.5 * galley.size().y,
+        );
+        ui.painter().galley(text_pos, galley, text_color);
+
+        if closeable {
+            let close_rect = Rect::from_center_size(
+                pos2(rect.right() - padding.x - 0.5 * close_size, rect.center().y),
+                Vec2::splat(close_size),
+            );
+            let close = ui.interact(close_rect, id.with("close"), Sense::click());
+            if close.clicked() {
+                self.action = Some(Action::Close(at));
+            }
+            if is_active || response.hovered() || close.hovered() {
+                let stroke = ui.style().interact(&close).fg_stroke;
+                let cross = close_rect.shrink(0.25 * close_size);
+                let painter = ui.painter();
+                painter.line_segment([cross.left_top(), cross.right_bottom()], stroke);
+                painter.line_segment([cross.right_top(), cross.left_bottom()], stroke);
+            }
+        }
+
+        rect
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn drop_zones(
+        &mut self,
+        ui: &Ui,
+        path: &[usize],
+        tab_count: usize,
+        rect: Rect,
+        bar_rect: Rect,
+        content_rect: Rect,
+        tab_rects: &[Rect],
+    ) {
+        let response = ui.interact(rect, self.id.with(("drop", path)), Sense::hover());
+        let Some(payload) = response.dnd_hover_payload::<TabDrag>() else {
+            return;
+        };
+        let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+            return;
+        };
+        if payload.area_id != self.id {
+            return;
+        }
+
+        let (zone, preview) = if bar_rect.contains(pointer) {
+            let index = tab_rects
+                .iter()
+                .position(|tab| pointer.x < tab.center().x)
+                .unwrap_or(tab_rects.len());
+            let x = match tab_rects.get(index) {
+                Some(tab) => tab.left(),
+                None => tab_rects.last().map_or(bar_rect.left(), |tab| tab.right()),
+            };
+            let marker = Rect::from_x_y_ranges(x - 1.0..=x + 1.0, bar_rect.y_range());
+            (DropZone::Tab(index), marker)
+        } else {
+            let t = (pointer - content_rect.min) / content_rect.size();
+            let edges = [
+                (t.x, DropZone::Left),
+                (1.0 - t.x, DropZone::Right),
+                (t.y, DropZone::Top),
+                (1.0 - t.y, DropZone::Bottom),
+            ];
+            let (distance, edge) = edges
+                .into_iter()
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .unwrap_or((1.0, DropZone::Center));
+            let zone = if distance < 0.25 {
+                edge
+            } else {
+                DropZone::Center
+            };
+
+            // Moving the only tab of a group within that group does nothing:
+            if payload.from.path == path && tab_count == 1 {
+                return;
+            }
+
+            let center = content_rect.center();
+            let preview = match zone {
+                DropZone::Left => content_rect.with_max_x(center.x),
+                DropZone::Right => content_rect.with_min_x(center.x),
+                DropZone::Top => content_rect.with_max_y(center.y),
+                DropZone::Bottom => content_rect.with_min_y(center.y),
+                DropZone::Tab(_) | DropZone::Center => content_rect,
+            };
+            (zone, preview)
+        };
+        self.drop_preview = Some(preview);
+
+        if response.dnd_release_payload::<TabDrag>().is_some() {
+            self.action = Some(Action::Move {
+                from: payload.from.clone(),
+                to: DropTarget {
+                    path: path.to_vec(),
+                    zone,
+                },
+            });
+        }
+    }
+
+    /// Detach a tab that was dropped outside the dock area.
+    fn drop_outside(&mut self, ui: &Ui, rect: Rect) {
+        let ctx = ui.ctx();
+        if !ctx.input(|i| i.pointer.any_released()) {
+            return;
+        }
+        let Some(payload) = DragAndDrop::payload::<TabDrag>(ctx) else {
+            return;
+        };
+        let pointer = ctx.input(|i| i.pointer.latest_pos());
+        if payload.area_id != self.id || pointer.is_some_and(|pos| rect.contains(pos)) {
+            return;
+        }
+
+        DragAndDrop::clear_payload(ctx);
+        let window_pos = ctx.input(|i| i.viewport().inner_rect.map(|r| r.min));
+        let position = pointer.map(|pos| window_pos.unwrap_or_default() + pos.to_vec2());
+        self.action = Some(Action::Detach {
+            at: payload.from.clone(),
+            position,
+            size: payload.size,
+        });
+    }
+
+    fn detached_ui<V: TabViewer>(
+        &mut self,
+        ctx: &Context,
+        viewer: &mut V,
+        detached: &mut [DetachedTab<V::Tab>],
+    ) {
+        for (index, window) in detached.iter_mut().enumerate() {
+            let tab_id = viewer.id(&mut window.tab);
+            let window_id = self.id.with(("detached", tab_id));
+            let title = viewer.title(&mut window.tab).text().to_owned();
+
+            let mut builder = ViewportBuilder::default()
+                .with_title(title.clone())
+                .with_inner_size(window.size);
+            if let Some(position) = window.position {
+                builder = builder.with_position(position);
+            }
+
+            let redock =
+                ctx.show_viewport_immediate(ViewportId(window_id), builder, |ctx, class| {
+                    if class == ViewportClass::Embedded {
+                        // Not a real viewport, so use a window within the parent viewport:
+                        let mut open = true;
+                        let mut redock = false;
+                        let mut egui_window = Window::new(title)
+                            .id(window_id)
+                            .open(&mut open)
+                            .default_size(window.size);
+                        if let Some(position) = window.position {
+                            let window_pos = ctx.input(|i| i.viewport().inner_rect.map(|r| r.min));
+                            egui_window = egui_window
+                                .default_pos(position - window_pos.unwrap_or_default().to_vec2());
+                        }
+                        egui_window.show(ctx, |ui| {
+                            redock = ui.button("Dock").clicked();
+                            ui.separator();
+                            viewer.ui(ui, &mut window.tab);
+                        });
+                        redock || !open
+                    } else {
+                        let mut redock = ctx.input(|i| i.viewport().close_requested());
+                        TopBottomPanel::top(window_id.with("bar")).show(ctx, |ui| {
+                            redock |= ui.button("Dock").clicked();
+                        });
+                        CentralPanel::default().show(ctx, |ui| {
+                            viewer.ui(ui, &mut window.tab);
+                        });
+                        redock
+                    }
+                });
+            if redock {
+                self.action = Some(Action::Redock(index));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(node: &DockNode<&'static str>) -> String {
+        match node {
+            DockNode::Tabs { tabs, active } => format!("{}:{active}", tabs.join(",")),
+            DockNode::Split {
+                direction,
+                children,
+                ..
+            } => format!(
+                "{direction:?}({} | {})",
+                layout(&children[0]),
+                layout(&children[1])
+            ),
+        }
+    }
+
+    fn move_tab(state: &mut DockState<&'static str>, from: (&[usize], usize), to: DropTarget) {
+        let from = TabPath {
+            path: from.0.to_vec(),
+            index: from.1,
+        };
+        state.move_tab(&from, to);
+        state.remove_empty();
+    }
+
+    #[test]
+    fn moving_tabs_splits_and_collapses() {
+        let mut state = DockState::new(vec!["a", "b", "c"]);
+
+        move_tab(
+            &mut state,
+            (&[], 1),
+            DropTarget {
+                path: vec![],
+                zone: DropZone::Right,
+            },
+        );
+        assert_eq!(layout(state.root().unwrap()), "Horizontal(a,c:0 | b:0)");
+
+        move_tab(
+            &mut state,
+            (&[0], 0),
+            DropTarget {
+                path: vec![1],
+                zone: DropZone::Bottom,
+            },
+        );
+        assert_eq!(
+            layout(state.root().unwrap()),
+            "Horizontal(c:0 | Vertical(b:0 | a:0))"
+        );
+
+        // Moving the last tab out of a group removes the group:
+        move_tab(
+            &mut state,
+            (&[0], 0),
+            DropTarget {
+                path: vec![1, 0],
+                zone: DropZone::Tab(0),
+            },
+        );
+        assert_eq!(layout(state.root().unwrap()), "Vertical(c,b:0 | a:0)");
+
+        // Reordering within a group:
+        move_tab(
+            &mut state,
+            (&[0], 0),
+            DropTarget {
+                path: vec![0],
+                zone: DropZone::Tab(2),
+            },
+        );
+        assert_eq!(layout(state.root().unwrap()), "Vertical(b,c:1 | a:0)");
+
+        // Splitting a group by its only tab changes nothing:
+        move_tab(
+            &mut state,
+            (&[1], 0),
+            DropTarget {
+                path: vec![1],
+                zone: DropZone::Left,
+            },
+        );
+        assert_eq!(layout(state.root().unwrap()), "Vertical(b,c:1 | a:0)");
+        assert_eq!(state.tabs().count(), 3);
+    }
+}
@@ -16,24 +16,32 @@ mod datepicker;
 
 pub mod syntax_highlighting;
 
+mod data_table;
+mod dock;
 #[doc(hidden)]
 pub mod image;
 mod layout;
 mod loaders;
+mod selection;
 mod sizing;
 mod strip;
 mod table;
+mod tree_view;
 
 #[cfg(feature = "chrono")]
 pub use crate::datepicker::DatePickerButton;
 
+pub use crate::data_table::*;
+pub use crate::dock::*;
 #[doc(hidden)]
 #[allow(deprecated)]
 pub use crate::image::RetainedImage;
 pub(crate) use crate::layout::StripLayout;
+pub use crate::selection::SelectionMode;
 pub use crate::sizing::Size;
 pub use crate::strip::*;
 pub use crate::table::*;
+pub use crate::tree_view::*;
 
 pub use loaders::install_image_loaders;
 
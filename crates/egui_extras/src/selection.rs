@@ -0,0 +1,263 @@
+//! Selecting items of a list with the mouse and keyboard, shared by [`crate::DataTable`] and [`crate::TreeView`].
+
+use std::hash::Hash;
+
+use egui::{ahash::HashSet, Key, Modifiers, Ui};
+
+/// Which rows of a [`crate::DataTable`] or nodes of a [`crate::TreeView`] can be selected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum SelectionMode {
+    /// Nothing can be selected.
+    None,
+
+    /// At most one item is selected at a time.
+    #[default]
+    Single,
+
+    /// Several items can be selected:
+    /// ctrl/cmd-click toggles an item, shift-click or shift+arrow keys select a range of items,
+    /// and ctrl/cmd+A selects all shown items.
+    Multiple,
+}
+
+/// The items that are shown, in order, which ranges are selected from and the cursor moves through.
+pub(crate) trait ShownItems<T> {
+    fn num_items(&self) -> usize;
+
+    fn item(&self, index: usize) -> &T;
+
+    /// Where the item is shown, if it is.
+    fn index_of(&self, item: &T) -> Option<usize>;
+}
+
+impl ShownItems<usize> for [usize] {
+    fn num_items(&self) -> usize {
+        self.len()
+    }
+
+    fn item(&self, index: usize) -> &usize {
+        &self[index]
+    }
+
+    fn index_of(&self, item: &usize) -> Option<usize> {
+        self.iter().position(|i| i == item)
+    }
+}
+
+/// The selected items, and the keyboard cursor.
+#[derive(Clone, Debug)]
+pub(crate) struct Selection<T> {
+    /// In the order they were selected.
+    selected: Vec<T>,
+
+    /// The same as [`Self::selected`], to quickly find out if an item is selected.
+    selected_set: HashSet<T>,
+
+    /// Where a range selection starts, i.e. the last item selected without shift.
+    anchor: Option<T>,
+
+    /// The item moved by the arrow keys.
+    cursor: Option<T>,
+}
+
+impl<T> Default for Selection<T> {
+    fn default() -> Self {
+        Self {
+            selected: Vec::new(),
+            selected_set: Default::default(),
+            anchor: None,
+            cursor: None,
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Selection<T> {
+    /// The selected items, in the order they were selected.
+    pub fn selected(&self) -> &[T] {
+        &self.selected
+    }
+
+    pub fn is_selected(&self, item: &T) -> bool {
+        self.selected_set.contains(item)
+    }
+
+    /// Are the same items selected, in any order?
+    pub fn same_as(&self, other: &Self) -> bool {
+        self.selected_set == other.selected_set
+    }
+
+    pub fn cursor(&self) -> Option<&T> {
+        self.cursor.as_ref()
+    }
+
+    /// Select these items, and only these.
+    pub fn set_selected(&mut self, selected: impl IntoIterator<Item = T>) {
+        self.clear();
+        for item in selected {
+            self.select(item);
+        }
+        self.anchor = self.selected.last().cloned();
+        self.cursor = self.anchor.clone();
+    }
+
+    fn select(&mut self, item: T) {
+        if self.selected_set.insert(item.clone()) {
+            self.selected.push(item);
+        }
+    }
+
+    fn deselect(&mut self, item: &T) {
+        if self.selected_set.remove(item) {
+            self.selected.retain(|i| i != item);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.selected.clear();
+        self.selected_set.clear();
+    }
+
+    fn select_only(&mut self, item: T) {
+        self.clear();
+        self.select(item.clone());
+        self.anchor = Some(item);
+    }
+
+    /// Select the shown items from the anchor to `index`.
+    fn select_range(&mut self, shown: &(impl ShownItems<T> + ?Sized), index: usize, add: bool) {
+        let anchor = self
+            .anchor
+            .as_ref()
+            .and_then(|anchor| shown.index_of(anchor))
+            .unwrap_or(index);
+        if !add {
+            self.clear();
+        }
+        for i in anchor.min(index)..=anchor.max(index) {
+            self.select(shown.item(i).clone());
+        }
+    }
+
+    /// Where the cursor is shown, if it is.
+    pub fn cursor_index(&self, shown: &(impl ShownItems<T> + ?Sized)) -> Option<usize> {
+        shown.index_of(self.cursor.as_ref()?)
+    }
+
+    /// A click on the shown item with this index.
+    pub fn click(
+        &mut self,
+        shown: &(impl ShownItems<T> + ?Sized),
+        index: usize,
+        modifiers: Modifiers,
+        mode: SelectionMode,
+    ) {
+        let item = shown.item(index).clone();
+        match mode {
+            SelectionMode::None => {}
+            SelectionMode::Single => self.select_only(item.clone()),
+            SelectionMode::Multiple => {
+                if modifiers.shift {
+                    self.select_range(shown, index, modifiers.command);
+                } else if modifiers.command {
+                    if self.is_selected(&item) {
+                        self.deselect(&item);
+                    } else {
+                        self.select(item.clone());
+                    }
+                    self.anchor = Some(item.clone());
+                } else {
+                    self.select_only(item.clone());
+                }
+            }
+        }
+        self.cursor = Some(item);
+    }
+
+    /// Move the cursor to the shown item with this index, selecting it.
+    ///
+    /// With `extend`, the range from the anchor is selected instead.
+    pub fn move_cursor(
+        &mut self,
+        shown: &(impl ShownItems<T> + ?Sized),
+        index: usize,
+        extend: bool,
+        mode: SelectionMode,
+    ) {
+        let item = shown.item(index).clone();
+        match mode {
+            SelectionMode::None => {}
+            SelectionMode::Single => self.select_only(item.clone()),
+            SelectionMode::Multiple => {
+                if extend {
+                    self.select_range(shown, index, false);
+                } else {
+                    self.select_only(item.clone());
+                }
+            }
+        }
+        self.cursor = Some(item);
+    }
+
+    /// Up/down/home/end move the cursor (with shift to select a range), space toggles the item at the cursor,
+    /// ctrl/cmd+A selects all shown items, and enter activates the item at the cursor.
+    ///
+    /// Returns the new index of the cursor if it moved, and the activated item.
+    pub fn handle_keyboard(
+        &mut self,
+        ui: &Ui,
+        shown: &(impl ShownItems<T> + ?Sized),
+        mode: SelectionMode,
+    ) -> (Option<usize>, Option<T>) {
+        let Some(last) = shown.num_items().checked_sub(1) else {
+            return (None, None);
+        };
+        let cursor = self.cursor_index(shown);
+        let (modifiers, pressed) = ui.input(|i| {
+            let pressed = [
+                Key::ArrowUp,
+                Key::ArrowDown,
+                Key::Home,
+                Key::End,
+                Key::Enter,
+                Key::Space,
+                Key::A,
+            ]
+            .map(|key| i.key_pressed(key));
+            (i.modifiers, pressed)
+        });
+        let [up, down, home, end, enter, space, a] = pressed;
+
+        let mut new_cursor = None;
+        if up {
+            new_cursor = Some(cursor.map_or(0, |c| c.saturating_sub(1)));
+        }
+        if down {
+            new_cursor = Some(cursor.map_or(0, |c| (c + 1).min(last)));
+        }
+        if home {
+            new_cursor = Some(0);
+        }
+        if end {
+            new_cursor = Some(last);
+        }
+        let mut activated = None;
+        if let Some(c) = cursor {
+            if enter {
+                activated = Some(shown.item(c).clone());
+            }
+            if space && mode == SelectionMode::Multiple {
+                self.click(shown, c, Modifiers::COMMAND, mode);
+            }
+        }
+        if a && modifiers.command && mode == SelectionMode::Multiple {
+            for i in 0..=last {
+                self.select(shown.item(i).clone());
+            }
+        }
+
+        if let Some(index) = new_cursor {
+            self.move_cursor(shown, index, modifiers.shift, mode);
+        }
+        (new_cursor, activated)
+    }
+}
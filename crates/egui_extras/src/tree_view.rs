@@ -0,0 +1,981 @@
+//! A tree of collapsible rows, like a file browser or a scene graph.
+//!
+//! Only the rows in view are shown, and children are only asked for when their parent is expanded,
+//! so this works with very large (and lazily loaded) trees.
+
+use std::{hash::Hash, sync::Arc};
+
+use egui::{
+    ahash::{HashMap, HashSet},
+    collapsing_header::paint_default_icon,
+    pos2, vec2, Align, Context, EventFilter, Id, Key, Layout, Rect, Response, ScrollArea, Sense,
+    Shape, Stroke, Ui,
+};
+
+use crate::{
+    selection::{Selection, ShownItems},
+    SelectionMode,
+};
+
+/// The nodes shown by a [`TreeView`].
+///
+/// Children are only asked for when their parent is expanded,
+/// so they can be loaded lazily, e.g. when browsing a file system.
+///
+/// The shown nodes are remembered, and only asked for again when a node is expanded or collapsed,
+/// the roots change, or nodes were dropped somewhere else with drag-and-drop.
+/// Call [`TreeViewState::refresh_rows`] when you change the children of a shown node yourself.
+pub trait TreeNodes {
+    /// Identifies a node.
+    ///
+    /// Must be unique within the tree, and stay the same from one frame to the next.
+    type NodeId: Clone + Eq + Hash + Send + Sync + 'static;
+
+    /// The top-level nodes, in order.
+    fn roots(&mut self) -> Vec<Self::NodeId>;
+
+    /// Does this node have any children?
+    ///
+    /// This decides if the node gets an expand button, and is called for every shown node,
+    /// so it should be cheap.
+    fn has_children(&mut self, node: &Self::NodeId) -> bool;
+
+    /// The children of this node, in order.
+    ///
+    /// Only called for expanded nodes.
+    fn children(&mut self, node: &Self::NodeId) -> Vec<Self::NodeId>;
+
+    /// Show the contents of the row of this node, e.g. an icon and a label.
+    ///
+    /// The [`Ui`] covers the part of the row to the right of the expand button.
+    fn row_ui(&mut self, ui: &mut Ui, node: &Self::NodeId);
+}
+
+/// Nodes of a [`TreeView`] that the user dragged to a new place.
+///
+/// The tree view doesn't move anything itself, so apply the move to your data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeDrop<NodeId> {
+    /// The dragged nodes, in the order they are shown.
+    ///
+    /// This is all the selected nodes if the user dragged one of them, otherwise just the dragged node.
+    pub nodes: Vec<NodeId>,
+
+    /// The new parent of the nodes, or `None` for the top level.
+    pub parent: Option<NodeId>,
+
+    /// Where among the children of `parent` to insert the nodes.
+    ///
+    /// This counts the children as they were before the move.
+    pub index: usize,
+}
+
+/// What happened in a [`TreeView`] this frame.
+pub struct TreeViewResponse<NodeId> {
+    /// The tree view as a whole.
+    ///
+    /// Its id is where the state of the tree view is stored, see [`TreeViewState::load`].
+    pub response: Response,
+
+    /// The selected nodes.
+    pub selected: Vec<NodeId>,
+
+    /// Did the user change the selection this frame?
+    pub selection_changed: bool,
+
+    /// A node the user double-clicked, or pressed enter on.
+    pub activated: Option<NodeId>,
+
+    /// Nodes the user dragged to a new place, if drag-and-drop is enabled with [`TreeView::drag_and_drop`].
+    pub dropped: Option<TreeDrop<NodeId>>,
+}
+
+// ----------------------------------------------------------------------------
+
+/// Which nodes of a [`TreeView`] are expanded and selected.
+///
+/// This is stored in [`egui::Memory`] for you,
+/// but you can load and change it to e.g. select a node from code:
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let tree_id = egui::Id::new("tree");
+/// let mut state = egui_extras::TreeViewState::<u32>::load(ui.ctx(), tree_id).unwrap_or_default();
+/// state.set_expanded(7, true);
+/// state.set_selected(vec![8]);
+/// state.store(ui.ctx(), tree_id);
+/// # });
+/// ```
+#[derive(Clone, Debug)]
+pub struct TreeViewState<NodeId> {
+    expanded: HashSet<NodeId>,
+
+    selection: Selection<NodeId>,
+
+    /// The shown rows, until a node is expanded or collapsed, or the roots change.
+    cached_rows: Option<Arc<ShownRows<NodeId>>>,
+}
+
+impl<NodeId> Default for TreeViewState<NodeId> {
+    fn default() -> Self {
+        Self {
+            expanded: Default::default(),
+            selection: Default::default(),
+            cached_rows: None,
+        }
+    }
+}
+
+impl<NodeId: Clone + Eq + Hash + Send + Sync + 'static> TreeViewState<NodeId> {
+    /// Load the state of the tree view with this id, see [`TreeViewResponse::response`].
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_temp(id))
+    }
+
+    /// Store the state of the tree view with this id.
+    pub fn store(self, ctx: &Context, id: Id) {
+        ctx.data_mut(|d| d.insert_temp(id, self));
+    }
+
+    /// Are the children of this node shown?
+    pub fn is_expanded(&self, node: &NodeId) -> bool {
+        self.expanded.contains(node)
+    }
+
+    /// Show or hide the children of this node.
+    pub fn set_expanded(&mut self, node: NodeId, expanded: bool) {
+        let changed = if expanded {
+            self.expanded.insert(node)
+        } else {
+            self.expanded.remove(&node)
+        };
+        if changed {
+            self.refresh_rows();
+        }
+    }
+
+    /// The selected nodes, in the order they were selected.
+    pub fn selected(&self) -> &[NodeId] {
+        self.selection.selected()
+    }
+
+    pub fn is_selected(&self, node: &NodeId) -> bool {
+        self.selection.is_selected(node)
+    }
+
+    /// Select these nodes, and only these.
+    pub fn set_selected(&mut self, selected: Vec<NodeId>) {
+        self.selection.set_selected(selected);
+    }
+
+    /// Ask the [`TreeNodes`] for the shown nodes again the next frame.
+    ///
+    /// A [`TreeView`] only does so by itself when a node is expanded or collapsed,
+    /// the roots change, or nodes were dropped somewhere else,
+    /// so call this when you change the children of a shown node.
+    pub fn refresh_rows(&mut self) {
+        self.cached_rows = None;
+    }
+
+    /// The shown rows, reusing the ones from the previous frame if nothing changed.
+    fn cached_rows<N: TreeNodes<NodeId = NodeId>>(
+        &mut self,
+        nodes: &mut N,
+    ) -> Arc<ShownRows<NodeId>> {
+        let roots = nodes.roots();
+        if let Some(cached) = &self.cached_rows {
+            if cached.roots == roots {
+                return cached.clone();
+            }
+        }
+
+        crate::profile_function!();
+        let mut shown = ShownRows {
+            roots: roots.clone(),
+            rows: Vec::new(),
+            indices: Default::default(),
+        };
+        self.add_rows(nodes, roots, 0, None, &mut shown);
+        let shown = Arc::new(shown);
+        self.cached_rows = Some(shown.clone());
+        shown
+    }
+
+    fn add_rows<N: TreeNodes<NodeId = NodeId>>(
+        &self,
+        nodes: &mut N,
+        siblings: Vec<NodeId>,
+        depth: usize,
+        parent: Option<usize>,
+        shown: &mut ShownRows<NodeId>,
+    ) {
+        for (index_in_parent, node) in siblings.into_iter().enumerate() {
+            let has_children = nodes.has_children(&node);
+            let expanded = has_children && self.is_expanded(&node);
+            let index = shown.rows.len();
+            let children = expanded.then(|| nodes.children(&node));
+            shown.indices.insert(node.clone(), index);
+            shown.rows.push(Row {
+                node,
+                depth,
+                parent,
+                index_in_parent,
+                has_children,
+                expanded,
+            });
+            if let Some(children) = children {
+                self.add_rows(nodes, children, depth + 1, Some(index), shown);
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A tree of collapsible rows with selection, keyboard navigation and drag-and-drop.
+///
+/// The nodes come from your implementation of [`TreeNodes`].
+/// Only the rows that are in view are shown, inside a [`ScrollArea`].
+///
+/// When the tree view has keyboard focus (after a click, or with tab),
+/// up/down moves between rows, right expands a node (or moves to its first child),
+/// left collapses a node (or moves to its parent), and enter activates a node.
+///
+/// ```
+/// use egui_extras::{TreeNodes, TreeView};
+///
+/// /// Each node has the children `10 * node + 1 ..= 10 * node + 3`.
+/// struct Numbers;
+///
+/// impl TreeNodes for Numbers {
+///     type NodeId = u64;
+///
+///     fn roots(&mut self) -> Vec<u64> {
+///         vec![1, 2, 3]
+///     }
+///
+///     fn has_children(&mut self, node: &u64) -> bool {
+///         *node < 1_000_000
+///     }
+///
+///     fn children(&mut self, node: &u64) -> Vec<u64> {
+///         (1..=3).map(|i| 10 * node + i).collect()
+///     }
+///
+///     fn row_ui(&mut self, ui: &mut egui::Ui, node: &u64) {
+///         ui.label(node.to_string());
+///     }
+/// }
+///
+/// # egui::__run_test_ui(|ui| {
+/// let response = TreeView::new("numbers").show(ui, &mut Numbers);
+/// if let Some(node) = response.activated {
+///     println!("Opened {node}");
+/// }
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+pub struct TreeView {
+    id_source: Id,
+    row_height: Option<f32>,
+    indent: Option<f32>,
+    selection_mode: SelectionMode,
+    drag_and_drop: bool,
+    max_height: f32,
+}
+
+impl TreeView {
+    pub fn new(id_source: impl Hash) -> Self {
+        Self {
+            id_source: Id::new(id_source),
+            row_height: None,
+            indent: None,
+            selection_mode: SelectionMode::default(),
+            drag_and_drop: false,
+            max_height: f32::INFINITY,
+        }
+    }
+
+    /// The height of each row.
+    ///
+    /// Default: [`egui::style::Spacing::interact_size`]`.y`.
+    #[inline]
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = Some(row_height);
+        self
+    }
+
+    /// How far each level of the tree is indented.
+    ///
+    /// Default: [`egui::style::Spacing::indent`].
+    #[inline]
+    pub fn indent(mut self, indent: f32) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    /// Default: [`SelectionMode::Single`].
+    #[inline]
+    pub fn selection_mode(mut self, selection_mode: SelectionMode) -> Self {
+        self.selection_mode = selection_mode;
+        self
+    }
+
+    /// Let the user drag nodes to a new place in the tree, see [`TreeViewResponse::dropped`].
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn drag_and_drop(mut self, drag_and_drop: bool) -> Self {
+        self.drag_and_drop = drag_and_drop;
+        self
+    }
+
+    /// The maximum height of the tree view, after which it scrolls.
+    ///
+    /// Default: [`f32::INFINITY`], i.e. the available height.
+    #[inline]
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    pub fn show<N: TreeNodes>(self, ui: &mut Ui, nodes: &mut N) -> TreeViewResponse<N::NodeId> {
+        crate::profile_function!();
+
+        let id = ui.make_persistent_id(self.id_source);
+        let mut state = TreeViewState::load(ui.ctx(), id).unwrap_or_default();
+        let selected_before = state.selection.clone();
+        let shown = state.cached_rows(nodes);
+        let mut tree = TreeFrame {
+            id,
+            shown,
+            state,
+            activated: None,
+            dropped: None,
+            drop_indicator: None,
+            scroll_to_cursor: false,
+        };
+
+        ui.memory_mut(|mem| {
+            mem.interested_in_focus(id);
+            let event_filter = EventFilter {
+                horizontal_arrows: true,
+                vertical_arrows: true,
+                ..Default::default()
+            };
+            mem.set_focus_lock_filter(id, event_filter);
+        });
+        let has_focus = ui.memory(|mem| mem.has_focus(id));
+        if has_focus && tree.handle_keyboard(ui, self.selection_mode) {
+            tree.shown = tree.state.cached_rows(nodes);
+        }
+
+        let row_height = self
+            .row_height
+            .unwrap_or_else(|| ui.spacing().interact_size.y);
+        let output = ScrollArea::vertical()
+            .id_source(id)
+            .max_height(self.max_height)
+            .auto_shrink([false, true])
+            .show_viewport(ui, |ui, viewport| {
+                ui.set_height(row_height * tree.shown.rows.len() as f32);
+
+                let x_range = ui.max_rect().x_range();
+                let top = ui.max_rect().top();
+                let row_rect = |index: usize| {
+                    let y = top + index as f32 * row_height;
+                    Rect::from_x_y_ranges(x_range, y..=y + row_height)
+                };
+
+                if tree.scroll_to_cursor {
+                    if let Some(cursor) = tree.cursor_index() {
+                        ui.scroll_to_rect(row_rect(cursor), None);
+                    }
+                }
+
+                let end =
+                    ((viewport.max.y / row_height).ceil() as usize).min(tree.shown.rows.len());
+                let start = ((viewport.min.y / row_height).floor() as usize).min(end);
+                for index in start..end {
+                    tree.show_row(ui, &self, nodes, index, row_rect(index), has_focus);
+                }
+
+                if let Some(indicator) = tree.drop_indicator.take() {
+                    ui.painter().add(indicator);
+                }
+            });
+
+        // Gives the tree view keyboard focus, without covering the rows:
+        let response = ui.interact(output.inner_rect, id, Sense::focusable_noninteractive());
+
+        let selection_changed = !tree.state.selection.same_as(&selected_before);
+        let selected = tree.state.selected().to_vec();
+        let TreeFrame {
+            mut state,
+            activated,
+            dropped,
+            ..
+        } = tree;
+        if dropped.is_some() {
+            state.refresh_rows(); // The nodes are probably moved before the next frame.
+        }
+        state.store(ui.ctx(), id);
+
+        TreeViewResponse {
+            response,
+            selected,
+            selection_changed,
+            activated,
+            dropped,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A shown node of the tree.
+#[derive(Debug)]
+struct Row<NodeId> {
+    node: NodeId,
+    depth: usize,
+
+    /// Index of the row of the parent.
+    parent: Option<usize>,
+
+    /// Index among the children of the parent.
+    index_in_parent: usize,
+
+    has_children: bool,
+    expanded: bool,
+}
+
+/// The shown nodes of a [`TreeView`], and the roots they were gathered from.
+#[derive(Debug)]
+struct ShownRows<NodeId> {
+    roots: Vec<NodeId>,
+
+    /// The roots and the descendants of expanded nodes, depth first.
+    rows: Vec<Row<NodeId>>,
+
+    /// The index of the row of each node.
+    indices: HashMap<NodeId, usize>,
+}
+
+impl<NodeId: Eq + Hash> ShownItems<NodeId> for ShownRows<NodeId> {
+    fn num_items(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn item(&self, index: usize) -> &NodeId {
+        &self.rows[index].node
+    }
+
+    fn index_of(&self, node: &NodeId) -> Option<usize> {
+        self.indices.get(node).copied()
+    }
+}
+
+/// Where a dragged node is dropped, relative to a row.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DropPlace {
+    Before,
+    Into,
+    After,
+}
+
+/// What is being dragged.
+struct DragPayload<NodeId> {
+    /// The tree view it's being dragged from.
+    tree_id: Id,
+    nodes: Vec<NodeId>,
+}
+
+/// The state of a [`TreeView`] while it is being shown.
+struct TreeFrame<NodeId> {
+    id: Id,
+
+    shown: Arc<ShownRows<NodeId>>,
+
+    state: TreeViewState<NodeId>,
+    activated: Option<NodeId>,
+    dropped: Option<TreeDrop<NodeId>>,
+
+    /// Painted on top of all rows.
+    drop_indicator: Option<Shape>,
+
+    scroll_to_cursor: bool,
+}
+
+impl<NodeId: Clone + Eq + Hash + Send + Sync + 'static> TreeFrame<NodeId> {
+    fn cursor_index(&self) -> Option<usize> {
+        self.state.selection.cursor_index(&*self.shown)
+    }
+
+    /// Left/right expand and collapse nodes, or move to the first child or the parent,
+    /// the rest of the keys are handled by [`Selection::handle_keyboard`].
+    ///
+    /// Returns `true` if a node was expanded or collapsed.
+    fn handle_keyboard(&mut self, ui: &Ui, mode: SelectionMode) -> bool {
+        let shown = self.shown.clone();
+        let (left, right, shift) = ui.input(|i| {
+            (
+                i.key_pressed(Key::ArrowLeft),
+                i.key_pressed(Key::ArrowRight),
+                i.modifiers.shift,
+            )
+        });
+
+        let mut new_cursor = None;
+        let mut expansion_changed = false;
+        if let Some(c) = self.cursor_index() {
+            let row = &shown.rows[c];
+            if right {
+                if row.has_children && !row.expanded {
+                    self.state.set_expanded(row.node.clone(), true);
+                    expansion_changed = true;
+                } else if row.expanded && c + 1 < shown.rows.len() {
+                    new_cursor = Some(c + 1);
+                }
+            }
+            if left {
+                if row.expanded {
+                    self.state.set_expanded(row.node.clone(), false);
+                    expansion_changed = true;
+                } else if let Some(parent) = row.parent {
+                    new_cursor = Some(parent);
+                }
+            }
+        }
+        if let Some(index) = new_cursor {
+            self.state
+                .selection
+                .move_cursor(&*shown, index, shift, mode);
+        }
+
+        let (moved, activated) = self.state.selection.handle_keyboard(ui, &*shown, mode);
+        self.scroll_to_cursor = new_cursor.is_some() || moved.is_some();
+        if activated.is_some() {
+            self.activated = activated;
+        }
+        expansion_changed
+    }
+
+    fn show_row<N: TreeNodes<NodeId = NodeId>>(
+        &mut self,
+        ui: &mut Ui,
+        view: &TreeView,
+        nodes: &mut N,
+        index: usize,
+        rect: Rect,
+        has_focus: bool,
+    ) {
+        let shown = self.shown.clone();
+        let row = &shown.rows[index];
+        let row_id = self.id.with(&row.node);
+
+        let mut sense = if view.drag_and_drop {
+            Sense::click_and_drag()
+        } else {
+            Sense::click()
+        };
+        sense.focusable = false; // The tree view as a whole has the keyboard focus.
+        let response = ui.interact(rect, row_id, sense);
+
+        let is_selected = self.state.is_selected(&row.node);
+        let is_cursor = self.state.selection.cursor() == Some(&row.node);
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.visuals();
+            if is_selected {
+                ui.painter()
+                    .rect_filled(rect, 2.0, visuals.selection.bg_fill);
+            } else if response.hovered() {
+                let fill = visuals.widgets.hovered.weak_bg_fill;
+                ui.painter().rect_filled(rect, 2.0, fill);
+            }
+            if has_focus && is_cursor {
+                let stroke = visuals.widgets.hovered.fg_stroke;
+                ui.painter().rect_stroke(rect.shrink(1.0), 2.0, stroke);
+            }
+        }
+
+        let indent = view.indent.unwrap_or_else(|| ui.spacing().indent);
+        let icon_width = ui.spacing().icon_width;
+        let left = rect.left() + row.depth as f32 * indent;
+        if row.has_children {
+            let icon_rect = Rect::from_center_size(
+                pos2(left + icon_width / 2.0, rect.center().y),
+                vec2(icon_width, icon_width),
+            );
+            let mut sense = Sense::click();
+            sense.focusable = false;
+            let icon_response = ui.interact(icon_rect, row_id.with("expand"), sense);
+            if icon_response.clicked() {
+                let expanded = !row.expanded;
+                self.state.set_expanded(row.node.clone(), expanded);
+                ui.ctx().request_repaint();
+            }
+            let openness = ui.ctx().animate_bool(icon_response.id, row.expanded);
+            paint_default_icon(ui, openness, &icon_response);
+        }
+
+        let content_left = left + icon_width + ui.spacing().icon_spacing;
+        let content_rect = Rect::from_x_y_ranges(content_left..=rect.right(), rect.y_range());
+        let mut content_ui =
+            ui.child_ui_with_id_source(content_rect, Layout::left_to_right(Align::Center), row_id);
+        content_ui.set_clip_rect(content_rect.intersect(ui.clip_rect()));
+        if is_selected {
+            content_ui.visuals_mut().override_text_color =
+                Some(ui.visuals().selection.stroke.color);
+        }
+        nodes.row_ui(&mut content_ui, &row.node);
+
+        if response.clicked() {
+            let modifiers = ui.input(|i| i.modifiers);
+            self.state
+                .selection
+                .click(&*shown, index, modifiers, view.selection_mode);
+            ui.memory_mut(|mem| mem.request_focus(self.id));
+        }
+        if response.double_clicked() {
+            self.activated = Some(row.node.clone());
+        }
+
+        if view.drag_and_drop {
+            self.drag_and_drop(ui, nodes, index, rect, indent, &response);
+        }
+    }
+
+    fn drag_and_drop<N: TreeNodes<NodeId = NodeId>>(
+        &mut self,
+        ui: &Ui,
+        nodes: &mut N,
+        index: usize,
+        rect: Rect,
+        indent: f32,
+        response: &Response,
+    ) {
+        let shown = self.shown.clone();
+        let row = &shown.rows[index];
+        if response.drag_started() {
+            let dragged = if self.state.is_selected(&row.node) {
+                shown
+                    .rows
+                    .iter()
+                    .filter(|row| self.state.is_selected(&row.node))
+                    .map(|row| row.node.clone())
+                    .collect()
+            } else {
+                vec![row.node.clone()]
+            };
+            response.dnd_set_drag_payload(DragPayload {
+                tree_id: self.id,
+                nodes: dragged,
+            });
+        }
+
+        let Some(payload) = response.dnd_hover_payload::<DragPayload<NodeId>>() else {
+            return;
+        };
+        let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+            return;
+        };
+        if payload.tree_id != self.id {
+            return;
+        }
+
+        let t = (pointer.y - rect.top()) / rect.height();
+        let place = if t < 0.25 {
+            DropPlace::Before
+        } else if t > 0.75 {
+            DropPlace::After
+        } else {
+            DropPlace::Into
+        };
+
+        // `None` means after the last child.
+        // Dropping right after an expanded node puts it first among its children.
+        let (parent, index_in_parent) = match place {
+            DropPlace::Before => (row.parent, Some(row.index_in_parent)),
+            DropPlace::After if row.expanded => (Some(index), Some(0)),
+            DropPlace::After => (row.parent, Some(row.index_in_parent + 1)),
+            DropPlace::Into => (Some(index), None),
+        };
+
+        // A node can't become its own descendant:
+        let mut ancestor = parent;
+        while let Some(a) = ancestor {
+            if payload.nodes.contains(&shown.rows[a].node) {
+                return;
+            }
+            ancestor = shown.rows[a].parent;
+        }
+
+        let stroke = Stroke::new(2.0, ui.visuals().selection.stroke.color);
+        let line_left =
+            rect.left() + parent.map_or(0.0, |p| (shown.rows[p].depth + 1) as f32 * indent);
+        self.drop_indicator = Some(match place {
+            DropPlace::Before => Shape::hline(line_left..=rect.right(), rect.top(), stroke),
+            DropPlace::After => Shape::hline(line_left..=rect.right(), rect.bottom(), stroke),
+            DropPlace::Into => Shape::rect_stroke(rect.shrink(1.0), 2.0, stroke),
+        });
+
+        if response
+            .dnd_release_payload::<DragPayload<NodeId>>()
+            .is_some()
+        {
+            let insert_at = index_in_parent.unwrap_or_else(|| nodes.children(&row.node).len());
+            self.dropped = Some(TreeDrop {
+                nodes: payload.nodes.clone(),
+                parent: parent.map(|p| shown.rows[p].node.clone()),
+                index: insert_at,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Modifiers;
+
+    use super::*;
+
+    /// Nodes `1..10`, where each node has the children `10 * node + 1 ..= 10 * node + 2`.
+    struct Numbers;
+
+    impl TreeNodes for Numbers {
+        type NodeId = u32;
+
+        fn roots(&mut self) -> Vec<u32> {
+            (1..10).collect()
+        }
+
+        fn has_children(&mut self, node: &u32) -> bool {
+            *node < 100
+        }
+
+        fn children(&mut self, node: &u32) -> Vec<u32> {
+            vec![10 * node + 1, 10 * node + 2]
+        }
+
+        fn row_ui(&mut self, ui: &mut Ui, node: &u32) {
+            ui.label(node.to_string());
+        }
+    }
+
+    /// [`Numbers`] that counts how often it is asked for children.
+    #[derive(Default)]
+    struct Counting {
+        roots: u32,
+        children_calls: usize,
+    }
+
+    impl TreeNodes for Counting {
+        type NodeId = u32;
+
+        fn roots(&mut self) -> Vec<u32> {
+            (1..=self.roots).collect()
+        }
+
+        fn has_children(&mut self, node: &u32) -> bool {
+            Numbers.has_children(node)
+        }
+
+        fn children(&mut self, node: &u32) -> Vec<u32> {
+            self.children_calls += 1;
+            Numbers.children(node)
+        }
+
+        fn row_ui(&mut self, ui: &mut Ui, node: &u32) {
+            Numbers.row_ui(ui, node);
+        }
+    }
+
+    #[test]
+    fn rows_are_cached_until_something_changes() {
+        let mut nodes = Counting {
+            roots: 3,
+            ..Default::default()
+        };
+        let mut state = TreeViewState::default();
+        state.set_expanded(2, true);
+        let rows = state.cached_rows(&mut nodes);
+        let shown =
+            |rows: &ShownRows<u32>| rows.rows.iter().map(|row| row.node).collect::<Vec<_>>();
+        assert_eq!(shown(&rows), vec![1, 2, 21, 22, 3]);
+        assert_eq!(rows.indices[&21], 2);
+        assert_eq!(nodes.children_calls, 1);
+
+        assert!(Arc::ptr_eq(&state.cached_rows(&mut nodes), &rows));
+        state.set_expanded(2, true);
+        assert!(Arc::ptr_eq(&state.cached_rows(&mut nodes), &rows));
+        assert_eq!(nodes.children_calls, 1);
+
+        state.set_expanded(21, true);
+        assert_eq!(
+            shown(&state.cached_rows(&mut nodes)),
+            vec![1, 2, 21, 211, 212, 22, 3]
+        );
+
+        nodes.roots = 4;
+        assert_eq!(
+            shown(&state.cached_rows(&mut nodes)),
+            vec![1, 2, 21, 211, 212, 22, 3, 4]
+        );
+
+        let calls = nodes.children_calls;
+        state.refresh_rows();
+        state.cached_rows(&mut nodes);
+        assert_eq!(nodes.children_calls, calls + 2);
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        let ctx = Context::default();
+        let frame = |keys: &[Key], modifiers: Modifiers| {
+            let events = keys
+                .iter()
+                .map(|&key| egui::Event::Key {
+                    key,
+                    physical_key: None,
+                    pressed: true,
+                    repeat: false,
+                    modifiers,
+                })
+                .collect();
+            let input = egui::RawInput {
+                events,
+                modifiers,
+                ..Default::default()
+            };
+            let mut response = None;
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let tree = TreeView::new("tree").selection_mode(SelectionMode::Multiple);
+                    response = Some(tree.show(ui, &mut Numbers));
+                });
+            });
+            response.unwrap()
+        };
+
+        let tree_id = frame(&[], Modifiers::NONE).response.id;
+        ctx.memory_mut(|mem| mem.request_focus(tree_id));
+        frame(&[], Modifiers::NONE);
+
+        let response = frame(&[Key::ArrowDown], Modifiers::NONE);
+        assert_eq!(response.selected, vec![1]);
+        assert!(response.selection_changed);
+
+        // Expand node 1, move to its first child, and select down to its second child:
+        frame(&[Key::ArrowRight], Modifiers::NONE);
+        let response = frame(&[Key::ArrowRight], Modifiers::NONE);
+        assert_eq!(response.selected, vec![11]);
+        let response = frame(&[Key::ArrowDown], Modifiers::SHIFT);
+        assert_eq!(response.selected, vec![11, 12]);
+
+        // Back to the parent, and collapse it:
+        let response = frame(&[Key::ArrowLeft], Modifiers::NONE);
+        assert_eq!(response.selected, vec![1]);
+        frame(&[Key::ArrowLeft], Modifiers::NONE);
+        let response = frame(&[Key::ArrowDown], Modifiers::NONE);
+        assert_eq!(response.selected, vec![2], "Node 1 should be collapsed");
+
+        let response = frame(&[Key::Enter], Modifiers::NONE);
+        assert_eq!(response.activated, Some(2));
+        assert!(!response.selection_changed);
+    }
+
+    #[test]
+    fn drag_and_drop() {
+        let ctx = Context::default();
+        let frame = |events: Vec<egui::Event>| {
+            let input = egui::RawInput {
+                screen_rect: Some(Rect::from_min_size(pos2(0.0, 0.0), vec2(400.0, 400.0))),
+                events,
+                ..Default::default()
+            };
+            let mut response = None;
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let tree = TreeView::new("tree")
+                        .row_height(20.0)
+                        .selection_mode(SelectionMode::Multiple)
+                        .drag_and_drop(true);
+                    response = Some(tree.show(ui, &mut Numbers));
+                });
+            });
+            response.unwrap()
+        };
+        let button = |pos, pressed| egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: Modifiers::NONE,
+        };
+        // The rows start below the margin of the panel, and are 20 high:
+        let row_y = |index: usize, t: f32| 8.0 + 20.0 * (index as f32 + t);
+        let drag = |from: usize, to: usize, t: f32| {
+            let (from, to) = (pos2(50.0, row_y(from, 0.5)), pos2(50.0, row_y(to, t)));
+            frame(vec![egui::Event::PointerMoved(from), button(from, true)]);
+            frame(vec![egui::Event::PointerMoved(from + vec2(0.0, 10.0))]);
+            frame(vec![egui::Event::PointerMoved(to)]);
+            frame(vec![egui::Event::PointerMoved(to)]);
+            let response = frame(vec![button(to, false)]);
+            frame(vec![]);
+            response.dropped
+        };
+        frame(vec![]);
+
+        // Node 1 after node 3:
+        assert_eq!(
+            drag(0, 2, 0.9),
+            Some(TreeDrop {
+                nodes: vec![1],
+                parent: None,
+                index: 3,
+            })
+        );
+
+        // Node 4 before node 2:
+        assert_eq!(
+            drag(3, 1, 0.1),
+            Some(TreeDrop {
+                nodes: vec![4],
+                parent: None,
+                index: 1,
+            })
+        );
+
+        // Node 5 into node 2, after its children:
+        assert_eq!(
+            drag(4, 1, 0.5),
+            Some(TreeDrop {
+                nodes: vec![5],
+                parent: Some(2),
+                index: 2,
+            })
+        );
+
+        // Dragging a selected node drags all of them, in the order they are shown:
+        let tree_id = frame(vec![]).response.id;
+        let mut state = TreeViewState::<u32>::load(&ctx, tree_id).unwrap();
+        state.set_selected(vec![3, 1]);
+        state.set_expanded(1, true);
+        state.store(&ctx, tree_id);
+        frame(vec![]);
+        // The rows are now 1, 11, 12, 2, 3, …
+        assert_eq!(
+            drag(4, 3, 0.5),
+            Some(TreeDrop {
+                nodes: vec![1, 3],
+                parent: Some(2),
+                index: 2,
+            })
+        );
+
+        assert_eq!(
+            drag(0, 1, 0.5),
+            None,
+            "can't drop a node into its own child"
+        );
+    }
+}
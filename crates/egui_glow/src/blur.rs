@@ -0,0 +1,271 @@
+#![allow(unsafe_code)]
+
+//! Gaussian blurring of [`egui::epaint::BlurPrimitive`]s.
+
+use egui::{emath::Rect, epaint::Mesh, pos2, Vec2};
+use glow::HasContext as _;
+
+use crate::check_for_gl_error;
+use crate::misc_util::{compile_shader, link_program};
+use crate::vao;
+
+const BLUR_FRAG_SRC: &str = include_str!("shader/blur_fragment.glsl");
+
+/// The largest number of samples on either side of a pixel.
+///
+/// Keep in sync with `MAX_RADIUS` in `blur_fragment.glsl`.
+const MAX_BLUR_RADIUS: f32 = 48.0;
+
+/// A shader program for one direction of a Gaussian blur,
+/// and two textures to blur back and forth between.
+pub(crate) struct Blurrer {
+    program: glow::Program,
+    vao: vao::VertexArrayObject,
+    vbo: glow::Buffer,
+    element_array_buffer: glow::Buffer,
+    u_screen_size: glow::UniformLocation,
+    u_sampler: glow::UniformLocation,
+    u_step: glow::UniformLocation,
+    u_sigma: glow::UniformLocation,
+    u_uv_bounds: glow::UniformLocation,
+
+    framebuffer: glow::Framebuffer,
+
+    /// Grown as needed, never shrunk.
+    textures: [glow::Texture; 2],
+    texture_size: [u32; 2],
+
+    /// The size of what we are blurring, in the lower left corner of the textures.
+    region_size: [u32; 2],
+}
+
+impl Blurrer {
+    /// `vert_src` and `frag_header` are the same as for the main egui program,
+    /// and so are the buffers.
+    pub(crate) unsafe fn new(
+        gl: &glow::Context,
+        vert_src: &str,
+        frag_header: &str,
+        vbo: glow::Buffer,
+        element_array_buffer: glow::Buffer,
+    ) -> Result<Self, String> {
+        unsafe {
+            let vert = compile_shader(gl, glow::VERTEX_SHADER, vert_src)?;
+            let frag = compile_shader(
+                gl,
+                glow::FRAGMENT_SHADER,
+                &format!("{frag_header}\n{BLUR_FRAG_SRC}"),
+            )?;
+            let program = link_program(gl, [vert, frag].iter())?;
+            gl.detach_shader(program, vert);
+            gl.detach_shader(program, frag);
+            gl.delete_shader(vert);
+            gl.delete_shader(frag);
+
+            let uniform = |name: &str| {
+                gl.get_uniform_location(program, name)
+                    .ok_or_else(|| format!("Missing uniform {name:?} in blur shader"))
+            };
+            let u_screen_size = uniform("u_screen_size")?;
+            let u_sampler = uniform("u_sampler")?;
+            let u_step = uniform("u_step")?;
+            let u_sigma = uniform("u_sigma")?;
+            let u_uv_bounds = uniform("u_uv_bounds")?;
+
+            let vao = vao::VertexArrayObject::new(
+                gl,
+                vbo,
+                crate::painter::vertex_buffer_infos(gl, program),
+            );
+
+            let framebuffer = gl.create_framebuffer()?;
+            let textures = [gl.create_texture()?, gl.create_texture()?];
+            for texture in textures {
+                gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                for (param, value) in [
+                    (glow::TEXTURE_MIN_FILTER, glow::LINEAR),
+                    (glow::TEXTURE_MAG_FILTER, glow::LINEAR),
+                    (glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+                    (glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+                ] {
+                    gl.tex_parameter_i32(glow::TEXTURE_2D, param, value as i32);
+                }
+            }
+            check_for_gl_error!(gl, "Blurrer::new");
+
+            Ok(Self {
+                program,
+                vao,
+                vbo,
+                element_array_buffer,
+                u_screen_size,
+                u_sampler,
+                u_step,
+                u_sigma,
+                u_uv_bounds,
+                framebuffer,
+                textures,
+                texture_size: [0, 0],
+                region_size: [0, 0],
+            })
+        }
+    }
+
+    /// Start blurring a region of this size, in pixels.
+    ///
+    /// This grows the textures if needed.
+    pub(crate) unsafe fn set_region_size(&mut self, gl: &glow::Context, size: [u32; 2]) {
+        self.region_size = size;
+        let [width, height] = size;
+        let [old_width, old_height] = self.texture_size;
+        if width <= old_width && height <= old_height {
+            return;
+        }
+        let size = [width.max(old_width), height.max(old_height)];
+        unsafe {
+            for texture in self.textures {
+                gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                gl.tex_image_2d(
+                    glow::TEXTURE_2D,
+                    0,
+                    glow::RGBA as i32,
+                    size[0] as i32,
+                    size[1] as i32,
+                    0,
+                    glow::RGBA,
+                    glow::UNSIGNED_BYTE,
+                    None,
+                );
+            }
+            check_for_gl_error!(gl, "Blurrer::set_region_size");
+        }
+        self.texture_size = size;
+    }
+
+    /// Render into one of the textures from now on.
+    ///
+    /// Returns `false` if rendering into textures isn't supported.
+    pub(crate) unsafe fn bind_as_target(&self, gl: &glow::Context, texture: usize) -> bool {
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+            gl.framebuffer_texture_2d(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                glow::TEXTURE_2D,
+                Some(self.textures[texture]),
+                0,
+            );
+            gl.check_framebuffer_status(glow::FRAMEBUFFER) == glow::FRAMEBUFFER_COMPLETE
+        }
+    }
+
+    /// Copy the region from the bound framebuffer into one of the textures.
+    ///
+    /// `x` and `y` are in pixels from the lower left corner of the framebuffer.
+    pub(crate) unsafe fn copy_from_framebuffer(
+        &self,
+        gl: &glow::Context,
+        texture: usize,
+        [x, y]: [i32; 2],
+    ) {
+        let [width, height] = self.region_size;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.textures[texture]));
+            gl.copy_tex_sub_image_2d(glow::TEXTURE_2D, 0, 0, 0, x, y, width as i32, height as i32);
+            check_for_gl_error!(gl, "copy_tex_sub_image_2d");
+        }
+    }
+
+    /// The uv coordinates of a point in the region.
+    ///
+    /// `pos` is in pixels from the top left corner of the region.
+    /// The textures are upside down, like everything in OpenGL.
+    pub(crate) fn uv(&self, pos: Vec2) -> egui::Pos2 {
+        let [width, height] = self.texture_size.map(|side| side as f32);
+        pos2(pos.x / width, (self.region_size[1] as f32 - pos.y) / height)
+    }
+
+    /// A mesh covering the whole region, for [`Self::blur`].
+    ///
+    /// The positions are in pixels.
+    pub(crate) fn region_mesh(&self) -> Mesh {
+        let size = Vec2::new(self.region_size[0] as f32, self.region_size[1] as f32);
+        let rect = Rect::from_min_size(egui::Pos2::ZERO, size);
+        let uv = Rect::from_min_max(self.uv(Vec2::ZERO), self.uv(size));
+        let mut mesh = Mesh::default();
+        mesh.add_rect_with_uv(rect, uv, egui::Color32::WHITE);
+        mesh
+    }
+
+    /// Blur the region of one of the textures in one direction,
+    /// painting it with `mesh` into the bound framebuffer.
+    ///
+    /// * `screen_size`: the size of the viewport, in the units of the mesh positions.
+    /// * `direction`: `(1, 0)` or `(0, 1)`.
+    /// * `sigma`: the standard deviation of the blur, in pixels.
+    pub(crate) unsafe fn blur(
+        &self,
+        gl: &glow::Context,
+        texture: usize,
+        mesh: &Mesh,
+        screen_size: Vec2,
+        direction: Vec2,
+        sigma: f32,
+    ) {
+        let [width, height] = self.texture_size.map(|side| side as f32);
+        let [region_width, region_height] = self.region_size.map(|side| side as f32);
+
+        // Skip pixels for very wide blurs, and let the linear filtering fill in between:
+        let stride = (3.0 * sigma / MAX_BLUR_RADIUS).ceil().max(1.0);
+        let step = stride * direction / Vec2::new(width, height);
+
+        unsafe {
+            gl.use_program(Some(self.program));
+            gl.uniform_2_f32(Some(&self.u_screen_size), screen_size.x, screen_size.y);
+            gl.uniform_1_i32(Some(&self.u_sampler), 0);
+            gl.uniform_2_f32(Some(&self.u_step), step.x, step.y);
+            gl.uniform_1_f32(Some(&self.u_sigma), (sigma / stride).max(0.01));
+            gl.uniform_4_f32(
+                Some(&self.u_uv_bounds),
+                0.5 / width,
+                0.5 / height,
+                (region_width - 0.5) / width,
+                (region_height - 0.5) / height,
+            );
+            gl.active_texture(glow::TEXTURE0);
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.textures[texture]));
+
+            self.vao.bind(gl);
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+            gl.buffer_data_u8_slice(
+                glow::ARRAY_BUFFER,
+                bytemuck::cast_slice(&mesh.vertices),
+                glow::STREAM_DRAW,
+            );
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.element_array_buffer));
+            gl.buffer_data_u8_slice(
+                glow::ELEMENT_ARRAY_BUFFER,
+                bytemuck::cast_slice(&mesh.indices),
+                glow::STREAM_DRAW,
+            );
+            gl.draw_elements(
+                glow::TRIANGLES,
+                mesh.indices.len() as i32,
+                glow::UNSIGNED_INT,
+                0,
+            );
+            self.vao.unbind(gl);
+            check_for_gl_error!(gl, "blur");
+        }
+    }
+
+    pub(crate) unsafe fn destroy(&self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_program(self.program);
+            gl.delete_framebuffer(self.framebuffer);
+            for texture in self.textures {
+                gl.delete_texture(texture);
+            }
+        }
+    }
+}
@@ -15,6 +15,7 @@
 pub mod painter;
 pub use glow;
 pub use painter::{CallbackFn, Painter, PainterError};
+mod blur;
 mod misc_util;
 mod shader_version;
 mod vao;
@@ -1,15 +1,19 @@
 #![allow(clippy::collapsible_else_if)]
 #![allow(unsafe_code)]
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use egui::{
     emath::Rect,
-    epaint::{Mesh, PaintCallbackInfo, Primitive, Vertex},
+    epaint::{BlurPrimitive, BlurSource, Mesh, PaintCallbackInfo, Primitive, Vertex},
 };
 use glow::HasContext as _;
 use memoffset::offset_of;
 
+use crate::blur::Blurrer;
 use crate::check_for_gl_error;
 use crate::misc_util::{compile_shader, link_program};
 use crate::shader_version::ShaderVersion;
@@ -83,6 +87,7 @@ pub struct Painter {
     program: glow::Program,
     u_screen_size: glow::UniformLocation,
     u_sampler: glow::UniformLocation,
+    u_sdf: glow::UniformLocation,
     is_webgl_1: bool,
     vao: crate::vao::VertexArrayObject,
     srgb_textures: bool,
@@ -90,8 +95,14 @@ pub struct Painter {
     vbo: glow::Buffer,
     element_array_buffer: glow::Buffer,
 
+    /// `None` if blurring isn't supported, in which case we paint an approximation.
+    blurrer: Option<Blurrer>,
+
     textures: HashMap<egui::TextureId, glow::Texture>,
 
+    /// Font textures containing signed distance fields, see [`egui::GlyphRasterization::Sdf`].
+    sdf_textures: HashSet<egui::TextureId>,
+
     next_native_tex_id: u64,
 
     /// Stores outdated OpenGL textures that are yet to be deleted
@@ -182,28 +193,25 @@ impl Painter {
         log::debug!("SRGB framebuffer Support: {:?}", supports_srgb_framebuffer);
 
         unsafe {
-            let vert = compile_shader(
-                &gl,
-                glow::VERTEX_SHADER,
-                &format!(
-                    "{}\n#define NEW_SHADER_INTERFACE {}\n{}\n{}",
-                    shader_version_declaration,
-                    shader_version.is_new_shader_interface() as i32,
-                    shader_prefix,
-                    VERT_SRC
-                ),
-            )?;
+            let vert_src = format!(
+                "{}\n#define NEW_SHADER_INTERFACE {}\n{}\n{}",
+                shader_version_declaration,
+                shader_version.is_new_shader_interface() as i32,
+                shader_prefix,
+                VERT_SRC
+            );
+            let frag_header = format!(
+                "{}\n#define NEW_SHADER_INTERFACE {}\n#define SRGB_TEXTURES {}\n{}",
+                shader_version_declaration,
+                shader_version.is_new_shader_interface() as i32,
+                srgb_textures as i32,
+                shader_prefix,
+            );
+            let vert = compile_shader(&gl, glow::VERTEX_SHADER, &vert_src)?;
             let frag = compile_shader(
                 &gl,
                 glow::FRAGMENT_SHADER,
-                &format!(
-                    "{}\n#define NEW_SHADER_INTERFACE {}\n#define SRGB_TEXTURES {}\n{}\n{}",
-                    shader_version_declaration,
-                    shader_version.is_new_shader_interface() as i32,
-                    srgb_textures as i32,
-                    shader_prefix,
-                    FRAG_SRC
-                ),
+                &format!("{frag_header}\n{FRAG_SRC}"),
             )?;
             let program = link_program(&gl, [vert, frag].iter())?;
             gl.detach_shader(program, vert);
@@ -212,44 +220,23 @@ impl Painter {
             gl.delete_shader(frag);
             let u_screen_size = gl.get_uniform_location(program, "u_screen_size").unwrap();
             let u_sampler = gl.get_uniform_location(program, "u_sampler").unwrap();
+            let u_sdf = gl.get_uniform_location(program, "u_sdf").unwrap();
 
             let vbo = gl.create_buffer()?;
-
-            let a_pos_loc = gl.get_attrib_location(program, "a_pos").unwrap();
-            let a_tc_loc = gl.get_attrib_location(program, "a_tc").unwrap();
-            let a_srgba_loc = gl.get_attrib_location(program, "a_srgba").unwrap();
-
-            let stride = std::mem::size_of::<Vertex>() as i32;
-            let buffer_infos = vec![
-                vao::BufferInfo {
-                    location: a_pos_loc,
-                    vector_size: 2,
-                    data_type: glow::FLOAT,
-                    normalized: false,
-                    stride,
-                    offset: offset_of!(Vertex, pos) as i32,
-                },
-                vao::BufferInfo {
-                    location: a_tc_loc,
-                    vector_size: 2,
-                    data_type: glow::FLOAT,
-                    normalized: false,
-                    stride,
-                    offset: offset_of!(Vertex, uv) as i32,
-                },
-                vao::BufferInfo {
-                    location: a_srgba_loc,
-                    vector_size: 4,
-                    data_type: glow::UNSIGNED_BYTE,
-                    normalized: false,
-                    stride,
-                    offset: offset_of!(Vertex, color) as i32,
-                },
-            ];
-            let vao = crate::vao::VertexArrayObject::new(&gl, vbo, buffer_infos);
+            let vao =
+                crate::vao::VertexArrayObject::new(&gl, vbo, vertex_buffer_infos(&gl, program));
 
             let element_array_buffer = gl.create_buffer()?;
 
+            let blurrer =
+                match Blurrer::new(&gl, &vert_src, &frag_header, vbo, element_array_buffer) {
+                    Ok(blurrer) => Some(blurrer),
+                    Err(err) => {
+                        log::warn!("Blurring is not supported, using an approximation: {err}");
+                        None
+                    }
+                };
+
             crate::check_for_gl_error_even_in_release!(&gl, "after Painter::new");
 
             Ok(Self {
@@ -258,13 +245,16 @@ impl Painter {
                 program,
                 u_screen_size,
                 u_sampler,
+                u_sdf,
                 is_webgl_1,
                 vao,
                 srgb_textures,
                 supports_srgb_framebuffer,
                 vbo,
                 element_array_buffer,
+                blurrer,
                 textures: Default::default(),
+                sdf_textures: Default::default(),
                 next_native_tex_id: 1 << 32,
                 textures_to_destroy: Vec::new(),
                 destroyed: false,
@@ -352,6 +342,37 @@ impl Painter {
         clear(&self.gl, screen_size_in_pixels, clear_color);
     }
 
+    /// Like [`Self::clear`], but only clears the `damage` rectangle (in points).
+    ///
+    /// See [`Self::paint_primitives_in_damage`].
+    pub fn clear_damage(
+        &self,
+        screen_size_in_pixels: [u32; 2],
+        pixels_per_point: f32,
+        damage: Rect,
+        clear_color: [f32; 4],
+    ) {
+        crate::profile_function!();
+        unsafe {
+            self.gl.viewport(
+                0,
+                0,
+                screen_size_in_pixels[0] as i32,
+                screen_size_in_pixels[1] as i32,
+            );
+            self.gl.enable(glow::SCISSOR_TEST);
+            set_clip_rect(&self.gl, screen_size_in_pixels, pixels_per_point, damage);
+            self.gl.clear_color(
+                clear_color[0],
+                clear_color[1],
+                clear_color[2],
+                clear_color[3],
+            );
+            self.gl.clear(glow::COLOR_BUFFER_BIT);
+            self.gl.disable(glow::SCISSOR_TEST);
+        }
+    }
+
     /// You are expected to have cleared the color buffer before calling this.
     pub fn paint_and_update_textures(
         &mut self,
@@ -373,6 +394,35 @@ impl Painter {
         }
     }
 
+    /// Like [`Self::paint_and_update_textures`], but only repaints the `damage` rectangle (in points).
+    ///
+    /// See [`Self::paint_primitives_in_damage`].
+    pub fn paint_and_update_textures_in_damage(
+        &mut self,
+        screen_size_px: [u32; 2],
+        pixels_per_point: f32,
+        clipped_primitives: &[egui::ClippedPrimitive],
+        textures_delta: &egui::TexturesDelta,
+        damage: Rect,
+    ) {
+        crate::profile_function!();
+
+        for (id, image_delta) in &textures_delta.set {
+            self.set_texture(*id, image_delta);
+        }
+
+        self.paint_primitives_in_damage(
+            screen_size_px,
+            pixels_per_point,
+            clipped_primitives,
+            damage,
+        );
+
+        for &id in &textures_delta.free {
+            self.free_texture(id);
+        }
+    }
+
     /// Main entry-point for painting a frame.
     ///
     /// You should call `target.clear_color(..)` before
@@ -400,6 +450,43 @@ impl Painter {
         clipped_primitives: &[egui::ClippedPrimitive],
     ) {
         crate::profile_function!();
+        self.paint_primitives_clipped_to(
+            screen_size_px,
+            pixels_per_point,
+            clipped_primitives,
+            Rect::EVERYTHING,
+        );
+    }
+
+    /// Like [`Self::paint_primitives`], but only repaints the `damage` rectangle (in points),
+    /// and leaves the rest of the framebuffer as it is.
+    ///
+    /// Use this with [`egui::FullOutput::damage`] when the framebuffer still contains the previous frame,
+    /// e.g. when painting into your own texture, or when the windowing system preserves the back buffer.
+    /// Call [`Self::clear_damage`] before this.
+    pub fn paint_primitives_in_damage(
+        &mut self,
+        screen_size_px: [u32; 2],
+        pixels_per_point: f32,
+        clipped_primitives: &[egui::ClippedPrimitive],
+        damage: Rect,
+    ) {
+        crate::profile_function!();
+        self.paint_primitives_clipped_to(
+            screen_size_px,
+            pixels_per_point,
+            clipped_primitives,
+            damage,
+        );
+    }
+
+    fn paint_primitives_clipped_to(
+        &mut self,
+        screen_size_px: [u32; 2],
+        pixels_per_point: f32,
+        clipped_primitives: &[egui::ClippedPrimitive],
+        damage: Rect,
+    ) {
         self.assert_not_destroyed();
 
         unsafe { self.prepare_painting(screen_size_px, pixels_per_point) };
@@ -409,19 +496,38 @@ impl Painter {
             primitive,
         } in clipped_primitives
         {
-            set_clip_rect(&self.gl, screen_size_px, pixels_per_point, *clip_rect);
+            let clip_rect = clip_rect.intersect(damage);
+            let [_, _, width, height] = scissor_rect(screen_size_px, pixels_per_point, clip_rect);
+            if width == 0 || height == 0 {
+                continue; // Outside of the damage, or less than a pixel
+            }
+            set_clip_rect(&self.gl, screen_size_px, pixels_per_point, clip_rect);
 
             match primitive {
                 Primitive::Mesh(mesh) => {
                     self.paint_mesh(mesh);
                 }
+                Primitive::Blur(blur) => {
+                    crate::profile_scope!("blur");
+                    let blurred =
+                        unsafe { self.paint_blur(screen_size_px, pixels_per_point, blur) };
+                    if !blurred {
+                        for mesh in &blur.fallback {
+                            self.paint_mesh(mesh);
+                        }
+                    }
+
+                    // Restore state:
+                    unsafe { self.prepare_painting(screen_size_px, pixels_per_point) };
+                    set_clip_rect(&self.gl, screen_size_px, pixels_per_point, clip_rect);
+                }
                 Primitive::Callback(callback) => {
                     if callback.rect.is_positive() {
                         crate::profile_scope!("callback");
 
                         let info = egui::PaintCallbackInfo {
                             viewport: callback.rect,
-                            clip_rect: *clip_rect,
+                            clip_rect,
                             pixels_per_point,
                             screen_size_px,
                         };
@@ -461,6 +567,108 @@ impl Painter {
         }
     }
 
+    /// Paint a blur, or return `false` if we can't.
+    ///
+    /// This changes a lot of state, and only restores the framebuffer and clip rect.
+    unsafe fn paint_blur(
+        &mut self,
+        screen_size_px: [u32; 2],
+        pixels_per_point: f32,
+        blur: &BlurPrimitive,
+    ) -> bool {
+        // Only blur the part of the region that is on screen:
+        let [screen_width, screen_height] = screen_size_px;
+        let to_px = |points: f32, max: u32| {
+            (points * pixels_per_point).round().clamp(0.0, max as f32) as u32
+        };
+        let x0 = to_px(blur.rect.min.x, screen_width);
+        let y0 = to_px(blur.rect.min.y, screen_height);
+        let x1 = to_px(blur.rect.max.x, screen_width);
+        let y1 = to_px(blur.rect.max.y, screen_height);
+        if x1 <= x0 || y1 <= y0 {
+            return true; // Nothing to paint
+        }
+        let region_size = [x1 - x0, y1 - y0];
+        let region_size_vec = egui::vec2(region_size[0] as f32, region_size[1] as f32);
+        let offset = egui::vec2(x0 as f32, y0 as f32);
+
+        let gl = self.gl.clone();
+        let screen_fbo = self.intermediate_fbo();
+        let Some(blurrer) = &mut self.blurrer else {
+            return false;
+        };
+
+        unsafe {
+            blurrer.set_region_size(&gl, region_size);
+
+            match &blur.source {
+                BlurSource::Meshes(meshes) => {
+                    if !blurrer.bind_as_target(&gl, 0) {
+                        gl.bind_framebuffer(glow::FRAMEBUFFER, screen_fbo);
+                        return false;
+                    }
+                    self.prepare_painting(region_size, pixels_per_point);
+                    gl.disable(glow::SCISSOR_TEST);
+                    gl.clear_color(0.0, 0.0, 0.0, 0.0);
+                    gl.clear(glow::COLOR_BUFFER_BIT);
+                    for mesh in meshes {
+                        let mut mesh = mesh.clone();
+                        mesh.translate(-offset / pixels_per_point);
+                        self.paint_mesh(&mesh);
+                    }
+                }
+                BlurSource::Backdrop => {
+                    if gl.get_parameter_i32(glow::SAMPLE_BUFFERS) > 0 {
+                        return false; // Can't copy from a multisampled framebuffer
+                    }
+                    blurrer.copy_from_framebuffer(&gl, 0, [x0 as i32, (screen_height - y1) as i32]);
+                }
+            }
+
+            let Some(blurrer) = &self.blurrer else {
+                return false;
+            };
+            let sigma = blur.sigma * pixels_per_point;
+
+            // Horizontally, from the first texture to the second:
+            if !blurrer.bind_as_target(&gl, 1) {
+                gl.bind_framebuffer(glow::FRAMEBUFFER, screen_fbo);
+                return false;
+            }
+            gl.viewport(0, 0, region_size[0] as i32, region_size[1] as i32);
+            gl.disable(glow::SCISSOR_TEST);
+            gl.disable(glow::BLEND);
+            blurrer.blur(
+                &gl,
+                0,
+                &blurrer.region_mesh(),
+                region_size_vec,
+                egui::vec2(1.0, 0.0),
+                sigma,
+            );
+
+            // Vertically, from the second texture to the screen:
+            gl.bind_framebuffer(glow::FRAMEBUFFER, screen_fbo);
+            gl.viewport(0, 0, screen_width as i32, screen_height as i32);
+            gl.enable(glow::SCISSOR_TEST);
+            gl.enable(glow::BLEND);
+            let mut mesh = blur.mesh.clone();
+            for vertex in &mut mesh.vertices {
+                vertex.uv = blurrer.uv(vertex.pos.to_vec2() * pixels_per_point - offset);
+            }
+            blurrer.blur(
+                &gl,
+                1,
+                &mesh,
+                egui::vec2(screen_width as f32, screen_height as f32) / pixels_per_point,
+                egui::vec2(0.0, 1.0),
+                sigma,
+            );
+        }
+
+        true
+    }
+
     #[inline(never)] // Easier profiling
     fn paint_mesh(&mut self, mesh: &Mesh) {
         debug_assert!(mesh.is_valid());
@@ -482,6 +690,10 @@ impl Painter {
                 );
 
                 self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                self.gl.uniform_1_i32(
+                    Some(&self.u_sdf),
+                    self.sdf_textures.contains(&mesh.texture_id) as i32,
+                );
             }
 
             unsafe {
@@ -524,6 +736,7 @@ impl Painter {
 
                 let data: &[u8] = bytemuck::cast_slice(image.pixels.as_ref());
 
+                self.sdf_textures.remove(&tex_id);
                 self.upload_texture_srgb(delta.pos, image.size, delta.options, data);
             }
             egui::ImageData::Font(image) => {
@@ -541,6 +754,11 @@ impl Painter {
                         .collect()
                 };
 
+                if image.sdf {
+                    self.sdf_textures.insert(tex_id);
+                } else {
+                    self.sdf_textures.remove(&tex_id);
+                }
                 self.upload_texture_srgb(delta.pos, image.size, delta.options, &data);
             }
         };
@@ -637,6 +855,7 @@ impl Painter {
     }
 
     pub fn free_texture(&mut self, tex_id: egui::TextureId) {
+        self.sdf_textures.remove(&tex_id);
         if let Some(old_tex) = self.textures.remove(&tex_id) {
             unsafe { self.gl.delete_texture(old_tex) };
         }
@@ -709,6 +928,9 @@ impl Painter {
     unsafe fn destroy_gl(&self) {
         unsafe {
             self.gl.delete_program(self.program);
+            if let Some(blurrer) = &self.blurrer {
+                blurrer.destroy(&self.gl);
+            }
             for tex in self.textures.values() {
                 self.gl.delete_texture(*tex);
             }
@@ -736,6 +958,48 @@ impl Painter {
     }
 }
 
+/// The layout of [`Vertex`] for the `a_pos`, `a_tc` and `a_srgba` attributes of a program.
+pub(crate) unsafe fn vertex_buffer_infos(
+    gl: &glow::Context,
+    program: glow::Program,
+) -> Vec<vao::BufferInfo> {
+    let (a_pos_loc, a_tc_loc, a_srgba_loc) = unsafe {
+        (
+            gl.get_attrib_location(program, "a_pos").unwrap(),
+            gl.get_attrib_location(program, "a_tc").unwrap(),
+            gl.get_attrib_location(program, "a_srgba").unwrap(),
+        )
+    };
+
+    let stride = std::mem::size_of::<Vertex>() as i32;
+    vec![
+        vao::BufferInfo {
+            location: a_pos_loc,
+            vector_size: 2,
+            data_type: glow::FLOAT,
+            normalized: false,
+            stride,
+            offset: offset_of!(Vertex, pos) as i32,
+        },
+        vao::BufferInfo {
+            location: a_tc_loc,
+            vector_size: 2,
+            data_type: glow::FLOAT,
+            normalized: false,
+            stride,
+            offset: offset_of!(Vertex, uv) as i32,
+        },
+        vao::BufferInfo {
+            location: a_srgba_loc,
+            vector_size: 4,
+            data_type: glow::UNSIGNED_BYTE,
+            normalized: false,
+            stride,
+            offset: offset_of!(Vertex, color) as i32,
+        },
+    ]
+}
+
 pub fn clear(gl: &glow::Context, screen_size_in_pixels: [u32; 2], clear_color: [f32; 4]) {
     crate::profile_function!();
     unsafe {
@@ -769,10 +1033,25 @@ impl Drop for Painter {
 
 fn set_clip_rect(
     gl: &glow::Context,
-    [width_px, height_px]: [u32; 2],
+    screen_size_px: [u32; 2],
     pixels_per_point: f32,
     clip_rect: Rect,
 ) {
+    let [x, y, width, height] = scissor_rect(screen_size_px, pixels_per_point, clip_rect);
+    unsafe {
+        gl.scissor(x, y, width, height);
+    }
+}
+
+/// `clip_rect` (in points) as a scissor rectangle in physical pixels:
+/// the left and bottom edge (GL counts from the bottom), the width and the height.
+///
+/// It is clamped to the screen.
+pub fn scissor_rect(
+    [width_px, height_px]: [u32; 2],
+    pixels_per_point: f32,
+    clip_rect: Rect,
+) -> [i32; 4] {
     // Transform clip rect to physical pixels:
     let clip_min_x = pixels_per_point * clip_rect.min.x;
     let clip_min_y = pixels_per_point * clip_rect.min.y;
@@ -791,12 +1070,35 @@ fn set_clip_rect(
     let clip_max_x = clip_max_x.clamp(clip_min_x, width_px as i32);
     let clip_max_y = clip_max_y.clamp(clip_min_y, height_px as i32);
 
-    unsafe {
-        gl.scissor(
-            clip_min_x,
-            height_px as i32 - clip_max_y,
-            clip_max_x - clip_min_x,
-            clip_max_y - clip_min_y,
-        );
-    }
+    [
+        clip_min_x,
+        height_px as i32 - clip_max_y,
+        clip_max_x - clip_min_x,
+        clip_max_y - clip_min_y,
+    ]
+}
+
+#[test]
+fn scissor_rect_in_damage() {
+    use egui::pos2;
+
+    let screen_size_px = [200, 100];
+    let clip_rect = Rect::from_min_max(pos2(10.0, 10.0), pos2(50.0, 40.0));
+    let scissor = |damage: Rect| scissor_rect(screen_size_px, 2.0, clip_rect.intersect(damage));
+
+    assert_eq!(scissor(Rect::EVERYTHING), [20, 20, 80, 60]);
+
+    let damage = Rect::from_min_max(pos2(40.0, 0.0), pos2(100.0, 20.0));
+    assert_eq!(scissor(damage), [80, 60, 20, 20]);
+
+    let damage = Rect::from_min_max(pos2(60.0, 60.0), pos2(70.0, 70.0));
+    let [_, _, width, height] = scissor(damage);
+    assert!(width == 0 || height == 0, "outside of the damage");
+
+    let [_, _, width, height] = scissor(Rect::NOTHING);
+    assert!(width == 0 || height == 0, "no damage");
+
+    let damage = Rect::from_min_max(pos2(0.0, 0.0), pos2(10.2, 100.0));
+    let [_, _, width, _] = scissor(damage);
+    assert_eq!(width, 0, "less than a pixel");
 }
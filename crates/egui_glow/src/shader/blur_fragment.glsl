@@ -0,0 +1,43 @@
+#ifdef GL_ES
+    precision mediump float;
+#endif
+
+// One direction of a separable Gaussian blur.
+// Both the input and the output are premultiplied and in gamma space.
+
+uniform sampler2D u_sampler;
+uniform vec2 u_step; // uv offset between two samples
+uniform float u_sigma; // standard deviation, in samples
+uniform vec4 u_uv_bounds; // the part of the texture to sample from: min_u, min_v, max_u, max_v
+
+#if NEW_SHADER_INTERFACE
+    in vec4 v_rgba_in_gamma;
+    in vec2 v_tc;
+    out vec4 f_color;
+    // a dirty hack applied to support webGL2
+    #define gl_FragColor f_color
+    #define texture2D texture
+#else
+    varying vec4 v_rgba_in_gamma;
+    varying vec2 v_tc;
+#endif
+
+// Loops need constant bounds in GLSL ES 1.00.
+// Keep in sync with `MAX_BLUR_RADIUS` in `blur.rs`.
+const int MAX_RADIUS = 48;
+
+void main() {
+    float radius = ceil(3.0 * u_sigma);
+    vec4 sum = vec4(0.0);
+    float total = 0.0;
+    for (int i = -MAX_RADIUS; i <= MAX_RADIUS; i++) {
+        float x = float(i);
+        if (abs(x) <= radius) {
+            float weight = exp(-0.5 * x * x / (u_sigma * u_sigma));
+            vec2 tc = clamp(v_tc + x * u_step, u_uv_bounds.xy, u_uv_bounds.zw);
+            sum += weight * texture2D(u_sampler, tc);
+            total += weight;
+        }
+    }
+    gl_FragColor = v_rgba_in_gamma * (sum / total);
+}
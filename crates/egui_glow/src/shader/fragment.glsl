@@ -1,8 +1,22 @@
+// Screen-space derivatives are needed for signed distance field textures.
+// They are built into desktop GL and GLSL ES 3.00, but an extension in GLSL ES 1.00 (WebGL1).
+#if defined(GL_ES) && !NEW_SHADER_INTERFACE
+    #ifdef GL_OES_standard_derivatives
+        #extension GL_OES_standard_derivatives : enable
+        #define HAS_DERIVATIVES 1
+    #else
+        #define HAS_DERIVATIVES 0
+    #endif
+#else
+    #define HAS_DERIVATIVES 1
+#endif
+
 #ifdef GL_ES
     precision mediump float;
 #endif
 
 uniform sampler2D u_sampler;
+uniform bool u_sdf; // Is the alpha channel of the texture a signed distance field?
 
 #if NEW_SHADER_INTERFACE
     in vec4 v_rgba_in_gamma;
@@ -29,6 +43,19 @@ vec4 srgba_gamma_from_linear(vec4 rgba) {
     return vec4(srgb_gamma_from_linear(rgba.rgb), rgba.a);
 }
 
+// Coverage from a signed distance field, where the outline is at 0.5. See `epaint::sdf`.
+float sdf_coverage(float distance) {
+#if HAS_DERIVATIVES
+    // Anti-alias over one pixel, however much the texture is scaled:
+    float half_width = 0.5 * length(vec2(dFdx(distance), dFdy(distance)));
+#else
+    float half_width = 0.0625; // Assume one texel per pixel
+#endif
+    half_width = max(half_width, 0.0001);
+    // Same gamma as `epaint::FontImage::srgba_pixels` applies to coverage:
+    return pow(smoothstep(0.5 - half_width, 0.5 + half_width, distance), 0.55);
+}
+
 void main() {
 #if SRGB_TEXTURES
     vec4 texture_in_gamma = srgba_gamma_from_linear(texture2D(u_sampler, v_tc));
@@ -36,6 +63,10 @@ void main() {
     vec4 texture_in_gamma = texture2D(u_sampler, v_tc);
 #endif
 
+    if (u_sdf) {
+        texture_in_gamma = vec4(sdf_coverage(texture_in_gamma.a));
+    }
+
     // We multiply the colors in gamma space, because that's the only way to get text to look right.
     gl_FragColor = v_rgba_in_gamma * texture_in_gamma;
 }
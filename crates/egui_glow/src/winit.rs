@@ -71,6 +71,7 @@ impl EguiGlow {
             platform_output,
             textures_delta,
             shapes,
+            damage: _,
             pixels_per_point,
             viewport_output,
         } = self.egui_ctx.run(raw_input, run_ui);
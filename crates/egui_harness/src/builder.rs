@@ -0,0 +1,109 @@
+use egui::{Context, Pos2, Rect, Ui, Vec2};
+
+use crate::Harness;
+
+/// Configures a [`Harness`] before it is created.
+///
+/// Create one with [`Harness::builder`].
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct HarnessBuilder {
+    pub(crate) screen_rect: Rect,
+    pub(crate) pixels_per_point: f32,
+    pub(crate) step_dt: f32,
+    pub(crate) max_steps: usize,
+}
+
+impl Default for HarnessBuilder {
+    fn default() -> Self {
+        Self {
+            screen_rect: Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0)),
+            pixels_per_point: 1.0,
+            step_dt: 1.0 / 60.0,
+            max_steps: 4,
+        }
+    }
+}
+
+impl HarnessBuilder {
+    /// The size of the simulated screen, in points.
+    ///
+    /// Default: 800x600.
+    #[inline]
+    pub fn with_size(mut self, size: impl Into<Vec2>) -> Self {
+        self.screen_rect = Rect::from_min_size(Pos2::ZERO, size.into());
+        self
+    }
+
+    /// The number of physical pixels per point of the simulated screen.
+    ///
+    /// Default: 1.0.
+    #[inline]
+    pub fn with_pixels_per_point(mut self, pixels_per_point: f32) -> Self {
+        self.pixels_per_point = pixels_per_point;
+        self
+    }
+
+    /// How much the simulated time advances for each frame, in seconds.
+    ///
+    /// Default: 1/60.
+    #[inline]
+    pub fn with_step_dt(mut self, step_dt: f32) -> Self {
+        self.step_dt = step_dt;
+        self
+    }
+
+    /// The maximum number of frames [`Harness::run`] will step before giving up
+    /// waiting for the ui to stop requesting repaints.
+    ///
+    /// Default: 4.
+    #[inline]
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Create a [`Harness`] that runs the given closure each frame.
+    ///
+    /// The first frame is run immediately, so the widgets can be queried right away.
+    pub fn build<'a>(self, mut app: impl FnMut(&Context) + 'a) -> Harness<'a> {
+        Harness::from_builder(&self, Box::new(move |ctx, _: &mut ()| app(ctx)), ())
+    }
+
+    /// Create a [`Harness`] that runs the given closure inside a [`egui::CentralPanel`] each frame.
+    pub fn build_ui<'a>(self, mut app: impl FnMut(&mut Ui) + 'a) -> Harness<'a> {
+        Harness::from_builder(
+            &self,
+            Box::new(move |ctx, _: &mut ()| {
+                egui::CentralPanel::default().show(ctx, |ui| app(ui));
+            }),
+            (),
+        )
+    }
+
+    /// Create a [`Harness`] that owns some state that is passed to the closure each frame.
+    ///
+    /// Use [`Harness::state`] to inspect the state from the test.
+    pub fn build_state<'a, State>(
+        self,
+        app: impl FnMut(&Context, &mut State) + 'a,
+        state: State,
+    ) -> Harness<'a, State> {
+        Harness::from_builder(&self, Box::new(app), state)
+    }
+
+    /// Like [`Self::build_state`], but runs the closure inside a [`egui::CentralPanel`].
+    pub fn build_ui_state<'a, State>(
+        self,
+        mut app: impl FnMut(&mut Ui, &mut State) + 'a,
+        state: State,
+    ) -> Harness<'a, State> {
+        Harness::from_builder(
+            &self,
+            Box::new(move |ctx, state: &mut State| {
+                egui::CentralPanel::default().show(ctx, |ui| app(ui, state));
+            }),
+            state,
+        )
+    }
+}
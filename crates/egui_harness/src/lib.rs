@@ -0,0 +1,562 @@
+//! A headless test harness for [`egui`](https://github.com/emilk/egui).
+//!
+//! [`Harness`] wraps an [`egui::Context`] and lets you step frames, find widgets
+//! and simulate user input, all without a window or a GPU.
+//!
+//! Widgets are found through the AccessKit tree egui produces each frame,
+//! so anything that fills in a [`egui::WidgetInfo`] (buttons, labels, checkboxes, text edits, …)
+//! can be found by its label or [`Role`].
+//!
+//! ```
+//! use egui_harness::Harness;
+//!
+//! let mut harness = Harness::new_ui_state(
+//!     |ui, checked: &mut bool| {
+//!         ui.checkbox(checked, "Check me");
+//!     },
+//!     false,
+//! );
+//!
+//! let checkbox = harness.get_by_label("Check me");
+//! assert_eq!(checkbox.toggled(), Some(false));
+//!
+//! harness.click(&checkbox);
+//! harness.run();
+//!
+//! assert!(*harness.state());
+//! assert_eq!(harness.get_by_label("Check me").toggled(), Some(true));
+//! ```
+//!
+//! [`Harness::render`] paints the last frame into an image using the [`SoftwareRenderer`],
+//! which can be used for pixel snapshot tests.
+//! With the `snapshot` feature, [`Harness::snapshot`] compares that image against a stored PNG.
+//!
+//! ## Feature flags
+#![cfg_attr(feature = "document-features", doc = document_features::document_features!())]
+//!
+
+mod builder;
+mod node;
+mod renderer;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+
+pub use crate::{builder::HarnessBuilder, node::Node, renderer::SoftwareRenderer};
+
+#[cfg(feature = "snapshot")]
+pub use crate::snapshot::*;
+
+pub use egui::accesskit::Role;
+
+use egui::{
+    accesskit, ColorImage, Context, Event, FullOutput, Key, Modifiers, PointerButton, Pos2,
+    RawInput, Ui, ViewportId,
+};
+
+type AppFn<'a, State> = Box<dyn FnMut(&Context, &mut State) + 'a>;
+
+/// Drives an egui ui frame by frame, without a window or GPU.
+///
+/// Input is queued with methods like [`Self::click`], [`Self::type_text`] and [`Self::press_key`],
+/// and is delivered to egui on the next call to [`Self::step`] or [`Self::run`].
+///
+/// See the [crate-level documentation](crate) for an example.
+pub struct Harness<'a, State = ()> {
+    ctx: Context,
+    input: RawInput,
+    output: FullOutput,
+    app: AppFn<'a, State>,
+    state: State,
+    step_dt: f32,
+    max_steps: usize,
+    pointer_pos: Pos2,
+    renderer: SoftwareRenderer,
+}
+
+impl<'a> Harness<'a> {
+    /// Create a [`HarnessBuilder`] to configure screen size, pixels per point, etc.
+    #[inline]
+    pub fn builder() -> HarnessBuilder {
+        HarnessBuilder::default()
+    }
+
+    /// Create a harness that runs the given closure each frame.
+    ///
+    /// The first frame is run immediately.
+    pub fn new(app: impl FnMut(&Context) + 'a) -> Self {
+        Self::builder().build(app)
+    }
+
+    /// Create a harness that runs the given closure inside a [`egui::CentralPanel`] each frame.
+    ///
+    /// The first frame is run immediately.
+    pub fn new_ui(app: impl FnMut(&mut Ui) + 'a) -> Self {
+        Self::builder().build_ui(app)
+    }
+}
+
+impl<'a, State> Harness<'a, State> {
+    /// Create a harness that owns some state that is passed to the closure each frame.
+    ///
+    /// Use [`Self::state`] to inspect the state from the test.
+    pub fn new_state(app: impl FnMut(&Context, &mut State) + 'a, state: State) -> Self {
+        Harness::builder().build_state(app, state)
+    }
+
+    /// Like [`Self::new_state`], but runs the closure inside a [`egui::CentralPanel`].
+    pub fn new_ui_state(app: impl FnMut(&mut Ui, &mut State) + 'a, state: State) -> Self {
+        Harness::builder().build_ui_state(app, state)
+    }
+
+    pub(crate) fn from_builder(
+        builder: &HarnessBuilder,
+        app: AppFn<'a, State>,
+        state: State,
+    ) -> Self {
+        let ctx = Context::default();
+        ctx.enable_accesskit();
+
+        let mut input = RawInput {
+            screen_rect: Some(builder.screen_rect),
+            time: Some(0.0),
+            predicted_dt: builder.step_dt,
+            ..Default::default()
+        };
+        input
+            .viewports
+            .entry(ViewportId::ROOT)
+            .or_default()
+            .native_pixels_per_point = Some(builder.pixels_per_point);
+
+        let mut harness = Self {
+            ctx,
+            input,
+            output: FullOutput::default(),
+            app,
+            state,
+            step_dt: builder.step_dt,
+            max_steps: builder.max_steps,
+            pointer_pos: Pos2::ZERO,
+            renderer: SoftwareRenderer::default(),
+        };
+        harness.step();
+        harness
+    }
+
+    /// Run a single frame, delivering all queued input events.
+    pub fn step(&mut self) {
+        let input = self.input.take();
+        // `take` resets these, but they describe the simulated screen and clock:
+        self.input.screen_rect = input.screen_rect;
+        self.input.time = input.time.map(|time| time + self.step_dt as f64);
+
+        let Self {
+            ctx, app, state, ..
+        } = self;
+        self.output = ctx.run(input, |ctx| app(ctx, state));
+        self.renderer.update_textures(&self.output.textures_delta);
+    }
+
+    /// Run frames until egui stops requesting immediate repaints,
+    /// or until the maximum number of steps is reached (see [`HarnessBuilder::with_max_steps`]).
+    ///
+    /// Use this after simulating input, so that animations and layout changes can settle.
+    pub fn run(&mut self) {
+        for _ in 0..self.max_steps {
+            self.step();
+            if !self.wants_repaint() {
+                break;
+            }
+        }
+    }
+
+    fn wants_repaint(&self) -> bool {
+        self.output
+            .viewport_output
+            .get(&ViewportId::ROOT)
+            .map_or(false, |output| output.repaint_delay.is_zero())
+    }
+
+    /// Paint the last frame into an image, using the [`SoftwareRenderer`].
+    ///
+    /// The image has the size of the simulated screen in physical pixels.
+    pub fn render(&self) -> ColorImage {
+        let pixels_per_point = self.output.pixels_per_point;
+        let screen_size = self.ctx.screen_rect().size() * pixels_per_point;
+        let primitives = self
+            .ctx
+            .tessellate(self.output.shapes.clone(), pixels_per_point);
+        self.renderer.render(
+            &primitives,
+            pixels_per_point,
+            [
+                screen_size.x.round() as usize,
+                screen_size.y.round() as usize,
+            ],
+        )
+    }
+
+    /// Render the last frame and compare it with the snapshot stored under `name`.
+    ///
+    /// See [`try_image_snapshot_options`].
+    ///
+    /// # Errors
+    /// If the snapshot is missing, or the image doesn't match it.
+    #[cfg(feature = "snapshot")]
+    pub fn try_snapshot_options(
+        &self,
+        name: &str,
+        options: &SnapshotOptions,
+    ) -> Result<(), SnapshotError> {
+        try_image_snapshot_options(&self.render(), name, options)
+    }
+
+    /// Like [`Self::try_snapshot_options`], with the default [`SnapshotOptions`].
+    ///
+    /// # Errors
+    /// If the snapshot is missing, or the image doesn't match it.
+    #[cfg(feature = "snapshot")]
+    pub fn try_snapshot(&self, name: &str) -> Result<(), SnapshotError> {
+        try_image_snapshot(&self.render(), name)
+    }
+
+    /// Render the last frame and assert that it matches the snapshot stored under `name`.
+    ///
+    /// # Panics
+    /// If the snapshot is missing, or the image doesn't match it.
+    #[cfg(feature = "snapshot")]
+    #[track_caller]
+    pub fn snapshot_options(&self, name: &str, options: &SnapshotOptions) {
+        image_snapshot_options(&self.render(), name, options);
+    }
+
+    /// Like [`Self::snapshot_options`], with the default [`SnapshotOptions`].
+    ///
+    /// # Panics
+    /// If the snapshot is missing, or the image doesn't match it.
+    #[cfg(feature = "snapshot")]
+    #[track_caller]
+    pub fn snapshot(&self, name: &str) {
+        image_snapshot(&self.render(), name);
+    }
+
+    /// The [`Context`] used to run the ui.
+    #[inline]
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+
+    /// The output of the last frame.
+    ///
+    /// [`egui::PlatformOutput::events`] contains the [`egui::WidgetInfo`] of any widget
+    /// that was clicked, changed or focused during that frame.
+    #[inline]
+    pub fn output(&self) -> &FullOutput {
+        &self.output
+    }
+
+    /// The input that will be sent to egui on the next frame.
+    ///
+    /// Use this to queue events that have no dedicated method on the harness.
+    #[inline]
+    pub fn input_mut(&mut self) -> &mut RawInput {
+        &mut self.input
+    }
+
+    /// The state passed to the closure each frame.
+    #[inline]
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Mutable access to the state passed to the closure each frame.
+    #[inline]
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    // ------------------------------------------------------------------------
+    // Queries:
+
+    /// All widgets in the AccessKit tree of the last frame that match the predicate.
+    pub fn query_all(&self, mut predicate: impl FnMut(&Node) -> bool) -> Vec<Node> {
+        let Some(update) = &self.output.platform_output.accesskit_update else {
+            return vec![];
+        };
+        update
+            .nodes
+            .iter()
+            .map(|(id, node)| Node::new(*id, node.clone()))
+            .filter(|node| predicate(node))
+            .collect()
+    }
+
+    /// All widgets with exactly this label.
+    pub fn query_all_by_label(&self, label: &str) -> Vec<Node> {
+        self.query_all(|node| node.label() == Some(label))
+    }
+
+    /// All widgets with this role.
+    pub fn query_all_by_role(&self, role: Role) -> Vec<Node> {
+        self.query_all(|node| node.role() == role)
+    }
+
+    /// The only widget with exactly this label, if there is one.
+    ///
+    /// # Panics
+    /// If more than one widget has the label.
+    pub fn query_by_label(&self, label: &str) -> Option<Node> {
+        single(self.query_all_by_label(label), || {
+            format!("label {label:?}")
+        })
+    }
+
+    /// The only widget with this role, if there is one.
+    ///
+    /// # Panics
+    /// If more than one widget has the role.
+    pub fn query_by_role(&self, role: Role) -> Option<Node> {
+        single(self.query_all_by_role(role), || format!("role {role:?}"))
+    }
+
+    /// The only widget with exactly this label.
+    ///
+    /// # Panics
+    /// If there is not exactly one widget with the label.
+    pub fn get_by_label(&self, label: &str) -> Node {
+        self.query_by_label(label)
+            .unwrap_or_else(|| panic!("No widget found with label {label:?}"))
+    }
+
+    /// The only widget with this role.
+    ///
+    /// # Panics
+    /// If there is not exactly one widget with the role.
+    pub fn get_by_role(&self, role: Role) -> Node {
+        self.query_by_role(role)
+            .unwrap_or_else(|| panic!("No widget found with role {role:?}"))
+    }
+
+    // ------------------------------------------------------------------------
+    // Input:
+
+    /// Queue an event for the next frame.
+    #[inline]
+    pub fn event(&mut self, event: Event) {
+        self.input.events.push(event);
+    }
+
+    /// Move the pointer to the given position.
+    pub fn hover_at(&mut self, pos: Pos2) {
+        self.pointer_pos = pos;
+        self.event(Event::PointerMoved(pos));
+    }
+
+    /// Move the pointer to the center of the widget.
+    pub fn hover(&mut self, node: &Node) {
+        self.hover_at(node.rect().center());
+    }
+
+    /// Press and release the primary mouse button at the given position.
+    pub fn click_at(&mut self, pos: Pos2) {
+        self.click_at_with_button(pos, PointerButton::Primary);
+    }
+
+    /// Press and release a mouse button at the given position.
+    pub fn click_at_with_button(&mut self, pos: Pos2, button: PointerButton) {
+        self.hover_at(pos);
+        for pressed in [true, false] {
+            self.event(Event::PointerButton {
+                pos,
+                button,
+                pressed,
+                modifiers: self.input.modifiers,
+            });
+        }
+    }
+
+    /// Click the center of the widget with the primary mouse button.
+    pub fn click(&mut self, node: &Node) {
+        self.click_at(node.rect().center());
+    }
+
+    /// Click the center of the widget with the secondary mouse button.
+    pub fn right_click(&mut self, node: &Node) {
+        self.click_at_with_button(node.rect().center(), PointerButton::Secondary);
+    }
+
+    /// Drag with the primary mouse button from one position to another.
+    ///
+    /// Unlike most other input methods this steps frames itself,
+    /// since egui needs to see the pointer move while the button is held down
+    /// to recognize it as a drag rather than a click.
+    pub fn drag_at(&mut self, from: Pos2, to: Pos2) {
+        let modifiers = self.input.modifiers;
+
+        self.hover_at(from);
+        self.event(Event::PointerButton {
+            pos: from,
+            button: PointerButton::Primary,
+            pressed: true,
+            modifiers,
+        });
+        self.step();
+
+        self.hover_at(to);
+        self.step();
+
+        self.event(Event::PointerButton {
+            pos: to,
+            button: PointerButton::Primary,
+            pressed: false,
+            modifiers,
+        });
+        self.step();
+    }
+
+    /// Drag the center of the widget by the given amount, in points.
+    ///
+    /// See [`Self::drag_at`].
+    pub fn drag(&mut self, node: &Node, delta: egui::Vec2) {
+        let from = node.rect().center();
+        self.drag_at(from, from + delta);
+    }
+
+    /// Give keyboard focus to the widget.
+    pub fn focus(&mut self, node: &Node) {
+        self.event(Event::AccessKitActionRequest(accesskit::ActionRequest {
+            action: accesskit::Action::Focus,
+            target: node.id(),
+            data: None,
+        }));
+    }
+
+    /// Set which modifier keys are held down for the following events.
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.input.modifiers = modifiers;
+    }
+
+    /// Press and release a key, with the current modifiers.
+    pub fn press_key(&mut self, key: Key) {
+        self.press_key_with_modifiers(key, self.input.modifiers);
+    }
+
+    /// Press and release a key with the given modifiers held down.
+    pub fn press_key_with_modifiers(&mut self, key: Key, modifiers: Modifiers) {
+        for pressed in [true, false] {
+            self.event(Event::Key {
+                key,
+                physical_key: None,
+                pressed,
+                repeat: false,
+                modifiers,
+            });
+        }
+    }
+
+    /// Type some text into whatever widget has keyboard focus.
+    ///
+    /// Use [`Self::press_key`] with [`Key::Enter`] for newlines.
+    pub fn type_text(&mut self, text: impl Into<String>) {
+        self.event(Event::Text(text.into()));
+    }
+
+    /// The last position the pointer was moved to.
+    #[inline]
+    pub fn pointer_pos(&self) -> Pos2 {
+        self.pointer_pos
+    }
+}
+
+fn single(mut nodes: Vec<Node>, what: impl FnOnce() -> String) -> Option<Node> {
+    assert!(
+        nodes.len() <= 1,
+        "Expected at most one widget with {}, found {}",
+        what(),
+        nodes.len()
+    );
+    nodes.pop()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn click_button() {
+        let mut harness = Harness::new_ui_state(
+            |ui, count: &mut i32| {
+                if ui.button("Increment").clicked() {
+                    *count += 1;
+                }
+            },
+            0,
+        );
+
+        let button = harness.get_by_label("Increment");
+        assert_eq!(button.role(), Role::Button);
+
+        harness.click(&button);
+        harness.run();
+        harness.click(&button);
+        harness.run();
+
+        assert_eq!(*harness.state(), 2);
+    }
+
+    #[test]
+    fn type_into_text_edit() {
+        let mut harness = Harness::new_ui_state(
+            |ui, text: &mut String| {
+                ui.text_edit_singleline(text);
+            },
+            String::new(),
+        );
+
+        let text_edit = harness.get_by_role(Role::TextInput);
+        harness.focus(&text_edit);
+        harness.step();
+        harness.type_text("Hello");
+        harness.press_key(Key::Backspace);
+        harness.run();
+
+        assert_eq!(harness.state(), "Hell");
+        assert_eq!(harness.get_by_role(Role::TextInput).value(), Some("Hell"));
+    }
+
+    #[test]
+    fn drag_value() {
+        let mut harness = Harness::new_ui_state(
+            |ui, value: &mut f64| {
+                ui.add(egui::DragValue::new(value).speed(1.0));
+            },
+            0.0,
+        );
+
+        let drag_value = harness.get_by_role(Role::SpinButton);
+        harness.drag(&drag_value, egui::vec2(10.0, 0.0));
+        harness.run();
+
+        assert!(*harness.state() > 0.0);
+    }
+
+    #[test]
+    fn render() {
+        let harness = Harness::builder()
+            .with_size(egui::vec2(200.0, 100.0))
+            .with_pixels_per_point(2.0)
+            .build_ui(|ui| {
+                ui.label("Hello");
+            });
+
+        let image = harness.render();
+        assert_eq!(image.size, [400, 200]);
+
+        let panel_fill = harness.ctx().style().visuals.panel_fill;
+        assert_eq!(image[(399, 199)], panel_fill);
+        assert!(
+            image.pixels.iter().any(|&pixel| pixel != panel_fill),
+            "Expected the label to be painted"
+        );
+    }
+}
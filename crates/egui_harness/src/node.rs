@@ -0,0 +1,81 @@
+use egui::accesskit::{self, NodeId, Role, Toggled};
+use egui::{Pos2, Rect};
+
+/// A snapshot of a single widget, as found in the AccessKit tree of the last frame.
+///
+/// Found with [`crate::Harness::get_by_label`], [`crate::Harness::get_by_role`] and friends.
+///
+/// This is a copy of the node, so it will not change when the harness steps more frames.
+/// Query again to see the new state of the widget.
+#[derive(Clone, Debug)]
+pub struct Node {
+    id: NodeId,
+    node: accesskit::Node,
+}
+
+impl Node {
+    pub(crate) fn new(id: NodeId, node: accesskit::Node) -> Self {
+        Self { id, node }
+    }
+
+    /// The AccessKit id of the widget.
+    ///
+    /// This is the same as the [`egui::Id::value`] of the widget.
+    #[inline]
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// The accessibility role, e.g. [`Role::Button`] or [`Role::CheckBox`].
+    #[inline]
+    pub fn role(&self) -> Role {
+        self.node.role()
+    }
+
+    /// The label of the widget, if any.
+    #[inline]
+    pub fn label(&self) -> Option<&str> {
+        self.node.name()
+    }
+
+    /// The current text value, e.g. the contents of a [`egui::TextEdit`].
+    #[inline]
+    pub fn value(&self) -> Option<&str> {
+        self.node.value()
+    }
+
+    /// The current numeric value, e.g. of a [`egui::Slider`] or [`egui::DragValue`].
+    #[inline]
+    pub fn numeric_value(&self) -> Option<f64> {
+        self.node.numeric_value()
+    }
+
+    /// Is the widget checked or selected?
+    ///
+    /// `None` for widgets that can't be toggled.
+    pub fn toggled(&self) -> Option<bool> {
+        self.node.toggled().map(|toggled| toggled == Toggled::True)
+    }
+
+    /// Is the widget disabled?
+    #[inline]
+    pub fn is_disabled(&self) -> bool {
+        self.node.is_disabled()
+    }
+
+    /// The screen rectangle of the widget, in points.
+    pub fn rect(&self) -> Rect {
+        self.node.bounds().map_or(Rect::NOTHING, |bounds| {
+            Rect::from_min_max(
+                Pos2::new(bounds.x0 as f32, bounds.y0 as f32),
+                Pos2::new(bounds.x1 as f32, bounds.y1 as f32),
+            )
+        })
+    }
+
+    /// The underlying AccessKit node.
+    #[inline]
+    pub fn accesskit_node(&self) -> &accesskit::Node {
+        &self.node
+    }
+}
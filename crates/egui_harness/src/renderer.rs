@@ -0,0 +1,680 @@
+use egui::{
+    ahash::HashMap,
+    epaint::{
+        textures::{TextureFilter, TextureWrapMode},
+        BlurPrimitive, BlurSource, ImageData, ImageDelta, Primitive, Vertex,
+    },
+    ClippedPrimitive, Color32, ColorImage, Mesh, Pos2, Rect, TextureId, TextureOptions,
+    TexturesDelta,
+};
+
+struct Texture {
+    image: ColorImage,
+    options: TextureOptions,
+
+    /// The alpha channel is a signed distance field, see [`egui::epaint::sdf`].
+    sdf: bool,
+}
+
+/// A pure-Rust renderer that paints egui meshes into a [`ColorImage`] on the CPU.
+///
+/// This is slow compared to a GPU backend, but needs no window or graphics driver,
+/// so it can be used for pixel snapshot tests on a headless machine.
+///
+/// Like the GPU backends it works with premultiplied alpha,
+/// blends in gamma space, and respects [`TextureOptions`] for filtering and wrapping.
+/// Font textures with signed distance fields ([`egui::GlyphRasterization::Sdf`]) are supported,
+/// and so are blurred shadows and backdrop blurs ([`BlurPrimitive`]).
+/// [`egui::PaintCallback`]s can't be rendered and are ignored.
+///
+/// ```
+/// use egui_harness::SoftwareRenderer;
+///
+/// let ctx = egui::Context::default();
+/// let output = ctx.run(Default::default(), |ctx| {
+///     egui::CentralPanel::default().show(ctx, |ui| {
+///         ui.label("Hello world!");
+///     });
+/// });
+///
+/// let mut renderer = SoftwareRenderer::default();
+/// renderer.update_textures(&output.textures_delta);
+/// let primitives = ctx.tessellate(output.shapes, output.pixels_per_point);
+/// let image = renderer.render(&primitives, output.pixels_per_point, [400, 300]);
+/// assert_eq!(image.size, [400, 300]);
+/// ```
+#[derive(Default)]
+pub struct SoftwareRenderer {
+    textures: HashMap<TextureId, Texture>,
+}
+
+impl SoftwareRenderer {
+    /// Apply the texture changes of a frame.
+    ///
+    /// Call this with [`egui::FullOutput::textures_delta`] every frame, before rendering.
+    pub fn update_textures(&mut self, textures_delta: &TexturesDelta) {
+        for (id, delta) in &textures_delta.set {
+            self.set_texture(*id, delta);
+        }
+        for id in &textures_delta.free {
+            self.textures.remove(id);
+        }
+    }
+
+    fn set_texture(&mut self, id: TextureId, delta: &ImageDelta) {
+        let sdf = matches!(&delta.image, ImageData::Font(image) if image.sdf);
+        let image = match &delta.image {
+            ImageData::Color(image) => (**image).clone(),
+            ImageData::Font(image) => ColorImage {
+                size: image.size,
+                pixels: image.srgba_pixels(None).collect(),
+            },
+        };
+
+        if let Some([x0, y0]) = delta.pos {
+            let Some(texture) = self.textures.get_mut(&id) else {
+                debug_assert!(false, "Partial update of unknown texture {id:?}");
+                return;
+            };
+            let [w, _] = texture.image.size;
+            for (y, row) in image.pixels.chunks_exact(image.size[0]).enumerate() {
+                let start = (y0 + y) * w + x0;
+                texture.image.pixels[start..start + row.len()].copy_from_slice(row);
+            }
+            texture.options = delta.options;
+            texture.sdf = sdf;
+        } else {
+            self.textures.insert(
+                id,
+                Texture {
+                    image,
+                    options: delta.options,
+                    sdf,
+                },
+            );
+        }
+    }
+
+    /// Paint the primitives into a new image of the given size in physical pixels.
+    ///
+    /// The image starts out fully transparent.
+    pub fn render(
+        &self,
+        primitives: &[ClippedPrimitive],
+        pixels_per_point: f32,
+        size_px: [usize; 2],
+    ) -> ColorImage {
+        let mut target = Target {
+            size: size_px,
+            pixels: vec![[0.0; 4]; size_px[0] * size_px[1]],
+        };
+
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            match primitive {
+                Primitive::Mesh(mesh) => {
+                    if let Some(texture) = self.textures.get(&mesh.texture_id) {
+                        paint_mesh(&mut target, *clip_rect, mesh, texture, pixels_per_point);
+                    }
+                }
+                Primitive::Blur(blur) => {
+                    self.paint_blur(&mut target, *clip_rect, blur, pixels_per_point);
+                }
+                Primitive::Callback(_) => {
+                    // Custom painting needs a GPU.
+                }
+            }
+        }
+
+        ColorImage {
+            size: size_px,
+            pixels: target
+                .pixels
+                .iter()
+                .map(|&[r, g, b, a]| {
+                    let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+                    Color32::from_rgba_premultiplied(to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+                })
+                .collect(),
+        }
+    }
+
+    fn paint_blur(
+        &self,
+        target: &mut Target,
+        clip_rect: Rect,
+        blur: &BlurPrimitive,
+        pixels_per_point: f32,
+    ) {
+        // Only blur the part of the region that is on screen:
+        let [w, h] = target.size;
+        let to_px = |points: f32, max: usize| {
+            (points * pixels_per_point).round().clamp(0.0, max as f32) as usize
+        };
+        let x0 = to_px(blur.rect.min.x, w);
+        let y0 = to_px(blur.rect.min.y, h);
+        let x1 = to_px(blur.rect.max.x, w);
+        let y1 = to_px(blur.rect.max.y, h);
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        let size = [x1 - x0, y1 - y0];
+
+        let mut image = Target {
+            size,
+            pixels: vec![[0.0; 4]; size[0] * size[1]],
+        };
+        match &blur.source {
+            BlurSource::Meshes(meshes) => {
+                let offset = egui::vec2(x0 as f32, y0 as f32) / pixels_per_point;
+                for mesh in meshes {
+                    if let Some(texture) = self.textures.get(&mesh.texture_id) {
+                        let mut mesh = mesh.clone();
+                        mesh.translate(-offset);
+                        paint_mesh(
+                            &mut image,
+                            Rect::EVERYTHING,
+                            &mesh,
+                            texture,
+                            pixels_per_point,
+                        );
+                    }
+                }
+            }
+            BlurSource::Backdrop => {
+                for y in 0..size[1] {
+                    let start = (y0 + y) * w + x0;
+                    image.pixels[y * size[0]..(y + 1) * size[0]]
+                        .copy_from_slice(&target.pixels[start..start + size[0]]);
+                }
+            }
+        }
+
+        let sigma = blur.sigma * pixels_per_point;
+        gaussian_blur(&mut image, sigma, [1, 0]);
+        gaussian_blur(&mut image, sigma, [0, 1]);
+
+        let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let texture = Texture {
+            image: ColorImage {
+                size,
+                pixels: image
+                    .pixels
+                    .iter()
+                    .map(|&[r, g, b, a]| {
+                        Color32::from_rgba_premultiplied(to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+                    })
+                    .collect(),
+            },
+            options: TextureOptions::LINEAR,
+            sdf: false,
+        };
+
+        // The uv coordinates must match the part of the region we blurred:
+        let mut mesh = blur.mesh.clone();
+        for vertex in &mut mesh.vertices {
+            let p = vertex.pos.to_vec2() * pixels_per_point;
+            vertex.uv = Pos2::new(
+                (p.x - x0 as f32) / size[0] as f32,
+                (p.y - y0 as f32) / size[1] as f32,
+            );
+        }
+        paint_mesh(target, clip_rect, &mesh, &texture, pixels_per_point);
+    }
+}
+
+fn paint_mesh(
+    target: &mut Target,
+    clip_rect: Rect,
+    mesh: &Mesh,
+    texture: &Texture,
+    pixels_per_point: f32,
+) {
+    // Same rounding as the GPU backends use for their scissor rects:
+    let [w, h] = target.size;
+    let to_px = |points: f32, max: usize| {
+        (points * pixels_per_point).round().clamp(0.0, max as f32) as usize
+    };
+    let clip_x0 = to_px(clip_rect.min.x, w);
+    let clip_y0 = to_px(clip_rect.min.y, h);
+    let clip_x1 = to_px(clip_rect.max.x, w);
+    let clip_y1 = to_px(clip_rect.max.y, h);
+    if clip_x1 <= clip_x0 || clip_y1 <= clip_y0 {
+        return;
+    }
+
+    for indices in mesh.indices.chunks_exact(3) {
+        let mut triangle = [0, 1, 2].map(|i| {
+            let vertex = mesh.vertices[indices[i] as usize];
+            Vertex {
+                pos: (vertex.pos.to_vec2() * pixels_per_point).to_pos2(),
+                ..vertex
+            }
+        });
+
+        let mut area = edge(triangle[0].pos, triangle[1].pos, triangle[2].pos);
+        if area == 0.0 {
+            continue;
+        }
+        if area < 0.0 {
+            // Make the winding order consistent, so that the fill rule below works.
+            triangle.swap(1, 2);
+            area = -area;
+        }
+        let [a, b, c] = triangle;
+
+        let texels_per_pixel = texels_per_pixel(texture, &triangle, area);
+        let filter = if texels_per_pixel > 1.0 {
+            texture.options.minification
+        } else {
+            texture.options.magnification
+        };
+
+        let bounds = Rect::from_points(&[a.pos, b.pos, c.pos]);
+        let min_x = (bounds.min.x.floor() as usize).max(clip_x0);
+        let min_y = (bounds.min.y.floor() as usize).max(clip_y0);
+        let max_x = (bounds.max.x.ceil() as usize).min(clip_x1);
+        let max_y = (bounds.max.y.ceil() as usize).min(clip_y1);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let w_a = edge(b.pos, c.pos, p);
+                let w_b = edge(c.pos, a.pos, p);
+                let w_c = edge(a.pos, b.pos, p);
+                if !covers(w_a, b.pos, c.pos)
+                    || !covers(w_b, c.pos, a.pos)
+                    || !covers(w_c, a.pos, b.pos)
+                {
+                    continue;
+                }
+                let weights = [w_a / area, w_b / area, w_c / area];
+
+                let color = interpolate(weights, [a, b, c].map(|v| rgba(v.color)));
+                let uv = interpolate(weights, [a, b, c].map(|v| [v.uv.x, v.uv.y, 0.0, 0.0]));
+                let mut texel = sample(texture, filter, uv[0], uv[1]);
+                if texture.sdf {
+                    texel = [egui::epaint::sdf::coverage(texel[3], texels_per_pixel); 4];
+                }
+
+                let src = [0, 1, 2, 3].map(|i| color[i] * texel[i]);
+                let dst = &mut target.pixels[y * w + x];
+                for i in 0..4 {
+                    dst[i] = src[i] + dst[i] * (1.0 - src[3]);
+                }
+            }
+        }
+    }
+}
+
+/// Blur an image in one direction with a Gaussian with the standard deviation `sigma`, in pixels.
+///
+/// Pixels outside the image are clamped to the edge, like a texture sampler would.
+fn gaussian_blur(image: &mut Target, sigma: f32, [dx, dy]: [usize; 2]) {
+    if sigma < 0.1 {
+        return;
+    }
+    let radius = (3.0 * sigma).ceil() as i64;
+    let weights: Vec<f32> = (-radius..=radius)
+        .map(|i| (-0.5 * (i as f32 / sigma).powi(2)).exp())
+        .collect();
+    let sum: f32 = weights.iter().sum();
+
+    let [w, h] = image.size;
+    let source = image.pixels.clone();
+    for y in 0..h {
+        for x in 0..w {
+            let mut blurred = [0.0; 4];
+            for (i, weight) in (-radius..=radius).zip(&weights) {
+                let sx = (x as i64 + i * dx as i64).clamp(0, w as i64 - 1) as usize;
+                let sy = (y as i64 + i * dy as i64).clamp(0, h as i64 - 1) as usize;
+                let texel = source[sy * w + sx];
+                for c in 0..4 {
+                    blurred[c] += weight / sum * texel[c];
+                }
+            }
+            image.pixels[y * w + x] = blurred;
+        }
+    }
+}
+
+/// Premultiplied, gamma-space RGBA in `[0, 1]`.
+type Rgba = [f32; 4];
+
+struct Target {
+    size: [usize; 2],
+    pixels: Vec<Rgba>,
+}
+
+/// Twice the signed area of the triangle `a, b, p`.
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Is a pixel with the given edge value on the inside of the edge from `a` to `b`?
+///
+/// Pixels exactly on an edge are only covered by one of the two triangles sharing it,
+/// so that blended meshes don't get double-painted seams.
+fn covers(edge_value: f32, a: Pos2, b: Pos2) -> bool {
+    if edge_value != 0.0 {
+        return edge_value > 0.0;
+    }
+    let d = b - a;
+    d.y < 0.0 || (d.y == 0.0 && d.x > 0.0)
+}
+
+fn interpolate(weights: [f32; 3], values: [Rgba; 3]) -> Rgba {
+    [0, 1, 2, 3]
+        .map(|i| weights[0] * values[0][i] + weights[1] * values[1][i] + weights[2] * values[2][i])
+}
+
+fn rgba(color: Color32) -> Rgba {
+    color.to_array().map(|c| c as f32 / 255.0)
+}
+
+/// How many texels one pixel of the triangle spans, in each direction.
+///
+/// Used to pick the magnification or minification filter.
+fn texels_per_pixel(texture: &Texture, triangle: &[Vertex; 3], area: f32) -> f32 {
+    let [w, h] = texture.image.size;
+    let [a, b, c] = triangle.map(|v| Pos2::new(v.uv.x * w as f32, v.uv.y * h as f32));
+    (edge(a, b, c).abs() / area).sqrt()
+}
+
+fn sample(texture: &Texture, filter: TextureFilter, u: f32, v: f32) -> Rgba {
+    let [w, h] = texture.image.size;
+    if w == 0 || h == 0 {
+        return [0.0; 4];
+    }
+    let wrap_mode = texture.options.wrap_mode;
+    let texel = |x: i64, y: i64| {
+        let x = wrap(x, w, wrap_mode);
+        let y = wrap(y, h, wrap_mode);
+        rgba(texture.image.pixels[y * w + x])
+    };
+
+    let x = u * w as f32;
+    let y = v * h as f32;
+    match filter {
+        TextureFilter::Nearest => texel(x.floor() as i64, y.floor() as i64),
+        TextureFilter::Linear => {
+            let x = x - 0.5;
+            let y = y - 0.5;
+            let (x0, y0) = (x.floor(), y.floor());
+            let (tx, ty) = (x - x0, y - y0);
+            let (x0, y0) = (x0 as i64, y0 as i64);
+            let top = lerp_rgba(texel(x0, y0), texel(x0 + 1, y0), tx);
+            let bottom = lerp_rgba(texel(x0, y0 + 1), texel(x0 + 1, y0 + 1), tx);
+            lerp_rgba(top, bottom, ty)
+        }
+    }
+}
+
+fn lerp_rgba(a: Rgba, b: Rgba, t: f32) -> Rgba {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn wrap(coord: i64, size: usize, wrap_mode: TextureWrapMode) -> usize {
+    let size = size as i64;
+    let coord = match wrap_mode {
+        TextureWrapMode::ClampToEdge => coord.clamp(0, size - 1),
+        TextureWrapMode::Repeat => coord.rem_euclid(size),
+        TextureWrapMode::MirroredRepeat => {
+            let period = coord.rem_euclid(2 * size);
+            if period < size {
+                period
+            } else {
+                2 * size - 1 - period
+            }
+        }
+    };
+    coord as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{epaint::Tessellator, pos2, vec2, ClippedPrimitive, Color32, Rect};
+
+    use super::*;
+
+    fn render_rects(rects: &[(Rect, Color32)], clip_rect: Rect) -> ColorImage {
+        let mut renderer = SoftwareRenderer::default();
+        renderer.update_textures(&TexturesDelta {
+            set: vec![(
+                TextureId::default(),
+                ImageDelta::full(
+                    ColorImage::new([1, 1], Color32::WHITE),
+                    TextureOptions::NEAREST,
+                ),
+            )],
+            free: vec![],
+        });
+
+        let primitives: Vec<ClippedPrimitive> = rects
+            .iter()
+            .map(|&(rect, color)| {
+                let mut mesh = Mesh::default();
+                mesh.add_colored_rect(rect, color);
+                ClippedPrimitive {
+                    clip_rect,
+                    primitive: Primitive::Mesh(mesh),
+                }
+            })
+            .collect();
+        renderer.render(&primitives, 1.0, [8, 8])
+    }
+
+    #[test]
+    fn rect_is_filled_exactly_once() {
+        let half_red = Color32::from_rgba_premultiplied(128, 0, 0, 128);
+        let rect = Rect::from_min_size(pos2(2.0, 2.0), vec2(4.0, 4.0));
+        let image = render_rects(&[(rect, half_red)], Rect::EVERYTHING);
+
+        assert_eq!(image[(1, 1)], Color32::TRANSPARENT);
+        // No seam along the diagonal between the two triangles:
+        for y in 2..6 {
+            for x in 2..6 {
+                assert_eq!(image[(x, y)], half_red, "pixel {x},{y}");
+            }
+        }
+        assert_eq!(image[(6, 6)], Color32::TRANSPARENT);
+    }
+
+    #[test]
+    fn premultiplied_blending() {
+        let rect = Rect::from_min_size(pos2(0.0, 0.0), vec2(8.0, 8.0));
+        let half_white = Color32::from_rgba_premultiplied(128, 128, 128, 128);
+        let image = render_rects(
+            &[(rect, Color32::BLUE), (rect, half_white)],
+            Rect::EVERYTHING,
+        );
+        assert_eq!(image[(4, 4)], Color32::from_rgb(128, 128, 255));
+    }
+
+    #[test]
+    fn clip_rect() {
+        let rect = Rect::from_min_size(pos2(0.0, 0.0), vec2(8.0, 8.0));
+        let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(4.0, 8.0));
+        let image = render_rects(&[(rect, Color32::RED)], clip_rect);
+        assert_eq!(image[(3, 4)], Color32::RED);
+        assert_eq!(image[(4, 4)], Color32::TRANSPARENT);
+    }
+
+    #[test]
+    fn tessellated_circle() {
+        let mut mesh = Mesh::default();
+        let mut tessellator = Tessellator::new(1.0, Default::default(), [1, 1], vec![]);
+        tessellator.tessellate_circle(
+            egui::epaint::CircleShape::filled(pos2(4.0, 4.0), 3.0, Color32::GREEN),
+            &mut mesh,
+        );
+        let primitives = [ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        }];
+
+        let mut renderer = SoftwareRenderer::default();
+        renderer.update_textures(&TexturesDelta {
+            set: vec![(
+                TextureId::default(),
+                ImageDelta::full(
+                    ColorImage::new([1, 1], Color32::WHITE),
+                    TextureOptions::LINEAR,
+                ),
+            )],
+            free: vec![],
+        });
+        let image = renderer.render(&primitives, 1.0, [8, 8]);
+        assert_eq!(image[(4, 4)], Color32::GREEN);
+        assert_eq!(image[(0, 0)], Color32::TRANSPARENT);
+    }
+
+    fn render_shapes(shapes: Vec<egui::Shape>, size: [usize; 2]) -> ColorImage {
+        let clip_rect = Rect::from_min_size(Pos2::ZERO, vec2(size[0] as f32, size[1] as f32));
+        let shapes = shapes
+            .into_iter()
+            .map(|shape| egui::epaint::ClippedShape { clip_rect, shape })
+            .collect();
+        let primitives =
+            Tessellator::new(1.0, Default::default(), [1, 1], vec![]).tessellate_shapes(shapes);
+
+        let mut renderer = SoftwareRenderer::default();
+        renderer.update_textures(&TexturesDelta {
+            set: vec![(
+                TextureId::default(),
+                ImageDelta::full(
+                    ColorImage::new([1, 1], Color32::WHITE),
+                    TextureOptions::LINEAR,
+                ),
+            )],
+            free: vec![],
+        });
+        renderer.render(&primitives, 1.0, size)
+    }
+
+    #[test]
+    fn blurred_shadow() {
+        let rect = Rect::from_min_size(pos2(12.0, 12.0), vec2(8.0, 8.0));
+        let shadow = egui::Shadow {
+            offset: egui::Vec2::ZERO,
+            blur: 8.0,
+            spread: 0.0,
+            color: Color32::BLACK,
+        };
+        let image = render_shapes(
+            vec![egui::Shape::shadow(
+                egui::Shape::rect_filled(rect, 0.0, Color32::WHITE),
+                shadow,
+            )],
+            [32, 32],
+        );
+
+        let alpha = |x: usize, y: usize| image[(x, y)].a();
+        assert!(alpha(16, 16) > 200, "{}", alpha(16, 16));
+        // The edge of the shape is half covered:
+        assert!((100..156).contains(&alpha(12, 16)), "{}", alpha(12, 16));
+        assert!(0 < alpha(9, 16) && alpha(9, 16) < alpha(12, 16));
+        assert_eq!(alpha(2, 16), 0);
+        // Symmetric:
+        assert!(alpha(9, 16).abs_diff(alpha(22, 16)) <= 1);
+        assert!(alpha(16, 9).abs_diff(alpha(16, 22)) <= 1);
+    }
+
+    #[test]
+    fn shadow_of_image() {
+        // The image texture is not even uploaded: a shadow only needs the shape of the image.
+        let rect = Rect::from_min_size(pos2(12.0, 12.0), vec2(8.0, 8.0));
+        let shadow = egui::Shadow {
+            offset: egui::Vec2::ZERO,
+            blur: 4.0,
+            spread: 0.0,
+            color: Color32::BLACK,
+        };
+        let image = render_shapes(
+            vec![egui::Shape::shadow(
+                egui::Shape::image(
+                    TextureId::User(7),
+                    rect,
+                    Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+                    Color32::WHITE,
+                ),
+                shadow,
+            )],
+            [32, 32],
+        );
+
+        let [r, g, b, a] = image[(16, 16)].to_array();
+        assert_eq!([r, g, b], [0, 0, 0]);
+        assert!(a > 200, "{a}");
+        assert_eq!(image[(2, 16)].a(), 0);
+    }
+
+    #[test]
+    fn backdrop_blur() {
+        let left = Rect::from_min_max(pos2(0.0, 0.0), pos2(16.0, 32.0));
+        let right = Rect::from_min_max(pos2(16.0, 0.0), pos2(32.0, 32.0));
+        let blurred = Rect::from_min_max(pos2(8.0, 8.0), pos2(24.0, 24.0));
+        let image = render_shapes(
+            vec![
+                egui::Shape::rect_filled(left, 0.0, Color32::RED),
+                egui::Shape::rect_filled(right, 0.0, Color32::BLUE),
+                egui::Shape::backdrop_blur(blurred, 0.0, 8.0),
+            ],
+            [32, 32],
+        );
+
+        // Outside of the blurred rectangle nothing changes:
+        assert_eq!(image[(15, 4)], Color32::RED);
+        assert_eq!(image[(16, 4)], Color32::BLUE);
+        // Inside it, the colors are mixed where they meet:
+        let [r, _, b, a] = image[(15, 16)].to_array();
+        assert_eq!(a, 255);
+        assert!(r > b && b > 64, "{:?}", image[(15, 16)]);
+        assert_eq!(image[(9, 16)], Color32::RED);
+    }
+
+    fn render_text(rasterization: egui::GlyphRasterization) -> ColorImage {
+        let ctx = egui::Context::default();
+        ctx.set_fonts(egui::FontDefinitions {
+            rasterization,
+            ..Default::default()
+        });
+
+        let mut renderer = SoftwareRenderer::default();
+        let mut image = ColorImage::default();
+        for _ in 0..2 {
+            // The fonts are set at the start of the next frame.
+            let output = ctx.run(Default::default(), |ctx| {
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::none())
+                    .show(ctx, |ui| {
+                        ui.label(
+                            egui::RichText::new("Hello")
+                                .size(20.0)
+                                .color(Color32::WHITE),
+                        );
+                    });
+            });
+            renderer.update_textures(&output.textures_delta);
+            let primitives = ctx.tessellate(output.shapes, output.pixels_per_point);
+            image = renderer.render(&primitives, output.pixels_per_point, [64, 32]);
+        }
+        image
+    }
+
+    #[test]
+    fn sdf_text_looks_like_coverage_text() {
+        let ink =
+            |image: &ColorImage| -> f32 { image.pixels.iter().map(|p| p.a() as f32 / 255.0).sum() };
+        let coverage = ink(&render_text(egui::GlyphRasterization::Coverage));
+        let sdf = ink(&render_text(egui::GlyphRasterization::Sdf));
+        assert!(coverage > 20.0, "No text was painted");
+        assert!(
+            (sdf / coverage - 1.0).abs() < 0.1,
+            "coverage: {coverage}, sdf: {sdf}"
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use egui::{Color32, ColorImage};
+
+/// Set this environment variable to overwrite the stored snapshots with the new images,
+/// e.g. `UPDATE_SNAPSHOTS=1 cargo test`.
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "UPDATE_SNAPSHOTS";
+
+/// How to compare an image against its stored snapshot.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct SnapshotOptions {
+    /// How much a single color channel of a pixel may differ before the pixel counts as changed.
+    ///
+    /// Default: 4.
+    pub threshold: u8,
+
+    /// How many pixels may change before the snapshot fails.
+    ///
+    /// Default: 0.
+    pub failed_pixel_count_threshold: usize,
+
+    /// The directory the snapshots are stored in, relative to the working directory
+    /// (which is the crate root for `cargo test`).
+    ///
+    /// Default: `tests/snapshots`.
+    pub output_path: PathBuf,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 4,
+            failed_pixel_count_threshold: 0,
+            output_path: PathBuf::from("tests/snapshots"),
+        }
+    }
+}
+
+impl SnapshotOptions {
+    /// See [`Self::threshold`].
+    #[inline]
+    pub fn with_threshold(mut self, threshold: u8) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// See [`Self::failed_pixel_count_threshold`].
+    #[inline]
+    pub fn with_failed_pixel_count_threshold(
+        mut self,
+        failed_pixel_count_threshold: usize,
+    ) -> Self {
+        self.failed_pixel_count_threshold = failed_pixel_count_threshold;
+        self
+    }
+
+    /// See [`Self::output_path`].
+    #[inline]
+    pub fn with_output_path(mut self, output_path: impl Into<PathBuf>) -> Self {
+        self.output_path = output_path.into();
+        self
+    }
+}
+
+/// Why an image didn't match its snapshot.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// There is no stored snapshot, or it couldn't be read.
+    OpenSnapshot {
+        path: PathBuf,
+        err: image::ImageError,
+    },
+
+    /// The image and the snapshot have different sizes.
+    SizeMismatch {
+        name: String,
+        expected: [usize; 2],
+        actual: [usize; 2],
+    },
+
+    /// Too many pixels differ between the image and the snapshot.
+    Diff {
+        name: String,
+        diff_count: usize,
+        max_channel_diff: u8,
+        diff_path: PathBuf,
+    },
+
+    /// The new image, the diff image or the updated snapshot couldn't be written.
+    WriteSnapshot {
+        path: PathBuf,
+        err: image::ImageError,
+    },
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpenSnapshot { path, err } => write!(
+                f,
+                "Failed to open snapshot {}: {err}. \
+                Run with {UPDATE_SNAPSHOTS_ENV_VAR}=1 to create it.",
+                path.display()
+            ),
+            Self::SizeMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Snapshot {name:?} has size {expected:?}, but the new image has size {actual:?}. \
+                Run with {UPDATE_SNAPSHOTS_ENV_VAR}=1 to accept the new image."
+            ),
+            Self::Diff {
+                name,
+                diff_count,
+                max_channel_diff,
+                diff_path,
+            } => write!(
+                f,
+                "Snapshot {name:?} differs in {diff_count} pixels \
+                (largest channel difference: {max_channel_diff}). \
+                See {} for the differences. \
+                Run with {UPDATE_SNAPSHOTS_ENV_VAR}=1 to accept the new image.",
+                diff_path.display()
+            ),
+            Self::WriteSnapshot { path, err } => {
+                write!(f, "Failed to write {}: {err}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+fn should_update_snapshots() -> bool {
+    std::env::var(UPDATE_SNAPSHOTS_ENV_VAR).map_or(false, |value| value != "0" && value != "false")
+}
+
+fn save_png(image: &ColorImage, path: &Path) -> Result<(), SnapshotError> {
+    let write_err = |err| SnapshotError::WriteSnapshot {
+        path: path.to_owned(),
+        err,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| write_err(image::ImageError::IoError(err)))?;
+    }
+    image::save_buffer(
+        path,
+        &unmultiplied_bytes(image),
+        image.size[0] as u32,
+        image.size[1] as u32,
+        image::ColorType::Rgba8,
+    )
+    .map_err(write_err)
+}
+
+fn unmultiplied_bytes(image: &ColorImage) -> Vec<u8> {
+    image
+        .pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+        .collect()
+}
+
+/// Compare the image with the snapshot stored at `{options.output_path}/{name}.png`.
+///
+/// If they differ, the new image is written to `{name}.new.png` and an image highlighting
+/// the changed pixels in red is written to `{name}.diff.png`, next to the snapshot.
+///
+/// If the `UPDATE_SNAPSHOTS` environment variable is set, the snapshot is overwritten instead.
+///
+/// # Errors
+/// If the snapshot is missing, or the image doesn't match it within the thresholds in `options`.
+pub fn try_image_snapshot_options(
+    new: &ColorImage,
+    name: &str,
+    options: &SnapshotOptions,
+) -> Result<(), SnapshotError> {
+    let dir = &options.output_path;
+    let snapshot_path = dir.join(format!("{name}.png"));
+    let new_path = dir.join(format!("{name}.new.png"));
+    let diff_path = dir.join(format!("{name}.diff.png"));
+
+    // Left over from a previous failed run:
+    std::fs::remove_file(&new_path).ok();
+    std::fs::remove_file(&diff_path).ok();
+
+    if should_update_snapshots() {
+        return save_png(new, &snapshot_path);
+    }
+
+    let snapshot = match image::open(&snapshot_path) {
+        Ok(snapshot) => snapshot.into_rgba8(),
+        Err(err) => {
+            save_png(new, &new_path)?;
+            return Err(SnapshotError::OpenSnapshot {
+                path: snapshot_path,
+                err,
+            });
+        }
+    };
+
+    let expected = [snapshot.width() as usize, snapshot.height() as usize];
+    if expected != new.size {
+        save_png(new, &new_path)?;
+        return Err(SnapshotError::SizeMismatch {
+            name: name.to_owned(),
+            expected,
+            actual: new.size,
+        });
+    }
+
+    // Compare unmultiplied, since that is what the png stores:
+    let new_bytes = unmultiplied_bytes(new);
+    let mut diff_count = 0;
+    let mut max_channel_diff = 0;
+    let mut diff_image = ColorImage::new(new.size, Color32::TRANSPARENT);
+    for (i, (new_pixel, old_pixel)) in new_bytes
+        .chunks_exact(4)
+        .zip(snapshot.as_raw().chunks_exact(4))
+        .enumerate()
+    {
+        let channel_diff = new_pixel
+            .iter()
+            .zip(old_pixel)
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or_default();
+        max_channel_diff = max_channel_diff.max(channel_diff);
+
+        diff_image.pixels[i] = if channel_diff > options.threshold {
+            diff_count += 1;
+            Color32::RED
+        } else {
+            // Show the unchanged parts faded, for context.
+            new.pixels[i].gamma_multiply(0.25)
+        };
+    }
+
+    if diff_count > options.failed_pixel_count_threshold {
+        save_png(new, &new_path)?;
+        save_png(&diff_image, &diff_path)?;
+        return Err(SnapshotError::Diff {
+            name: name.to_owned(),
+            diff_count,
+            max_channel_diff,
+            diff_path,
+        });
+    }
+
+    Ok(())
+}
+
+/// [`try_image_snapshot_options`] with the default [`SnapshotOptions`].
+///
+/// # Errors
+/// If the snapshot is missing, or the image doesn't match it.
+pub fn try_image_snapshot(new: &ColorImage, name: &str) -> Result<(), SnapshotError> {
+    try_image_snapshot_options(new, name, &SnapshotOptions::default())
+}
+
+/// Assert that the image matches its stored snapshot.
+///
+/// See [`try_image_snapshot_options`].
+///
+/// # Panics
+/// If the snapshot is missing, or the image doesn't match it.
+#[track_caller]
+pub fn image_snapshot_options(new: &ColorImage, name: &str, options: &SnapshotOptions) {
+    if let Err(err) = try_image_snapshot_options(new, name, options) {
+        panic!("{err}");
+    }
+}
+
+/// Assert that the image matches its stored snapshot, using the default [`SnapshotOptions`].
+///
+/// See [`try_image_snapshot_options`].
+///
+/// # Panics
+/// If the snapshot is missing, or the image doesn't match it.
+#[track_caller]
+pub fn image_snapshot(new: &ColorImage, name: &str) {
+    image_snapshot_options(new, name, &SnapshotOptions::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_diff() {
+        if should_update_snapshots() {
+            return; // This test checks the comparison, which is skipped when updating.
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let options = SnapshotOptions::default().with_output_path(dir);
+
+        let image = ColorImage::new([4, 4], Color32::BLUE);
+        save_png(&image, &dir.join("square.png")).unwrap();
+        assert!(try_image_snapshot_options(&image, "square", &options).is_ok());
+
+        let mut changed = image.clone();
+        changed.pixels[5] = Color32::from_rgb(0, 0, 253);
+        assert!(
+            try_image_snapshot_options(&changed, "square", &options).is_ok(),
+            "Small differences should be within the threshold"
+        );
+
+        changed.pixels[6] = Color32::RED;
+        let err = try_image_snapshot_options(&changed, "square", &options).unwrap_err();
+        assert!(matches!(err, SnapshotError::Diff { diff_count: 1, .. }));
+        assert!(dir.join("square.new.png").exists());
+        assert!(dir.join("square.diff.png").exists());
+
+        let err = try_image_snapshot_options(&image, "missing", &options).unwrap_err();
+        assert!(matches!(err, SnapshotError::OpenSnapshot { .. }));
+    }
+}
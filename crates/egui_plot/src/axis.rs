@@ -1,12 +1,15 @@
 use std::{fmt::Debug, ops::RangeInclusive, sync::Arc};
 
 use egui::{
-    emath::{remap_clamp, round_to_decimals, Rot2},
-    epaint::TextShape,
-    Pos2, Rangef, Rect, Response, Sense, TextStyle, Ui, Vec2, WidgetText,
+    emath::{lerp, remap_clamp, round_to_decimals, Rot2},
+    epaint::{Mesh, TextShape},
+    pos2, Pos2, Rangef, Rect, Response, Sense, TextStyle, Ui, Vec2, WidgetText,
 };
 
-use super::{transform::PlotTransform, GridMark};
+use super::{
+    transform::{PlotBounds, PlotTransform},
+    Colormap, GridMark,
+};
 
 pub(super) type AxisFormatterFn = dyn Fn(GridMark, usize, &RangeInclusive<f64>) -> String;
 
@@ -400,3 +403,113 @@ impl AxisWidget {
         (response, thickness)
     }
 }
+
+// ----------------------------------------------------------------------------
+
+/// Space between the plot and the colorbar.
+const COLORBAR_MARGIN: f32 = 8.0;
+
+/// Width of the bar itself, without the labels.
+const COLORBAR_WIDTH: f32 = 12.0;
+
+/// A bar next to the plot, showing which values the colors of a [`crate::Heatmap`] stand for.
+///
+/// The values are labelled like a y axis on the right of the plot.
+#[derive(Clone)]
+pub(super) struct ColorbarWidget {
+    pub colormap: Colormap,
+    pub range: RangeInclusive<f64>,
+    pub hints: AxisHints,
+
+    /// The region of the bar and its labels.
+    pub rect: Rect,
+}
+
+impl ColorbarWidget {
+    pub fn new(colormap: Colormap, range: RangeInclusive<f64>, label: String) -> Self {
+        Self {
+            colormap,
+            range,
+            hints: AxisHints::new_y().label(label).placement(HPlacement::Right),
+            rect: Rect::NOTHING,
+        }
+    }
+
+    /// The width needed for the bar and the labels, assuming they are as wide as the hints say.
+    pub fn thickness(&self) -> f32 {
+        COLORBAR_MARGIN + COLORBAR_WIDTH + self.hints.thickness(Axis::Y)
+    }
+
+    /// Returns the actual thickness of the colorbar.
+    pub fn ui(self, ui: &mut Ui) -> f32 {
+        let Self {
+            colormap,
+            range,
+            hints,
+            rect,
+        } = self;
+
+        let bar_rect = Rect::from_x_y_ranges(
+            rect.left() + COLORBAR_MARGIN..=rect.left() + COLORBAR_MARGIN + COLORBAR_WIDTH,
+            rect.y_range(),
+        );
+        if ui.is_rect_visible(bar_rect) {
+            const SEGMENTS: u32 = 64;
+            let mut mesh = Mesh::default();
+            for i in 0..=SEGMENTS {
+                let t = i as f32 / SEGMENTS as f32;
+                let y = lerp(bar_rect.bottom()..=bar_rect.top(), t);
+                let color = colormap.color(t as f64);
+                mesh.colored_vertex(pos2(bar_rect.left(), y), color);
+                mesh.colored_vertex(pos2(bar_rect.right(), y), color);
+                if i > 0 {
+                    let top_left = 2 * i;
+                    mesh.add_triangle(top_left - 2, top_left - 1, top_left);
+                    mesh.add_triangle(top_left - 1, top_left + 1, top_left);
+                }
+            }
+            ui.painter().add(mesh);
+            ui.painter()
+                .rect_stroke(bar_rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+        }
+
+        let (min, max) = (*range.start(), *range.end());
+        let transform = PlotTransform::new(
+            bar_rect,
+            PlotBounds::from_min_max([0.0, min], [1.0, max]),
+            false,
+            false,
+        );
+
+        // Label round values (1, 2 or 5 times a power of ten) that are far enough apart:
+        let min_step = transform.dvalue_dpos()[1].abs() * hints.label_spacing.max as f64;
+        let magnitude = 10.0_f64.powf(min_step.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .into_iter()
+            .map(|factor| factor * magnitude)
+            .find(|&step| step >= min_step)
+            .unwrap_or(10.0 * magnitude);
+        let steps = if step.is_finite() && step > 0.0 {
+            ((min / step).ceil() as i64..=(max / step).floor() as i64)
+                .map(|i| GridMark {
+                    value: i as f64 * step,
+                    step_size: step,
+                })
+                .collect()
+        } else {
+            Vec::new() // no room
+        };
+
+        let min_thickness = hints.thickness(Axis::Y);
+        let labels = AxisWidget {
+            range,
+            hints,
+            rect: Rect::from_x_y_ranges(bar_rect.right() + 4.0..=rect.right(), rect.y_range()),
+            transform: Some(transform),
+            steps: Arc::new(steps),
+        };
+        let (_response, label_thickness) = labels.ui(ui, Axis::Y);
+
+        COLORBAR_MARGIN + COLORBAR_WIDTH + min_thickness.max(label_thickness + 4.0)
+    }
+}
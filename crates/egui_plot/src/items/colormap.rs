@@ -0,0 +1,128 @@
+use egui::{lerp, Color32};
+
+/// Maps values to colors, e.g. for a [`super::Heatmap`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Colormap {
+    /// Dark blue to green to yellow.
+    ///
+    /// Perceptually uniform, and readable for color blind people and in grayscale.
+    #[default]
+    Viridis,
+
+    /// Black to purple to light yellow.
+    ///
+    /// Perceptually uniform, and readable for color blind people and in grayscale.
+    Magma,
+
+    /// Blue to white to red.
+    ///
+    /// For values that go both ways from a center, which is shown as white.
+    Diverging,
+}
+
+impl Colormap {
+    /// Get a list of all colormaps.
+    pub fn all() -> impl ExactSizeIterator<Item = Self> {
+        [Self::Viridis, Self::Magma, Self::Diverging]
+            .iter()
+            .copied()
+    }
+
+    /// Does this colormap have a center that values diverge from?
+    pub fn is_diverging(self) -> bool {
+        self == Self::Diverging
+    }
+
+    /// The color for `t`, which goes from 0 (lowest value) to 1 (highest value).
+    ///
+    /// Values outside that range are clamped. `NaN` is transparent.
+    pub fn color(self, t: f64) -> Color32 {
+        if t.is_nan() {
+            return Color32::TRANSPARENT;
+        }
+
+        let stops = self.stops();
+        let t = t.clamp(0.0, 1.0) as f32 * (stops.len() - 1) as f32;
+        let i = (t as usize).min(stops.len() - 2);
+        let (t, [r0, g0, b0], [r1, g1, b1]) = (t - i as f32, stops[i], stops[i + 1]);
+        let channel = |a: u8, b: u8| lerp(a as f32..=b as f32, t).round() as u8;
+        Color32::from_rgb(channel(r0, r1), channel(g0, g1), channel(b0, b1))
+    }
+
+    /// Evenly spaced colors, interpolated in between.
+    fn stops(self) -> &'static [[u8; 3]] {
+        match self {
+            Self::Viridis => &[
+                [0x44, 0x01, 0x54],
+                [0x47, 0x2c, 0x7a],
+                [0x3b, 0x51, 0x8b],
+                [0x2c, 0x71, 0x8e],
+                [0x21, 0x90, 0x8d],
+                [0x27, 0xad, 0x81],
+                [0x5c, 0xc8, 0x63],
+                [0xaa, 0xdc, 0x32],
+                [0xfd, 0xe7, 0x25],
+            ],
+            Self::Magma => &[
+                [0x00, 0x00, 0x04],
+                [0x1c, 0x10, 0x44],
+                [0x4f, 0x12, 0x7b],
+                [0x81, 0x25, 0x81],
+                [0xb5, 0x36, 0x7a],
+                [0xe5, 0x50, 0x64],
+                [0xfb, 0x87, 0x61],
+                [0xfe, 0xc2, 0x87],
+                [0xfc, 0xfd, 0xbf],
+            ],
+            Self::Diverging => &[
+                [0x21, 0x66, 0xac],
+                [0x43, 0x93, 0xc3],
+                [0x92, 0xc5, 0xde],
+                [0xd1, 0xe5, 0xf0],
+                [0xf7, 0xf7, 0xf7],
+                [0xfd, 0xdb, 0xc7],
+                [0xf4, 0xa5, 0x82],
+                [0xd6, 0x60, 0x4d],
+                [0xb2, 0x18, 0x2b],
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for Colormap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Viridis => "Viridis",
+            Self::Magma => "Magma",
+            Self::Diverging => "Diverging",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_are_the_first_and_last_stops() {
+        for colormap in Colormap::all() {
+            let stops = colormap.stops();
+            let [r, g, b] = stops[0];
+            assert_eq!(colormap.color(0.0), Color32::from_rgb(r, g, b));
+            assert_eq!(colormap.color(-1.0), Color32::from_rgb(r, g, b));
+            let [r, g, b] = stops[stops.len() - 1];
+            assert_eq!(colormap.color(1.0), Color32::from_rgb(r, g, b));
+            assert_eq!(colormap.color(2.0), Color32::from_rgb(r, g, b));
+            assert_eq!(colormap.color(f64::NAN), Color32::TRANSPARENT);
+        }
+        assert_eq!(
+            Colormap::Viridis.color(0.0),
+            Color32::from_rgb(0x44, 0x01, 0x54)
+        );
+        assert_eq!(
+            Colormap::Viridis.color(1.0),
+            Color32::from_rgb(0xfd, 0xe7, 0x25)
+        );
+    }
+}
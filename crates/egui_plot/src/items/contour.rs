@@ -0,0 +1,888 @@
+use std::ops::{Range, RangeInclusive};
+
+use egui::{
+    ahash, ecolor::tint_color_towards, emath::round_to_decimals, epaint::Mesh, Color32, Id, Shape,
+    Stroke, Ui,
+};
+
+use crate::PlotBounds;
+
+use super::{Colormap, LineStyle, PlotGeometry, PlotItem, PlotPoint, PlotTransform, Text};
+
+/// A grid of values, with a value at the center of each cell.
+struct Grid {
+    /// Row by row, starting with the bottom row (lowest y).
+    values: Vec<f64>,
+    columns: usize,
+    x_range: RangeInclusive<f64>,
+    y_range: RangeInclusive<f64>,
+}
+
+impl Grid {
+    fn new(values: Vec<f64>, columns: usize) -> Self {
+        let columns = columns.max(1);
+        let rows = values.len() / columns;
+        Self {
+            values,
+            columns,
+            x_range: 0.0..=columns as f64,
+            y_range: 0.0..=rows as f64,
+        }
+    }
+
+    fn rows(&self) -> usize {
+        self.values.len() / self.columns
+    }
+
+    fn value(&self, column: usize, row: usize) -> f64 {
+        self.values[row * self.columns + column]
+    }
+
+    /// The center of a cell.
+    fn point(&self, column: f64, row: f64) -> PlotPoint {
+        let (x_min, y_min) = (*self.x_range.start(), *self.y_range.start());
+        let cell_width = (self.x_range.end() - x_min) / self.columns as f64;
+        let cell_height = (self.y_range.end() - y_min) / self.rows() as f64;
+        PlotPoint::new(
+            x_min + (column + 0.5) * cell_width,
+            y_min + (row + 0.5) * cell_height,
+        )
+    }
+
+    /// The lowest and highest finite value.
+    fn value_range(&self) -> Option<(f64, f64)> {
+        let (min, max) = self
+            .values
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+        (min <= max).then_some((min, max))
+    }
+
+    /// The explicit levels, or else `num_levels` evenly spaced levels between the lowest and highest value.
+    fn levels(&self, levels: &Option<Vec<f64>>, num_levels: usize) -> Vec<f64> {
+        if let Some(levels) = levels {
+            return levels.clone();
+        }
+        let Some((min, max)) = self.value_range() else {
+            return Vec::new();
+        };
+        (1..=num_levels)
+            .map(|i| min + (max - min) * i as f64 / (num_levels + 1) as f64)
+            .collect()
+    }
+
+    /// The corners of the cell between the centers of the grid cells with these indices,
+    /// counter-clockwise from the bottom left, or `None` if a value is missing.
+    fn corners(&self, column: usize, row: usize) -> Option<[(PlotPoint, f64); 4]> {
+        let corners = [(0, 0), (1, 0), (1, 1), (0, 1)].map(|(dc, dr)| {
+            let (c, r) = (column + dc, row + dr);
+            (self.point(c as f64, r as f64), self.value(c, r))
+        });
+        corners
+            .iter()
+            .all(|(_, value)| value.is_finite())
+            .then_some(corners)
+    }
+
+    /// Marching squares: the lines where the values cross `level`.
+    ///
+    /// Returns all points, and the range of points of each line.
+    fn isolines(&self, level: f64) -> (Vec<PlotPoint>, Vec<Range<usize>>) {
+        // A crossing of `level` on an edge between two neighboring values.
+        // Edges are numbered by their first value, and whether they go right (even) or up (odd).
+        let mut crossings: ahash::HashMap<usize, PlotPoint> = Default::default();
+        let mut segments: Vec<[usize; 2]> = Vec::new();
+
+        let columns = self.columns;
+        for row in 0..self.rows().saturating_sub(1) {
+            for column in 0..columns - 1 {
+                let Some(corners) = self.corners(column, row) else {
+                    continue;
+                };
+
+                // Bottom, right, top and left edge of the cell:
+                let first = row * columns + column;
+                let edges = [
+                    2 * first,
+                    2 * (first + 1) + 1,
+                    2 * (first + columns),
+                    2 * first + 1,
+                ];
+                let mut crossed = [0; 4];
+                let mut num_crossed = 0;
+                for (side, &edge) in edges.iter().enumerate() {
+                    let (a, b) = (corners[side], corners[(side + 1) % 4]);
+                    if (a.1 >= level) != (b.1 >= level) {
+                        let t = (level - a.1) / (b.1 - a.1);
+                        crossings.entry(edge).or_insert_with(|| {
+                            PlotPoint::new(a.0.x + t * (b.0.x - a.0.x), a.0.y + t * (b.0.y - a.0.y))
+                        });
+                        crossed[num_crossed] = edge;
+                        num_crossed += 1;
+                    }
+                }
+
+                match num_crossed {
+                    2 => segments.push([crossed[0], crossed[1]]),
+                    4 => {
+                        // A saddle: decide from the center value which diagonal is connected.
+                        let center = corners.iter().map(|(_, value)| value).sum::<f64>() / 4.0;
+                        let bottom_left_is_high = corners[0].1 >= level;
+                        let [bottom, right, top, left] = edges;
+                        if bottom_left_is_high == (center >= level) {
+                            segments.push([bottom, right]);
+                            segments.push([top, left]);
+                        } else {
+                            segments.push([left, bottom]);
+                            segments.push([right, top]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Join the segments into lines:
+        let mut segments_at: ahash::HashMap<usize, Vec<usize>> = Default::default();
+        for (i, segment) in segments.iter().enumerate() {
+            for &edge in segment {
+                segments_at.entry(edge).or_default().push(i);
+            }
+        }
+        let mut used = vec![false; segments.len()];
+        let next_edge = |edge: usize, used: &mut [bool]| {
+            let i = *segments_at.get(&edge)?.iter().find(|&&i| !used[i])?;
+            used[i] = true;
+            let [a, b] = segments[i];
+            Some(if a == edge { b } else { a })
+        };
+
+        let mut points = Vec::new();
+        let mut lines = Vec::new();
+        for start in 0..segments.len() {
+            if used[start] {
+                continue;
+            }
+            used[start] = true;
+
+            let mut forward = segments[start].to_vec();
+            while let Some(edge) = next_edge(forward[forward.len() - 1], &mut used) {
+                forward.push(edge);
+            }
+            let mut backward = Vec::new();
+            while let Some(edge) = next_edge(*backward.last().unwrap_or(&forward[0]), &mut used) {
+                backward.push(edge);
+            }
+
+            let line_start = points.len();
+            points.extend(
+                backward
+                    .iter()
+                    .rev()
+                    .chain(&forward)
+                    .map(|edge| crossings[edge]),
+            );
+            lines.push(line_start..points.len());
+        }
+        (points, lines)
+    }
+
+    /// The triangles covering the area where the values are within `range`.
+    fn band(&self, range: RangeInclusive<f64>) -> Vec<PlotPoint> {
+        let (low, high) = (*range.start(), *range.end());
+        let mut triangles = Vec::new();
+        for row in 0..self.rows().saturating_sub(1) {
+            for column in 0..self.columns - 1 {
+                let Some(corners) = self.corners(column, row) else {
+                    continue;
+                };
+
+                // Clip the cell to the part within the range. All vertices stay on the border
+                // of the cell, so the result is convex.
+                let polygon = clip(&corners, |value| value >= low, low);
+                let polygon = clip(&polygon, |value| value <= high, high);
+                for i in 1..polygon.len().saturating_sub(1) {
+                    triangles.extend([polygon[0].0, polygon[i].0, polygon[i + 1].0]);
+                }
+            }
+        }
+        triangles
+    }
+}
+
+/// Sutherland–Hodgman clipping of a polygon with values at its vertices,
+/// interpolating where the values cross `threshold`.
+fn clip(
+    polygon: &[(PlotPoint, f64)],
+    inside: impl Fn(f64) -> bool,
+    threshold: f64,
+) -> Vec<(PlotPoint, f64)> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 2);
+    for (i, &a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        if inside(a.1) {
+            clipped.push(a);
+        }
+        if inside(a.1) != inside(b.1) {
+            let t = (threshold - a.1) / (b.1 - a.1);
+            let point = PlotPoint::new(a.0.x + t * (b.0.x - a.0.x), a.0.y + t * (b.0.y - a.0.y));
+            clipped.push((point, threshold));
+        }
+    }
+    clipped
+}
+
+/// The level with three significant digits.
+fn format_level(level: f64) -> String {
+    let magnitude = if level == 0.0 {
+        0
+    } else {
+        level.abs().log10().floor() as i32
+    };
+    round_to_decimals(level, (2 - magnitude).clamp(0, 12) as usize).to_string()
+}
+
+fn bounds_of(points: &[PlotPoint]) -> PlotBounds {
+    let mut bounds = PlotBounds::NOTHING;
+    for point in points {
+        bounds.extend_with(point);
+    }
+    bounds
+}
+
+// ----------------------------------------------------------------------------
+
+/// Lines where a grid of values crosses given levels (isolines), computed with marching squares.
+///
+/// Each level gets its own color and legend entry, and is labelled with its value.
+///
+/// The values are at the centers of the cells, like for a [`super::Heatmap`],
+/// so the two can be used to show the same grid.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui_plot::{Contour, Legend, Plot};
+///
+/// let columns = 40;
+/// let values: Vec<f64> = (0..columns * columns)
+///     .map(|i| {
+///         let (x, y) = ((i % columns) as f64 / 10.0 - 2.0, (i / columns) as f64 / 10.0 - 2.0);
+///         (-x * x - y * y).exp()
+///     })
+///     .collect();
+/// let contour = Contour::new(values, columns)
+///     .x_range(-2.0..=2.0)
+///     .y_range(-2.0..=2.0)
+///     .levels([0.25, 0.5, 0.75])
+///     .name("height");
+///
+/// Plot::new("contour_plot")
+///     .legend(Legend::default())
+///     .show(ui, |plot_ui| plot_ui.contour(contour));
+/// # });
+/// ```
+pub struct Contour {
+    grid: Grid,
+    levels: Option<Vec<f64>>,
+    num_levels: usize,
+    colormap: Colormap,
+    color: Color32,
+    width: f32,
+    style: LineStyle,
+    labels: bool,
+    name: String,
+    highlight: bool,
+    allow_hover: bool,
+    id: Option<Id>,
+}
+
+impl Contour {
+    /// A grid of `values`, given row by row starting with the bottom row (lowest y),
+    /// with `columns` values per row.
+    ///
+    /// By default each cell is 1 by 1, with the grid starting at the origin.
+    /// Values that aren't finite (like `NaN`) leave a hole.
+    pub fn new(values: impl Into<Vec<f64>>, columns: usize) -> Self {
+        Self {
+            grid: Grid::new(values.into(), columns),
+            levels: None,
+            num_levels: 8,
+            colormap: Colormap::default(),
+            color: Color32::TRANSPARENT,
+            width: 1.0,
+            style: LineStyle::Solid,
+            labels: true,
+            name: Default::default(),
+            highlight: false,
+            allow_hover: true,
+            id: None,
+        }
+    }
+
+    /// The x coordinates of the left edge of the first column and the right edge of the last column.
+    #[inline]
+    pub fn x_range(mut self, x_range: RangeInclusive<f64>) -> Self {
+        self.grid.x_range = x_range;
+        self
+    }
+
+    /// The y coordinates of the bottom edge of the first row and the top edge of the last row.
+    #[inline]
+    pub fn y_range(mut self, y_range: RangeInclusive<f64>) -> Self {
+        self.grid.y_range = y_range;
+        self
+    }
+
+    /// Draw lines at these values.
+    #[inline]
+    pub fn levels(mut self, levels: impl Into<Vec<f64>>) -> Self {
+        self.levels = Some(levels.into());
+        self
+    }
+
+    /// Draw lines at this many evenly spaced values between the lowest and highest value.
+    ///
+    /// Ignored if [`Self::levels`] are given. Default: 8.
+    #[inline]
+    pub fn num_levels(mut self, num_levels: usize) -> Self {
+        self.num_levels = num_levels;
+        self
+    }
+
+    /// Color the levels with this colormap, from the lowest to the highest level.
+    /// Default: [`Colormap::Viridis`].
+    #[inline]
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Give all levels this color, instead of using the colormap.
+    #[inline]
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Stroke width. A high value means the plot thickens.
+    #[inline]
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// Set the line's style. Default is `LineStyle::Solid`.
+    #[inline]
+    pub fn style(mut self, style: LineStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Label each line with its level, as a [`Text`] in the same color. Default: `true`.
+    #[inline]
+    pub fn labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Highlight the lines in the plot by scaling up the stroke.
+    #[inline]
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Allowed hovering this item in the plot. Default: `true`.
+    #[inline]
+    pub fn allow_hover(mut self, hovering: bool) -> Self {
+        self.allow_hover = hovering;
+        self
+    }
+
+    /// Name of the contour lines.
+    ///
+    /// Each level shows up in the plot legend as e.g. `name = 0.5`, if legends are turned on,
+    /// so levels can be hidden separately.
+    #[allow(clippy::needless_pass_by_value)]
+    #[inline]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Set the contour's id which is used to identify it in the plot's response.
+    #[inline]
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// One item per level, and its labels.
+    pub(crate) fn into_items(self) -> Vec<Box<dyn PlotItem>> {
+        let levels = self.grid.levels(&self.levels, self.num_levels);
+        let (lowest, highest) = levels
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &level| {
+                (min.min(level), max.max(level))
+            });
+
+        let mut items: Vec<Box<dyn PlotItem>> = Vec::new();
+        for level in levels {
+            let (points, lines) = self.grid.isolines(level);
+            if lines.is_empty() {
+                continue;
+            }
+
+            let color = if self.color == Color32::TRANSPARENT {
+                let t = if highest > lowest {
+                    (level - lowest) / (highest - lowest)
+                } else {
+                    0.5
+                };
+                self.colormap.color(t)
+            } else {
+                self.color
+            };
+            let name = if self.name.is_empty() {
+                format_level(level)
+            } else {
+                format!("{} = {}", self.name, format_level(level))
+            };
+
+            if self.labels {
+                // Label the middle of each line that is long enough to hold one:
+                for line in &lines {
+                    if line.len() >= 8 {
+                        let position = points[line.start + line.len() / 2];
+                        let mut text = Text::new(position, format_level(level))
+                            .color(color)
+                            .name(&name)
+                            .highlight(self.highlight)
+                            .allow_hover(false);
+                        if let Some(id) = self.id {
+                            text = text.id(id);
+                        }
+                        items.push(Box::new(text));
+                    }
+                }
+            }
+
+            items.push(Box::new(ContourLevel {
+                points,
+                lines,
+                stroke: Stroke::new(self.width, color),
+                style: self.style,
+                name,
+                highlight: self.highlight,
+                allow_hover: self.allow_hover,
+                id: self.id,
+            }));
+        }
+        items
+    }
+}
+
+/// The lines of one level of a [`Contour`].
+struct ContourLevel {
+    points: Vec<PlotPoint>,
+    lines: Vec<Range<usize>>,
+    stroke: Stroke,
+    style: LineStyle,
+    name: String,
+    highlight: bool,
+    allow_hover: bool,
+    id: Option<Id>,
+}
+
+impl PlotItem for ContourLevel {
+    fn shapes(&self, _ui: &Ui, transform: &PlotTransform, shapes: &mut Vec<Shape>) {
+        for line in &self.lines {
+            let line = self.points[line.clone()]
+                .iter()
+                .map(|point| transform.position_from_point(point))
+                .collect();
+            self.style
+                .style_line(line, self.stroke, self.highlight, shapes);
+        }
+    }
+
+    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {}
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn color(&self) -> Color32 {
+        self.stroke.color
+    }
+
+    fn highlight(&mut self) {
+        self.highlight = true;
+    }
+
+    fn highlighted(&self) -> bool {
+        self.highlight
+    }
+
+    fn allow_hover(&self) -> bool {
+        self.allow_hover
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        PlotGeometry::Points(&self.points)
+    }
+
+    fn bounds(&self) -> PlotBounds {
+        bounds_of(&self.points)
+    }
+
+    fn id(&self) -> Option<Id> {
+        self.id
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Areas between levels of a grid of values, each filled with its own color.
+///
+/// The areas are computed with marching squares,
+/// so the same levels given to a [`Contour`] make lines around them.
+/// Each area gets its own legend entry.
+///
+/// The values are at the centers of the cells, like for a [`super::Heatmap`].
+pub struct FilledContour {
+    grid: Grid,
+    levels: Option<Vec<f64>>,
+    num_levels: usize,
+    colormap: Colormap,
+    name: String,
+    highlight: bool,
+    id: Option<Id>,
+}
+
+impl FilledContour {
+    /// A grid of `values`, given row by row starting with the bottom row (lowest y),
+    /// with `columns` values per row.
+    ///
+    /// By default each cell is 1 by 1, with the grid starting at the origin.
+    /// Values that aren't finite (like `NaN`) leave a hole.
+    pub fn new(values: impl Into<Vec<f64>>, columns: usize) -> Self {
+        Self {
+            grid: Grid::new(values.into(), columns),
+            levels: None,
+            num_levels: 8,
+            colormap: Colormap::default(),
+            name: Default::default(),
+            highlight: false,
+            id: None,
+        }
+    }
+
+    /// The x coordinates of the left edge of the first column and the right edge of the last column.
+    #[inline]
+    pub fn x_range(mut self, x_range: RangeInclusive<f64>) -> Self {
+        self.grid.x_range = x_range;
+        self
+    }
+
+    /// The y coordinates of the bottom edge of the first row and the top edge of the last row.
+    #[inline]
+    pub fn y_range(mut self, y_range: RangeInclusive<f64>) -> Self {
+        self.grid.y_range = y_range;
+        self
+    }
+
+    /// Fill the areas between these values, and the lowest and highest value.
+    #[inline]
+    pub fn levels(mut self, levels: impl Into<Vec<f64>>) -> Self {
+        self.levels = Some(levels.into());
+        self
+    }
+
+    /// Use this many evenly spaced levels between the lowest and highest value,
+    /// making one more area.
+    ///
+    /// Ignored if [`Self::levels`] are given. Default: 8.
+    #[inline]
+    pub fn num_levels(mut self, num_levels: usize) -> Self {
+        self.num_levels = num_levels;
+        self
+    }
+
+    /// Color the areas with this colormap, from the lowest to the highest values.
+    /// Default: [`Colormap::Viridis`].
+    #[inline]
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Highlight the areas in the plot by lightening them.
+    #[inline]
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Name of the filled contour.
+    ///
+    /// Each area shows up in the plot legend as e.g. `name: 0.5 – 1`, if legends are turned on,
+    /// so areas can be hidden separately.
+    #[allow(clippy::needless_pass_by_value)]
+    #[inline]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Set the filled contour's id which is used to identify it in the plot's response.
+    #[inline]
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// One item per area between two levels.
+    pub(crate) fn into_items(self) -> Vec<Box<dyn PlotItem>> {
+        let Some((min, max)) = self.grid.value_range() else {
+            return Vec::new();
+        };
+        let mut edges = vec![min];
+        edges.extend(
+            self.grid
+                .levels(&self.levels, self.num_levels)
+                .into_iter()
+                .filter(|&level| min < level && level < max),
+        );
+        edges.push(max);
+        edges.sort_by(f64::total_cmp);
+
+        let mut items: Vec<Box<dyn PlotItem>> = Vec::new();
+        for band in edges.windows(2) {
+            let (low, high) = (band[0], band[1]);
+            let triangles = self.grid.band(low..=high);
+            if triangles.is_empty() {
+                continue;
+            }
+
+            let t = if max > min {
+                ((low + high) / 2.0 - min) / (max - min)
+            } else {
+                0.5
+            };
+            let range = format!("{} – {}", format_level(low), format_level(high));
+            let name = if self.name.is_empty() {
+                range
+            } else {
+                format!("{}: {range}", self.name)
+            };
+            items.push(Box::new(ContourBand {
+                triangles,
+                color: self.colormap.color(t),
+                name,
+                highlight: self.highlight,
+                id: self.id,
+            }));
+        }
+        items
+    }
+}
+
+/// The area between two levels of a [`FilledContour`].
+struct ContourBand {
+    /// Three points per triangle.
+    triangles: Vec<PlotPoint>,
+    color: Color32,
+    name: String,
+    highlight: bool,
+    id: Option<Id>,
+}
+
+impl PlotItem for ContourBand {
+    fn shapes(&self, ui: &Ui, transform: &PlotTransform, shapes: &mut Vec<Shape>) {
+        let color = if self.highlight {
+            tint_color_towards(self.color, ui.visuals().strong_text_color())
+        } else {
+            self.color
+        };
+        let mut mesh = Mesh::default();
+        mesh.reserve_vertices(self.triangles.len());
+        mesh.reserve_triangles(self.triangles.len() / 3);
+        for (i, point) in self.triangles.iter().enumerate() {
+            mesh.colored_vertex(transform.position_from_point(point), color);
+            if i % 3 == 2 {
+                let i = i as u32;
+                mesh.add_triangle(i - 2, i - 1, i);
+            }
+        }
+        shapes.push(Shape::mesh(mesh));
+    }
+
+    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {}
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn color(&self) -> Color32 {
+        self.color
+    }
+
+    fn highlight(&mut self) {
+        self.highlight = true;
+    }
+
+    fn highlighted(&self) -> bool {
+        self.highlight
+    }
+
+    fn allow_hover(&self) -> bool {
+        false
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        PlotGeometry::None
+    }
+
+    fn bounds(&self) -> PlotBounds {
+        bounds_of(&self.triangles)
+    }
+
+    fn id(&self) -> Option<Id> {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The lines of `grid` at `level`, as coordinates.
+    fn isolines(grid: &Grid, level: f64) -> Vec<Vec<[f64; 2]>> {
+        let (points, lines) = grid.isolines(level);
+        lines
+            .into_iter()
+            .map(|line| points[line].iter().map(|p| [p.x, p.y]).collect())
+            .collect()
+    }
+
+    /// The lines of `grid` at `level`, ignoring their direction and order.
+    fn sorted_isolines(grid: &Grid, level: f64) -> Vec<Vec<[f64; 2]>> {
+        let mut lines = isolines(grid, level);
+        for line in &mut lines {
+            line.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        }
+        lines.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        lines
+    }
+
+    fn area(triangles: &[PlotPoint]) -> f64 {
+        triangles
+            .chunks_exact(3)
+            .map(|t| {
+                let (a, b, c) = (t[0], t[1], t[2]);
+                ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn single_crossing() {
+        // Cell centers at x = 0.5 and 1.5, so the crossing is in between.
+        let grid = Grid::new(vec![0.0, 1.0, 0.0, 1.0], 2);
+        assert_eq!(
+            sorted_isolines(&grid, 0.5),
+            vec![vec![[1.0, 0.5], [1.0, 1.5]]]
+        );
+        assert!(isolines(&grid, 2.0).is_empty());
+    }
+
+    #[test]
+    fn open_line_across_cells() {
+        let grid = Grid::new(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0], 3);
+        let lines = isolines(&grid, 0.5);
+        assert_eq!(lines.len(), 1, "the segments are joined into one line");
+        let mut line = lines[0].clone();
+        if line[0][0] > line[line.len() - 1][0] {
+            line.reverse();
+        }
+        assert_eq!(line, vec![[0.5, 1.0], [1.5, 1.0], [2.5, 1.0]]);
+    }
+
+    #[test]
+    fn closed_line_around_a_peak() {
+        let mut values = vec![0.0; 9];
+        values[4] = 1.0;
+        let grid = Grid::new(values, 3);
+        let lines = isolines(&grid, 0.5);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.len(), 5);
+        assert_eq!(line[0], line[4], "the line is closed");
+        let mut corners = line[..4].to_vec();
+        corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            corners,
+            vec![[1.0, 1.5], [1.5, 1.0], [1.5, 2.0], [2.0, 1.5]]
+        );
+    }
+
+    #[test]
+    fn saddle() {
+        // High bottom left and top right, with a center at the level, which counts as high:
+        // the high corners are connected, so the lines cut off the low corners.
+        let grid = Grid::new(vec![1.0, 0.0, 0.0, 1.0], 2);
+        assert_eq!(
+            sorted_isolines(&grid, 0.5),
+            vec![
+                vec![[0.5, 1.0], [1.0, 1.5]], // around the top left
+                vec![[1.0, 0.5], [1.5, 1.0]], // around the bottom right
+            ]
+        );
+
+        // The other diagonal:
+        let grid = Grid::new(vec![0.0, 1.0, 1.0, 0.0], 2);
+        assert_eq!(
+            sorted_isolines(&grid, 0.5),
+            vec![
+                vec![[0.5, 1.0], [1.0, 0.5]], // around the bottom left
+                vec![[1.0, 1.5], [1.5, 1.0]], // around the top right
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_values_leave_holes() {
+        let nan = f64::NAN;
+        let grid = Grid::new(vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, nan, 1.0], 4);
+        assert_eq!(
+            sorted_isolines(&grid, 0.5),
+            vec![vec![[0.5, 1.0], [1.5, 1.0]]]
+        );
+        assert_eq!(area(&grid.band(0.0..=1.0)), 1.0);
+        assert_eq!(grid.value_range(), Some((0.0, 1.0)));
+    }
+
+    #[test]
+    fn band_of_a_ramp() {
+        // The value is x - 0.5, from x = 0.5 to x = 2.5, and y = 0.5 to y = 1.5.
+        let grid = Grid::new(vec![0.0, 1.0, 2.0, 0.0, 1.0, 2.0], 3);
+        assert_eq!(area(&grid.band(0.0..=2.0)), 2.0);
+        assert_eq!(area(&grid.band(0.5..=1.5)), 1.0);
+        assert!((area(&grid.band(0.0..=0.25)) - 0.25).abs() < 1e-12);
+        assert!(grid.band(3.0..=4.0).is_empty());
+    }
+
+    #[test]
+    fn format_levels() {
+        assert_eq!(format_level(0.0), "0");
+        assert_eq!(format_level(0.5), "0.5");
+        assert_eq!(format_level(-1.23456), "-1.23");
+        assert_eq!(format_level(-0.0012345), "-0.00123");
+        assert_eq!(format_level(12345.6), "12346");
+        assert_eq!(format_level(1e20), "100000000000000000000");
+    }
+}
@@ -0,0 +1,406 @@
+use std::{ops::RangeInclusive, sync::Arc};
+
+use crate::transform::PlotBounds;
+
+use super::PlotPoint;
+
+/// How many buckets (or points) of one level of a [`DecimationPyramid`] make up a bucket of the next.
+const BRANCHING: usize = 8;
+
+/// A large series of points, sorted by x, prepared for fast level-of-detail rendering.
+///
+/// Showing millions of points is slow, and mostly wasted:
+/// a line can't show more detail than the pixel columns it covers.
+/// So for each pixel column only the first, last, lowest and highest point is kept
+/// (so-called min/max or M4 decimation), which looks the same as showing all points.
+///
+/// To make this fast even when zoomed out, the pyramid stores the lowest and highest point
+/// of groups of 8, 64, 512, … points, so only a few points per pixel column are looked at each frame.
+/// Building the pyramid takes a little time and memory, so build it once and keep it around,
+/// e.g. in an [`Arc`].
+///
+/// The points are meant to be sorted by x, like a time series.
+/// If they aren't, they are sorted when the pyramid is built,
+/// so a line through them connects them in the order of x.
+/// Points with a NaN x are dropped.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use std::sync::Arc;
+/// use egui_plot::{DecimationPyramid, Line, Plot, PlotPoints};
+///
+/// // Build this once, and store it in your app:
+/// let samples: Vec<f64> = (0..1_000_000).map(|i| (i as f64 * 0.001).sin()).collect();
+/// let pyramid = Arc::new(DecimationPyramid::from_ys_f64(&samples));
+///
+/// Plot::new("big_plot").show(ui, |plot_ui| {
+///     plot_ui.line(Line::new(PlotPoints::decimated(pyramid.clone())));
+/// });
+/// # });
+/// ```
+pub struct DecimationPyramid {
+    points: Vec<PlotPoint>,
+
+    /// For each level, the indices of the lowest and highest point of each bucket.
+    ///
+    /// The buckets of level `i` hold `BRANCHING^(i+1)` points.
+    levels: Vec<Vec<[usize; 2]>>,
+
+    bounds: PlotBounds,
+}
+
+impl DecimationPyramid {
+    pub fn new(mut points: Vec<PlotPoint>) -> Self {
+        points.retain(|point| !point.x.is_nan());
+        if !points.windows(2).all(|w| w[0].x <= w[1].x) {
+            points.sort_by(|a, b| a.x.total_cmp(&b.x));
+        }
+
+        let mut bounds = PlotBounds::NOTHING;
+        for point in &points {
+            bounds.extend_with(point);
+        }
+
+        let lowest = |a: usize, b: usize| if points[b].y < points[a].y { b } else { a };
+        let highest = |a: usize, b: usize| if points[b].y > points[a].y { b } else { a };
+
+        let mut levels: Vec<Vec<[usize; 2]>> = Vec::new();
+        if points.len() > BRANCHING {
+            let first_level = (0..points.len())
+                .step_by(BRANCHING)
+                .map(|start| {
+                    let end = (start + BRANCHING).min(points.len());
+                    (start + 1..end).fold([start, start], |[min, max], i| {
+                        [lowest(min, i), highest(max, i)]
+                    })
+                })
+                .collect();
+            levels.push(first_level);
+        }
+        while let Some(below) = levels.last().filter(|below| below.len() > BRANCHING) {
+            let level = below
+                .chunks(BRANCHING)
+                .map(|chunk| {
+                    chunk
+                        .iter()
+                        .skip(1)
+                        .fold(chunk[0], |[min, max], &[b_min, b_max]| {
+                            [lowest(min, b_min), highest(max, b_max)]
+                        })
+                })
+                .collect();
+            levels.push(level);
+        }
+
+        Self {
+            points,
+            levels,
+            bounds,
+        }
+    }
+
+    /// From a series of y-values.
+    /// The x-values will be the indices of these values.
+    pub fn from_ys_f64(ys: &[f64]) -> Self {
+        Self::new(
+            ys.iter()
+                .enumerate()
+                .map(|(i, &y)| PlotPoint::new(i as f64, y))
+                .collect(),
+        )
+    }
+
+    /// All the points, sorted by x.
+    pub fn points(&self) -> &[PlotPoint] {
+        &self.points
+    }
+
+    pub fn bounds(&self) -> PlotBounds {
+        self.bounds
+    }
+
+    /// The points to show for this range of x, if it is `columns` pixels wide.
+    ///
+    /// Includes the closest point on either side of the range, so that a line reaches the edges.
+    pub fn decimate(&self, x_range: RangeInclusive<f64>, columns: usize) -> Vec<PlotPoint> {
+        let (x_min, x_max) = (*x_range.start(), *x_range.end());
+
+        let start = self
+            .points
+            .partition_point(|p| p.x < x_min)
+            .saturating_sub(1);
+        let end = (self.points.partition_point(|p| p.x <= x_max) + 1).min(self.points.len());
+        if end <= start {
+            return Vec::new();
+        }
+        let columns = columns.max(1);
+        if end - start <= 4 * columns || x_max <= x_min {
+            return self.points[start..end].to_vec();
+        }
+
+        // The coarsest level with at least two buckets per column:
+        let mut level = 0;
+        let mut bucket_size = 1;
+        while level < self.levels.len() && 2 * bucket_size * BRANCHING * columns <= end - start {
+            level += 1;
+            bucket_size *= BRANCHING;
+        }
+
+        let mut decimator = Decimator {
+            points: &self.points,
+            x_min,
+            column_width: (x_max - x_min) / columns as f64,
+            column: None,
+            indices: Vec::with_capacity(4 * columns + 8),
+        };
+        for bucket in start / bucket_size..=(end - 1) / bucket_size {
+            self.add_bucket(&mut decimator, level, bucket, start..end);
+        }
+        decimator.finish()
+    }
+
+    /// Add the points of a bucket of the given level that are within `range`.
+    ///
+    /// Buckets that are cut off by the edge of the view, or that span more than one column,
+    /// are split into the buckets of the level below, so that each column gets exactly its own extremes.
+    fn add_bucket(
+        &self,
+        decimator: &mut Decimator<'_>,
+        level: usize,
+        bucket: usize,
+        range: std::ops::Range<usize>,
+    ) {
+        let bucket_size = BRANCHING.pow(level as u32);
+        let first = (bucket * bucket_size).max(range.start);
+        let last = ((bucket + 1) * bucket_size).min(range.end);
+        if last <= first {
+            return;
+        }
+        let last = last - 1;
+
+        if level == 0 {
+            decimator.add([first; 4]);
+            return;
+        }
+
+        let is_whole = last - first + 1 == bucket_size;
+        if is_whole && decimator.column_of(first) == decimator.column_of(last) {
+            let [min, max] = self.levels[level - 1][bucket];
+            decimator.add([first, min, max, last]);
+        } else {
+            for child in bucket * BRANCHING..(bucket + 1) * BRANCHING {
+                self.add_bucket(decimator, level - 1, child, range.clone());
+            }
+        }
+    }
+}
+
+impl From<Vec<PlotPoint>> for DecimationPyramid {
+    fn from(points: Vec<PlotPoint>) -> Self {
+        Self::new(points)
+    }
+}
+
+/// Collects the first, lowest, highest and last point of each column.
+struct Decimator<'a> {
+    points: &'a [PlotPoint],
+    x_min: f64,
+    column_width: f64,
+
+    /// The current column, and the indices of its first, lowest, highest and last point.
+    column: Option<(i64, [usize; 4])>,
+
+    /// The indices of the points to show, in order.
+    indices: Vec<usize>,
+}
+
+impl Decimator<'_> {
+    /// The pixel column of the point with this index.
+    fn column_of(&self, index: usize) -> i64 {
+        ((self.points[index].x - self.x_min) / self.column_width).floor() as i64
+    }
+
+    /// Add a group of points within one column,
+    /// given by the indices of its first, lowest, highest and last point.
+    fn add(&mut self, [first, min, max, last]: [usize; 4]) {
+        let column = self.column_of(first);
+        let y = |i: usize| self.points[i].y;
+        match &mut self.column {
+            Some((current, extremes)) if *current == column => {
+                if y(min) < y(extremes[1]) {
+                    extremes[1] = min;
+                }
+                if y(max) > y(extremes[2]) {
+                    extremes[2] = max;
+                }
+                extremes[3] = last;
+            }
+            _ => {
+                self.flush();
+                self.column = Some((column, [first, min, max, last]));
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some((_, mut extremes)) = self.column.take() {
+            extremes.sort_unstable();
+            for i in extremes {
+                if self.indices.last() != Some(&i) {
+                    self.indices.push(i);
+                }
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<PlotPoint> {
+        self.flush();
+        self.indices.iter().map(|&i| self.points[i]).collect()
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The points of a [`DecimationPyramid`] that are shown this frame, see [`super::PlotPoints::Decimated`].
+pub struct DecimatedPoints {
+    pub(super) pyramid: Arc<DecimationPyramid>,
+
+    /// Updated for the current view by [`super::PlotItem::prepare`].
+    pub(super) shown: Vec<PlotPoint>,
+}
+
+impl DecimatedPoints {
+    pub(super) fn new(pyramid: Arc<DecimationPyramid>) -> Self {
+        Self {
+            pyramid,
+            shown: Vec::new(),
+        }
+    }
+
+    /// The points of this series.
+    pub fn pyramid(&self) -> &Arc<DecimationPyramid> {
+        &self.pyramid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A bumpy series with uneven spacing in x.
+    fn series(n: usize) -> Vec<PlotPoint> {
+        let mut seed = 12345_u64;
+        (0..n)
+            .map(|i| {
+                seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                let noise = (seed >> 33) as f64 / (1_u64 << 31) as f64;
+                PlotPoint::new(i as f64 + 0.4 * (i as f64).sin(), noise + (i % 1000) as f64)
+            })
+            .collect()
+    }
+
+    /// Check that each column of the decimated points has the same first, last, lowest
+    /// and highest point as the raw points, including the partial columns at the edges
+    /// and the points just outside the range.
+    fn assert_decimated(raw: &[PlotPoint], x_range: RangeInclusive<f64>, columns: usize) {
+        let pyramid = DecimationPyramid::new(raw.to_vec());
+        let decimated = pyramid.decimate(x_range.clone(), columns);
+
+        let (x_min, x_max) = (*x_range.start(), *x_range.end());
+        let column_width = (x_max - x_min) / columns as f64;
+        let column_of = |p: &PlotPoint| ((p.x - x_min) / column_width).floor() as i64;
+
+        let points = pyramid.points();
+        let start = points.partition_point(|p| p.x < x_min).saturating_sub(1);
+        let end = (points.partition_point(|p| p.x <= x_max) + 1).min(points.len());
+        let raw = &points[start..end];
+
+        let as_tuple = |p: &PlotPoint| (p.x, p.y);
+        assert_eq!(decimated.first().map(as_tuple), raw.first().map(as_tuple));
+        assert_eq!(decimated.last().map(as_tuple), raw.last().map(as_tuple));
+        assert!(decimated.windows(2).all(|w| w[0].x <= w[1].x));
+
+        for column in column_of(&raw[0])..=column_of(&raw[raw.len() - 1]) {
+            let raw_column: Vec<_> = raw.iter().filter(|p| column_of(p) == column).collect();
+            let shown: Vec<_> = decimated
+                .iter()
+                .filter(|p| column_of(p) == column)
+                .collect();
+            assert_eq!(raw_column.is_empty(), shown.is_empty(), "column {column}");
+            if raw_column.is_empty() {
+                continue;
+            }
+
+            let min_y =
+                |points: &[&PlotPoint]| points.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+            let max_y = |points: &[&PlotPoint]| {
+                points.iter().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max)
+            };
+            assert_eq!(
+                as_tuple(shown[0]),
+                as_tuple(raw_column[0]),
+                "column {column}"
+            );
+            assert_eq!(
+                as_tuple(shown[shown.len() - 1]),
+                as_tuple(raw_column[raw_column.len() - 1]),
+                "column {column}"
+            );
+            assert_eq!(min_y(&shown), min_y(&raw_column), "column {column}");
+            assert_eq!(max_y(&shown), max_y(&raw_column), "column {column}");
+            assert!(shown.len() <= 4, "column {column}");
+        }
+    }
+
+    #[test]
+    fn decimate_matches_brute_force() {
+        let raw = series(100_000);
+        assert_decimated(&raw, 0.0..=100_000.0, 100);
+        assert_decimated(&raw, 1234.5..=98_765.4, 97);
+        assert_decimated(&raw, 50_000.3..=50_321.7, 13);
+        assert_decimated(&raw, -500.0..=300.0, 7);
+    }
+
+    #[test]
+    fn decimate_unsorted() {
+        let sorted = series(20_000);
+        let mut shuffled = sorted.clone();
+        shuffled.reverse();
+        shuffled.swap(10, 15_000);
+
+        let pyramid = DecimationPyramid::new(shuffled.clone());
+        let xs = |points: &[PlotPoint]| points.iter().map(|p| p.x).collect::<Vec<_>>();
+        assert_eq!(xs(pyramid.points()), xs(&sorted));
+        assert_decimated(&shuffled, 1000.5..=17_000.5, 31);
+    }
+
+    #[test]
+    fn decimate_skips_nan_x() {
+        let mut raw = series(20_000);
+        for i in [0, 7, 5000, 19_999] {
+            raw[i].x = f64::NAN;
+        }
+
+        let pyramid = DecimationPyramid::new(raw.clone());
+        assert_eq!(pyramid.points().len(), raw.len() - 4);
+        assert!(pyramid.points().windows(2).all(|w| w[0].x <= w[1].x));
+        assert_decimated(&raw, 0.0..=20_000.0, 50);
+    }
+
+    #[test]
+    fn decimate_short_series() {
+        let raw = series(BRANCHING - 3);
+        let pyramid = DecimationPyramid::new(raw.clone());
+        assert!(pyramid.levels.is_empty());
+        assert_eq!(pyramid.decimate(-10.0..=10.0, 100).len(), raw.len());
+
+        // The closest point on either side of the range is included:
+        let shown = pyramid.decimate(1.5..=2.5, 100);
+        assert_eq!(shown.first().map(|p| p.x), Some(raw[1].x));
+        assert_eq!(shown.last().map(|p| p.x), Some(raw[3].x));
+
+        assert!(DecimationPyramid::new(vec![])
+            .decimate(0.0..=1.0, 10)
+            .is_empty());
+    }
+}
@@ -0,0 +1,530 @@
+use std::{ops::RangeInclusive, sync::Arc};
+
+use egui::{
+    emath::NumExt as _, pos2, vec2, Align2, Color32, ColorImage, Id, Pos2, Rect, Shape, Stroke,
+    TextStyle, TextureHandle, TextureOptions, Ui,
+};
+
+use crate::{axis::ColorbarWidget, format_number, Cursor, LabelFormatter, PlotBounds};
+
+use super::{ClosestElem, Colormap, PlotConfig, PlotGeometry, PlotItem, PlotPoint, PlotTransform};
+
+/// A grid of values, shown as cells colored by a [`Colormap`].
+///
+/// Next to the plot, a colorbar shows which colors stand for which values.
+/// Hover a cell to see its value.
+///
+/// The grid is uploaded to the GPU as a texture, which is only updated when the values change.
+/// Give each heatmap in a plot a unique [`Self::name`] or [`Self::id`], so they don't share a texture.
+/// Keep the values in an [`Arc`] and pass the same one each frame
+/// to skip comparing them with the values of the last frame.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui_plot::{Colormap, Heatmap, Plot};
+///
+/// let columns = 50;
+/// let values: Vec<f64> = (0..columns * 40)
+///     .map(|i| ((i % columns) as f64 * 0.2).sin() * ((i / columns) as f64 * 0.15).cos())
+///     .collect();
+/// let heatmap = Heatmap::new(values, columns)
+///     .x_range(-5.0..=5.0)
+///     .y_range(-4.0..=4.0)
+///     .colormap(Colormap::Diverging)
+///     .name("field");
+///
+/// Plot::new("heatmap_plot").show(ui, |plot_ui| plot_ui.heatmap(heatmap));
+/// # });
+/// ```
+pub struct Heatmap {
+    /// Row by row, starting with the bottom row (lowest y).
+    pub(super) values: Arc<[f64]>,
+    pub(super) columns: usize,
+    pub(super) x_range: RangeInclusive<f64>,
+    pub(super) y_range: RangeInclusive<f64>,
+    pub(super) colormap: Colormap,
+    pub(super) value_range: Option<RangeInclusive<f64>>,
+    pub(super) colorbar: bool,
+    pub(super) name: String,
+    pub(super) highlight: bool,
+    pub(super) allow_hover: bool,
+    id: Option<Id>,
+
+    /// Set by [`PlotItem::prepare`].
+    texture: Option<TextureHandle>,
+}
+
+impl Heatmap {
+    /// A grid of `values`, given row by row starting with the bottom row (lowest y),
+    /// with `columns` values per row.
+    ///
+    /// By default each cell is 1 by 1, with the grid starting at the origin.
+    /// Values that aren't finite (like `NaN`) are transparent.
+    pub fn new(values: impl Into<Arc<[f64]>>, columns: usize) -> Self {
+        let values = values.into();
+        let columns = columns.max(1);
+        let rows = values.len() / columns;
+        Self {
+            values,
+            columns,
+            x_range: 0.0..=columns as f64,
+            y_range: 0.0..=rows as f64,
+            colormap: Colormap::default(),
+            value_range: None,
+            colorbar: true,
+            name: Default::default(),
+            highlight: false,
+            allow_hover: true,
+            id: None,
+            texture: None,
+        }
+    }
+
+    /// The x coordinates of the left edge of the first column and the right edge of the last column.
+    #[inline]
+    pub fn x_range(mut self, x_range: RangeInclusive<f64>) -> Self {
+        self.x_range = x_range;
+        self
+    }
+
+    /// The y coordinates of the bottom edge of the first row and the top edge of the last row.
+    #[inline]
+    pub fn y_range(mut self, y_range: RangeInclusive<f64>) -> Self {
+        self.y_range = y_range;
+        self
+    }
+
+    /// Which colors to use. Default: [`Colormap::Viridis`].
+    #[inline]
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// The values that get the first and last color of the colormap.
+    /// Values outside this range are clamped.
+    ///
+    /// By default this is the range of the values,
+    /// made symmetrical around zero for a [`Colormap::Diverging`].
+    #[inline]
+    pub fn value_range(mut self, value_range: RangeInclusive<f64>) -> Self {
+        self.value_range = Some(value_range);
+        self
+    }
+
+    /// Show a colorbar next to the plot. Default: `true`.
+    ///
+    /// If there is more than one heatmap in a plot, the colorbar is for the last one.
+    #[inline]
+    pub fn colorbar(mut self, colorbar: bool) -> Self {
+        self.colorbar = colorbar;
+        self
+    }
+
+    /// Highlight this heatmap in the plot by outlining it.
+    #[inline]
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Allowed hovering this item in the plot. Default: `true`.
+    #[inline]
+    pub fn allow_hover(mut self, hovering: bool) -> Self {
+        self.allow_hover = hovering;
+        self
+    }
+
+    /// Name of this heatmap.
+    ///
+    /// This name will show up in the plot legend, if legends are turned on,
+    /// and as the label of the colorbar.
+    ///
+    /// Multiple plot items may share the same name, in which case they will also share an entry in
+    /// the legend.
+    #[allow(clippy::needless_pass_by_value)]
+    #[inline]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Set the heatmap's id which is used to identify it in the plot's response,
+    /// and to cache its texture.
+    #[inline]
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rows() == 0
+    }
+
+    fn rows(&self) -> usize {
+        self.values.len() / self.columns
+    }
+
+    /// The explicit value range, or else the range of the values.
+    pub(crate) fn resolved_value_range(&self) -> RangeInclusive<f64> {
+        if let Some(value_range) = &self.value_range {
+            return value_range.clone();
+        }
+
+        let (mut min, mut max) = self
+            .values
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+        if min > max {
+            (min, max) = (0.0, 1.0); // no finite values
+        }
+        if self.colormap.is_diverging() {
+            max = min.abs().max(max.abs());
+            min = -max;
+        }
+        if min == max {
+            (min, max) = (min - 0.5, max + 0.5);
+        }
+        min..=max
+    }
+
+    /// Remember the value range, so that it is only computed once per frame.
+    pub(crate) fn resolve_value_range(&mut self) {
+        self.value_range = Some(self.resolved_value_range());
+    }
+
+    pub(crate) fn colorbar_widget(&self) -> Option<ColorbarWidget> {
+        self.colorbar.then(|| {
+            ColorbarWidget::new(
+                self.colormap,
+                self.resolved_value_range(),
+                self.name.clone(),
+            )
+        })
+    }
+
+    fn image(&self, value_range: &RangeInclusive<f64>) -> ColorImage {
+        let pixels = self
+            .values
+            .chunks_exact(self.columns)
+            .rev() // textures start at the top
+            .flatten()
+            .map(|&value| {
+                if value.is_finite() {
+                    self.colormap.color(normalize(value, value_range))
+                } else {
+                    Color32::TRANSPARENT
+                }
+            })
+            .collect();
+        ColorImage {
+            size: [self.columns, self.rows()],
+            pixels,
+        }
+    }
+
+    /// The cell with this index, in plot coordinates.
+    fn cell_bounds(&self, index: usize) -> (PlotPoint, PlotPoint) {
+        let (column, row) = ((index % self.columns) as f64, (index / self.columns) as f64);
+        let (x_min, y_min) = (*self.x_range.start(), *self.y_range.start());
+        let cell_width = (self.x_range.end() - x_min) / self.columns as f64;
+        let cell_height = (self.y_range.end() - y_min) / self.rows() as f64;
+        (
+            PlotPoint::new(x_min + column * cell_width, y_min + row * cell_height),
+            PlotPoint::new(
+                x_min + (column + 1.0) * cell_width,
+                y_min + (row + 1.0) * cell_height,
+            ),
+        )
+    }
+
+    fn screen_rect(&self, transform: &PlotTransform) -> Rect {
+        transform.rect_from_values(
+            &PlotPoint::new(*self.x_range.start(), *self.y_range.start()),
+            &PlotPoint::new(*self.x_range.end(), *self.y_range.end()),
+        )
+    }
+}
+
+/// Where `value` is in `value_range`, from 0 to 1.
+///
+/// If the range is a single value, that value is in the middle.
+fn normalize(value: f64, value_range: &RangeInclusive<f64>) -> f64 {
+    let (min, max) = (*value_range.start(), *value_range.end());
+    if min < max {
+        (value - min) / (max - min)
+    } else if value < min {
+        0.0
+    } else if value > min {
+        1.0
+    } else {
+        0.5
+    }
+}
+
+/// The texture of a heatmap, and what it was made from.
+#[derive(Clone)]
+struct CachedTexture {
+    values: Arc<[f64]>,
+    columns: usize,
+    colormap: Colormap,
+    value_range: RangeInclusive<f64>,
+    texture: TextureHandle,
+}
+
+impl CachedTexture {
+    fn is_for(&self, heatmap: &Heatmap, value_range: &RangeInclusive<f64>) -> bool {
+        let same_values = Arc::ptr_eq(&self.values, &heatmap.values)
+            || self
+                .values
+                .iter()
+                .map(|value| value.to_bits())
+                .eq(heatmap.values.iter().map(|value| value.to_bits()));
+        self.columns == heatmap.columns
+            && self.colormap == heatmap.colormap
+            && self.value_range.start().to_bits() == value_range.start().to_bits()
+            && self.value_range.end().to_bits() == value_range.end().to_bits()
+            && same_values
+    }
+}
+
+impl PlotItem for Heatmap {
+    fn shapes(&self, ui: &Ui, transform: &PlotTransform, shapes: &mut Vec<Shape>) {
+        let rect = self.screen_rect(transform);
+        if let Some(texture) = &self.texture {
+            let uv = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
+            shapes.push(Shape::image(texture.id(), rect, uv, Color32::WHITE));
+        }
+        if self.highlight {
+            shapes.push(Shape::rect_stroke(
+                rect,
+                0.0,
+                Stroke::new(1.0, ui.visuals().strong_text_color()),
+            ));
+        }
+    }
+
+    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {}
+
+    fn prepare(&mut self, ui: &Ui, plot_id: Id, _transform: &PlotTransform) {
+        let value_range = self.resolved_value_range();
+
+        let cache_id = plot_id.with((
+            "egui_plot_heatmap_texture",
+            self.id.unwrap_or_else(|| Id::new(&self.name)),
+        ));
+        let cached = ui
+            .ctx()
+            .data(|data| data.get_temp::<CachedTexture>(cache_id));
+        let texture = match cached {
+            Some(cached) if cached.is_for(self, &value_range) => cached.texture,
+            cached => {
+                let image = self.image(&value_range);
+                let texture = if let Some(CachedTexture { mut texture, .. }) = cached {
+                    texture.set(image, TextureOptions::NEAREST);
+                    texture
+                } else {
+                    ui.ctx()
+                        .load_texture("egui_plot_heatmap", image, TextureOptions::NEAREST)
+                };
+                let cached = CachedTexture {
+                    values: self.values.clone(),
+                    columns: self.columns,
+                    colormap: self.colormap,
+                    value_range,
+                    texture: texture.clone(),
+                };
+                ui.ctx().data_mut(|data| data.insert_temp(cache_id, cached));
+                texture
+            }
+        };
+        self.texture = Some(texture);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn color(&self) -> Color32 {
+        self.colormap.color(0.5)
+    }
+
+    fn highlight(&mut self) {
+        self.highlight = true;
+    }
+
+    fn highlighted(&self) -> bool {
+        self.highlight
+    }
+
+    fn allow_hover(&self) -> bool {
+        self.allow_hover
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        PlotGeometry::Rects
+    }
+
+    fn bounds(&self) -> PlotBounds {
+        let mut bounds = PlotBounds::NOTHING;
+        bounds.extend_with(&PlotPoint::new(
+            *self.x_range.start(),
+            *self.y_range.start(),
+        ));
+        bounds.extend_with(&PlotPoint::new(*self.x_range.end(), *self.y_range.end()));
+        bounds
+    }
+
+    fn id(&self) -> Option<Id> {
+        self.id
+    }
+
+    fn find_closest(&self, point: Pos2, transform: &PlotTransform) -> Option<ClosestElem> {
+        if !self.screen_rect(transform).contains(point) {
+            return None;
+        }
+        let value = transform.value_from_position(point);
+        let (x_min, y_min) = (*self.x_range.start(), *self.y_range.start());
+        let column = (self.columns as f64 * (value.x - x_min) / (self.x_range.end() - x_min))
+            .floor()
+            .clamp(0.0, self.columns as f64 - 1.0) as usize;
+        let row = (self.rows() as f64 * (value.y - y_min) / (self.y_range.end() - y_min))
+            .floor()
+            .clamp(0.0, self.rows() as f64 - 1.0) as usize;
+        Some(ClosestElem {
+            index: row * self.columns + column,
+            dist_sq: 0.0,
+        })
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        cursors: &mut Vec<Cursor>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        let (min, max) = self.cell_bounds(elem.index);
+        let cell_rect = plot.transform.rect_from_values(&min, &max);
+        shapes.push(Shape::rect_stroke(
+            cell_rect,
+            0.0,
+            Stroke::new(1.0, plot.ui.visuals().strong_text_color()),
+        ));
+
+        let center = PlotPoint::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0);
+        if plot.show_x {
+            cursors.push(Cursor::Vertical { x: center.x });
+        }
+        if plot.show_y {
+            cursors.push(Cursor::Horizontal { y: center.y });
+        }
+
+        let mut text = if let Some(custom_label) = label_formatter {
+            custom_label(&self.name, &center)
+        } else {
+            let scale = plot.transform.dvalue_dpos();
+            let x_decimals = ((-scale[0].abs().log10()).ceil().at_least(0.0) as usize).clamp(1, 6);
+            let y_decimals = ((-scale[1].abs().log10()).ceil().at_least(0.0) as usize).clamp(1, 6);
+            let prefix = if self.name.is_empty() {
+                String::new()
+            } else {
+                format!("{}\n", self.name)
+            };
+            format!(
+                "{}x = {:.*}\ny = {:.*}",
+                prefix, x_decimals, center.x, y_decimals, center.y
+            )
+        };
+        text.push_str(&format!(
+            "\nvalue = {}",
+            format_number(self.values[elem.index], 3)
+        ));
+
+        let font_id = TextStyle::Body.resolve(plot.ui.style());
+        plot.ui.fonts(|f| {
+            shapes.push(Shape::text(
+                f,
+                cell_rect.right_top() + vec2(3.0, -2.0),
+                Align2::LEFT_BOTTOM,
+                text,
+                font_id,
+                plot.ui.visuals().text_color(),
+            ));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bottom_row_is_at_the_bottom_of_the_image() {
+        let heatmap = Heatmap::new(vec![0.0, 1.0, 2.0, 3.0], 2);
+        let image = heatmap.image(&(0.0..=3.0));
+        let color = |value| Colormap::Viridis.color(value / 3.0);
+        assert_eq!(image.size, [2, 2]);
+        assert_eq!(
+            image.pixels,
+            vec![color(2.0), color(3.0), color(0.0), color(1.0)]
+        );
+    }
+
+    #[test]
+    fn value_range_of_constant_or_missing_values() {
+        let constant = Heatmap::new(vec![2.0; 4], 2);
+        assert_eq!(constant.resolved_value_range(), 1.5..=2.5);
+
+        let missing = Heatmap::new(vec![f64::NAN, f64::INFINITY], 2);
+        assert_eq!(missing.resolved_value_range(), 0.0..=1.0);
+
+        let diverging = Heatmap::new(vec![-1.0, 3.0, f64::NAN], 3).colormap(Colormap::Diverging);
+        assert_eq!(diverging.resolved_value_range(), -3.0..=3.0);
+    }
+
+    #[test]
+    fn single_value_range() {
+        let heatmap = Heatmap::new(vec![1.0, 2.0, 3.0, f64::NAN], 4).value_range(2.0..=2.0);
+        let image = heatmap.image(&heatmap.resolved_value_range());
+        let colormap = Colormap::Viridis;
+        assert_eq!(
+            image.pixels,
+            vec![
+                colormap.color(0.0),
+                colormap.color(0.5),
+                colormap.color(1.0),
+                Color32::TRANSPARENT,
+            ]
+        );
+    }
+
+    #[test]
+    fn find_closest_in_reversed_x_range() {
+        // Column 0 is at the right, from x = 4 to x = 3.
+        let heatmap = Heatmap::new(vec![0.0; 8], 4).x_range(4.0..=0.0);
+        let frame = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0));
+        let mut bounds = PlotBounds::NOTHING;
+        bounds.extend_with(&PlotPoint::new(0.0, 0.0));
+        bounds.extend_with(&PlotPoint::new(4.0, 2.0));
+        let transform = PlotTransform::new(frame, bounds, false, false);
+
+        let closest = |x, y| {
+            let point = transform.position_from_point(&PlotPoint::new(x, y));
+            heatmap
+                .find_closest(point, &transform)
+                .map(|elem| elem.index)
+        };
+        assert_eq!(closest(3.5, 0.5), Some(0));
+        assert_eq!(closest(0.5, 0.5), Some(3));
+        assert_eq!(closest(2.5, 1.5), Some(5));
+        assert_eq!(closest(5.0, 0.5), None);
+
+        let (min, max) = heatmap.cell_bounds(0);
+        assert_eq!((min.x, max.x), (4.0, 3.0));
+    }
+}
@@ -12,16 +12,27 @@ use rect_elem::*;
 
 pub use bar::Bar;
 pub use box_elem::{BoxElem, BoxSpread};
+pub use colormap::Colormap;
+pub use contour::{Contour, FilledContour};
+pub use decimation::{DecimatedPoints, DecimationPyramid};
+pub use heatmap::Heatmap;
+pub use ring_buffer::PlotRingBuffer;
 pub use values::{
     ClosestElem, LineStyle, MarkerShape, Orientation, PlotGeometry, PlotPoint, PlotPoints,
 };
 
 mod bar;
 mod box_elem;
+mod colormap;
+mod contour;
+mod decimation;
+mod heatmap;
 mod rect_elem;
+mod ring_buffer;
 mod values;
 
 const DEFAULT_FILL_ALPHA: f32 = 0.05;
+const BAND_FILL_ALPHA: f32 = 0.25;
 
 /// Container to pass-through several parameters related to plot visualization
 pub struct PlotConfig<'a> {
@@ -38,6 +49,12 @@ pub trait PlotItem {
     /// For plot-items which are generated based on x values (plotting functions).
     fn initialize(&mut self, x_range: RangeInclusive<f64>);
 
+    /// Called once the final transform of this frame is known, before [`Self::shapes`].
+    ///
+    /// Used e.g. to decimate huge series to what is visible.
+    /// `plot_id` is the id of the plot, e.g. to keep things in its memory.
+    fn prepare(&mut self, _ui: &Ui, _plot_id: Id, _transform: &PlotTransform) {}
+
     fn name(&self) -> &str;
 
     fn color(&self) -> Color32;
@@ -93,16 +110,14 @@ pub trait PlotItem {
             }
         };
 
-        let line_color = if plot.ui.visuals().dark_mode {
-            Color32::from_gray(100).additive()
-        } else {
-            Color32::from_black_alpha(180)
-        };
-
         // this method is only called, if the value is in the result set of find_closest()
         let value = points[elem.index];
         let pointer = plot.transform.position_from_point(&value);
-        shapes.push(Shape::circle_filled(pointer, 3.0, line_color));
+        shapes.push(Shape::circle_filled(
+            pointer,
+            3.0,
+            hover_point_color(plot.ui),
+        ));
 
         rulers_at_value(
             pointer,
@@ -581,6 +596,10 @@ impl PlotItem for Line {
         self.series.generate_points(x_range);
     }
 
+    fn prepare(&mut self, ui: &Ui, _plot_id: Id, transform: &PlotTransform) {
+        self.series.decimate(transform, ui.ctx().pixels_per_point());
+    }
+
     fn name(&self) -> &str {
         self.name.as_str()
     }
@@ -766,6 +785,285 @@ impl PlotItem for Polygon {
     }
 }
 
+/// A shaded band between a lower and an upper series, e.g. to show the uncertainty around a [`Line`].
+///
+/// The points of the two series are paired by index, so they should have the same x coordinates.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui_plot::{Band, Line, Plot};
+///
+/// let mean: Vec<[f64; 2]> = (0..100).map(|i| [i as f64, (i as f64 / 10.0).sin()]).collect();
+/// let sigma: Vec<f64> = (0..100).map(|i| 0.1 + i as f64 / 500.0).collect();
+/// Plot::new("band").show(ui, |plot_ui| {
+///     plot_ui.band(Band::around(mean.clone(), sigma).name("measured"));
+///     plot_ui.line(Line::new(mean).name("measured"));
+/// });
+/// # });
+/// ```
+pub struct Band {
+    pub(super) lower: PlotPoints,
+    pub(super) upper: PlotPoints,
+    pub(super) stroke: Stroke,
+    pub(super) name: String,
+    pub(super) highlight: bool,
+    pub(super) allow_hover: bool,
+    pub(super) fill_color: Option<Color32>,
+    id: Option<Id>,
+}
+
+impl Band {
+    /// Fill between the `lower` and `upper` series.
+    pub fn new(lower: impl Into<PlotPoints>, upper: impl Into<PlotPoints>) -> Self {
+        Self {
+            lower: lower.into(),
+            upper: upper.into(),
+            stroke: Stroke::new(0.0, Color32::TRANSPARENT),
+            name: Default::default(),
+            highlight: false,
+            allow_hover: true,
+            fill_color: None,
+            id: None,
+        }
+    }
+
+    /// Fill `sigma[i]` below and above the `i`-th point of `center`.
+    ///
+    /// `center` has to be explicit points, not a function: use [`Self::new`] for those.
+    pub fn around(center: impl Into<PlotPoints>, sigma: impl Into<Vec<f64>>) -> Self {
+        let center = center.into();
+        let sigma = sigma.into();
+        let offset = |sign: f64| -> Vec<PlotPoint> {
+            center
+                .points()
+                .iter()
+                .zip(&sigma)
+                .map(|(point, sigma)| PlotPoint::new(point.x, point.y + sign * sigma.abs()))
+                .collect()
+        };
+        Self::new(
+            PlotPoints::Owned(offset(-1.0)),
+            PlotPoints::Owned(offset(1.0)),
+        )
+    }
+
+    /// Highlight this band in the plot by reducing the fill transparency.
+    #[inline]
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Allowed hovering this item in the plot. Default: `true`.
+    #[inline]
+    pub fn allow_hover(mut self, hovering: bool) -> Self {
+        self.allow_hover = hovering;
+        self
+    }
+
+    /// Set the band's color. The fill is this color with added transparency.
+    ///
+    /// Default is an automatically picked color.
+    #[inline]
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.stroke.color = color.into();
+        self
+    }
+
+    /// Outline the lower and upper edges with this stroke width. Default: no outline.
+    #[inline]
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.stroke.width = width.into();
+        self
+    }
+
+    /// Fill color. Defaults to the color with added transparency.
+    #[inline]
+    pub fn fill_color(mut self, color: impl Into<Color32>) -> Self {
+        self.fill_color = Some(color.into());
+        self
+    }
+
+    /// Name of this band.
+    ///
+    /// This name will show up in the plot legend, if legends are turned on.
+    ///
+    /// Multiple plot items may share the same name, in which case they will also share an entry in
+    /// the legend. Give a band the same name as the line it surrounds to show and hide them together.
+    #[allow(clippy::needless_pass_by_value)]
+    #[inline]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Set the band's id which is used to identify it in the plot's response.
+    #[inline]
+    pub fn id(mut self, id: Id) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// The pairs of lower and upper points.
+    fn pairs(&self) -> impl Iterator<Item = (&PlotPoint, &PlotPoint)> {
+        self.lower.points().iter().zip(self.upper.points())
+    }
+}
+
+impl PlotItem for Band {
+    fn shapes(&self, _ui: &Ui, transform: &PlotTransform, shapes: &mut Vec<Shape>) {
+        let (lower, upper): (Vec<Pos2>, Vec<Pos2>) = self
+            .pairs()
+            .map(|(lower, upper)| {
+                (
+                    transform.position_from_point(lower),
+                    transform.position_from_point(upper),
+                )
+            })
+            .unzip();
+
+        let fill_color = self.fill_color.unwrap_or_else(|| {
+            let fill_alpha = if self.highlight {
+                2.0 * BAND_FILL_ALPHA
+            } else {
+                BAND_FILL_ALPHA
+            };
+            Rgba::from(self.stroke.color)
+                .to_opaque()
+                .multiply(fill_alpha)
+                .into()
+        });
+        let mut mesh = Mesh::default();
+        mesh.reserve_vertices(2 * lower.len());
+        mesh.reserve_triangles(2 * lower.len().saturating_sub(1));
+        for (i, (&lower, &upper)) in lower.iter().zip(&upper).enumerate() {
+            mesh.colored_vertex(lower, fill_color);
+            mesh.colored_vertex(upper, fill_color);
+            if i > 0 {
+                let i = 2 * i as u32;
+                mesh.add_triangle(i - 2, i - 1, i);
+                mesh.add_triangle(i - 1, i, i + 1);
+            }
+        }
+        shapes.push(Shape::Mesh(mesh));
+
+        if self.stroke.width > 0.0 {
+            LineStyle::Solid.style_line(lower, self.stroke, self.highlight, shapes);
+            LineStyle::Solid.style_line(upper, self.stroke, self.highlight, shapes);
+        }
+    }
+
+    fn initialize(&mut self, x_range: RangeInclusive<f64>) {
+        self.lower.generate_points(x_range.clone());
+        self.upper.generate_points(x_range);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn color(&self) -> Color32 {
+        self.stroke.color
+    }
+
+    fn highlight(&mut self) {
+        self.highlight = true;
+    }
+
+    fn highlighted(&self) -> bool {
+        self.highlight
+    }
+
+    fn allow_hover(&self) -> bool {
+        self.allow_hover
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        PlotGeometry::Rects
+    }
+
+    fn bounds(&self) -> PlotBounds {
+        let mut bounds = self.lower.bounds();
+        bounds.merge(&self.upper.bounds());
+        bounds
+    }
+
+    fn find_closest(&self, point: Pos2, transform: &PlotTransform) -> Option<ClosestElem> {
+        // The distance to the span between the lower and upper point.
+        // Inside the band it's a bit more than zero, so that whatever is close to the pointer
+        // in there, like the points of the line it surrounds, can be hovered too.
+        let inside_dist_sq = 6.0_f32 * 6.0;
+        self.pairs()
+            .enumerate()
+            .map(|(index, (lower, upper))| {
+                let span = Rect::from_two_pos(
+                    transform.position_from_point(lower),
+                    transform.position_from_point(upper),
+                );
+                let dist_sq = span.distance_sq_to_pos(point).at_least(inside_dist_sq);
+                ClosestElem { index, dist_sq }
+            })
+            .min_by_key(|e| e.dist_sq.ord())
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        cursors: &mut Vec<Cursor>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        let (lower, upper) = (
+            self.lower.points()[elem.index],
+            self.upper.points()[elem.index],
+        );
+        let (lower_pos, upper_pos) = (
+            plot.transform.position_from_point(&lower),
+            plot.transform.position_from_point(&upper),
+        );
+        let color = hover_point_color(plot.ui);
+        shapes.push(Shape::line_segment([lower_pos, upper_pos], (1.0, color)));
+        shapes.push(Shape::circle_filled(lower_pos, 3.0, color));
+        shapes.push(Shape::circle_filled(upper_pos, 3.0, color));
+
+        if plot.show_y {
+            cursors.push(Cursor::Horizontal { y: lower.y });
+        }
+
+        let text = if label_formatter.is_some() {
+            format!(
+                "{}\n{}",
+                value_text(upper, &self.name, plot, label_formatter),
+                value_text(lower, &self.name, plot, label_formatter)
+            )
+        } else if !plot.show_y {
+            value_text(upper, &self.name, plot, label_formatter)
+        } else {
+            let prefix = if self.name.is_empty() {
+                String::new()
+            } else {
+                format!("{}\n", self.name)
+            };
+            let [x_decimals, y_decimals] = value_decimals(plot);
+            let x = if plot.show_x {
+                format!("x = {:.*}\n", x_decimals, upper.x)
+            } else {
+                String::new()
+            };
+            format!(
+                "{prefix}{x}y = {:.*} … {:.*}",
+                y_decimals, lower.y, y_decimals, upper.y
+            )
+        };
+        rulers_and_text_at_value(upper_pos, upper, text, plot, shapes, cursors);
+    }
+
+    fn id(&self) -> Option<Id> {
+        self.id
+    }
+}
+
 /// Text inside the plot.
 #[derive(Clone)]
 pub struct Text {
@@ -928,6 +1226,12 @@ pub struct Points {
     pub(super) allow_hover: bool,
 
     pub(super) stems: Option<f32>,
+
+    x_errors: Option<ErrorBars>,
+    y_errors: Option<ErrorBars>,
+
+    /// Half the length of the caps at the ends of the error bars, in ui points.
+    pub(super) error_bar_cap: f32,
     id: Option<Id>,
 }
 
@@ -943,6 +1247,9 @@ impl Points {
             highlight: false,
             allow_hover: true,
             stems: None,
+            x_errors: None,
+            y_errors: None,
+            error_bar_cap: 3.0,
             id: None,
         }
     }
@@ -1009,12 +1316,110 @@ impl Points {
         self
     }
 
+    /// Add horizontal error bars, reaching `errors[i]` to both sides of the `i`-th point.
+    ///
+    /// Points without a finite error get no error bar.
+    /// Error bars aren't drawn for [`PlotPoints::decimated`] series.
+    #[inline]
+    pub fn x_errors(mut self, errors: impl Into<Vec<f64>>) -> Self {
+        self.x_errors = Some(ErrorBars::symmetric(errors.into()));
+        self
+    }
+
+    /// Add horizontal error bars, reaching `left[i]` to the left and `right[i]` to the right
+    /// of the `i`-th point.
+    #[inline]
+    pub fn x_errors_asymmetric(
+        mut self,
+        left: impl Into<Vec<f64>>,
+        right: impl Into<Vec<f64>>,
+    ) -> Self {
+        self.x_errors = Some(ErrorBars {
+            below: left.into(),
+            above: right.into(),
+        });
+        self
+    }
+
+    /// Add vertical error bars, reaching `errors[i]` below and above the `i`-th point.
+    ///
+    /// Points without a finite error get no error bar.
+    /// Error bars aren't drawn for [`PlotPoints::decimated`] series.
+    #[inline]
+    pub fn y_errors(mut self, errors: impl Into<Vec<f64>>) -> Self {
+        self.y_errors = Some(ErrorBars::symmetric(errors.into()));
+        self
+    }
+
+    /// Add vertical error bars, reaching `below[i]` below and `above[i]` above the `i`-th point.
+    #[inline]
+    pub fn y_errors_asymmetric(
+        mut self,
+        below: impl Into<Vec<f64>>,
+        above: impl Into<Vec<f64>>,
+    ) -> Self {
+        self.y_errors = Some(ErrorBars {
+            below: below.into(),
+            above: above.into(),
+        });
+        self
+    }
+
+    /// Set half the length of the caps at the ends of the error bars, in ui points.
+    /// Zero means no caps. Default: 3.0.
+    #[inline]
+    pub fn error_bar_cap(mut self, cap: impl Into<f32>) -> Self {
+        self.error_bar_cap = cap.into();
+        self
+    }
+
     /// Set the points' id which is used to identify them in the plot's response.
     #[inline]
     pub fn id(mut self, id: Id) -> Self {
         self.id = Some(id);
         self
     }
+
+    /// The error bars, unless they can't be matched to the points.
+    fn error_bars(&self) -> [Option<&ErrorBars>; 2] {
+        if matches!(self.series, PlotPoints::Decimated(_)) {
+            [None, None]
+        } else {
+            [self.x_errors.as_ref(), self.y_errors.as_ref()]
+        }
+    }
+}
+
+/// The errors below and above each point along one axis, matched to the points by index.
+struct ErrorBars {
+    below: Vec<f64>,
+    above: Vec<f64>,
+}
+
+impl ErrorBars {
+    fn symmetric(errors: Vec<f64>) -> Self {
+        Self {
+            below: errors.clone(),
+            above: errors,
+        }
+    }
+
+    /// The errors below and above the point with this index, if it has an error bar.
+    fn get(&self, index: usize) -> Option<(f64, f64)> {
+        let below = self.below.get(index)?.abs();
+        let above = self.above.get(index)?.abs();
+        (below.is_finite() && above.is_finite()).then_some((below, above))
+    }
+
+    /// A line describing the error of the point with this index, e.g. `y error = ±0.5`.
+    fn text(&self, index: usize, axis: &str, decimals: usize) -> Option<String> {
+        let (below, above) = self.get(index)?;
+        Some(if below == above {
+            format!("{axis} error = ±{below:.decimals$}")
+        } else {
+            format!("{axis} error = -{below:.decimals$} / +{above:.decimals$}")
+        })
+    }
 }
 
 impl PlotItem for Points {
@@ -1051,6 +1456,41 @@ impl PlotItem for Points {
 
         let y_reference = stems.map(|y| transform.position_from_point(&PlotPoint::new(0.0, y)).y);
 
+        let [x_errors, y_errors] = self.error_bars();
+        if x_errors.is_some() || y_errors.is_some() {
+            let error_stroke = Stroke::new(if *highlight { 2.0 } else { 1.0 }, *color);
+            let cap = self.error_bar_cap;
+            let mut error_bar = |from: PlotPoint, to: PlotPoint, cap_direction: Vec2| {
+                let ends = [
+                    transform.position_from_point(&from),
+                    transform.position_from_point(&to),
+                ];
+                shapes.push(Shape::line_segment(ends, error_stroke));
+                if cap > 0.0 {
+                    for end in ends {
+                        let cap = cap * cap_direction;
+                        shapes.push(Shape::line_segment([end - cap, end + cap], error_stroke));
+                    }
+                }
+            };
+            for (index, point) in series.points().iter().enumerate() {
+                if let Some((left, right)) = x_errors.and_then(|errors| errors.get(index)) {
+                    error_bar(
+                        PlotPoint::new(point.x - left, point.y),
+                        PlotPoint::new(point.x + right, point.y),
+                        Vec2::Y,
+                    );
+                }
+                if let Some((below, above)) = y_errors.and_then(|errors| errors.get(index)) {
+                    error_bar(
+                        PlotPoint::new(point.x, point.y - below),
+                        PlotPoint::new(point.x, point.y + above),
+                        Vec2::X,
+                    );
+                }
+            }
+        }
+
         series
             .points()
             .iter()
@@ -1150,6 +1590,10 @@ impl PlotItem for Points {
         self.series.generate_points(x_range);
     }
 
+    fn prepare(&mut self, ui: &Ui, _plot_id: Id, transform: &PlotTransform) {
+        self.series.decimate(transform, ui.ctx().pixels_per_point());
+    }
+
     fn name(&self) -> &str {
         self.name.as_str()
     }
@@ -1175,7 +1619,55 @@ impl PlotItem for Points {
     }
 
     fn bounds(&self) -> PlotBounds {
-        self.series.bounds()
+        let mut bounds = self.series.bounds();
+        let [x_errors, y_errors] = self.error_bars();
+        for (index, point) in self.series.points().iter().enumerate() {
+            if let Some((left, right)) = x_errors.and_then(|errors| errors.get(index)) {
+                bounds.extend_with_x(point.x - left);
+                bounds.extend_with_x(point.x + right);
+            }
+            if let Some((below, above)) = y_errors.and_then(|errors| errors.get(index)) {
+                bounds.extend_with_y(point.y - below);
+                bounds.extend_with_y(point.y + above);
+            }
+        }
+        bounds
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        cursors: &mut Vec<Cursor>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        let value = self.series.points()[elem.index];
+        let pointer = plot.transform.position_from_point(&value);
+        shapes.push(Shape::circle_filled(
+            pointer,
+            3.0,
+            hover_point_color(plot.ui),
+        ));
+
+        // Add the errors of the point to the text:
+        let mut text = value_text(value, &self.name, plot, label_formatter);
+        let [x_errors, y_errors] = self.error_bars();
+        let [x_decimals, y_decimals] = value_decimals(plot);
+        if plot.show_x {
+            if let Some(line) = x_errors.and_then(|errors| errors.text(elem.index, "x", x_decimals))
+            {
+                text = format!("{text}\n{line}");
+            }
+        }
+        if plot.show_y {
+            if let Some(line) = y_errors.and_then(|errors| errors.text(elem.index, "y", y_decimals))
+            {
+                text = format!("{text}\n{line}");
+            }
+        }
+
+        rulers_and_text_at_value(pointer, value, text, plot, shapes, cursors);
     }
 
     fn id(&self) -> Option<Id> {
@@ -2034,6 +2526,19 @@ pub(super) fn rulers_at_value(
     shapes: &mut Vec<Shape>,
     cursors: &mut Vec<Cursor>,
     label_formatter: &LabelFormatter,
+) {
+    let text = value_text(value, name, plot, label_formatter);
+    rulers_and_text_at_value(pointer, value, text, plot, shapes, cursors);
+}
+
+/// Rulers through `value`, and `text` next to the pointer.
+fn rulers_and_text_at_value(
+    pointer: Pos2,
+    value: PlotPoint,
+    text: String,
+    plot: &PlotConfig<'_>,
+    shapes: &mut Vec<Shape>,
+    cursors: &mut Vec<Cursor>,
 ) {
     if plot.show_x {
         cursors.push(Cursor::Vertical { x: value.x });
@@ -2042,32 +2547,6 @@ pub(super) fn rulers_at_value(
         cursors.push(Cursor::Horizontal { y: value.y });
     }
 
-    let prefix = if name.is_empty() {
-        String::new()
-    } else {
-        format!("{name}\n")
-    };
-
-    let text = {
-        let scale = plot.transform.dvalue_dpos();
-        let x_decimals = ((-scale[0].abs().log10()).ceil().at_least(0.0) as usize).clamp(1, 6);
-        let y_decimals = ((-scale[1].abs().log10()).ceil().at_least(0.0) as usize).clamp(1, 6);
-        if let Some(custom_label) = label_formatter {
-            custom_label(name, &value)
-        } else if plot.show_x && plot.show_y {
-            format!(
-                "{}x = {:.*}\ny = {:.*}",
-                prefix, x_decimals, value.x, y_decimals, value.y
-            )
-        } else if plot.show_x {
-            format!("{}x = {:.*}", prefix, x_decimals, value.x)
-        } else if plot.show_y {
-            format!("{}y = {:.*}", prefix, y_decimals, value.y)
-        } else {
-            unreachable!()
-        }
-    };
-
     let font_id = TextStyle::Body.resolve(plot.ui.style());
     plot.ui.fonts(|f| {
         shapes.push(Shape::text(
@@ -2081,6 +2560,53 @@ pub(super) fn rulers_at_value(
     });
 }
 
+/// How many decimals to show for x and y values, so they change when the pointer moves a point.
+fn value_decimals(plot: &PlotConfig<'_>) -> [usize; 2] {
+    let scale = plot.transform.dvalue_dpos();
+    let x_decimals = ((-scale[0].abs().log10()).ceil().at_least(0.0) as usize).clamp(1, 6);
+    let y_decimals = ((-scale[1].abs().log10()).ceil().at_least(0.0) as usize).clamp(1, 6);
+    [x_decimals, y_decimals]
+}
+
+/// The text shown when hovering `value`.
+fn value_text(
+    value: PlotPoint,
+    name: &str,
+    plot: &PlotConfig<'_>,
+    label_formatter: &LabelFormatter,
+) -> String {
+    let prefix = if name.is_empty() {
+        String::new()
+    } else {
+        format!("{name}\n")
+    };
+
+    let [x_decimals, y_decimals] = value_decimals(plot);
+    if let Some(custom_label) = label_formatter {
+        custom_label(name, &value)
+    } else if plot.show_x && plot.show_y {
+        format!(
+            "{}x = {:.*}\ny = {:.*}",
+            prefix, x_decimals, value.x, y_decimals, value.y
+        )
+    } else if plot.show_x {
+        format!("{}x = {:.*}", prefix, x_decimals, value.x)
+    } else if plot.show_y {
+        format!("{}y = {:.*}", prefix, y_decimals, value.y)
+    } else {
+        unreachable!()
+    }
+}
+
+/// The color of the marker on a hovered point.
+fn hover_point_color(ui: &Ui) -> Color32 {
+    if ui.visuals().dark_mode {
+        Color32::from_gray(100).additive()
+    } else {
+        Color32::from_black_alpha(180)
+    }
+}
+
 fn find_closest_rect<'a, T>(
     rects: impl IntoIterator<Item = &'a T>,
     point: Pos2,
@@ -2100,3 +2626,80 @@ where
         })
         .min_by_key(|e| e.dist_sq.ord())
 }
+
+#[cfg(test)]
+mod tests {
+    use egui::{CentralPanel, Context, RawInput};
+
+    use super::*;
+
+    fn series() -> Vec<[f64; 2]> {
+        vec![[0.0, 0.0], [1.0, 2.0], [2.0, 1.0]]
+    }
+
+    /// The number of error bars that are drawn, i.e. line segments without caps.
+    fn num_error_bars(points: Points) -> usize {
+        let mut bounds = PlotBounds::NOTHING;
+        bounds.extend_with(&PlotPoint::new(-5.0, -5.0));
+        bounds.extend_with(&PlotPoint::new(5.0, 5.0));
+        let frame = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0));
+        let transform = PlotTransform::new(frame, bounds, false, false);
+
+        let mut shapes = Vec::new();
+        let _ = Context::default().run(RawInput::default(), |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                points
+                    .error_bar_cap(0.0)
+                    .shapes(ui, &transform, &mut shapes);
+            });
+        });
+        shapes
+            .iter()
+            .filter(|shape| matches!(shape, Shape::LineSegment { .. }))
+            .count()
+    }
+
+    #[test]
+    fn asymmetric_errors_grow_the_bounds() {
+        let points = Points::new(series())
+            .x_errors_asymmetric(vec![0.5, 0.0, 0.0], vec![0.0, 0.0, 3.0])
+            .y_errors_asymmetric(vec![1.0, 0.0, 0.0], vec![0.0, 0.25, 0.0]);
+        let bounds = points.bounds();
+        assert_eq!(bounds.min(), [-0.5, -1.0]);
+        assert_eq!(bounds.max(), [5.0, 2.25]);
+    }
+
+    #[test]
+    fn short_error_arrays_are_ignored() {
+        let points = Points::new(series())
+            .x_errors(vec![0.5])
+            .y_errors_asymmetric(vec![1.0, 1.0, 1.0], vec![1.0]);
+        let bounds = points.bounds();
+        assert_eq!(bounds.min(), [-0.5, -1.0]);
+        assert_eq!(bounds.max(), [2.0, 2.0]);
+
+        // One error bar in each direction, for the first point:
+        let points = Points::new(series())
+            .x_errors(vec![0.5])
+            .y_errors_asymmetric(vec![1.0, 1.0, 1.0], vec![1.0]);
+        assert_eq!(num_error_bars(points), 2);
+        let points = Points::new(series()).y_errors(vec![1.0, f64::NAN, 1.0, 1.0]);
+        assert_eq!(num_error_bars(points), 2);
+    }
+
+    #[test]
+    fn band_bounds_cover_both_series() {
+        let band = Band::new(
+            vec![[0.0, -1.0], [1.0, -2.0]],
+            vec![[0.0, 1.0], [1.0, 2.0], [4.0, 3.0]],
+        );
+        let bounds = band.bounds();
+        assert_eq!(bounds.min(), [0.0, -2.0]);
+        assert_eq!(bounds.max(), [4.0, 3.0]);
+
+        let band = Band::around(vec![[0.0, 0.0], [1.0, 1.0], [2.0, 0.0]], vec![0.5, -1.0]);
+        let bounds = band.bounds();
+        assert_eq!(bounds.min(), [0.0, -0.5]);
+        assert_eq!(bounds.max(), [1.0, 2.0]);
+    }
+}
@@ -0,0 +1,129 @@
+use super::PlotPoint;
+
+/// A fixed-size buffer of the latest points of a real-time series, e.g. telemetry.
+///
+/// Pushing a point when the buffer is full drops the oldest one.
+/// The points are kept in one contiguous slice, so a plot can show them without copying.
+///
+/// To plot it every frame without copying it, keep it in an [`std::sync::Arc`]
+/// and show it with [`super::PlotPoints::shared`].
+/// Use [`std::sync::Arc::make_mut`] to push new points:
+/// this doesn't copy the buffer either, as the plot lets go of it at the end of the frame.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use std::sync::Arc;
+/// use egui_plot::{Line, Plot, PlotPoints, PlotRingBuffer};
+///
+/// // Keep this in your app:
+/// let mut samples = Arc::new(PlotRingBuffer::new(10_000));
+///
+/// // Each frame:
+/// let time = ui.input(|i| i.time);
+/// Arc::make_mut(&mut samples).push([time, time.sin()]);
+/// Plot::new("telemetry").follow_latest(10.0).show(ui, |plot_ui| {
+///     plot_ui.line(Line::new(PlotPoints::shared(&samples)));
+/// });
+/// # });
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlotRingBuffer {
+    /// Room for twice the capacity, so that the oldest half only has to be dropped
+    /// once every `capacity` pushes.
+    points: Vec<PlotPoint>,
+    capacity: usize,
+}
+
+impl PlotRingBuffer {
+    /// A buffer that keeps the latest `capacity` points.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            points: Vec::with_capacity(2 * capacity),
+            capacity,
+        }
+    }
+
+    /// How many points are kept at most.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// How many points there are.
+    pub fn len(&self) -> usize {
+        self.points.len().min(self.capacity)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Add a point, dropping the oldest one if the buffer is full.
+    pub fn push(&mut self, point: impl Into<PlotPoint>) {
+        if self.points.len() == 2 * self.capacity {
+            self.points.drain(..self.capacity);
+        }
+        self.points.push(point.into());
+    }
+
+    /// Remove all points.
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    /// The points, from oldest to latest.
+    pub fn points(&self) -> &[PlotPoint] {
+        &self.points[self.points.len() - self.len()..]
+    }
+
+    /// The latest point, if any.
+    pub fn latest(&self) -> Option<PlotPoint> {
+        self.points.last().copied()
+    }
+}
+
+impl Extend<PlotPoint> for PlotRingBuffer {
+    fn extend<T: IntoIterator<Item = PlotPoint>>(&mut self, iter: T) {
+        for point in iter {
+            self.push(point);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_latest_points_in_order() {
+        let mut buffer = PlotRingBuffer::new(3);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.latest(), None);
+
+        // Past the capacity, and past twice the capacity where the oldest half is drained:
+        for n in 1..=20 {
+            buffer.push([n as f64, -(n as f64)]);
+            let xs: Vec<f64> = buffer.points().iter().map(|p| p.x).collect();
+            let expected: Vec<f64> = (n.max(3) - 2..=n)
+                .filter(|&i| 1 <= i)
+                .map(|i| i as f64)
+                .collect();
+            assert_eq!(xs, expected, "after {n} pushes");
+            assert_eq!(buffer.len(), n.min(3));
+            assert_eq!(buffer.latest().map(|p| p.y), Some(-(n as f64)));
+        }
+        assert!(buffer.points.len() <= 2 * buffer.capacity());
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert!(buffer.points().is_empty());
+    }
+
+    #[test]
+    fn capacity_is_at_least_one() {
+        let mut buffer = PlotRingBuffer::new(0);
+        assert_eq!(buffer.capacity(), 1);
+        buffer.extend([PlotPoint::new(1.0, 1.0), PlotPoint::new(2.0, 2.0)]);
+        assert_eq!(buffer.points(), &[PlotPoint::new(2.0, 2.0)]);
+    }
+}
@@ -1,8 +1,13 @@
-use std::ops::{Bound, RangeBounds, RangeInclusive};
+use std::{
+    ops::{Bound, RangeBounds, RangeInclusive},
+    sync::Arc,
+};
 
 use egui::{Pos2, Shape, Stroke, Vec2};
 
-use crate::transform::PlotBounds;
+use crate::transform::{PlotBounds, PlotTransform};
+
+use super::{DecimatedPoints, DecimationPyramid, PlotRingBuffer};
 
 /// A point coordinate in the plot.
 ///
@@ -152,10 +157,14 @@ impl Default for Orientation {
 
 /// Represents many [`PlotPoint`]s.
 ///
-/// These can be an owned `Vec` or generated with a function.
+/// These can be an owned `Vec`, generated with a function,
+/// a huge series that is decimated to the visible pixels (see [`DecimationPyramid`]),
+/// or a real-time series that is shared with the app (see [`PlotRingBuffer`]).
 pub enum PlotPoints {
     Owned(Vec<PlotPoint>),
     Generator(ExplicitGenerator),
+    Decimated(DecimatedPoints),
+    Shared(Arc<PlotRingBuffer>),
     // Borrowed(&[PlotPoint]), // TODO(EmbersArc): Lifetimes are tricky in this case.
 }
 
@@ -177,6 +186,12 @@ impl From<Vec<[f64; 2]>> for PlotPoints {
     }
 }
 
+impl From<Arc<PlotRingBuffer>> for PlotPoints {
+    fn from(buffer: Arc<PlotRingBuffer>) -> Self {
+        Self::Shared(buffer)
+    }
+}
+
 impl FromIterator<[f64; 2]> for PlotPoints {
     fn from_iter<T: IntoIterator<Item = [f64; 2]>>(iter: T) -> Self {
         Self::Owned(iter.into_iter().map(|point| point.into()).collect())
@@ -192,9 +207,24 @@ impl PlotPoints {
         match self {
             Self::Owned(points) => points.as_slice(),
             Self::Generator(_) => &[],
+            Self::Decimated(decimated) => decimated.shown.as_slice(),
+            Self::Shared(buffer) => buffer.points(),
         }
     }
 
+    /// Show a huge series of points, by only showing the few points that make a difference
+    /// at the current zoom level.
+    ///
+    /// See [`DecimationPyramid`] for details.
+    pub fn decimated(pyramid: impl Into<Arc<DecimationPyramid>>) -> Self {
+        Self::Decimated(DecimatedPoints::new(pyramid.into()))
+    }
+
+    /// Show the points of a [`PlotRingBuffer`] without copying them.
+    pub fn shared(buffer: &Arc<PlotRingBuffer>) -> Self {
+        Self::Shared(buffer.clone())
+    }
+
     /// Draw a line based on a function `y=f(x)`, a range (which can be infinite) for x and the number of points.
     pub fn from_explicit_callback(
         function: impl Fn(f64) -> f64 + 'static,
@@ -270,6 +300,8 @@ impl PlotPoints {
         match self {
             Self::Owned(points) => points.is_empty(),
             Self::Generator(_) => false,
+            Self::Decimated(decimated) => decimated.pyramid.points().is_empty(),
+            Self::Shared(buffer) => buffer.is_empty(),
         }
     }
 
@@ -293,6 +325,16 @@ impl PlotPoints {
         }
     }
 
+    /// If decimated, pick the points to show for the visible part of the plot.
+    pub(super) fn decimate(&mut self, transform: &PlotTransform, pixels_per_point: f32) {
+        if let Self::Decimated(decimated) = self {
+            let columns = (transform.frame().width() * pixels_per_point).ceil() as usize;
+            decimated.shown = decimated
+                .pyramid
+                .decimate(transform.bounds().range_x(), columns);
+        }
+    }
+
     /// Returns the intersection of two ranges if they intersect.
     fn range_intersection(
         range1: &RangeInclusive<f64>,
@@ -305,14 +347,15 @@ impl PlotPoints {
 
     pub(super) fn bounds(&self) -> PlotBounds {
         match self {
-            Self::Owned(points) => {
+            Self::Owned(_) | Self::Shared(_) => {
                 let mut bounds = PlotBounds::NOTHING;
-                for point in points {
+                for point in self.points() {
                     bounds.extend_with(point);
                 }
                 bounds
             }
             Self::Generator(generator) => generator.estimate_bounds(),
+            Self::Decimated(decimated) => decimated.pyramid.bounds(),
         }
     }
 }
@@ -23,9 +23,10 @@ use epaint::Hsva;
 pub use crate::{
     axis::{Axis, AxisHints, HPlacement, Placement, VPlacement},
     items::{
-        Arrows, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, ClosestElem, HLine, Line, LineStyle,
+        Arrows, Band, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, ClosestElem, Colormap, Contour,
+        DecimatedPoints, DecimationPyramid, FilledContour, HLine, Heatmap, Line, LineStyle,
         MarkerShape, Orientation, PlotConfig, PlotGeometry, PlotImage, PlotItem, PlotPoint,
-        PlotPoints, Points, Polygon, Text, VLine,
+        PlotPoints, PlotRingBuffer, Points, Polygon, Text, VLine,
     },
     legend::{Corner, Legend},
     memory::PlotMemory,
@@ -155,6 +156,7 @@ pub struct Plot {
     allow_boxed_zoom: bool,
     default_auto_bounds: Vec2b,
     min_auto_bounds: PlotBounds,
+    follow_latest: Option<f64>,
     margin_fraction: Vec2,
     boxed_zoom_pointer_button: PointerButton,
     linked_axes: Option<(Id, Vec2b)>,
@@ -202,6 +204,7 @@ impl Plot {
             allow_boxed_zoom: true,
             default_auto_bounds: true.into(),
             min_auto_bounds: PlotBounds::NOTHING,
+            follow_latest: None,
             margin_fraction: Vec2::splat(0.05),
             boxed_zoom_pointer_button: PointerButton::Secondary,
             linked_axes: None,
@@ -520,6 +523,20 @@ impl Plot {
         self
     }
 
+    /// Scroll the x axis along with the data, showing the latest `x_width` of it.
+    ///
+    /// The right edge of the plot follows the largest x value of all items,
+    /// which is useful for real-time data, e.g. in a [`PlotRingBuffer`].
+    /// Like auto-bounds, this stops when the user pans or zooms along the x axis,
+    /// and starts again when they double-click the plot.
+    ///
+    /// Default: off.
+    #[inline]
+    pub fn follow_latest(mut self, x_width: impl Into<f64>) -> Self {
+        self.follow_latest = Some(x_width.into());
+        self
+    }
+
     /// Expand bounds to fit all items across the x axis, including values given by `include_x`.
     #[deprecated = "Use `auto_bounds` instead"]
     #[inline]
@@ -737,8 +754,9 @@ impl Plot {
             allow_double_click_reset,
             allow_boxed_zoom,
             boxed_zoom_pointer_button,
-            default_auto_bounds,
+            mut default_auto_bounds,
             min_auto_bounds,
+            follow_latest,
             margin_fraction,
             width,
             height,
@@ -800,7 +818,7 @@ impl Plot {
 
         let plot_id = id.unwrap_or_else(|| ui.make_persistent_id(id_source));
 
-        let ([x_axis_widgets, y_axis_widgets], plot_rect) = axis_widgets(
+        let ([x_axis_widgets, y_axis_widgets], colorbar_rect, plot_rect) = axis_widgets(
             PlotMemory::load(ui.ctx(), plot_id).as_ref(), // TODO(emilk): avoid loading plot memory twice
             show_axes,
             complete_rect,
@@ -810,6 +828,11 @@ impl Plot {
         // Allocate the plot window.
         let response = ui.allocate_rect(plot_rect, sense);
 
+        if follow_latest.is_some() {
+            // Following the latest data is a kind of auto-bounds along x.
+            default_auto_bounds.x = true;
+        }
+
         // Load or initialize the memory.
         ui.ctx().check_for_id_clash(plot_id, plot_rect, "Plot");
 
@@ -832,6 +855,7 @@ impl Plot {
             last_click_pos_for_zoom: None,
             x_axis_thickness: Default::default(),
             y_axis_thickness: Default::default(),
+            colorbar_thickness: None,
         });
 
         let last_plot_transform = mem.transform;
@@ -845,6 +869,7 @@ impl Plot {
             last_auto_bounds: mem.auto_bounds,
             response,
             bounds_modifications: Vec::new(),
+            colorbar: None,
         };
         let inner = build_fn(&mut plot_ui);
         let PlotUi {
@@ -852,6 +877,7 @@ impl Plot {
             mut response,
             last_plot_transform,
             bounds_modifications,
+            colorbar,
             ..
         } = plot_ui;
 
@@ -993,6 +1019,24 @@ impl Plot {
             }
         }
 
+        // Scroll along with the latest data.
+        if let Some(x_width) = follow_latest {
+            if mem.auto_bounds.x {
+                let latest = items
+                    .iter()
+                    .map(|item| item.bounds())
+                    .filter(PlotBounds::is_valid_x)
+                    .map(|item_bounds| item_bounds.max()[0])
+                    .reduce(f64::max);
+                if let Some(latest) = latest {
+                    bounds.set_x(&PlotBounds::from_min_max(
+                        [latest - x_width, bounds.min()[1]],
+                        [latest, bounds.max()[1]],
+                    ));
+                }
+            }
+        }
+
         mem.transform = PlotTransform::new(plot_rect, bounds, center_axis.x, center_axis.y);
 
         // Enforce aspect ratio
@@ -1151,9 +1195,29 @@ impl Plot {
             mem.y_axis_thickness.insert(i, thickness);
         }
 
+        // The colorbar of a heatmap. If it is new, there is room for it from the next frame on.
+        let colorbar =
+            colorbar.filter(|colorbar| !mem.hidden_items.contains(colorbar.hints.label.text()));
+        let colorbar_thickness = colorbar.map(|mut colorbar| {
+            let min_thickness = colorbar
+                .thickness()
+                .max(mem.colorbar_thickness.unwrap_or_default());
+            if let Some(rect) = colorbar_rect {
+                colorbar.rect = rect;
+                min_thickness.max(colorbar.ui(ui))
+            } else {
+                min_thickness
+            }
+        });
+        if colorbar_thickness.is_some() != mem.colorbar_thickness.is_some() {
+            ui.ctx().request_repaint();
+        }
+        mem.colorbar_thickness = colorbar_thickness;
+
         // Initialize values from functions.
         for item in &mut items {
             item.initialize(mem.transform.bounds().range_x());
+            item.prepare(ui, plot_id, &mem.transform);
         }
 
         let prepared = PreparedPlot {
@@ -1236,13 +1300,13 @@ impl Plot {
     }
 }
 
-/// Returns the rect left after adding axes.
+/// Returns the rect left after adding axes, and the rect of the colorbar if there was one last frame.
 fn axis_widgets(
     mem: Option<&PlotMemory>,
     show_axes: Vec2b,
     complete_rect: Rect,
     [x_axes, y_axes]: [&[AxisHints]; 2],
-) -> ([Vec<AxisWidget>; 2], Rect) {
+) -> ([Vec<AxisWidget>; 2], Option<Rect>, Rect) {
     // Next we want to create this layout.
     // Indices are only examples.
     //
@@ -1265,6 +1329,7 @@ fn axis_widgets(
     //      |      X-axis 1      |   |
     //  +   +--------------------+---+
     //
+    // The colorbar of a heatmap, if any, goes to the right of all of this.
 
     let mut x_axis_widgets = Vec::<AxisWidget>::new();
     let mut y_axis_widgets = Vec::<AxisWidget>::new();
@@ -1272,6 +1337,16 @@ fn axis_widgets(
     // Will shrink as we add more axes.
     let mut rect_left = complete_rect;
 
+    let mut colorbar_rect = None;
+    if let Some(width) = mem.and_then(|mem| mem.colorbar_thickness) {
+        let right = rect_left.right();
+        *rect_left.right_mut() -= width;
+        colorbar_rect = Some(Rect::from_x_y_ranges(
+            rect_left.right()..=right,
+            rect_left.y_range(),
+        ));
+    }
+
     if show_axes.x {
         // We will fix this later, once we know how much space the y axes take up.
         let initial_x_range = complete_rect.x_range();
@@ -1335,6 +1410,7 @@ fn axis_widgets(
     if plot_rect.width() <= 0.0 || plot_rect.height() <= 0.0 {
         y_axis_widgets.clear();
         x_axis_widgets.clear();
+        colorbar_rect = None;
         plot_rect = complete_rect;
     }
 
@@ -1344,7 +1420,11 @@ fn axis_widgets(
         widget.rect = Rect::from_x_y_ranges(plot_rect.x_range(), widget.rect.y_range());
     }
 
-    ([x_axis_widgets, y_axis_widgets], plot_rect)
+    // The colorbar is as high as the plot:
+    let colorbar_rect =
+        colorbar_rect.map(|rect| Rect::from_x_y_ranges(rect.x_range(), plot_rect.y_range()));
+
+    ([x_axis_widgets, y_axis_widgets], colorbar_rect, plot_rect)
 }
 
 /// User-requested modifications to the plot bounds. We collect them in the plot build function to later apply
@@ -1774,3 +1854,93 @@ pub fn color_from_strength(ui: &Ui, strength: f32) -> Color32 {
     let base_color = ui.visuals().text_color();
     base_color.gamma_multiply(strength.sqrt())
 }
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, vec2, CentralPanel, Context, Event, PointerButton, Pos2, RawInput, Rect};
+
+    use super::*;
+
+    /// Show a plot of the data up to `latest` that follows the latest 10 units of x,
+    /// and return its bounds.
+    fn show_following_plot(ctx: &Context, latest: usize, events: Vec<Event>) -> PlotBounds {
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(400.0, 300.0))),
+            events,
+            ..Default::default()
+        };
+        let mut bounds = None;
+        let _ = ctx.run(input, |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                let response = Plot::new("plot").follow_latest(10.0).show(ui, |plot_ui| {
+                    let points: PlotPoints =
+                        (0..=latest).map(|i| [i as f64, (i as f64).sin()]).collect();
+                    plot_ui.line(Line::new(points));
+                });
+                bounds = Some(*response.transform.bounds());
+            });
+        });
+        bounds.unwrap()
+    }
+
+    #[test]
+    fn follow_latest_until_panned() {
+        let ctx = Context::default();
+        let bounds = show_following_plot(&ctx, 20, vec![]);
+        assert_eq!(bounds.range_x(), 10.0..=20.0);
+        let bounds = show_following_plot(&ctx, 30, vec![]);
+        assert_eq!(bounds.range_x(), 20.0..=30.0);
+        assert!(
+            bounds.min()[1] < -0.9 && 0.9 < bounds.max()[1],
+            "y is still auto"
+        );
+
+        // Drag the plot to the right:
+        let button = |pos, pressed| Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        };
+        let (from, to) = (pos2(200.0, 150.0), pos2(300.0, 150.0));
+        show_following_plot(
+            &ctx,
+            30,
+            vec![Event::PointerMoved(from), button(from, true)],
+        );
+        show_following_plot(&ctx, 30, vec![Event::PointerMoved(pos2(250.0, 150.0))]);
+        show_following_plot(&ctx, 30, vec![Event::PointerMoved(to)]);
+        let panned = show_following_plot(&ctx, 30, vec![button(to, false)]);
+        assert!(*panned.range_x().end() < 30.0);
+
+        // No longer following new data:
+        let bounds = show_following_plot(&ctx, 40, vec![]);
+        assert_eq!(bounds.range_x(), panned.range_x());
+    }
+
+    #[test]
+    fn unnamed_heatmaps_in_different_plots_keep_their_textures() {
+        let ctx = Context::default();
+        let run = || {
+            let input = RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(400.0, 300.0))),
+                ..Default::default()
+            };
+            ctx.run(input, |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    for (i, value) in [1.0, 2.0].into_iter().enumerate() {
+                        Plot::new(("heatmap", i)).height(100.0).show(ui, |plot_ui| {
+                            plot_ui.heatmap(Heatmap::new(vec![0.0, value], 2));
+                        });
+                    }
+                });
+            })
+        };
+        let _ = run();
+        let output = run();
+        assert!(
+            output.textures_delta.set.is_empty(),
+            "the textures shouldn't be updated when nothing changed"
+        );
+    }
+}
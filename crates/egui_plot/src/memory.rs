@@ -32,6 +32,11 @@ pub struct PlotMemory {
     /// in order to fit the labels, if necessary.
     pub(crate) x_axis_thickness: BTreeMap<usize, f32>,
     pub(crate) y_axis_thickness: BTreeMap<usize, f32>,
+
+    /// The width of the colorbar of a heatmap the previous frame, if there was one.
+    ///
+    /// This is used in the next frame to make room for it.
+    pub(crate) colorbar_thickness: Option<f32>,
 }
 
 impl PlotMemory {
@@ -1,4 +1,4 @@
-use crate::*;
+use crate::{axis::ColorbarWidget, *};
 
 /// Provides methods to interact with a plot while building it. It is the single argument of the closure
 /// provided to [`Plot::show`]. See [`Plot`] for an example of how to use it.
@@ -10,6 +10,9 @@ pub struct PlotUi {
     pub(crate) last_auto_bounds: Vec2b,
     pub(crate) response: Response,
     pub(crate) bounds_modifications: Vec<BoundsModification>,
+
+    /// The colorbar of the last heatmap, if it wants one.
+    pub(crate) colorbar: Option<ColorbarWidget>,
 }
 
 impl PlotUi {
@@ -147,6 +150,19 @@ impl PlotUi {
         self.items.push(Box::new(polygon));
     }
 
+    /// Add a shaded band between two series.
+    pub fn band(&mut self, mut band: Band) {
+        if band.lower.is_empty() || band.upper.is_empty() {
+            return;
+        };
+
+        // Give the band an automatic color if no color has been assigned.
+        if band.stroke.color == Color32::TRANSPARENT {
+            band.stroke.color = self.auto_color();
+        }
+        self.items.push(Box::new(band));
+    }
+
     /// Add a text.
     pub fn text(&mut self, text: Text) {
         if text.text.is_empty() {
@@ -187,6 +203,29 @@ impl PlotUi {
         self.items.push(Box::new(image));
     }
 
+    /// Add a heatmap, and its colorbar.
+    pub fn heatmap(&mut self, mut heatmap: Heatmap) {
+        if heatmap.is_empty() {
+            return;
+        };
+
+        heatmap.resolve_value_range();
+        if let Some(colorbar) = heatmap.colorbar_widget() {
+            self.colorbar = Some(colorbar);
+        }
+        self.items.push(Box::new(heatmap));
+    }
+
+    /// Add contour lines, one legend entry per level.
+    pub fn contour(&mut self, contour: Contour) {
+        self.items.extend(contour.into_items());
+    }
+
+    /// Add filled contours, one legend entry per area between two levels.
+    pub fn filled_contour(&mut self, filled_contour: FilledContour) {
+        self.items.extend(filled_contour.into_items());
+    }
+
     /// Add a horizontal line.
     /// Can be useful e.g. to show min/max bounds or similar.
     /// Always fills the full width of the plot.
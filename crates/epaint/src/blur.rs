@@ -0,0 +1,201 @@
+//! Gaussian blur, for soft shadows and glows of any shape,
+//! and for blurring what is painted behind a panel.
+//!
+//! Blurring needs help from the renderer, so a [`BlurShape`] is tessellated into a [`BlurPrimitive`]
+//! that also carries a cheaper approximation, for renderers that can't blur.
+
+use emath::NumExt as _;
+
+use crate::{
+    stroke::PathStroke, Color32, Mesh, Rect, Rounding, Shadow, Shape, TextureId, Vec2, WHITE_UV,
+};
+
+/// A blurred shadow of a shape, or a blur of what is behind a rectangle.
+///
+/// See [`Shape::shadow`] and [`Shape::backdrop_blur`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlurShape {
+    /// The shadow or glow cast by a shape:
+    /// its silhouette in [`Shadow::color`], moved by [`Shadow::offset`],
+    /// grown by [`Shadow::spread`] and blurred by [`Shadow::blur`].
+    ///
+    /// The shape itself is not painted, so paint it on top.
+    /// Images cast the shadow of their whole rectangle, whatever their alpha.
+    Shadow { shape: Box<Shape>, shadow: Shadow },
+
+    /// Blur what has already been painted inside a rounded rectangle.
+    ///
+    /// Paint a translucent fill on top of it for a frosted glass effect.
+    ///
+    /// There is no cheap approximation of this, so renderers that can't blur
+    /// (or, like `egui-wgpu` with an sRGB framebuffer, can't read back what they painted)
+    /// paint nothing here.
+    Backdrop {
+        rect: Rect,
+        rounding: Rounding,
+
+        /// The width of the blur, in the same sense as [`Shadow::blur`].
+        blur: f32,
+    },
+}
+
+impl BlurShape {
+    /// The visual bounding rectangle (includes the fuzzy edge of shadows)
+    pub fn visual_bounding_rect(&self) -> Rect {
+        match self {
+            Self::Shadow { shape, shadow } => {
+                if shadow.color == Color32::TRANSPARENT {
+                    Rect::NOTHING
+                } else {
+                    // The Gaussian is cut off at three standard deviations:
+                    let margin = 3.0 * sigma_from_blur(shadow.blur.at_least(0.0));
+                    shape
+                        .visual_bounding_rect()
+                        .translate(shadow.offset)
+                        .expand(shadow.spread + margin)
+                }
+            }
+            Self::Backdrop { rect, .. } => *rect,
+        }
+    }
+}
+
+impl From<BlurShape> for Shape {
+    #[inline(always)]
+    fn from(shape: BlurShape) -> Self {
+        Self::Blur(shape)
+    }
+}
+
+/// The standard deviation of the Gaussian for a blur of the given width.
+///
+/// An edge blurred with this fades from 2% to 98% opacity over a distance of `blur`,
+/// which matches the penumbra of [`Shadow`] with the tessellator's approximation.
+pub(crate) fn sigma_from_blur(blur: f32) -> f32 {
+    blur / 4.0
+}
+
+/// A Gaussian blur, produced by tessellating a [`BlurShape`].
+///
+/// To paint it, a renderer:
+/// 1. Makes an image of what is inside [`Self::rect`],
+///    by painting the meshes of [`BlurSource::Meshes`] into a transparent image,
+///    or by copying what has been painted so far for [`BlurSource::Backdrop`].
+/// 2. Blurs that image with a Gaussian with a standard deviation of [`Self::sigma`],
+///    one direction at a time.
+/// 3. Paints the blurred image with [`Self::mesh`].
+///
+/// A renderer that can't do this paints the [`Self::fallback`] meshes instead.
+#[derive(Clone, Debug)]
+pub struct BlurPrimitive {
+    /// The area to blur, in points, rounded outwards to whole physical pixels.
+    ///
+    /// This includes a margin for the blur to fade out in.
+    /// Renderers may shrink it to the part that can affect the screen.
+    pub rect: Rect,
+
+    /// The standard deviation of the Gaussian, in points.
+    pub sigma: f32,
+
+    /// What to blur.
+    pub source: BlurSource,
+
+    /// How to paint the blurred image.
+    ///
+    /// The uv coordinates map [`Self::rect`] to `(0, 0) - (1, 1)`,
+    /// and the texture id should be ignored in favor of the blurred image.
+    /// The vertex colors are white, with an alpha for anti-aliasing.
+    pub mesh: Mesh,
+
+    /// What to paint instead, for renderers that can't blur.
+    ///
+    /// For shadows this is the tessellator's approximation of a blur with a wide feathering.
+    /// For backdrop blurs it is empty.
+    pub fallback: Vec<Mesh>,
+}
+
+/// What a [`BlurPrimitive`] blurs.
+#[derive(Clone, Debug)]
+pub enum BlurSource {
+    /// The silhouette of a shadow, to be painted into a transparent image.
+    Meshes(Vec<Mesh>),
+
+    /// What has been painted so far, behind the blur.
+    Backdrop,
+}
+
+/// Grow a shape in all directions, as for [`Shadow::spread`].
+///
+/// Closed paths are grown by giving them a wider stroke, so they get the stroke color there.
+/// That doesn't matter for the single-colored silhouette of a shadow.
+/// Text and meshes can't be grown.
+pub(crate) fn spread_shape(shape: &mut Shape, spread: f32) {
+    if spread == 0.0 {
+        return;
+    }
+    match shape {
+        Shape::Vec(shapes) => {
+            for shape in shapes {
+                spread_shape(shape, spread);
+            }
+        }
+        Shape::Circle(circle) => {
+            circle.radius = (circle.radius + spread).max(0.0);
+        }
+        Shape::Ellipse(ellipse) => {
+            ellipse.radius = (ellipse.radius + Vec2::splat(spread)).max(Vec2::ZERO);
+        }
+        Shape::Rect(rect) => {
+            rect.rect = rect.rect.expand(spread);
+            rect.rounding += spread.max(0.0);
+        }
+        Shape::LineSegment { stroke, .. } => {
+            stroke.width = (stroke.width + 2.0 * spread).max(0.0);
+        }
+        Shape::Path(path) => {
+            if path.closed
+                && path.fill != Color32::TRANSPARENT
+                && path.stroke.is_empty()
+                && 0.0 < spread
+            {
+                path.stroke = PathStroke::new(2.0 * spread, path.fill);
+            } else {
+                path.stroke.width = (path.stroke.width + 2.0 * spread).max(0.0);
+            }
+        }
+        Shape::QuadraticBezier(bezier) => {
+            bezier.stroke.width = (bezier.stroke.width + 2.0 * spread).max(0.0);
+        }
+        Shape::CubicBezier(bezier) => {
+            bezier.stroke.width = (bezier.stroke.width + 2.0 * spread).max(0.0);
+        }
+        Shape::GradientFill(gradient_fill) => {
+            // The gradient always fills the shape, whatever its fill color:
+            if let Shape::Path(path) = &mut *gradient_fill.shape {
+                path.fill = Color32::WHITE;
+            }
+            spread_shape(&mut gradient_fill.shape, spread);
+        }
+        Shape::Noop | Shape::Text(_) | Shape::Mesh(_) | Shape::Blur(_) | Shape::Callback(_) => {}
+    }
+}
+
+/// Turn meshes into the silhouette of a shadow, in the shadow color.
+///
+/// The alpha of each vertex is kept, so that anti-aliased edges and text stay as they are.
+///
+/// Meshes textured with anything but the font texture (i.e. images) would otherwise tint the
+/// silhouette with the colors of the image, so they are painted untextured instead:
+/// the shadow of an image is that of its whole rectangle, including any transparent parts.
+pub(crate) fn color_silhouette(mesh: &mut Mesh, color: Color32) {
+    let untextured = mesh.texture_id != TextureId::default();
+    if untextured {
+        mesh.texture_id = TextureId::default();
+    }
+    for vertex in &mut mesh.vertices {
+        vertex.color = color.gamma_multiply(vertex.color.a() as f32 / 255.0);
+        if untextured {
+            vertex.uv = WHITE_UV;
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! Finding the part of the screen that changed from one frame to the next.
+
+use std::{
+    hash::{BuildHasher as _, Hasher as _},
+    sync::Arc,
+};
+
+use emath::{NumExt as _, Rect, Rot2};
+
+use crate::{
+    shape_hash::{hash_rect, hash_shape},
+    BlurShape, ClippedShape, Galley, GradientFillShape, Shape, TextureId,
+};
+
+/// What we remember about each shape of last frame.
+#[derive(Clone, Copy)]
+struct ShapeSummary {
+    /// `None` for shapes that we can't compare, which are always considered changed.
+    hash: Option<u64>,
+
+    /// Where the shape paints, in points. [`Rect::NOTHING`] if nowhere.
+    rect: Rect,
+}
+
+impl ShapeSummary {
+    fn is_same_as(&self, other: &Self) -> bool {
+        self.hash.is_some() && self.hash == other.hash
+    }
+}
+
+/// Finds the part of the screen where the shapes changed since last frame.
+///
+/// A renderer that still has last frame on screen then only needs to repaint that part,
+/// which saves a lot of power when only something small is animating.
+///
+/// The shapes are compared by their hashes, in order, so that a changed shape,
+/// or a shape that moved above or below other shapes, damages the area it covers now
+/// as well as the area it covered last frame.
+///
+/// ```
+/// # use epaint::*;
+/// let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 100.0));
+/// let frame = |spinner_angle: f32| {
+///     let spinner = pos2(50.0, 50.0) + 10.0 * Vec2::angled(spinner_angle);
+///     vec![
+///         ClippedShape { clip_rect, shape: Shape::rect_filled(clip_rect, 0.0, Color32::GRAY) },
+///         ClippedShape { clip_rect, shape: Shape::circle_filled(spinner, 2.0, Color32::WHITE) },
+///     ]
+/// };
+///
+/// let mut tracker = DamageTracker::default();
+/// assert_eq!(tracker.damage(&frame(0.0), &[]), None, "Everything is new");
+/// assert_eq!(tracker.damage(&frame(0.0), &[]), Some(Rect::NOTHING), "Nothing changed");
+///
+/// let damage = tracker.damage(&frame(1.0), &[]).unwrap();
+/// assert!(damage.contains(pos2(60.0, 50.0)) && damage.width() < 20.0);
+/// ```
+#[derive(Default)]
+pub struct DamageTracker {
+    /// `None` before the first frame.
+    last_frame: Option<Vec<ShapeSummary>>,
+
+    /// Text shapes are hashed by the address of their galley,
+    /// so we keep the galleys of last frame alive to make sure no new galley can get the same address.
+    galleys: Vec<Arc<Galley>>,
+}
+
+impl DamageTracker {
+    /// If two frames differ by more than this many shapes, we don't bother finding out exactly which.
+    const MAX_CHANGES: usize = 256;
+
+    /// Compare `shapes` with the shapes of the previous call,
+    /// and return the bounding rectangle (in points) of everything that changed.
+    ///
+    /// `changed_textures` are the textures whose contents changed since the previous call.
+    /// Shapes using them are considered changed.
+    ///
+    /// Returns `None` on the first call (and after [`Self::clear`]),
+    /// when everything needs painting,
+    /// and [`Rect::NOTHING`] if nothing changed.
+    ///
+    /// The rectangle does not include the anti-aliasing of the edges,
+    /// so expand it by a physical pixel before using it.
+    pub fn damage(
+        &mut self,
+        shapes: &[ClippedShape],
+        changed_textures: &[TextureId],
+    ) -> Option<Rect> {
+        crate::profile_function!();
+
+        let summaries = shapes
+            .iter()
+            .map(|clipped_shape| summarize(clipped_shape, changed_textures))
+            .collect();
+        let last_frame = self.last_frame.replace(summaries);
+
+        let mut galleys = Vec::new();
+        for clipped_shape in shapes {
+            collect_galleys(&clipped_shape.shape, &mut galleys);
+        }
+        self.galleys = galleys;
+
+        let last_frame = last_frame?;
+        let this_frame = self.last_frame.as_deref().unwrap_or_default();
+        let mut damage = changed_rect(&last_frame, this_frame);
+        include_backdrops(&mut damage, shapes);
+        Some(damage)
+    }
+
+    /// Forget last frame, so that the next call to [`Self::damage`] returns `None`.
+    pub fn clear(&mut self) {
+        self.last_frame = None;
+        self.galleys.clear();
+    }
+}
+
+fn summarize(clipped_shape: &ClippedShape, changed_textures: &[TextureId]) -> ShapeSummary {
+    let ClippedShape { clip_rect, shape } = clipped_shape;
+
+    let rect = visual_rect(shape).intersect(*clip_rect);
+    let rect = if rect.is_positive() {
+        rect
+    } else {
+        Rect::NOTHING
+    };
+
+    let mut hasher = ahash::RandomState::with_seeds(1, 2, 3, 4).build_hasher();
+    hash_rect(&mut hasher, *clip_rect);
+    let hash = (hash_shape(shape, &mut hasher) && !uses_any_texture(shape, changed_textures))
+        .then(|| hasher.finish());
+
+    ShapeSummary { hash, rect }
+}
+
+/// Like [`Shape::visual_bounding_rect`], but also covers rotated and underlined text.
+fn visual_rect(shape: &Shape) -> Rect {
+    match shape {
+        Shape::Vec(shapes) => shapes
+            .iter()
+            .fold(Rect::NOTHING, |rect, shape| rect.union(visual_rect(shape))),
+        Shape::Text(text_shape) => {
+            let galley = &text_shape.galley;
+            let rect = galley
+                .mesh_bounds
+                .union(galley.rect)
+                .expand(text_shape.underline.width);
+            if text_shape.angle == 0.0 {
+                rect.translate(text_shape.pos.to_vec2())
+            } else {
+                let rot = Rot2::from_angle(text_shape.angle);
+                let corners = [
+                    rect.left_top(),
+                    rect.right_top(),
+                    rect.left_bottom(),
+                    rect.right_bottom(),
+                ];
+                Rect::from_points(&corners.map(|corner| text_shape.pos + rot * corner.to_vec2()))
+            }
+        }
+        _ => shape.visual_bounding_rect(),
+    }
+}
+
+fn uses_any_texture(shape: &Shape, textures: &[TextureId]) -> bool {
+    if textures.is_empty() {
+        return false;
+    }
+    match shape {
+        Shape::Vec(shapes) => shapes.iter().any(|shape| uses_any_texture(shape, textures)),
+        Shape::Blur(BlurShape::Shadow { shape, .. })
+        | Shape::GradientFill(GradientFillShape { shape, .. }) => uses_any_texture(shape, textures),
+        Shape::Noop | Shape::Blur(BlurShape::Backdrop { .. }) | Shape::Callback(_) => false,
+        Shape::Circle(_)
+        | Shape::Ellipse(_)
+        | Shape::LineSegment { .. }
+        | Shape::Path(_)
+        | Shape::Rect(_)
+        | Shape::Text(_)
+        | Shape::Mesh(_)
+        | Shape::QuadraticBezier(_)
+        | Shape::CubicBezier(_) => textures.contains(&shape.texture_id()),
+    }
+}
+
+fn collect_galleys(shape: &Shape, galleys: &mut Vec<Arc<Galley>>) {
+    match shape {
+        Shape::Vec(shapes) => {
+            for shape in shapes {
+                collect_galleys(shape, galleys);
+            }
+        }
+        Shape::Text(text_shape) => galleys.push(text_shape.galley.clone()),
+        Shape::Blur(BlurShape::Shadow { shape, .. })
+        | Shape::GradientFill(GradientFillShape { shape, .. }) => collect_galleys(shape, galleys),
+        _ => {}
+    }
+}
+
+/// The union of the rectangles of the shapes that are not in both frames, in the same order.
+fn changed_rect(last_frame: &[ShapeSummary], this_frame: &[ShapeSummary]) -> Rect {
+    // Most of the time, only a few shapes in the middle change:
+    let prefix = last_frame
+        .iter()
+        .zip(this_frame)
+        .take_while(|(a, b)| a.is_same_as(b))
+        .count();
+    let (last_frame, this_frame) = (&last_frame[prefix..], &this_frame[prefix..]);
+    let suffix = last_frame
+        .iter()
+        .rev()
+        .zip(this_frame.iter().rev())
+        .take_while(|(a, b)| a.is_same_as(b))
+        .count();
+    let last_frame = &last_frame[..last_frame.len() - suffix];
+    let this_frame = &this_frame[..this_frame.len() - suffix];
+
+    let union = |rect: Rect, summary: &ShapeSummary| rect.union(summary.rect);
+
+    if let Some((kept_last, kept_this)) =
+        common_subsequence(last_frame, this_frame, DamageTracker::MAX_CHANGES)
+    {
+        // A pixel that none of the other shapes cover is painted by the same shapes
+        // in the same order as last frame, so it hasn't changed.
+        let changed = |summaries: &[ShapeSummary], kept: &[bool]| {
+            summaries
+                .iter()
+                .zip(kept)
+                .filter(|(_, kept)| !**kept)
+                .fold(Rect::NOTHING, |rect, (summary, _)| union(rect, summary))
+        };
+        changed(last_frame, &kept_last).union(changed(this_frame, &kept_this))
+    } else {
+        let rect = last_frame.iter().fold(Rect::NOTHING, union);
+        this_frame.iter().fold(rect, union)
+    }
+}
+
+/// Find a longest common subsequence of the two lists with Myers' diff algorithm.
+///
+/// Returns which elements of each list are part of it,
+/// or `None` if that takes more than `max_changes` insertions and removals.
+fn common_subsequence(
+    a: &[ShapeSummary],
+    b: &[ShapeSummary],
+    max_changes: usize,
+) -> Option<(Vec<bool>, Vec<bool>)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max_d = (a.len() + b.len()).at_most(max_changes) as isize;
+    let offset = max_d + 1;
+    let index = |k: isize| (k + offset) as usize;
+
+    // The furthest `x` reached on each diagonal `k = x - y`, for each number of changes `d`:
+    let mut v = vec![0_isize; 2 * offset as usize + 1];
+    let mut trace = Vec::new();
+
+    for d in 0..=max_d {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)] // down: insertion
+            } else {
+                v[index(k - 1)] + 1 // right: removal
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize].is_same_as(&b[y as usize]) {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+
+            if n <= x && m <= y {
+                return Some(backtrack(&trace, index, a.len(), b.len()));
+            }
+        }
+    }
+
+    None
+}
+
+fn backtrack(
+    trace: &[Vec<isize>],
+    index: impl Fn(isize) -> usize,
+    n: usize,
+    m: usize,
+) -> (Vec<bool>, Vec<bool>) {
+    let mut kept_a = vec![false; n];
+    let mut kept_b = vec![false; m];
+    let (mut x, mut y) = (n as isize, m as isize);
+
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let previous_x = v[index(previous_k)];
+        let previous_y = previous_x - previous_k;
+
+        // Follow the diagonal of equal elements back:
+        while previous_x < x && previous_y < y {
+            x -= 1;
+            y -= 1;
+            kept_a[x as usize] = true;
+            kept_b[y as usize] = true;
+        }
+
+        if 0 < d {
+            x = previous_x;
+            y = previous_y;
+        }
+    }
+
+    (kept_a, kept_b)
+}
+
+/// A backdrop blur samples what is painted around it,
+/// so if that changed, the whole blurred region must be repainted.
+fn include_backdrops(damage: &mut Rect, shapes: &[ClippedShape]) {
+    fn backdrop_regions(clip_rect: Rect, shape: &Shape, regions: &mut Vec<Rect>) {
+        match shape {
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    backdrop_regions(clip_rect, shape, regions);
+                }
+            }
+            Shape::Blur(BlurShape::Backdrop { rect, blur, .. }) => {
+                let margin = 3.0 * crate::blur::sigma_from_blur(blur.at_least(0.0));
+                let rect = rect.intersect(clip_rect);
+                if rect.is_positive() {
+                    regions.push(rect.expand(margin));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut regions = Vec::new();
+    for ClippedShape { clip_rect, shape } in shapes {
+        backdrop_regions(*clip_rect, shape, &mut regions);
+    }
+
+    // Repainting a backdrop can damage another one:
+    loop {
+        let mut grew = false;
+        for region in &regions {
+            if damage.intersects(*region) && !damage.contains_rect(*region) {
+                *damage = damage.union(*region);
+                grew = true;
+            }
+        }
+        if !grew {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn damage_of_reordered_and_retextured_shapes() {
+        let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 100.0));
+        let square = |x: f32, texture_id: TextureId| {
+            let mut rect_shape = RectShape::filled(
+                Rect::from_min_size(pos2(x, 0.0), vec2(10.0, 10.0)),
+                0.0,
+                Color32::RED,
+            );
+            rect_shape.fill_texture_id = texture_id;
+            ClippedShape {
+                clip_rect,
+                shape: rect_shape.into(),
+            }
+        };
+        let user_texture = TextureId::User(1);
+        let squares = [0.0, 20.0, 40.0, 60.0].map(|x| square(x, TextureId::default()));
+
+        let mut tracker = DamageTracker::default();
+        assert_eq!(tracker.damage(&squares, &[]), None);
+
+        // Bring the second square to the front:
+        let reordered = [&squares[0], &squares[2], &squares[3], &squares[1]].map(Clone::clone);
+        let damage = tracker.damage(&reordered, &[]).unwrap();
+        assert_eq!(damage, squares[1].shape.visual_bounding_rect());
+
+        let textured = [
+            reordered[0].clone(),
+            reordered[1].clone(),
+            reordered[2].clone(),
+            square(20.0, user_texture),
+        ];
+        tracker.damage(&textured, &[]);
+        assert_eq!(tracker.damage(&textured, &[]), Some(Rect::NOTHING));
+        let damage = tracker.damage(&textured, &[user_texture]).unwrap();
+        assert_eq!(damage, squares[1].shape.visual_bounding_rect());
+    }
+}
@@ -0,0 +1,495 @@
+//! Gradient fills for shapes.
+
+use emath::{lerp, pos2, Pos2, Rect, Vec2};
+
+use crate::{
+    stroke::PathStroke, CircleShape, Color32, EllipseShape, Mesh, PathShape, RectShape, Shape,
+    Stroke, Vertex,
+};
+
+/// How the position along a [`Gradient`] is measured.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum GradientKind {
+    /// Changes along the line from `from` (`0.0`) to `to` (`1.0`),
+    /// and is constant along the lines perpendicular to it.
+    Linear { from: Pos2, to: Pos2 },
+
+    /// Changes with the distance from `center` (`0.0`) out to `radius` (`1.0`).
+    Radial { center: Pos2, radius: f32 },
+}
+
+/// A color gradient, used to fill a shape instead of a solid color.
+///
+/// The positions are relative to the bounding rectangle of the filled shape,
+/// with `(0, 0)` in the top left corner and `(1, 1)` in the bottom right corner.
+/// This means the gradient stretches with the shape,
+/// and a radial gradient is an ellipse unless the shape is square.
+///
+/// Colors are interpolated in gamma space, just like the vertex colors of a [`Mesh`].
+///
+/// ```
+/// # use epaint::{Color32, Gradient, RectShape, Shape, pos2, Rect};
+/// let progress_bar = Shape::gradient_fill(
+///     RectShape::filled(
+///         Rect::from_min_max(pos2(0.0, 0.0), pos2(200.0, 16.0)),
+///         8.0,
+///         Color32::WHITE,
+///     ),
+///     Gradient::horizontal(Color32::DARK_GREEN, Color32::LIGHT_GREEN),
+/// );
+///
+/// let glow = Gradient::radial(
+///     pos2(0.5, 0.5),
+///     0.5,
+///     [(0.0, Color32::WHITE), (0.3, Color32::YELLOW), (1.0, Color32::TRANSPARENT)],
+/// );
+/// assert_eq!(glow.color_at(pos2(0.5, 0.5)), Color32::WHITE);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Gradient {
+    pub kind: GradientKind,
+
+    /// Positions along the gradient and the colors there, sorted by position.
+    ///
+    /// Before the first stop the color of the first stop is used,
+    /// and after the last stop the color of the last stop.
+    pub stops: Vec<(f32, Color32)>,
+}
+
+impl Gradient {
+    /// A gradient along the line from `from` to `to`, with any number of color stops.
+    ///
+    /// The stops don't need to be sorted.
+    pub fn linear(from: Pos2, to: Pos2, stops: impl IntoIterator<Item = (f32, Color32)>) -> Self {
+        Self::new(GradientKind::Linear { from, to }, stops)
+    }
+
+    /// A gradient out from `center`, with any number of color stops.
+    ///
+    /// The stops don't need to be sorted.
+    pub fn radial(
+        center: Pos2,
+        radius: f32,
+        stops: impl IntoIterator<Item = (f32, Color32)>,
+    ) -> Self {
+        Self::new(GradientKind::Radial { center, radius }, stops)
+    }
+
+    /// From `left` at the left edge to `right` at the right edge.
+    pub fn horizontal(left: Color32, right: Color32) -> Self {
+        Self::linear(pos2(0.0, 0.5), pos2(1.0, 0.5), [(0.0, left), (1.0, right)])
+    }
+
+    /// From `top` at the top edge to `bottom` at the bottom edge.
+    pub fn vertical(top: Color32, bottom: Color32) -> Self {
+        Self::linear(pos2(0.5, 0.0), pos2(0.5, 1.0), [(0.0, top), (1.0, bottom)])
+    }
+
+    fn new(kind: GradientKind, stops: impl IntoIterator<Item = (f32, Color32)>) -> Self {
+        let mut stops: Vec<_> = stops.into_iter().collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { kind, stops }
+    }
+
+    /// The color at this position, relative to the bounding rectangle of the shape.
+    pub fn color_at(&self, pos: Pos2) -> Color32 {
+        self.color_at_t(self.t_at(pos))
+    }
+
+    /// The position along the gradient.
+    fn t_at(&self, pos: Pos2) -> f32 {
+        match self.kind {
+            GradientKind::Linear { from, to } => {
+                let dir = to - from;
+                let length_sq = dir.length_sq();
+                if length_sq > 0.0 {
+                    (pos - from).dot(dir) / length_sq
+                } else {
+                    0.0
+                }
+            }
+            GradientKind::Radial { center, radius } => {
+                if radius > 0.0 {
+                    (pos - center).length() / radius
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    fn color_at_t(&self, t: f32) -> Color32 {
+        let Some(&(first_t, first_color)) = self.stops.first() else {
+            return Color32::TRANSPARENT;
+        };
+        if t <= first_t {
+            return first_color;
+        }
+        for window in self.stops.windows(2) {
+            let [(t0, color0), (t1, color1)] = [window[0], window[1]];
+            if t <= t1 {
+                return if t1 > t0 {
+                    lerp_color(color0, color1, (t - t0) / (t1 - t0))
+                } else {
+                    color1
+                };
+            }
+        }
+        self.stops[self.stops.len() - 1].1
+    }
+}
+
+fn lerp_color(a: Color32, b: Color32, t: f32) -> Color32 {
+    let [a, b] = [a, b].map(|color| color.to_array().map(|c| c as f32));
+    let [r, g, b, a] = [0, 1, 2, 3].map(|i| lerp(a[i]..=b[i], t).round() as u8);
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+// ----------------------------------------------------------------------------
+
+/// A shape filled with a [`Gradient`] instead of a single color.
+///
+/// See [`Shape::gradient_fill`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradientFillShape {
+    /// A [`Shape::Circle`], [`Shape::Ellipse`], [`Shape::Rect`] or closed [`Shape::Path`].
+    ///
+    /// Its fill color is ignored, and its stroke is painted on top of the gradient as usual.
+    /// Other shapes are painted as if there was no gradient.
+    pub shape: Box<Shape>,
+
+    pub gradient: Gradient,
+}
+
+impl GradientFillShape {
+    /// The visual bounding rectangle (includes stroke width)
+    pub fn visual_bounding_rect(&self) -> Rect {
+        let rect = self.shape.visual_bounding_rect();
+        match fill_rect(&self.shape) {
+            Some(fill_rect) => rect.union(fill_rect),
+            None => rect,
+        }
+    }
+
+    /// Split into the part to color with the gradient, filled with white,
+    /// the rectangle the gradient is relative to, and the stroke.
+    ///
+    /// Returns `None` if the shape can't be filled with a gradient.
+    pub(crate) fn split(&self) -> Option<(Shape, Rect, Shape)> {
+        let rect = fill_rect(&self.shape)?;
+        let (fill, stroke) = match &*self.shape {
+            Shape::Circle(circle) => (
+                CircleShape {
+                    fill: Color32::WHITE,
+                    stroke: Stroke::NONE,
+                    ..*circle
+                }
+                .into(),
+                CircleShape {
+                    fill: Color32::TRANSPARENT,
+                    ..*circle
+                }
+                .into(),
+            ),
+            Shape::Ellipse(ellipse) => (
+                EllipseShape {
+                    fill: Color32::WHITE,
+                    stroke: Stroke::NONE,
+                    ..*ellipse
+                }
+                .into(),
+                EllipseShape {
+                    fill: Color32::TRANSPARENT,
+                    ..*ellipse
+                }
+                .into(),
+            ),
+            Shape::Rect(rect_shape) => (
+                RectShape {
+                    fill: Color32::WHITE,
+                    stroke: Stroke::NONE,
+                    ..*rect_shape
+                }
+                .into(),
+                RectShape {
+                    fill: Color32::TRANSPARENT,
+                    ..*rect_shape
+                }
+                .into(),
+            ),
+            Shape::Path(path) => (
+                PathShape {
+                    points: path.points.clone(),
+                    closed: true,
+                    fill: Color32::WHITE,
+                    stroke: PathStroke::NONE,
+                }
+                .into(),
+                PathShape {
+                    fill: Color32::TRANSPARENT,
+                    ..path.clone()
+                }
+                .into(),
+            ),
+            _ => return None,
+        };
+        Some((fill, rect, stroke))
+    }
+}
+
+impl From<GradientFillShape> for Shape {
+    #[inline(always)]
+    fn from(shape: GradientFillShape) -> Self {
+        Self::GradientFill(shape)
+    }
+}
+
+/// The rectangle that a [`Gradient`] filling `shape` is relative to,
+/// or `None` if it can't be filled with a gradient.
+fn fill_rect(shape: &Shape) -> Option<Rect> {
+    match shape {
+        Shape::Circle(circle) => Some(Rect::from_center_size(
+            circle.center,
+            Vec2::splat(2.0 * circle.radius),
+        )),
+        Shape::Ellipse(ellipse) => {
+            Some(Rect::from_center_size(ellipse.center, 2.0 * ellipse.radius))
+        }
+        Shape::Rect(rect_shape) => Some(rect_shape.rect),
+        Shape::Path(path) if path.closed => Some(Rect::from_points(&path.points)),
+        _ => None,
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A radial gradient is approximated by cutting the mesh into this many cells per radius,
+/// in each direction.
+const RADIAL_CELLS_PER_RADIUS: i32 = 16;
+
+/// Color the part of `mesh` starting at `first_vertex` and `first_index` with the gradient.
+///
+/// The mesh should have been tessellated with a white fill,
+/// so that the alpha of each vertex is its coverage (e.g. from feathering).
+///
+/// Since vertex colors are interpolated linearly across each triangle,
+/// the triangles are first cut where the gradient changes direction: at each stop of a linear gradient,
+/// and along a grid around the center of a radial gradient.
+///
+/// `rect` is the bounding rectangle of the shape.
+pub(crate) fn fill_with_gradient(
+    mesh: &mut Mesh,
+    first_vertex: usize,
+    first_index: usize,
+    rect: Rect,
+    gradient: &Gradient,
+) {
+    crate::profile_function!();
+
+    let size = rect.size().max(Vec2::splat(f32::EPSILON));
+    let relative = |pos: Pos2| pos2((pos.x - rect.min.x) / size.x, (pos.y - rect.min.y) / size.y);
+
+    match gradient.kind {
+        GradientKind::Linear { from, to } => {
+            let dir = to - from;
+            let length_sq = dir.length_sq();
+            if length_sq > 0.0 {
+                for &(t, _) in &gradient.stops {
+                    cut_triangles(mesh, first_index, |pos| {
+                        (relative(pos) - from).dot(dir) / length_sq - t
+                    });
+                }
+            }
+        }
+        GradientKind::Radial { center, radius } => {
+            if radius > 0.0 {
+                let step = radius / RADIAL_CELLS_PER_RADIUS as f32;
+                for i in -RADIAL_CELLS_PER_RADIUS..=RADIAL_CELLS_PER_RADIUS {
+                    let offset = i as f32 * step;
+                    cut_triangles(mesh, first_index, |pos| {
+                        relative(pos).x - (center.x + offset)
+                    });
+                    cut_triangles(mesh, first_index, |pos| {
+                        relative(pos).y - (center.y + offset)
+                    });
+                }
+            }
+        }
+    }
+
+    for vertex in &mut mesh.vertices[first_vertex..] {
+        let coverage = vertex.color.a() as f32 / 255.0;
+        vertex.color = gradient
+            .color_at(relative(vertex.pos))
+            .gamma_multiply(coverage);
+    }
+}
+
+/// Cut the triangles starting at `first_index` along the line where `side` is zero.
+///
+/// `side` must be an affine function of the position.
+/// Vertices on the cut are shared between neighboring triangles, so no cracks appear.
+fn cut_triangles(mesh: &mut Mesh, first_index: usize, side: impl Fn(Pos2) -> f32) {
+    let triangles = mesh.indices.split_off(first_index);
+    let mut cut_edges: ahash::HashMap<(u32, u32), u32> = Default::default();
+
+    for triangle in triangles.chunks_exact(3) {
+        let sides = [0, 1, 2].map(|i| side(mesh.vertices[triangle[i] as usize].pos));
+        if sides.iter().all(|&s| 0.0 <= s) || sides.iter().all(|&s| s <= 0.0) {
+            mesh.indices.extend_from_slice(triangle);
+            continue;
+        }
+
+        // Split into the convex polygons on either side, keeping the winding order:
+        let mut above = Vec::with_capacity(4);
+        let mut below = Vec::with_capacity(4);
+        for i in 0..3 {
+            let j = (i + 1) % 3;
+            let (a, b) = (triangle[i], triangle[j]);
+            if 0.0 <= sides[i] {
+                above.push(a);
+            }
+            if sides[i] <= 0.0 {
+                below.push(a);
+            }
+            if (sides[i] < 0.0 && 0.0 < sides[j]) || (0.0 < sides[i] && sides[j] < 0.0) {
+                // Always cut from the lower index, so shared edges get the same vertex:
+                let (lo, hi, side_lo, side_hi) = if a < b {
+                    (a, b, sides[i], sides[j])
+                } else {
+                    (b, a, sides[j], sides[i])
+                };
+                let cut = *cut_edges.entry((lo, hi)).or_insert_with(|| {
+                    let t = side_lo / (side_lo - side_hi);
+                    let vertex =
+                        lerp_vertex(mesh.vertices[lo as usize], mesh.vertices[hi as usize], t);
+                    mesh.vertices.push(vertex);
+                    mesh.vertices.len() as u32 - 1
+                });
+                above.push(cut);
+                below.push(cut);
+            }
+        }
+
+        for polygon in [above, below] {
+            for k in 2..polygon.len() {
+                mesh.add_triangle(polygon[0], polygon[k - 1], polygon[k]);
+            }
+        }
+    }
+}
+
+fn lerp_vertex(a: Vertex, b: Vertex, t: f32) -> Vertex {
+    Vertex {
+        pos: a.pos.lerp(b.pos, t),
+        uv: a.uv.lerp(b.uv, t),
+        color: lerp_color(a.color, b.color, t),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_at() {
+        let gradient = Gradient::linear(
+            pos2(0.0, 0.0),
+            pos2(1.0, 0.0),
+            [
+                (1.0, Color32::BLUE),
+                (0.0, Color32::RED),
+                (0.5, Color32::GREEN),
+            ],
+        );
+        assert_eq!(gradient.color_at(pos2(-1.0, 0.0)), Color32::RED);
+        assert_eq!(gradient.color_at(pos2(0.5, 0.7)), Color32::GREEN);
+        assert_eq!(gradient.color_at(pos2(2.0, 0.0)), Color32::BLUE);
+        assert_eq!(
+            gradient.color_at(pos2(0.25, 0.0)),
+            Color32::from_rgb(128, 128, 0)
+        );
+
+        let gradient = Gradient::radial(
+            pos2(0.5, 0.5),
+            0.5,
+            [(0.0, Color32::WHITE), (1.0, Color32::BLACK)],
+        );
+        assert_eq!(gradient.color_at(pos2(0.5, 0.5)), Color32::WHITE);
+        assert_eq!(gradient.color_at(pos2(0.0, 0.5)), Color32::BLACK);
+        assert_eq!(gradient.color_at(pos2(1.0, 1.0)), Color32::BLACK);
+    }
+
+    #[test]
+    fn test_cut_at_stops() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 10.0));
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(rect, Color32::WHITE);
+
+        let gradient = Gradient::linear(
+            pos2(0.0, 0.5),
+            pos2(1.0, 0.5),
+            [
+                (0.0, Color32::RED),
+                (0.3, Color32::GREEN),
+                (1.0, Color32::BLUE),
+            ],
+        );
+        fill_with_gradient(&mut mesh, 0, 0, rect, &gradient);
+        assert!(mesh.is_valid());
+
+        // The top and bottom edges and the diagonal are cut at the middle stop,
+        // and the new vertices get its exact color:
+        let middle: Vec<_> = mesh
+            .vertices
+            .iter()
+            .filter(|v| (v.pos.x - 30.0).abs() < 1e-3)
+            .collect();
+        assert_eq!(middle.len(), 3);
+        assert!(middle.iter().all(|v| v.color == Color32::GREEN));
+
+        // Each triangle lies between two stops:
+        for triangle in mesh.indices.chunks_exact(3) {
+            let xs = triangle.iter().map(|&i| mesh.vertices[i as usize].pos.x);
+            let min_x = xs.clone().fold(f32::INFINITY, f32::min);
+            let max_x = xs.fold(f32::NEG_INFINITY, f32::max);
+            assert!(max_x <= 30.0 + 1e-3 || 30.0 - 1e-3 <= min_x);
+        }
+    }
+
+    #[test]
+    fn test_gradient_fill_keeps_stroke() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 10.0));
+        let stroke = Stroke::new(1.0, Color32::BLACK);
+        let shape = Shape::gradient_fill(
+            RectShape::new(rect, 2.0, Color32::WHITE, stroke),
+            Gradient::horizontal(Color32::RED, Color32::BLUE),
+        );
+        assert_eq!(shape.visual_bounding_rect(), rect.expand(0.5));
+
+        let mut tessellator =
+            crate::Tessellator::new(1.0, Default::default(), [1024, 1024], vec![]);
+        let mut mesh = Mesh::default();
+        tessellator.tessellate_shape(shape, &mut mesh);
+        assert!(mesh.is_valid());
+
+        // The fill color is replaced by the gradient, but the stroke is left alone:
+        assert!(mesh.vertices.iter().all(|v| v.color != Color32::WHITE));
+        assert!(mesh.vertices.iter().any(|v| v.color == Color32::BLACK));
+        let opaque_fill = |v: &&Vertex| v.color.a() == 255 && v.color != Color32::BLACK;
+        let left = mesh
+            .vertices
+            .iter()
+            .filter(opaque_fill)
+            .find(|v| v.pos.x < 5.0);
+        let right = mesh
+            .vertices
+            .iter()
+            .filter(opaque_fill)
+            .find(|v| v.pos.x > 95.0);
+        assert!(left.unwrap().color.r() > 200);
+        assert!(right.unwrap().color.b() > 200);
+    }
+}
@@ -264,6 +264,10 @@ impl std::fmt::Debug for ColorImage {
 /// Each value represents "coverage", i.e. how much a texel is covered by a character.
 ///
 /// This is roughly interpreted as the opacity of a white image.
+///
+/// Full-color glyphs (e.g. color emojis) are stored in [`Self::colors`] instead.
+///
+/// If [`Self::sdf`] is set, each value is instead a signed distance field, see [`crate::sdf`].
 #[derive(Clone, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FontImage {
@@ -274,6 +278,20 @@ pub struct FontImage {
     ///
     /// Often you want to use [`Self::srgba_pixels`] instead.
     pub pixels: Vec<f32>,
+
+    /// Premultiplied colors of full-color glyphs, e.g. color emojis.
+    ///
+    /// Empty until the first color is set, and then one color per pixel.
+    /// Where a color is not [`Color32::TRANSPARENT`] it is used instead of the coverage.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub colors: Vec<Color32>,
+
+    /// The values are a signed distance field rather than coverage.
+    ///
+    /// A backend painting such a texture needs to turn the distance into coverage in its fragment shader,
+    /// see [`crate::sdf::coverage`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sdf: bool,
 }
 
 impl FontImage {
@@ -281,6 +299,8 @@ impl FontImage {
         Self {
             size,
             pixels: vec![0.0; size[0] * size[1]],
+            colors: vec![],
+            sdf: false,
         }
     }
 
@@ -299,31 +319,62 @@ impl FontImage {
     /// `gamma` should normally be set to `None`.
     ///
     /// If you are having problems with text looking skinny and pixelated, try using a low gamma, e.g. `0.4`.
+    ///
+    /// For a signed distance field ([`Self::sdf`]) the gamma is ignored,
+    /// and the distances are returned as they are.
     #[inline]
     pub fn srgba_pixels(&self, gamma: Option<f32>) -> impl ExactSizeIterator<Item = Color32> + '_ {
         let gamma = gamma.unwrap_or(0.55); // TODO(emilk): this default coverage gamma is a magic constant, chosen by eye. I don't even know why we need it.
-        self.pixels.iter().map(move |coverage| {
-            let alpha = coverage.powf(gamma);
+        self.pixels.iter().enumerate().map(move |(i, &coverage)| {
+            if let Some(&color) = self.colors.get(i) {
+                if color != Color32::TRANSPARENT {
+                    return color;
+                }
+            }
+            let alpha = if self.sdf {
+                coverage
+            } else {
+                coverage.powf(gamma)
+            };
             // We want to multiply with `vec4(alpha)` in the fragment shader:
             let a = fast_round(alpha * 255.0);
             Color32::from_rgba_premultiplied(a, a, a, a)
         })
     }
 
+    /// Set the premultiplied color of a pixel of a full-color glyph.
+    pub fn set_color(&mut self, (x, y): (usize, usize), color: Color32) {
+        let [w, h] = self.size;
+        assert!(x < w && y < h);
+        if self.colors.is_empty() {
+            if color == Color32::TRANSPARENT {
+                return;
+            }
+            self.colors = vec![Color32::TRANSPARENT; self.pixels.len()];
+        }
+        self.colors[y * w + x] = color;
+    }
+
     /// Clone a sub-region as a new image.
     pub fn region(&self, [x, y]: [usize; 2], [w, h]: [usize; 2]) -> Self {
         assert!(x + w <= self.width());
         assert!(y + h <= self.height());
 
         let mut pixels = Vec::with_capacity(w * h);
+        let mut colors = Vec::with_capacity(if self.colors.is_empty() { 0 } else { w * h });
         for y in y..y + h {
             let offset = y * self.width() + x;
             pixels.extend(&self.pixels[offset..(offset + w)]);
+            if !self.colors.is_empty() {
+                colors.extend(&self.colors[offset..(offset + w)]);
+            }
         }
         assert_eq!(pixels.len(), w * h);
         Self {
             size: [w, h],
             pixels,
+            colors,
+            sdf: self.sdf,
         }
     }
 }
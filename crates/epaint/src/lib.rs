@@ -26,16 +26,22 @@
 #![cfg_attr(not(feature = "puffin"), forbid(unsafe_code))]
 
 mod bezier;
+mod blur;
 pub mod color;
+mod damage;
+mod gradient;
 pub mod image;
 mod margin;
 mod mesh;
 pub mod mutex;
+pub mod sdf;
 mod shadow;
 mod shape;
+mod shape_hash;
 pub mod shape_transform;
 pub mod stats;
 mod stroke;
+mod tessellation_cache;
 pub mod tessellator;
 pub mod text;
 mod texture_atlas;
@@ -45,7 +51,10 @@ pub mod util;
 
 pub use self::{
     bezier::{CubicBezierShape, QuadraticBezierShape},
+    blur::{BlurPrimitive, BlurShape, BlurSource},
     color::ColorMode,
+    damage::DamageTracker,
+    gradient::{Gradient, GradientFillShape, GradientKind},
     image::{ColorImage, FontImage, ImageData, ImageDelta},
     margin::Margin,
     mesh::{Mesh, Mesh16, Vertex},
@@ -56,6 +65,7 @@ pub use self::{
     },
     stats::PaintStats,
     stroke::{PathStroke, Stroke},
+    tessellation_cache::TessellationCache,
     tessellator::{TessellationOptions, Tessellator},
     text::{FontFamily, FontId, Fonts, Galley},
     texture_atlas::TextureAtlas,
@@ -118,7 +128,7 @@ pub struct ClippedShape {
     pub shape: Shape,
 }
 
-/// A [`Mesh`] or [`PaintCallback`] within a clip rectangle.
+/// A [`Mesh`], [`PaintCallback`] or [`BlurPrimitive`] within a clip rectangle.
 ///
 /// Everything is using logical points.
 #[derive(Clone, Debug)]
@@ -127,15 +137,16 @@ pub struct ClippedPrimitive {
     /// Only show the part of the [`Mesh`] that falls within this.
     pub clip_rect: emath::Rect,
 
-    /// What to paint - either a [`Mesh`] or a [`PaintCallback`].
+    /// What to paint - a [`Mesh`], a [`PaintCallback`] or a [`BlurPrimitive`].
     pub primitive: Primitive,
 }
 
-/// A rendering primitive - either a [`Mesh`] or a [`PaintCallback`].
+/// A rendering primitive - a [`Mesh`], a [`PaintCallback`] or a [`BlurPrimitive`].
 #[derive(Clone, Debug)]
 pub enum Primitive {
     Mesh(Mesh),
     Callback(PaintCallback),
+    Blur(BlurPrimitive),
 }
 
 // ----------------------------------------------------------------------------
@@ -0,0 +1,202 @@
+//! Signed distance fields, used for text that stays crisp when scaled up.
+//!
+//! Instead of how much of a texel is covered by a glyph,
+//! each texel of a signed distance field stores how far its center is from the outline of the glyph.
+//! The value is `0.5` on the outline, larger on the inside and smaller on the outside.
+//!
+//! When sampled with linear filtering this interpolates to a sharp outline at any scale,
+//! which the fragment shader turns into an anti-aliased edge with [`coverage`].
+//!
+//! See [`crate::text::GlyphRasterization::Sdf`].
+
+/// How far from the outline the distance field reaches, in texels.
+///
+/// Texels further away than this are clamped to `0.0` (outside) or `1.0` (inside).
+pub const SPREAD: f32 = 4.0;
+
+/// The coverage of a pixel, given a sampled value of a signed distance field
+/// and how many texels of the distance field one pixel spans.
+///
+/// This is what the fragment shaders of the egui backends do, but they use
+/// the screen-space derivatives of the sampled value instead of `texels_per_pixel`.
+///
+/// Like [`crate::FontImage::srgba_pixels`], this applies a gamma of `0.55` to the coverage,
+/// so that text has the same weight as with [`crate::text::GlyphRasterization::Coverage`].
+pub fn coverage(value: f32, texels_per_pixel: f32) -> f32 {
+    let half_width = (0.5 * texels_per_pixel / (2.0 * SPREAD)).max(1e-4);
+    smoothstep(0.5 - half_width, 0.5 + half_width, value).powf(0.55)
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Encode the signed distance to an outline (positive outside, in texels) as a texel value.
+pub(crate) fn encode(distance: f32) -> f32 {
+    (0.5 - distance / (2.0 * SPREAD)).clamp(0.0, 1.0)
+}
+
+/// Convert a coverage image (e.g. a rasterized glyph) into a signed distance field.
+///
+/// Add at least [`SPREAD`] texels of empty padding around the glyph,
+/// or the distance field will be cut off at the edges.
+///
+/// Partially covered texels are used to place the outline with sub-texel precision.
+pub(crate) fn from_coverage(coverage: &[f32], width: usize, height: usize) -> Vec<f32> {
+    crate::profile_function!();
+    assert_eq!(coverage.len(), width * height);
+
+    // Squared distances to the nearest texel outside and inside the outline:
+    let mut outer = vec![0.0; coverage.len()];
+    let mut inner = vec![0.0; coverage.len()];
+    for (i, &a) in coverage.iter().enumerate() {
+        // Rasterizers leave tiny rounding errors in texels that are fully inside or outside,
+        // which would otherwise be taken for texels right on the outline.
+        if 1.0 - EPSILON <= a {
+            inner[i] = INF;
+        } else if a <= EPSILON {
+            outer[i] = INF;
+        } else {
+            // Approximate how far the texel center is from the outline:
+            let d = 0.5 - a;
+            if 0.0 < d {
+                outer[i] = d * d;
+            } else {
+                inner[i] = d * d;
+            }
+        }
+    }
+
+    distance_transform(&mut outer, width, height);
+    distance_transform(&mut inner, width, height);
+
+    outer
+        .iter()
+        .zip(&inner)
+        .map(|(outer, inner)| encode(outer.sqrt() - inner.sqrt()))
+        .collect()
+}
+
+const INF: f32 = 1e20;
+
+/// Coverage this close to `0.0` or `1.0` is treated as fully outside or inside.
+const EPSILON: f32 = 1.0 / 256.0;
+
+/// Replace each value with the smallest squared distance to a texel plus its value,
+/// using the algorithm of Felzenszwalb and Huttenlocher,
+/// "Distance Transforms of Sampled Functions" (2012).
+fn distance_transform(grid: &mut [f32], width: usize, height: usize) {
+    let n = width.max(height);
+    let mut scratch = Scratch {
+        f: vec![0.0; n],
+        v: vec![0; n],
+        z: vec![0.0; n + 1],
+    };
+    for x in 0..width {
+        distance_transform_1d(grid, x, width, height, &mut scratch);
+    }
+    for y in 0..height {
+        distance_transform_1d(grid, y * width, 1, width, &mut scratch);
+    }
+}
+
+struct Scratch {
+    /// The input values.
+    f: Vec<f32>,
+
+    /// Locations of the parabolas in the lower envelope.
+    v: Vec<usize>,
+
+    /// Boundaries between the parabolas.
+    z: Vec<f32>,
+}
+
+fn distance_transform_1d(
+    grid: &mut [f32],
+    offset: usize,
+    stride: usize,
+    length: usize,
+    scratch: &mut Scratch,
+) {
+    let Scratch { f, v, z } = scratch;
+
+    v[0] = 0;
+    z[0] = -INF;
+    z[1] = INF;
+    f[0] = grid[offset];
+
+    let mut k = 0;
+    for q in 1..length {
+        f[q] = grid[offset + q * stride];
+        let q2 = (q * q) as f32;
+        let mut s;
+        loop {
+            let r = v[k];
+            s = (f[q] - f[r] + q2 - (r * r) as f32) / (q - r) as f32 / 2.0;
+            // `z[0]` is minus infinity, so this stops at the first parabola at the latest:
+            if s <= z[k] && 0 < k {
+                k -= 1;
+            } else {
+                break;
+            }
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = INF;
+    }
+
+    let mut k = 0;
+    for q in 0..length {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let r = v[k];
+        let qr = q as f32 - r as f32;
+        grid[offset + q * stride] = f[r] + qr * qr;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_field_of_disc() {
+        let (size, center, radius) = (32, 16.0, 8.0);
+        let distance_to_outline = |x: usize, y: usize| {
+            let (dx, dy) = (x as f32 + 0.5 - center, y as f32 + 0.5 - center);
+            dx.hypot(dy) - radius
+        };
+
+        let mut coverage = vec![0.0; size * size];
+        for y in 0..size {
+            for x in 0..size {
+                coverage[y * size + x] = (0.5 - distance_to_outline(x, y)).clamp(0.0, 1.0);
+            }
+        }
+
+        let sdf = from_coverage(&coverage, size, size);
+        for y in 0..size {
+            for x in 0..size {
+                let expected = encode(distance_to_outline(x, y));
+                let actual = sdf[y * size + x];
+                assert!(
+                    (actual - expected).abs() < 0.5 / (2.0 * SPREAD),
+                    "at {x},{y}: expected {expected}, got {actual}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_coverage() {
+        assert_eq!(coverage(encode(-1.0), 1.0), 1.0);
+        assert_eq!(coverage(encode(1.0), 1.0), 0.0);
+        assert_eq!(coverage(encode(0.0), 1.0), 0.5_f32.powf(0.55));
+
+        // Zoomed in, the edge gets sharper:
+        assert_eq!(coverage(encode(-0.2), 0.1), 1.0);
+    }
+}
@@ -5,7 +5,7 @@ use std::{any::Any, sync::Arc};
 use crate::{
     stroke::PathStroke,
     text::{FontId, Fonts, Galley},
-    Color32, Mesh, Stroke, TextureId,
+    BlurShape, Color32, Gradient, GradientFillShape, Mesh, Shadow, Stroke, TextureId,
 };
 use emath::*;
 
@@ -63,6 +63,16 @@ pub enum Shape {
     /// A cubic [Bézier Curve](https://en.wikipedia.org/wiki/B%C3%A9zier_curve).
     CubicBezier(CubicBezierShape),
 
+    /// A blurred shadow of a shape, or a blur of what is painted behind a rectangle.
+    ///
+    /// See [`Self::shadow`] and [`Self::backdrop_blur`].
+    Blur(BlurShape),
+
+    /// A shape filled with a [`Gradient`].
+    ///
+    /// See [`Self::gradient_fill`].
+    GradientFill(GradientFillShape),
+
     /// Backend-specific painting.
     Callback(PaintCallback),
 }
@@ -317,6 +327,52 @@ impl Shape {
         Self::Mesh(mesh)
     }
 
+    /// The shadow or glow cast by a shape, blurred with a Gaussian.
+    ///
+    /// Only the shadow is painted, so add the shape itself after it.
+    ///
+    /// ```
+    /// # use epaint::{Color32, Shadow, Shape, pos2, vec2};
+    /// let circle = Shape::circle_filled(pos2(50.0, 50.0), 20.0, Color32::WHITE);
+    /// let shadow = Shadow {
+    ///     offset: vec2(2.0, 4.0),
+    ///     blur: 16.0,
+    ///     spread: 0.0,
+    ///     color: Color32::from_black_alpha(96),
+    /// };
+    /// let shapes = vec![Shape::shadow(circle.clone(), shadow), circle];
+    /// ```
+    #[inline]
+    pub fn shadow(shape: impl Into<Self>, shadow: Shadow) -> Self {
+        Self::Blur(BlurShape::Shadow {
+            shape: Box::new(shape.into()),
+            shadow,
+        })
+    }
+
+    /// Blur what has been painted so far inside a rounded rectangle.
+    ///
+    /// `blur` is the width of the blur, in the same sense as [`Shadow::blur`].
+    #[inline]
+    pub fn backdrop_blur(rect: Rect, rounding: impl Into<Rounding>, blur: f32) -> Self {
+        Self::Blur(BlurShape::Backdrop {
+            rect,
+            rounding: rounding.into(),
+            blur,
+        })
+    }
+
+    /// Fill a circle, ellipse, rectangle or closed path with a [`Gradient`] instead of its fill color.
+    ///
+    /// The stroke of the shape is painted on top of the gradient.
+    #[inline]
+    pub fn gradient_fill(shape: impl Into<Self>, gradient: Gradient) -> Self {
+        Self::GradientFill(GradientFillShape {
+            shape: Box::new(shape.into()),
+            gradient,
+        })
+    }
+
     /// An image at the given position.
     ///
     /// `uv` should normally be `Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0))`
@@ -355,6 +411,8 @@ impl Shape {
             Self::Mesh(mesh) => mesh.calc_bounds(),
             Self::QuadraticBezier(bezier) => bezier.visual_bounding_rect(),
             Self::CubicBezier(bezier) => bezier.visual_bounding_rect(),
+            Self::Blur(blur) => blur.visual_bounding_rect(),
+            Self::GradientFill(gradient_fill) => gradient_fill.visual_bounding_rect(),
             Self::Callback(custom) => custom.rect,
         }
     }
@@ -368,6 +426,8 @@ impl Shape {
             mesh.texture_id
         } else if let Self::Rect(rect_shape) = self {
             rect_shape.fill_texture_id
+        } else if let Self::GradientFill(gradient_fill) = self {
+            gradient_fill.shape.texture_id()
         } else {
             super::TextureId::default()
         }
@@ -458,6 +518,25 @@ impl Shape {
                 }
                 cubic_curve.stroke.width *= transform.scaling;
             }
+            Self::Blur(BlurShape::Shadow { shape, shadow }) => {
+                shape.transform(transform);
+                shadow.offset *= transform.scaling;
+                shadow.blur *= transform.scaling;
+                shadow.spread *= transform.scaling;
+            }
+            Self::Blur(BlurShape::Backdrop {
+                rect,
+                rounding,
+                blur,
+            }) => {
+                *rect = transform * *rect;
+                *rounding *= transform.scaling;
+                *blur *= transform.scaling;
+            }
+            Self::GradientFill(gradient_fill) => {
+                // The gradient is relative to the shape, so it follows along:
+                gradient_fill.shape.transform(transform);
+            }
             Self::Callback(shape) => {
                 shape.rect = transform * shape.rect;
             }
@@ -0,0 +1,249 @@
+//! Hashing shapes, to find out which shapes are the same as last frame.
+
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use emath::{OrderedFloat, Pos2, Rect, Vec2};
+
+use crate::{
+    BlurShape, CircleShape, ColorMode, CubicBezierShape, EllipseShape, Gradient, GradientFillShape,
+    GradientKind, Mesh, PathShape, PathStroke, QuadraticBezierShape, RectShape, Rounding, Shadow,
+    Shape, TextShape, Vertex,
+};
+
+#[inline]
+fn hash_f32(state: &mut impl Hasher, value: f32) {
+    OrderedFloat(value).hash(state);
+}
+
+#[inline]
+fn hash_pos2(state: &mut impl Hasher, pos: Pos2) {
+    hash_f32(state, pos.x);
+    hash_f32(state, pos.y);
+}
+
+#[inline]
+fn hash_vec2(state: &mut impl Hasher, vec: Vec2) {
+    hash_f32(state, vec.x);
+    hash_f32(state, vec.y);
+}
+
+#[inline]
+pub(crate) fn hash_rect(state: &mut impl Hasher, rect: Rect) {
+    hash_pos2(state, rect.min);
+    hash_pos2(state, rect.max);
+}
+
+fn hash_rounding(state: &mut impl Hasher, rounding: Rounding) {
+    let Rounding { nw, ne, sw, se } = rounding;
+    for corner in [nw, ne, sw, se] {
+        hash_f32(state, corner);
+    }
+}
+
+fn hash_gradient(state: &mut impl Hasher, gradient: &Gradient) {
+    let Gradient { kind, stops } = gradient;
+    match *kind {
+        GradientKind::Linear { from, to } => {
+            0_u8.hash(state);
+            hash_pos2(state, from);
+            hash_pos2(state, to);
+        }
+        GradientKind::Radial { center, radius } => {
+            1_u8.hash(state);
+            hash_pos2(state, center);
+            hash_f32(state, radius);
+        }
+    }
+    stops.len().hash(state);
+    for &(position, color) in stops {
+        hash_f32(state, position);
+        color.hash(state);
+    }
+}
+
+/// Returns `false` if the stroke is colored by a callback, which we can't hash.
+fn hash_path_stroke(state: &mut impl Hasher, stroke: &PathStroke) -> bool {
+    let PathStroke { width, color } = stroke;
+    hash_f32(state, *width);
+    match color {
+        ColorMode::Solid(color) => {
+            color.hash(state);
+            true
+        }
+        ColorMode::UV(_) => false,
+    }
+}
+
+/// Hash everything about the shape that affects how it looks.
+///
+/// Text shapes are hashed by the address of their galley,
+/// so keep the galleys alive for as long as you compare the hashes.
+///
+/// Returns `false` for shapes we can't hash: paint callbacks,
+/// and paths colored by a [`ColorMode::UV`] callback.
+pub(crate) fn hash_shape(shape: &Shape, state: &mut impl Hasher) -> bool {
+    std::mem::discriminant(shape).hash(state);
+
+    match shape {
+        Shape::Noop => {}
+        Shape::Vec(shapes) => {
+            shapes.len().hash(state);
+            for shape in shapes {
+                if !hash_shape(shape, state) {
+                    return false;
+                }
+            }
+        }
+        Shape::Circle(CircleShape {
+            center,
+            radius,
+            fill,
+            stroke,
+        }) => {
+            hash_pos2(state, *center);
+            hash_f32(state, *radius);
+            fill.hash(state);
+            stroke.hash(state);
+        }
+        Shape::Ellipse(EllipseShape {
+            center,
+            radius,
+            fill,
+            stroke,
+        }) => {
+            hash_pos2(state, *center);
+            hash_vec2(state, *radius);
+            fill.hash(state);
+            stroke.hash(state);
+        }
+        Shape::LineSegment { points, stroke } => {
+            for point in points {
+                hash_pos2(state, *point);
+            }
+            return hash_path_stroke(state, stroke);
+        }
+        Shape::Path(PathShape {
+            points,
+            closed,
+            fill,
+            stroke,
+        }) => {
+            points.len().hash(state);
+            for point in points {
+                hash_pos2(state, *point);
+            }
+            closed.hash(state);
+            fill.hash(state);
+            return hash_path_stroke(state, stroke);
+        }
+        Shape::Rect(RectShape {
+            rect,
+            rounding,
+            fill,
+            stroke,
+            blur_width,
+            fill_texture_id,
+            uv,
+        }) => {
+            hash_rect(state, *rect);
+            hash_rounding(state, *rounding);
+            fill.hash(state);
+            stroke.hash(state);
+            hash_f32(state, *blur_width);
+            fill_texture_id.hash(state);
+            hash_rect(state, *uv);
+        }
+        Shape::Text(TextShape {
+            pos,
+            galley,
+            underline,
+            fallback_color,
+            override_text_color,
+            opacity_factor,
+            angle,
+        }) => {
+            hash_pos2(state, *pos);
+            // Galleys are immutable, so the address is enough as long as the galley is alive:
+            Arc::as_ptr(galley).hash(state);
+            underline.hash(state);
+            fallback_color.hash(state);
+            override_text_color.hash(state);
+            hash_f32(state, *opacity_factor);
+            hash_f32(state, *angle);
+        }
+        Shape::QuadraticBezier(QuadraticBezierShape {
+            points,
+            closed,
+            fill,
+            stroke,
+        }) => {
+            for point in points {
+                hash_pos2(state, *point);
+            }
+            closed.hash(state);
+            fill.hash(state);
+            return hash_path_stroke(state, stroke);
+        }
+        Shape::CubicBezier(CubicBezierShape {
+            points,
+            closed,
+            fill,
+            stroke,
+        }) => {
+            for point in points {
+                hash_pos2(state, *point);
+            }
+            closed.hash(state);
+            fill.hash(state);
+            return hash_path_stroke(state, stroke);
+        }
+        Shape::Mesh(Mesh {
+            indices,
+            vertices,
+            texture_id,
+        }) => {
+            indices.hash(state);
+            vertices.len().hash(state);
+            for &Vertex { pos, uv, color } in vertices {
+                hash_pos2(state, pos);
+                hash_pos2(state, uv);
+                color.hash(state);
+            }
+            texture_id.hash(state);
+        }
+        Shape::Blur(BlurShape::Shadow { shape, shadow }) => {
+            let Shadow {
+                offset,
+                blur,
+                spread,
+                color,
+            } = *shadow;
+            hash_vec2(state, offset);
+            hash_f32(state, blur);
+            hash_f32(state, spread);
+            color.hash(state);
+            return hash_shape(shape, state);
+        }
+        Shape::Blur(BlurShape::Backdrop {
+            rect,
+            rounding,
+            blur,
+        }) => {
+            hash_rect(state, *rect);
+            hash_rounding(state, *rounding);
+            hash_f32(state, *blur);
+        }
+        Shape::GradientFill(GradientFillShape { shape, gradient }) => {
+            hash_gradient(state, gradient);
+            return hash_shape(shape, state);
+        }
+        Shape::Callback(_) => {
+            return false;
+        }
+    }
+
+    true
+}
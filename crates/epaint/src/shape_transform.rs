@@ -119,6 +119,20 @@ pub fn adjust_colors(
             }
         }
 
+        Shape::Blur(BlurShape::Shadow { shape: _, shadow }) => {
+            // The shape only provides the silhouette
+            adjust_color(&mut shadow.color);
+        }
+
+        Shape::Blur(BlurShape::Backdrop { .. }) => {}
+
+        Shape::GradientFill(GradientFillShape { shape, gradient }) => {
+            adjust_colors(shape, adjust_color);
+            for (_, color) in &mut gradient.stops {
+                adjust_color(color);
+            }
+        }
+
         Shape::Callback(_) => {
             // Can't tint user callback code
         }
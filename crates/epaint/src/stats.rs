@@ -174,6 +174,12 @@ pub struct PaintStats {
     pub clipped_primitives: AllocInfo,
     pub vertices: AllocInfo,
     pub indices: AllocInfo,
+
+    /// Number of shapes whose mesh was reused from last frame, see [`crate::TessellationCache`].
+    pub tessellation_cache_hits: usize,
+
+    /// Number of shapes that weren't in the [`crate::TessellationCache`].
+    pub tessellation_cache_misses: usize,
 }
 
 impl PaintStats {
@@ -205,7 +211,8 @@ impl PaintStats {
             | Shape::LineSegment { .. }
             | Shape::Rect { .. }
             | Shape::CubicBezier(_)
-            | Shape::QuadraticBezier(_) => {}
+            | Shape::QuadraticBezier(_)
+            | Shape::Blur(BlurShape::Backdrop { .. }) => {}
             Shape::Path(path_shape) => {
                 self.shape_path += AllocInfo::from_slice(&path_shape.points);
             }
@@ -220,6 +227,10 @@ impl PaintStats {
             Shape::Mesh(mesh) => {
                 self.shape_mesh += AllocInfo::from_mesh(mesh);
             }
+            Shape::Blur(BlurShape::Shadow { shape, .. })
+            | Shape::GradientFill(GradientFillShape { shape, .. }) => {
+                self.add(shape);
+            }
             Shape::Callback(_) => {
                 self.num_callbacks += 1;
             }
@@ -232,13 +243,32 @@ impl PaintStats {
     ) -> Self {
         self.clipped_primitives += AllocInfo::from_slice(clipped_primitives);
         for clipped_primitive in clipped_primitives {
-            if let Primitive::Mesh(mesh) = &clipped_primitive.primitive {
-                self.vertices += AllocInfo::from_slice(&mesh.vertices);
-                self.indices += AllocInfo::from_slice(&mesh.indices);
+            match &clipped_primitive.primitive {
+                Primitive::Mesh(mesh) => {
+                    self.vertices += AllocInfo::from_slice(&mesh.vertices);
+                    self.indices += AllocInfo::from_slice(&mesh.indices);
+                }
+                Primitive::Blur(blur) => {
+                    let source = match &blur.source {
+                        BlurSource::Meshes(meshes) => meshes.as_slice(),
+                        BlurSource::Backdrop => &[],
+                    };
+                    for mesh in source.iter().chain([&blur.mesh]) {
+                        self.vertices += AllocInfo::from_slice(&mesh.vertices);
+                        self.indices += AllocInfo::from_slice(&mesh.indices);
+                    }
+                }
+                Primitive::Callback(_) => {}
             }
         }
         self
     }
+
+    pub fn with_tessellation_cache(mut self, cache: &crate::TessellationCache) -> Self {
+        self.tessellation_cache_hits = cache.hits();
+        self.tessellation_cache_misses = cache.misses();
+        self
+    }
 }
 
 fn megabytes(size: usize) -> String {
@@ -0,0 +1,230 @@
+//! Reusing the tessellation of shapes that didn't change since last frame.
+
+use std::{
+    hash::{BuildHasher as _, Hasher as _},
+    sync::Arc,
+};
+
+use emath::Rect;
+
+use crate::{
+    shape_hash::{hash_rect, hash_shape},
+    Galley, Mesh, Shape, Tessellator,
+};
+
+/// The tessellated mesh of a single shape.
+struct CachedMesh {
+    mesh: Mesh,
+
+    /// Value of [`TessellationCache::generation`] when this was last used.
+    last_used: u64,
+
+    /// Text shapes are hashed by the address of their galley,
+    /// so we keep the galley alive to make sure no other galley can get the same address.
+    _galley: Option<Arc<Galley>>,
+}
+
+/// Remembers the tessellation of shapes from one frame to the next.
+///
+/// Most shapes are the same from one frame to the next,
+/// so instead of tessellating them again, we can reuse their meshes from last frame.
+/// Each [`crate::ClippedShape`] is hashed, and if we tessellated the same shape with the same clip rectangle
+/// last frame, we copy that mesh instead of tessellating the shape again.
+///
+/// Use it with [`Tessellator::tessellate_shapes_cached`], and keep the same cache from frame to frame.
+///
+/// Hashing a shape is cheaper than tessellating it, but not free,
+/// so this is only worth it if most of your shapes stay the same.
+/// Shapes that weren't used last frame are forgotten,
+/// as is everything when the settings of the [`Tessellator`] change.
+///
+/// Shapes that are not cached: [`Shape::Mesh`] (it would only be copied anyway),
+/// [`Shape::Blur`], [`Shape::Callback`], and paths colored by a [`ColorMode::UV`] callback.
+#[derive(Default)]
+pub struct TessellationCache {
+    /// Hash of the settings of the [`Tessellator`] that produced the cached meshes.
+    settings: u64,
+
+    /// Increased by one each frame.
+    generation: u64,
+
+    meshes: ahash::HashMap<u64, CachedMesh>,
+
+    hits: usize,
+    misses: usize,
+}
+
+impl TessellationCache {
+    /// Number of shapes whose mesh was reused during the last frame.
+    #[inline]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of shapes that had to be tessellated during the last frame.
+    ///
+    /// Shapes that are never cached are not counted.
+    #[inline]
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Fraction of the cacheable shapes whose mesh was reused during the last frame, in `0..=1`.
+    pub fn hit_rate(&self) -> f32 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f32 / total as f32
+        }
+    }
+
+    /// Number of cached meshes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.meshes.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty()
+    }
+
+    /// Forget all cached meshes.
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Called at the start of each frame with a hash of the settings of the tessellator.
+    pub(crate) fn begin_frame(&mut self, settings: u64) {
+        if self.settings != settings {
+            self.meshes.clear();
+            self.settings = settings;
+        }
+        self.generation += 1;
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Called at the end of each frame to forget the shapes that weren't painted.
+    pub(crate) fn end_frame(&mut self) {
+        let generation = self.generation;
+        self.meshes
+            .retain(|_, cached| cached.last_used == generation);
+    }
+
+    /// Append the mesh of `shape` to `out`, reusing last frame's mesh if possible.
+    ///
+    /// The clip rectangle of the tessellator must already be set to `clip_rect`.
+    pub(crate) fn tessellate_shape(
+        &mut self,
+        tessellator: &mut Tessellator,
+        clip_rect: Rect,
+        shape: Shape,
+        out: &mut Mesh,
+    ) {
+        let cacheable = match &shape {
+            Shape::Circle(_)
+            | Shape::Ellipse(_)
+            | Shape::LineSegment { .. }
+            | Shape::Path(_)
+            | Shape::Rect(_)
+            | Shape::Text(_)
+            | Shape::QuadraticBezier(_)
+            | Shape::CubicBezier(_)
+            | Shape::GradientFill(_) => true,
+            Shape::Noop | Shape::Vec(_) | Shape::Mesh(_) | Shape::Blur(_) | Shape::Callback(_) => {
+                false
+            }
+        };
+
+        let mut hasher = ahash::RandomState::with_seeds(1, 2, 3, 4).build_hasher();
+        hash_rect(&mut hasher, clip_rect);
+        if !cacheable || !hash_shape(&shape, &mut hasher) {
+            tessellator.tessellate_shape(shape, out);
+            return;
+        }
+        let key = hasher.finish();
+
+        if let Some(cached) = self.meshes.get_mut(&key) {
+            cached.last_used = self.generation;
+            out.append_ref(&cached.mesh);
+            self.hits += 1;
+            return;
+        }
+
+        self.misses += 1;
+        let galley = match &shape {
+            Shape::Text(text_shape) => Some(text_shape.galley.clone()),
+            _ => None,
+        };
+        let mut mesh = Mesh::with_texture(shape.texture_id());
+        tessellator.tessellate_shape(shape, &mut mesh);
+        out.append_ref(&mesh);
+        self.meshes.insert(
+            key,
+            CachedMesh {
+                mesh,
+                last_used: self.generation,
+                _galley: galley,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    use super::TessellationCache;
+
+    #[test]
+    fn reuses_unchanged_shapes() {
+        let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 100.0));
+        let shapes = |x: f32| {
+            vec![
+                ClippedShape {
+                    clip_rect,
+                    shape: Shape::circle_filled(pos2(50.0, 50.0), 10.0, Color32::RED),
+                },
+                ClippedShape {
+                    clip_rect,
+                    shape: Shape::Vec(vec![
+                        Shape::rect_filled(
+                            Rect::from_min_max(pos2(10.0, 10.0), pos2(20.0, 20.0)),
+                            2.0,
+                            Color32::BLUE,
+                        ),
+                        Shape::line_segment([pos2(x, 0.0), pos2(x, 100.0)], (1.0, Color32::WHITE)),
+                    ]),
+                },
+            ]
+        };
+        let tessellator = Tessellator::new(1.0, Default::default(), [1024, 1024], vec![]);
+
+        let mut cache = TessellationCache::default();
+        let frame = |x: f32, cache: &mut TessellationCache| {
+            let cached = tessellator
+                .clone()
+                .tessellate_shapes_cached(shapes(x), cache);
+            let uncached = tessellator.clone().tessellate_shapes(shapes(x));
+            assert_eq!(cached.len(), uncached.len());
+            for (cached, uncached) in cached.iter().zip(&uncached) {
+                match (&cached.primitive, &uncached.primitive) {
+                    (Primitive::Mesh(cached), Primitive::Mesh(uncached)) => {
+                        assert_eq!(cached, uncached);
+                    }
+                    _ => panic!("Expected meshes"),
+                }
+            }
+            (cache.hits(), cache.misses())
+        };
+
+        assert_eq!(frame(30.0, &mut cache), (0, 3));
+        assert_eq!(frame(30.0, &mut cache), (3, 0));
+        assert_eq!(frame(40.0, &mut cache), (2, 1));
+        assert_eq!(cache.len(), 3, "The old line should have been forgotten");
+    }
+}
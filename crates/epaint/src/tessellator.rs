@@ -5,6 +5,8 @@
 
 #![allow(clippy::identity_op)]
 
+use crate::gradient::fill_with_gradient;
+use crate::tessellation_cache::TessellationCache;
 use crate::texture_atlas::PreparedDisc;
 use crate::*;
 use emath::*;
@@ -1158,6 +1160,17 @@ fn mul_color(color: Color32, factor: f32) -> Color32 {
     color.gamma_multiply(factor)
 }
 
+/// The non-empty meshes among some primitives.
+fn only_meshes(clipped_primitives: Vec<ClippedPrimitive>) -> Vec<Mesh> {
+    clipped_primitives
+        .into_iter()
+        .filter_map(|clipped_primitive| match clipped_primitive.primitive {
+            Primitive::Mesh(mesh) if !mesh.is_empty() => Some(mesh),
+            _ => None,
+        })
+        .collect()
+}
+
 // ----------------------------------------------------------------------------
 
 /// Converts [`Shape`]s into triangles ([`Mesh`]).
@@ -1216,6 +1229,58 @@ impl Tessellator {
         }
     }
 
+    /// Hash of everything besides the shapes themselves that affects the tessellation.
+    ///
+    /// Used to know when a [`TessellationCache`] is stale.
+    fn settings_hash(&self) -> u64 {
+        use std::hash::{BuildHasher as _, Hash as _, Hasher as _};
+
+        let Self {
+            pixels_per_point,
+            options,
+            font_tex_size,
+            prepared_discs,
+            feathering,
+            clip_rect: _,
+            scratchpad_points: _,
+            scratchpad_path: _,
+        } = self;
+        let TessellationOptions {
+            feathering: _, // included in `self.feathering`
+            feathering_size_in_pixels: _,
+            coarse_tessellation_culling,
+            prerasterized_discs,
+            round_text_to_pixels,
+            debug_paint_clip_rects: _, // not part of the mesh of any shape
+            debug_paint_text_rects,
+            debug_ignore_clip_rects: _,
+            bezier_tolerance,
+            epsilon,
+            parallel_tessellation: _,
+            validate_meshes,
+        } = options;
+
+        let mut hasher = ahash::RandomState::with_seeds(1, 2, 3, 4).build_hasher();
+        for value in [*pixels_per_point, *feathering, *bezier_tolerance, *epsilon] {
+            OrderedFloat(value).hash(&mut hasher);
+        }
+        (
+            coarse_tessellation_culling,
+            prerasterized_discs,
+            round_text_to_pixels,
+            debug_paint_text_rects,
+            validate_meshes,
+            font_tex_size,
+        )
+            .hash(&mut hasher);
+        for PreparedDisc { r, w, uv } in prepared_discs {
+            for value in [*r, *w, uv.min.x, uv.min.y, uv.max.x, uv.max.y] {
+                OrderedFloat(value).hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
     /// Set the [`Rect`] to use for culling.
     pub fn set_clip_rect(&mut self, clip_rect: Rect) {
         self.clip_rect = clip_rect;
@@ -1235,6 +1300,15 @@ impl Tessellator {
         &mut self,
         clipped_shape: ClippedShape,
         out_primitives: &mut Vec<ClippedPrimitive>,
+    ) {
+        self.tessellate_clipped_shape_with_cache(clipped_shape, out_primitives, None);
+    }
+
+    fn tessellate_clipped_shape_with_cache(
+        &mut self,
+        clipped_shape: ClippedShape,
+        out_primitives: &mut Vec<ClippedPrimitive>,
+        mut cache: Option<&mut TessellationCache>,
     ) {
         let ClippedShape { clip_rect, shape } = clipped_shape;
 
@@ -1244,7 +1318,11 @@ impl Tessellator {
 
         if let Shape::Vec(shapes) = shape {
             for shape in shapes {
-                self.tessellate_clipped_shape(ClippedShape { clip_rect, shape }, out_primitives);
+                self.tessellate_clipped_shape_with_cache(
+                    ClippedShape { clip_rect, shape },
+                    out_primitives,
+                    cache.as_deref_mut(),
+                );
             }
             return;
         }
@@ -1257,6 +1335,17 @@ impl Tessellator {
             return;
         }
 
+        if let Shape::Blur(blur) = shape {
+            self.clip_rect = clip_rect;
+            if let Some(blur) = self.tessellate_blur(blur) {
+                out_primitives.push(ClippedPrimitive {
+                    clip_rect,
+                    primitive: Primitive::Blur(blur),
+                });
+            }
+            return;
+        }
+
         let start_new_mesh = match out_primitives.last() {
             None => true,
             Some(output_clipped_primitive) => {
@@ -1265,7 +1354,7 @@ impl Tessellator {
                         Primitive::Mesh(output_mesh) => {
                             output_mesh.texture_id != shape.texture_id()
                         }
-                        Primitive::Callback(_) => true,
+                        Primitive::Callback(_) | Primitive::Blur(_) => true,
                     }
             }
         };
@@ -1281,7 +1370,11 @@ impl Tessellator {
 
         if let Primitive::Mesh(out_mesh) = &mut out.primitive {
             self.clip_rect = clip_rect;
-            self.tessellate_shape(shape, out_mesh);
+            if let Some(cache) = cache {
+                cache.tessellate_shape(self, clip_rect, shape, out_mesh);
+            } else {
+                self.tessellate_shape(shape, out_mesh);
+            }
         } else {
             unreachable!();
         }
@@ -1345,12 +1438,206 @@ impl Tessellator {
                 self.tessellate_quadratic_bezier(&quadratic_shape, out);
             }
             Shape::CubicBezier(cubic_shape) => self.tessellate_cubic_bezier(&cubic_shape, out),
+            Shape::Blur(blur) => {
+                // We can't blur into a single mesh, so use the approximation:
+                if let Some(blur) = self.tessellate_blur(blur) {
+                    for mesh in blur.fallback {
+                        out.append(mesh);
+                    }
+                }
+            }
+            Shape::GradientFill(gradient_fill) => {
+                self.tessellate_gradient_fill(gradient_fill, out);
+            }
             Shape::Callback(_) => {
                 panic!("Shape::Callback passed to Tessellator");
             }
         }
     }
 
+    /// Tessellate a [`BlurShape`] into a [`BlurPrimitive`], for the renderer to blur.
+    ///
+    /// Returns `None` if there is nothing to paint.
+    pub fn tessellate_blur(&mut self, blur: BlurShape) -> Option<BlurPrimitive> {
+        crate::profile_function!();
+
+        let clip_rect = self.clip_rect;
+
+        match blur {
+            BlurShape::Shadow { shape, shadow } => {
+                let Shadow {
+                    offset,
+                    blur,
+                    spread,
+                    color,
+                } = shadow;
+                if color == Color32::TRANSPARENT {
+                    return None;
+                }
+
+                let sigma = crate::blur::sigma_from_blur(blur.at_least(0.0));
+                let margin = 3.0 * sigma;
+
+                let mut shape = *shape;
+                shape.translate(offset);
+                crate::blur::spread_shape(&mut shape, spread);
+
+                let mut source = vec![];
+                self.tessellate_clipped_shape(
+                    ClippedShape {
+                        clip_rect: clip_rect.expand(margin),
+                        shape: shape.clone(),
+                    },
+                    &mut source,
+                );
+                let mut source = only_meshes(source);
+                if source.is_empty() {
+                    self.clip_rect = clip_rect;
+                    return None;
+                }
+                for mesh in &mut source {
+                    crate::blur::color_silhouette(mesh, color);
+                }
+
+                let mut fallback = vec![];
+                self.tessellate_blur_approximation(shape, blur, clip_rect, &mut fallback);
+                let mut fallback = only_meshes(fallback);
+                for mesh in &mut fallback {
+                    crate::blur::color_silhouette(mesh, color);
+                }
+                self.clip_rect = clip_rect;
+
+                let bounds = source.iter().fold(Rect::NOTHING, |bounds, mesh| {
+                    bounds.union(mesh.calc_bounds())
+                });
+                let rect = self
+                    .round_rect_outwards(bounds.expand(margin).intersect(clip_rect.expand(margin)));
+                if !rect.is_positive() {
+                    return None;
+                }
+
+                let mut mesh = Mesh::default();
+                mesh.add_rect_with_uv(
+                    rect,
+                    Rect::from_min_max(Pos2::ZERO, pos2(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+
+                Some(BlurPrimitive {
+                    rect,
+                    sigma,
+                    source: BlurSource::Meshes(source),
+                    mesh,
+                    fallback,
+                })
+            }
+
+            BlurShape::Backdrop {
+                rect,
+                rounding,
+                blur,
+            } => {
+                if blur <= 0.0 || !rect.intersects(clip_rect) {
+                    return None;
+                }
+
+                let sigma = crate::blur::sigma_from_blur(blur);
+                let region =
+                    self.round_rect_outwards(rect.intersect(clip_rect).expand(3.0 * sigma));
+                if !region.is_positive() {
+                    return None;
+                }
+
+                let mut mesh = Mesh::default();
+                let path = &mut self.scratchpad_path;
+                path.clear();
+                path::rounded_rectangle(&mut self.scratchpad_points, rect, rounding);
+                path.add_line_loop(&self.scratchpad_points);
+                let uv_from_pos = |p: Pos2| {
+                    pos2(
+                        remap(p.x, region.x_range(), 0.0..=1.0),
+                        remap(p.y, region.y_range(), 0.0..=1.0),
+                    )
+                };
+                path.fill_with_uv(
+                    self.feathering,
+                    Color32::WHITE,
+                    TextureId::default(),
+                    uv_from_pos,
+                    &mut mesh,
+                );
+
+                Some(BlurPrimitive {
+                    rect: region,
+                    sigma,
+                    source: BlurSource::Backdrop,
+                    mesh,
+                    fallback: vec![],
+                })
+            }
+        }
+    }
+
+    /// The approximation of a blur, using a wide feathering,
+    /// or the blur width of [`RectShape`] for rectangles.
+    fn tessellate_blur_approximation(
+        &mut self,
+        shape: Shape,
+        blur: f32,
+        clip_rect: Rect,
+        out_primitives: &mut Vec<ClippedPrimitive>,
+    ) {
+        match shape {
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    self.tessellate_blur_approximation(shape, blur, clip_rect, out_primitives);
+                }
+            }
+            Shape::Rect(rect) => {
+                let blur_width = rect.blur_width.max(blur);
+                let shape = Shape::Rect(rect.with_blur_width(blur_width));
+                self.tessellate_clipped_shape(ClippedShape { clip_rect, shape }, out_primitives);
+            }
+            shape => {
+                let old_feathering = self.feathering;
+                self.feathering = self.feathering.max(blur);
+                self.tessellate_clipped_shape(ClippedShape { clip_rect, shape }, out_primitives);
+                self.feathering = old_feathering;
+            }
+        }
+    }
+
+    /// Round a rectangle outwards to whole physical pixels.
+    fn round_rect_outwards(&self, rect: Rect) -> Rect {
+        let ppp = self.pixels_per_point;
+        Rect::from_min_max(
+            (rect.min * ppp).floor() / ppp,
+            (rect.max * ppp).ceil() / ppp,
+        )
+    }
+
+    /// Tessellate a single [`GradientFillShape`] into a [`Mesh`].
+    ///
+    /// * `gradient_fill`: the shape to tessellate.
+    /// * `out`: triangles are appended to this.
+    pub fn tessellate_gradient_fill(&mut self, gradient_fill: GradientFillShape, out: &mut Mesh) {
+        let Some((fill, rect, stroke)) = gradient_fill.split() else {
+            self.tessellate_shape(*gradient_fill.shape, out);
+            return;
+        };
+
+        let (first_vertex, first_index) = (out.vertices.len(), out.indices.len());
+        self.tessellate_shape(fill, out);
+        fill_with_gradient(
+            out,
+            first_vertex,
+            first_index,
+            rect,
+            &gradient_fill.gradient,
+        );
+        self.tessellate_shape(stroke, out);
+    }
+
     /// Tessellate a single [`CircleShape`] into a [`Mesh`].
     ///
     /// * `shape`: the circle to tessellate.
@@ -1751,9 +2038,17 @@ impl Tessellator {
                             // Only override the glyph color (not background color, strike-through color, etc)
                             if row.visuals.glyph_vertex_range.contains(&i) {
                                 color = *override_text_color;
+                            } else if row.visuals.color_glyph_vertex_range.contains(&i) {
+                                let a = override_text_color.a();
+                                color = Color32::from_rgba_premultiplied(a, a, a, a);
                             }
                         } else if color == Color32::PLACEHOLDER {
-                            color = *fallback_color;
+                            color = if row.visuals.color_glyph_vertex_range.contains(&i) {
+                                let a = fallback_color.a();
+                                Color32::from_rgba_premultiplied(a, a, a, a)
+                            } else {
+                                *fallback_color
+                            };
                         }
 
                         if *opacity_factor < 1.0 {
@@ -1917,12 +2212,43 @@ impl Tessellator {
             self.parallel_tessellation_of_large_shapes(&mut shapes);
         }
 
+        self.tessellate_shapes_with_cache(shapes, None)
+    }
+
+    /// Like [`Self::tessellate_shapes`], but reuses the meshes of shapes that were
+    /// also tessellated last frame.
+    ///
+    /// Keep the same [`TessellationCache`] from one frame to the next.
+    /// Shapes that are not in the cache are tessellated on this thread,
+    /// i.e. [`TessellationOptions::parallel_tessellation`] is ignored.
+    pub fn tessellate_shapes_cached(
+        &mut self,
+        shapes: Vec<ClippedShape>,
+        cache: &mut TessellationCache,
+    ) -> Vec<ClippedPrimitive> {
+        crate::profile_function!();
+
+        cache.begin_frame(self.settings_hash());
+        let clipped_primitives = self.tessellate_shapes_with_cache(shapes, Some(&mut *cache));
+        cache.end_frame();
+        clipped_primitives
+    }
+
+    fn tessellate_shapes_with_cache(
+        &mut self,
+        shapes: Vec<ClippedShape>,
+        mut cache: Option<&mut TessellationCache>,
+    ) -> Vec<ClippedPrimitive> {
         let mut clipped_primitives: Vec<ClippedPrimitive> = Vec::default();
 
         {
             crate::profile_scope!("tessellate");
             for clipped_shape in shapes {
-                self.tessellate_clipped_shape(clipped_shape, &mut clipped_primitives);
+                self.tessellate_clipped_shape_with_cache(
+                    clipped_shape,
+                    &mut clipped_primitives,
+                    cache.as_deref_mut(),
+                );
             }
         }
 
@@ -1940,7 +2266,7 @@ impl Tessellator {
             p.clip_rect.is_positive()
                 && match &p.primitive {
                     Primitive::Mesh(mesh) => !mesh.is_empty(),
-                    Primitive::Callback(_) => true,
+                    Primitive::Callback(_) | Primitive::Blur(_) => true,
                 }
         });
 
@@ -1972,12 +2298,15 @@ impl Tessellator {
 
                 Shape::QuadraticBezier(_) | Shape::CubicBezier(_) | Shape::Ellipse(_) => true,
 
+                Shape::GradientFill(gradient_fill) => should_parallelize(&gradient_fill.shape),
+
                 Shape::Noop
                 | Shape::Text(_)
                 | Shape::Circle(_)
                 | Shape::Mesh(_)
                 | Shape::LineSegment { .. }
                 | Shape::Rect(_)
+                | Shape::Blur(_)
                 | Shape::Callback(_) => false,
             }
         }
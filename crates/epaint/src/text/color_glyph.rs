@@ -0,0 +1,439 @@
+//! Full-color glyphs, e.g. color emojis, from `COLR`, `CBDT` and `sbix` fonts.
+//!
+//! Only used with the `color_glyphs` feature.
+
+use ab_glyph::{Font as _, ScaleFont as _};
+use emath::{vec2, Vec2};
+
+use crate::Color32;
+
+/// A rasterized full-color glyph.
+pub(crate) struct ColorGlyph {
+    /// Width and height in pixels.
+    pub size: [usize; 2],
+
+    /// Premultiplied colors, row by row.
+    pub pixels: Vec<Color32>,
+
+    /// Coverage of the parts in the text color, row by row, if there are any.
+    ///
+    /// These are the `COLR` layers with the palette index `0xFFFF`.
+    pub foreground: Option<Vec<f32>>,
+
+    /// From the glyph origin on the baseline to the top left corner, in pixels.
+    pub offset: Vec2,
+}
+
+/// Rasterize the glyph in full color, if the font has colors for it.
+///
+/// `scale_in_pixels` is the same scale as is used for the outlines of the font.
+pub(crate) fn rasterize(
+    font: &ab_glyph::FontArc,
+    face_index: u32,
+    glyph_id: ab_glyph::GlyphId,
+    scale_in_pixels: f32,
+) -> Option<ColorGlyph> {
+    rasterize_colr(font, face_index, glyph_id, scale_in_pixels)
+        .or_else(|| rasterize_bitmap(font, glyph_id, scale_in_pixels))
+}
+
+// ----------------------------------------------------------------------------
+// COLR
+
+/// Collects the layers of a `COLR` glyph.
+///
+/// Every layer is the outline of a glyph filled with a single color.
+/// Gradients are approximated by their average color, and transforms are ignored.
+struct LayerCollector {
+    palette: u16,
+    outline: Option<ttf_parser::GlyphId>,
+    clips: Vec<Option<ttf_parser::GlyphId>>,
+    layers: Vec<(ab_glyph::GlyphId, Color32)>,
+}
+
+impl<'a> ttf_parser::colr::Painter<'a> for LayerCollector {
+    fn outline_glyph(&mut self, glyph_id: ttf_parser::GlyphId) {
+        self.outline = Some(glyph_id);
+    }
+
+    fn paint(&mut self, paint: ttf_parser::colr::Paint<'a>) {
+        use ttf_parser::colr::Paint;
+
+        let color = match paint {
+            Paint::Solid(color) => to_color32(color),
+            Paint::LinearGradient(gradient) => {
+                average_color(gradient.stops(self.palette, &[]).map(|stop| stop.color))
+            }
+            Paint::RadialGradient(gradient) => {
+                average_color(gradient.stops(self.palette, &[]).map(|stop| stop.color))
+            }
+            Paint::SweepGradient(gradient) => {
+                average_color(gradient.stops(self.palette, &[]).map(|stop| stop.color))
+            }
+        };
+
+        // COLRv0 paints the last outline, COLRv1 fills the innermost glyph clip:
+        let glyph_id = self
+            .clips
+            .iter()
+            .rev()
+            .find_map(|clip| *clip)
+            .or(self.outline);
+        if let Some(glyph_id) = glyph_id {
+            self.layers.push((ab_glyph::GlyphId(glyph_id.0), color));
+        }
+    }
+
+    fn push_clip(&mut self) {
+        self.clips.push(self.outline);
+    }
+
+    fn push_clip_box(&mut self, _clipbox: ttf_parser::colr::ClipBox) {
+        self.clips.push(None);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn push_layer(&mut self, _mode: ttf_parser::colr::CompositeMode) {}
+
+    fn pop_layer(&mut self) {}
+
+    fn push_transform(&mut self, _transform: ttf_parser::Transform) {}
+
+    fn pop_transform(&mut self) {}
+}
+
+fn to_color32(color: ttf_parser::RgbaColor) -> Color32 {
+    Color32::from_rgba_unmultiplied(color.red, color.green, color.blue, color.alpha)
+}
+
+fn average_color(colors: impl Iterator<Item = ttf_parser::RgbaColor>) -> Color32 {
+    let mut sum = [0_u32; 4];
+    let mut count = 0;
+    for color in colors {
+        let color = to_color32(color);
+        for (sum, channel) in sum.iter_mut().zip(color.to_array()) {
+            *sum += channel as u32;
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return Color32::TRANSPARENT;
+    }
+    let [r, g, b, a] = sum.map(|sum| (sum / count) as u8);
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+fn rasterize_colr(
+    font: &ab_glyph::FontArc,
+    face_index: u32,
+    glyph_id: ab_glyph::GlyphId,
+    scale_in_pixels: f32,
+) -> Option<ColorGlyph> {
+    let face = ttf_parser::Face::parse(font.font_data(), face_index).ok()?;
+    let ttf_glyph_id = ttf_parser::GlyphId(glyph_id.0);
+    if !face.is_color_glyph(ttf_glyph_id) {
+        return None;
+    }
+
+    // The text color isn't known until the text is painted, so the layers are collected
+    // with a black and a white foreground color, and the difference is what's in the text color:
+    let collect_layers = |foreground: u8| {
+        const PALETTE: u16 = 0;
+        let foreground = ttf_parser::RgbaColor::new(foreground, foreground, foreground, 255);
+        let mut collector = LayerCollector {
+            palette: PALETTE,
+            outline: None,
+            clips: vec![],
+            layers: vec![],
+        };
+        face.paint_color_glyph(ttf_glyph_id, PALETTE, foreground, &mut collector)?;
+        Some(collector.layers)
+    };
+    let on_black = collect_layers(0)?;
+    let on_white = collect_layers(255)?;
+    if on_black.len() != on_white.len() {
+        return None;
+    }
+
+    let outlines: Vec<_> = on_black
+        .into_iter()
+        .zip(on_white)
+        .filter_map(|((layer_glyph_id, on_black), (_, on_white))| {
+            let (color, foreground) = split_foreground(on_black, on_white);
+            let glyph = layer_glyph_id
+                .with_scale_and_position(scale_in_pixels, ab_glyph::Point { x: 0.0, y: 0.0 });
+            Some((font.outline_glyph(glyph)?, color, foreground))
+        })
+        .collect();
+
+    let (first, _, _) = outlines.first()?;
+    let mut bounds = first.px_bounds();
+    for (outline, _, _) in &outlines {
+        let bb = outline.px_bounds();
+        bounds.min.x = bounds.min.x.min(bb.min.x);
+        bounds.min.y = bounds.min.y.min(bb.min.y);
+        bounds.max.x = bounds.max.x.max(bb.max.x);
+        bounds.max.y = bounds.max.y.max(bb.max.y);
+    }
+
+    let width = bounds.width() as usize;
+    let height = bounds.height() as usize;
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let mut pixels = vec![Color32::TRANSPARENT; width * height];
+    let mut foreground = vec![0.0; width * height];
+    for (outline, color, layer_foreground) in &outlines {
+        let bb = outline.px_bounds();
+        let x0 = (bb.min.x - bounds.min.x) as usize;
+        let y0 = (bb.min.y - bounds.min.y) as usize;
+        outline.draw(|x, y, coverage| {
+            let (x, y) = (x0 + x as usize, y0 + y as usize);
+            if 0.0 < coverage && x < width && y < height {
+                let i = y * width + x;
+                let coverage = coverage.min(1.0);
+                let src = color.gamma_multiply(coverage);
+                let src_foreground = layer_foreground * coverage;
+                let keep = transmittance(src, src_foreground);
+                pixels[i] = blend_over(pixels[i], src, keep);
+                foreground[i] = src_foreground + foreground[i] * keep;
+            }
+        });
+    }
+
+    Some(ColorGlyph {
+        size: [width, height],
+        pixels,
+        foreground: foreground
+            .iter()
+            .any(|&coverage| 0.0 < coverage)
+            .then_some(foreground),
+        offset: vec2(bounds.min.x, bounds.min.y),
+    })
+}
+
+/// Split a layer into its premultiplied color and its coverage in the text color,
+/// from the colors it has with a black and a white text color.
+fn split_foreground(on_black: Color32, on_white: Color32) -> (Color32, f32) {
+    let foreground = on_white.r().saturating_sub(on_black.r());
+    let [r, g, b, a] = on_black.to_array();
+    let color = Color32::from_rgba_premultiplied(r, g, b, a.saturating_sub(foreground));
+    (color, foreground as f32 / 255.0)
+}
+
+/// How much is still seen of what is below premultiplied `src`
+/// and `src_foreground` coverage in the text color.
+fn transmittance(src: Color32, src_foreground: f32) -> f32 {
+    (1.0 - src.a() as f32 / 255.0 - src_foreground).max(0.0)
+}
+
+/// Paint premultiplied `src` over `dst`, of which `keep` is still seen.
+fn blend_over(dst: Color32, src: Color32, keep: f32) -> Color32 {
+    let [dr, dg, db, da] = dst.to_array();
+    let [sr, sg, sb, sa] = src.to_array();
+    let channel = |s: u8, d: u8| (s as f32 + d as f32 * keep).round().min(255.0) as u8;
+    Color32::from_rgba_premultiplied(
+        channel(sr, dr),
+        channel(sg, dg),
+        channel(sb, db),
+        channel(sa, da),
+    )
+}
+
+// ----------------------------------------------------------------------------
+// Bitmaps
+
+fn rasterize_bitmap(
+    font: &ab_glyph::FontArc,
+    glyph_id: ab_glyph::GlyphId,
+    scale_in_pixels: f32,
+) -> Option<ColorGlyph> {
+    let pixels_per_em = font.as_scaled(scale_in_pixels).h_scale_factor() * font.units_per_em()?;
+    let image = font.glyph_raster_image2(glyph_id, pixels_per_em.round() as u16)?;
+
+    let (size, pixels) = match image.format {
+        ab_glyph::GlyphImageFormat::Png => decode_png(image.data)?,
+        ab_glyph::GlyphImageFormat::BitmapPremulBgra32 => {
+            let size = [image.width as usize, image.height as usize];
+            let pixels: Vec<Color32> = image
+                .data
+                .chunks_exact(4)
+                .map(|bgra| Color32::from_rgba_premultiplied(bgra[2], bgra[1], bgra[0], bgra[3]))
+                .collect();
+            if pixels.len() != size[0] * size[1] {
+                return None;
+            }
+            (size, pixels)
+        }
+        _ => return None, // Monochrome bitmaps: use the outlines instead
+    };
+    if size[0] == 0 || size[1] == 0 {
+        return None;
+    }
+
+    // The bitmap is of the closest strike, so it needs to be scaled to the requested size:
+    let scale = pixels_per_em / image.pixels_per_em.max(1) as f32;
+    let target_size = [
+        ((size[0] as f32 * scale).round() as usize).max(1),
+        ((size[1] as f32 * scale).round() as usize).max(1),
+    ];
+    let pixels = resample(&pixels, size, target_size);
+
+    // The origin is the bottom left corner of the image, relative to the glyph origin, y-up:
+    let offset = vec2(
+        image.origin.x * scale,
+        -(image.origin.y * scale) - target_size[1] as f32,
+    );
+
+    Some(ColorGlyph {
+        size: target_size,
+        pixels,
+        foreground: None,
+        offset: vec2(offset.x.round(), offset.y.round()),
+    })
+}
+
+fn decode_png(data: &[u8]) -> Option<([usize; 2], Vec<Color32>)> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).ok()?;
+    let bytes = &buffer[..info.buffer_size()];
+
+    let pixels: Vec<Color32> = match info.color_type {
+        png::ColorType::Rgba => bytes
+            .chunks_exact(4)
+            .map(|p| Color32::from_rgba_unmultiplied(p[0], p[1], p[2], p[3]))
+            .collect(),
+        png::ColorType::Rgb => bytes
+            .chunks_exact(3)
+            .map(|p| Color32::from_rgb(p[0], p[1], p[2]))
+            .collect(),
+        png::ColorType::GrayscaleAlpha => bytes
+            .chunks_exact(2)
+            .map(|p| Color32::from_rgba_unmultiplied(p[0], p[0], p[0], p[1]))
+            .collect(),
+        png::ColorType::Grayscale => bytes.iter().map(|&l| Color32::from_gray(l)).collect(),
+        png::ColorType::Indexed => return None, // expanded by `normalize_to_color8`
+    };
+
+    let size = [info.width as usize, info.height as usize];
+    (pixels.len() == size[0] * size[1]).then_some((size, pixels))
+}
+
+/// Resize premultiplied pixels using a box filter.
+fn resample(pixels: &[Color32], [w, h]: [usize; 2], [tw, th]: [usize; 2]) -> Vec<Color32> {
+    if [w, h] == [tw, th] {
+        return pixels.to_vec();
+    }
+
+    let sx = w as f32 / tw as f32;
+    let sy = h as f32 / th as f32;
+
+    // Which source pixels overlap a target pixel, and by how much:
+    let footprint = |t: usize, scale: f32, len: usize| {
+        let start = t as f32 * scale;
+        let end = (start + scale).min(len as f32);
+        let first = start.floor() as usize;
+        let last = (end.ceil() as usize).clamp(first + 1, len);
+        (first..last).map(move |s| {
+            let overlap = end.min(s as f32 + 1.0) - start.max(s as f32);
+            (s, overlap.max(0.0))
+        })
+    };
+
+    let mut out = Vec::with_capacity(tw * th);
+    for ty in 0..th {
+        for tx in 0..tw {
+            let mut sum = [0.0_f32; 4];
+            let mut total_weight = 0.0;
+            for (y, wy) in footprint(ty, sy, h) {
+                for (x, wx) in footprint(tx, sx, w) {
+                    let weight = wx * wy;
+                    for (sum, channel) in sum.iter_mut().zip(pixels[y * w + x].to_array()) {
+                        *sum += weight * channel as f32;
+                    }
+                    total_weight += weight;
+                }
+            }
+            let [r, g, b, a] = if 0.0 < total_weight {
+                sum.map(|sum| (sum / total_weight).round() as u8)
+            } else {
+                [0; 4]
+            };
+            out.push(Color32::from_rgba_premultiplied(r, g, b, a));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample() {
+        let red = Color32::from_rgb(255, 0, 0);
+        let blue = Color32::from_rgb(0, 0, 255);
+        let pixels = [red, red, blue, blue, red, red, blue, blue];
+        assert_eq!(
+            resample(&pixels, [4, 2], [2, 1]),
+            [red, blue],
+            "downscaling keeps the colors"
+        );
+        assert_eq!(
+            resample(&pixels, [4, 2], [1, 1]),
+            [Color32::from_rgb(128, 0, 128)]
+        );
+        assert_eq!(resample(&[red], [1, 1], [2, 2]), [red; 4], "upscaling");
+    }
+
+    #[test]
+    fn test_blend_over() {
+        let over = |dst, src| blend_over(dst, src, transmittance(src, 0.0));
+        let half_white = Color32::from_rgba_premultiplied(128, 128, 128, 128);
+        assert_eq!(over(Color32::TRANSPARENT, half_white), half_white);
+        assert_eq!(over(Color32::BLACK, Color32::WHITE), Color32::WHITE);
+        assert_eq!(
+            over(Color32::BLACK, half_white),
+            Color32::from_rgba_premultiplied(128, 128, 128, 255)
+        );
+
+        // Parts in the text color hide what's below them too:
+        assert_eq!(
+            blend_over(
+                Color32::WHITE,
+                Color32::TRANSPARENT,
+                transmittance(Color32::TRANSPARENT, 1.0)
+            ),
+            Color32::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn test_split_foreground() {
+        let red = Color32::from_rgb(255, 0, 0);
+        assert_eq!(split_foreground(red, red), (red, 0.0), "no text color");
+        assert_eq!(
+            split_foreground(Color32::BLACK, Color32::WHITE),
+            (Color32::TRANSPARENT, 1.0),
+            "all text color"
+        );
+
+        // A gradient from red to a half-transparent text color, averaged:
+        let on_black = Color32::from_rgba_premultiplied(128, 0, 0, 192);
+        let on_white = Color32::from_rgba_premultiplied(192, 64, 64, 192);
+        assert_eq!(
+            split_foreground(on_black, on_white),
+            (
+                Color32::from_rgba_premultiplied(128, 0, 0, 128),
+                64.0 / 255.0
+            )
+        );
+    }
+}
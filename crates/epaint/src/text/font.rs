@@ -1,6 +1,6 @@
 use crate::{
     mutex::{Mutex, RwLock},
-    text::FontTweak,
+    text::{system_fonts::SystemFontFallback, FontTweak, GlyphRasterization},
     TextureAtlas,
 };
 use emath::{vec2, Vec2};
@@ -24,12 +24,36 @@ pub struct UvRect {
 
     /// Bottom right corner (exclusive).
     pub max: [u16; 2],
+
+    /// The texture contains the colors of this glyph, e.g. a color emoji,
+    /// so it should not be tinted with the text color.
+    pub is_color: bool,
+
+    /// Top left corner UV of the parts of a full-color glyph that are in the text color, if any.
+    ///
+    /// They are stored as coverage, the same size as the colors, see [`Self::foreground`].
+    pub foreground_min: Option<[u16; 2]>,
 }
 
 impl UvRect {
     pub fn is_nothing(&self) -> bool {
         self.min == self.max
     }
+
+    /// The parts of a full-color glyph that are in the text color, as a glyph that is tinted with it.
+    pub fn foreground(&self) -> Option<Self> {
+        let min = self.foreground_min?;
+        Some(Self {
+            min,
+            max: [
+                min[0] + (self.max[0] - self.min[0]),
+                min[1] + (self.max[1] - self.min[1]),
+            ],
+            is_color: false,
+            foreground_min: None,
+            ..*self
+        })
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -78,6 +102,21 @@ pub struct FontImpl {
     pixels_per_point: f32,
     glyph_info_cache: RwLock<ahash::HashMap<char, GlyphInfo>>, // TODO(emilk): standard Mutex
     atlas: Arc<Mutex<TextureAtlas>>,
+
+    /// Rasterize glyphs as signed distance fields, see [`GlyphRasterization::Sdf`].
+    sdf: bool,
+
+    /// Which face in the font file this is. Needed to find the colors of `COLR` glyphs.
+    #[cfg(feature = "color_glyphs")]
+    face_index: u32,
+
+    /// Needed for shaping. Without it, characters are laid out one by one.
+    #[cfg(feature = "text_shaping")]
+    face_data: Option<Arc<super::shaping::FaceData>>,
+
+    /// Shaped glyphs aren't tied to a single character, so they are cached by id.
+    #[cfg(feature = "text_shaping")]
+    glyph_id_cache: RwLock<ahash::HashMap<ab_glyph::GlyphId, GlyphInfo>>,
 }
 
 impl FontImpl {
@@ -122,6 +161,8 @@ impl FontImpl {
         // Round to closest pixel:
         let y_offset_in_points = (y_offset_points * pixels_per_point).round() / pixels_per_point;
 
+        let sdf = atlas.lock().rasterization() == GlyphRasterization::Sdf;
+
         Self {
             name,
             ab_glyph_font,
@@ -132,9 +173,30 @@ impl FontImpl {
             pixels_per_point,
             glyph_info_cache: Default::default(),
             atlas,
+            sdf,
+            #[cfg(feature = "color_glyphs")]
+            face_index: 0,
+            #[cfg(feature = "text_shaping")]
+            face_data: None,
+            #[cfg(feature = "text_shaping")]
+            glyph_id_cache: Default::default(),
         }
     }
 
+    /// Which face in the font file this is, if it is a collection (`.ttc`).
+    #[cfg(feature = "color_glyphs")]
+    pub(crate) fn with_face_index(mut self, face_index: u32) -> Self {
+        self.face_index = face_index;
+        self
+    }
+
+    /// Use this font file for shaping.
+    #[cfg(feature = "text_shaping")]
+    pub(crate) fn with_face_data(mut self, face_data: Arc<super::shaping::FaceData>) -> Self {
+        self.face_data = Some(face_data);
+        self
+    }
+
     /// Code points that will always be replaced by the replacement character.
     ///
     /// See also [`invisible_char`].
@@ -173,7 +235,7 @@ impl FontImpl {
     }
 
     /// `\n` will result in `None`
-    fn glyph_info(&self, c: char) -> Option<GlyphInfo> {
+    pub(crate) fn glyph_info(&self, c: char) -> Option<GlyphInfo> {
         {
             if let Some(glyph_info) = self.glyph_info_cache.read().get(&c) {
                 return Some(*glyph_info);
@@ -244,6 +306,63 @@ impl FontImpl {
             / self.pixels_per_point
     }
 
+    /// Can this font be used for shaping?
+    #[cfg(feature = "text_shaping")]
+    pub(crate) fn can_shape(&self) -> bool {
+        self.face_data.is_some()
+    }
+
+    /// Shape a run of text that uses this font and a single direction.
+    ///
+    /// Returns the clusters in logical order, with all units in points.
+    #[cfg(feature = "text_shaping")]
+    pub(crate) fn shape(&self, text: &str, rtl: bool) -> Vec<ShapedCluster> {
+        use ab_glyph::{Font as _, ScaleFont as _};
+
+        let Some(face_data) = &self.face_data else {
+            return vec![];
+        };
+
+        let points_per_unit = self
+            .ab_glyph_font
+            .as_scaled(self.scale_in_pixels as f32)
+            .h_scale_factor()
+            / self.pixels_per_point;
+
+        super::shaping::shape(face_data, text, rtl)
+            .into_iter()
+            .map(|cluster| ShapedCluster {
+                byte_offset: cluster.byte_offset,
+                advance: cluster.advance * points_per_unit,
+                glyphs: cluster
+                    .glyphs
+                    .into_iter()
+                    .map(|(glyph_id, offset)| {
+                        (
+                            self.glyph_info_by_id(glyph_id).uv_rect,
+                            offset * points_per_unit,
+                        )
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "text_shaping")]
+    fn glyph_info_by_id(&self, glyph_id: ab_glyph::GlyphId) -> GlyphInfo {
+        if glyph_id.0 == 0 {
+            return GlyphInfo::default(); // Missing glyph, or an invisible character
+        }
+
+        if let Some(glyph_info) = self.glyph_id_cache.read().get(&glyph_id) {
+            return *glyph_info;
+        }
+
+        let glyph_info = self.allocate_glyph(glyph_id);
+        self.glyph_id_cache.write().insert(glyph_id, glyph_info);
+        glyph_info
+    }
+
     /// Height of one row of text in points.
     #[inline(always)]
     pub fn row_height(&self) -> f32 {
@@ -267,6 +386,37 @@ impl FontImpl {
         assert!(glyph_id.0 != 0);
         use ab_glyph::{Font as _, ScaleFont};
 
+        let advance_width_in_points = self
+            .ab_glyph_font
+            .as_scaled(self.scale_in_pixels as f32)
+            .h_advance(glyph_id)
+            / self.pixels_per_point;
+
+        // A distance field has no colors, so color glyphs fall back to their outlines, if any.
+        #[cfg(feature = "color_glyphs")]
+        if !self.sdf {
+            if let Some(color_glyph) = super::color_glyph::rasterize(
+                &self.ab_glyph_font,
+                self.face_index,
+                glyph_id,
+                self.scale_in_pixels as f32,
+            ) {
+                return GlyphInfo {
+                    id: glyph_id,
+                    advance_width: advance_width_in_points,
+                    uv_rect: self.allocate_color_glyph(&color_glyph),
+                };
+            }
+        }
+
+        if self.sdf {
+            return GlyphInfo {
+                id: glyph_id,
+                advance_width: advance_width_in_points,
+                uv_rect: self.allocate_sdf_glyph(glyph_id),
+            };
+        }
+
         let glyph = glyph_id.with_scale_and_position(
             self.scale_in_pixels as f32,
             ab_glyph::Point { x: 0.0, y: 0.0 },
@@ -303,23 +453,131 @@ impl FontImpl {
                         (glyph_pos.0 + glyph_width) as u16,
                         (glyph_pos.1 + glyph_height) as u16,
                     ],
+                    is_color: false,
+                    foreground_min: None,
                 }
             }
         });
         let uv_rect = uv_rect.unwrap_or_default();
 
-        let advance_width_in_points = self
-            .ab_glyph_font
-            .as_scaled(self.scale_in_pixels as f32)
-            .h_advance(glyph_id)
-            / self.pixels_per_point;
-
         GlyphInfo {
             id: glyph_id,
             advance_width: advance_width_in_points,
             uv_rect,
         }
     }
+
+    /// Rasterize the glyph as a signed distance field.
+    fn allocate_sdf_glyph(&self, glyph_id: ab_glyph::GlyphId) -> UvRect {
+        use ab_glyph::Font as _;
+
+        /// Small glyphs are rasterized at (at least) this size, so the distance field has enough detail.
+        const MIN_SDF_SCALE_IN_PIXELS: f32 = 32.0;
+
+        let scale_in_pixels = self.scale_in_pixels as f32;
+        let oversampling = (MIN_SDF_SCALE_IN_PIXELS / scale_in_pixels).ceil().max(1.0);
+        let glyph = glyph_id.with_scale_and_position(
+            scale_in_pixels * oversampling,
+            ab_glyph::Point { x: 0.0, y: 0.0 },
+        );
+        let Some(glyph) = self.ab_glyph_font.outline_glyph(glyph) else {
+            return UvRect::default();
+        };
+
+        let bb = glyph.px_bounds();
+        if bb.width() == 0.0 || bb.height() == 0.0 {
+            return UvRect::default();
+        }
+
+        // Leave room for the distance field around the outline:
+        let padding = crate::sdf::SPREAD.ceil() as usize;
+        let sdf_width = bb.width() as usize + 2 * padding;
+        let sdf_height = bb.height() as usize + 2 * padding;
+
+        let mut coverage = vec![0.0; sdf_width * sdf_height];
+        glyph.draw(|x, y, v| {
+            let (x, y) = (x as usize + padding, y as usize + padding);
+            if x < sdf_width && y < sdf_height {
+                coverage[y * sdf_width + x] = v;
+            }
+        });
+        let distances = crate::sdf::from_coverage(&coverage, sdf_width, sdf_height);
+
+        let glyph_pos = {
+            let atlas = &mut self.atlas.lock();
+            let (glyph_pos, image) = atlas.allocate((sdf_width, sdf_height));
+            for (i, &distance) in distances.iter().enumerate() {
+                image[(glyph_pos.0 + i % sdf_width, glyph_pos.1 + i / sdf_width)] = distance;
+            }
+            glyph_pos
+        };
+
+        let offset_in_pixels =
+            (vec2(bb.min.x, bb.min.y) - Vec2::splat(padding as f32)) / oversampling;
+        let offset = offset_in_pixels / self.pixels_per_point + self.y_offset_in_points * Vec2::Y;
+        UvRect {
+            offset,
+            size: vec2(sdf_width as f32, sdf_height as f32)
+                / (oversampling * self.pixels_per_point),
+            min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+            max: [
+                (glyph_pos.0 + sdf_width) as u16,
+                (glyph_pos.1 + sdf_height) as u16,
+            ],
+            is_color: false,
+            foreground_min: None,
+        }
+    }
+
+    #[cfg(feature = "color_glyphs")]
+    fn allocate_color_glyph(&self, color_glyph: &super::color_glyph::ColorGlyph) -> UvRect {
+        let [glyph_width, glyph_height] = color_glyph.size;
+        let (glyph_pos, foreground_pos) = {
+            let atlas = &mut self.atlas.lock();
+            let (glyph_pos, image) = atlas.allocate((glyph_width, glyph_height));
+            for (i, &color) in color_glyph.pixels.iter().enumerate() {
+                let px = glyph_pos.0 + i % glyph_width;
+                let py = glyph_pos.1 + i / glyph_width;
+                image.set_color((px, py), color);
+            }
+            let foreground_pos = color_glyph.foreground.as_ref().map(|foreground| {
+                let (foreground_pos, image) = atlas.allocate((glyph_width, glyph_height));
+                for (i, &coverage) in foreground.iter().enumerate() {
+                    let px = foreground_pos.0 + i % glyph_width;
+                    let py = foreground_pos.1 + i / glyph_width;
+                    image[(px, py)] = coverage;
+                }
+                [foreground_pos.0 as u16, foreground_pos.1 as u16]
+            });
+            (glyph_pos, foreground_pos)
+        };
+
+        let offset = color_glyph.offset / self.pixels_per_point + self.y_offset_in_points * Vec2::Y;
+        UvRect {
+            offset,
+            size: vec2(glyph_width as f32, glyph_height as f32) / self.pixels_per_point,
+            min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+            max: [
+                (glyph_pos.0 + glyph_width) as u16,
+                (glyph_pos.1 + glyph_height) as u16,
+            ],
+            is_color: true,
+            foreground_min: foreground_pos,
+        }
+    }
+}
+
+/// A cluster of shaped glyphs, from [`FontImpl::shape`].
+#[cfg(feature = "text_shaping")]
+pub(crate) struct ShapedCluster {
+    /// Byte offset of the first character of the cluster in the shaped text.
+    pub byte_offset: usize,
+
+    /// Advance width of the whole cluster.
+    pub advance: f32,
+
+    /// The glyphs, with their offset from the left edge of the cluster and the baseline.
+    pub glyphs: Vec<(UvRect, Vec2)>,
 }
 
 type FontIndex = usize;
@@ -336,6 +594,9 @@ pub struct Font {
     pixels_per_point: f32,
     row_height: f32,
     glyph_info_cache: ahash::HashMap<char, (FontIndex, GlyphInfo)>,
+
+    /// Where to look for characters none of the [`Self::fonts`] support, and the font size in points.
+    system_font_fallback: Option<(Arc<Mutex<SystemFontFallback>>, f32)>,
 }
 
 impl Font {
@@ -348,6 +609,7 @@ impl Font {
                 pixels_per_point: 1.0,
                 row_height: 0.0,
                 glyph_info_cache: Default::default(),
+                system_font_fallback: None,
             };
         }
 
@@ -361,6 +623,7 @@ impl Font {
             pixels_per_point,
             row_height,
             glyph_info_cache: Default::default(),
+            system_font_fallback: None,
         };
 
         const PRIMARY_REPLACEMENT_CHAR: char = '◻'; // white medium square
@@ -379,6 +642,18 @@ impl Font {
         slf
     }
 
+    /// Look for characters that none of the fonts support in the system fonts.
+    pub(crate) fn with_system_font_fallback(
+        mut self,
+        fallback: Arc<Mutex<SystemFontFallback>>,
+        scale_in_points: f32,
+    ) -> Self {
+        if !self.fonts.is_empty() {
+            self.system_font_fallback = Some((fallback, scale_in_points));
+        }
+        self
+    }
+
     pub fn preload_characters(&mut self, s: &str) {
         for c in s.chars() {
             self.glyph_info(c);
@@ -462,6 +737,19 @@ impl Font {
         (Some(font_impl), glyph_info)
     }
 
+    /// The font to shape this character with.
+    ///
+    /// `None` if no font supports the character, or the font can't be used for shaping.
+    #[cfg(feature = "text_shaping")]
+    pub(crate) fn shaping_font_impl(&mut self, c: char) -> Option<Arc<FontImpl>> {
+        let font_index_glyph_info = self.glyph_info(c);
+        if font_index_glyph_info == self.replacement_glyph {
+            return None;
+        }
+        let font_impl = &self.fonts[font_index_glyph_info.0];
+        font_impl.can_shape().then(|| font_impl.clone())
+    }
+
     fn glyph_info_no_cache_or_fallback(&mut self, c: char) -> Option<(FontIndex, GlyphInfo)> {
         for (font_index, font_impl) in self.fonts.iter().enumerate() {
             if let Some(glyph_info) = font_impl.glyph_info(c) {
@@ -469,7 +757,25 @@ impl Font {
                 return Some((font_index, glyph_info));
             }
         }
-        None
+        self.system_font_glyph_info(c)
+    }
+
+    fn system_font_glyph_info(&mut self, c: char) -> Option<(FontIndex, GlyphInfo)> {
+        let (fallback, scale_in_points) = self.system_font_fallback.as_ref()?;
+        let font_impl = fallback.lock().font_impl(*scale_in_points, c)?;
+        let glyph_info = font_impl.glyph_info(c)?;
+
+        let existing_index = self
+            .fonts
+            .iter()
+            .position(|existing| Arc::ptr_eq(existing, &font_impl));
+        let font_index = existing_index.unwrap_or_else(|| {
+            self.fonts.push(font_impl);
+            self.characters = None;
+            self.fonts.len() - 1
+        });
+        self.glyph_info_cache.insert(c, (font_index, glyph_info));
+        Some((font_index, glyph_info))
     }
 }
 
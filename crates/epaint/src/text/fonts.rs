@@ -4,7 +4,8 @@ use crate::{
     mutex::{Mutex, MutexGuard},
     text::{
         font::{Font, FontImpl},
-        Galley, LayoutJob,
+        system_fonts::SystemFontFallback,
+        Galley, LayoutJob, SystemFonts,
     },
     TextureAtlas,
 };
@@ -189,6 +190,33 @@ impl Default for FontTweak {
 
 // ----------------------------------------------------------------------------
 
+/// How glyphs are stored in the font texture atlas.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum GlyphRasterization {
+    /// Store how much of each texel is covered by the glyph.
+    ///
+    /// This looks best when text is painted at the size it was laid out for,
+    /// which is almost always the case.
+    #[default]
+    Coverage,
+
+    /// Store a signed distance field of each glyph, see [`crate::sdf`].
+    ///
+    /// Text stays crisp when scaled up, e.g. when zooming into a transformed layer,
+    /// where coverage glyphs become blurry.
+    /// Small text looks slightly softer, and glyphs take up more room in the atlas.
+    ///
+    /// Full-color glyphs (e.g. color emojis) are painted as single-color outlines.
+    ///
+    /// This requires support from the backend: `egui_glow` and `egui-wgpu` have it.
+    /// A custom backend needs to check [`crate::FontImage::sdf`] and use [`crate::sdf::coverage`]
+    /// in its fragment shader.
+    Sdf,
+}
+
+// ----------------------------------------------------------------------------
+
 fn ab_glyph_font_from_font_data(name: &str, data: &FontData) -> ab_glyph::FontArc {
     match &data.font {
         std::borrow::Cow::Borrowed(bytes) => {
@@ -245,6 +273,16 @@ pub struct FontDefinitions {
     /// the first font and then move to the second, and so on.
     /// So the first font is the primary, and then comes a list of fallbacks in order of priority.
     pub families: BTreeMap<FontFamily, Vec<String>>,
+
+    /// Fonts installed on the system, searched for characters that no font in a family supports.
+    ///
+    /// Empty by default. Use [`SystemFonts::load`] to opt in.
+    pub system_fonts: SystemFonts,
+
+    /// How to store the glyphs in the font texture.
+    ///
+    /// Default: [`GlyphRasterization::Coverage`].
+    pub rasterization: GlyphRasterization,
 }
 
 impl Default for FontDefinitions {
@@ -319,6 +357,8 @@ impl Default for FontDefinitions {
         Self {
             font_data,
             families,
+            system_fonts: Default::default(),
+            rasterization: Default::default(),
         }
     }
 }
@@ -333,6 +373,8 @@ impl FontDefinitions {
         Self {
             font_data: Default::default(),
             families,
+            system_fonts: Default::default(),
+            rasterization: Default::default(),
         }
     }
 
@@ -576,6 +618,7 @@ pub struct FontsImpl {
     definitions: FontDefinitions,
     atlas: Arc<Mutex<TextureAtlas>>,
     font_impl_cache: FontImplCache,
+    system_font_fallback: Option<Arc<Mutex<SystemFontFallback>>>,
     sized_family: ahash::HashMap<(OrderedFloat<f32>, FontFamily), Font>,
 }
 
@@ -594,19 +637,31 @@ impl FontsImpl {
 
         let texture_width = max_texture_side.at_most(8 * 1024);
         let initial_height = 32; // Keep initial font atlas small, so it is fast to upload to GPU. This will expand as needed anyways.
-        let atlas = TextureAtlas::new([texture_width, initial_height]);
+        let atlas = TextureAtlas::with_rasterization(
+            [texture_width, initial_height],
+            definitions.rasterization,
+        );
 
         let atlas = Arc::new(Mutex::new(atlas));
 
         let font_impl_cache =
             FontImplCache::new(atlas.clone(), pixels_per_point, &definitions.font_data);
 
+        let system_font_fallback = (!definitions.system_fonts.is_empty()).then(|| {
+            Arc::new(Mutex::new(SystemFontFallback::new(
+                atlas.clone(),
+                pixels_per_point,
+                &definitions.system_fonts,
+            )))
+        });
+
         Self {
             pixels_per_point,
             max_texture_side,
             definitions,
             atlas,
             font_impl_cache,
+            system_font_fallback,
             sized_family: Default::default(),
         }
     }
@@ -637,7 +692,11 @@ impl FontsImpl {
                     .map(|font_name| self.font_impl_cache.font_impl(*size, font_name))
                     .collect();
 
-                Font::new(fonts)
+                let font = Font::new(fonts);
+                match &self.system_font_fallback {
+                    Some(fallback) => font.with_system_font_fallback(fallback.clone(), *size),
+                    None => font,
+                }
             })
     }
 
@@ -715,11 +774,33 @@ impl GalleyCache {
 
 // ----------------------------------------------------------------------------
 
+/// The size in pixels to give a [`FontImpl`] of this font.
+///
+/// `None` if the font unit size is out of range.
+pub(super) fn scale_in_pixels(
+    pixels_per_point: f32,
+    scale_in_points: f32,
+    ab_glyph_font: &ab_glyph::FontArc,
+) -> Option<f32> {
+    use ab_glyph::Font as _;
+
+    // Scale the font properly (see https://github.com/emilk/egui/issues/2068).
+    let units_per_em = ab_glyph_font.units_per_em()?;
+    let font_scaling = ab_glyph_font.height_unscaled() / units_per_em;
+    Some(pixels_per_point * scale_in_points * font_scaling)
+}
+
 struct FontImplCache {
     atlas: Arc<Mutex<TextureAtlas>>,
     pixels_per_point: f32,
     ab_glyph_fonts: BTreeMap<String, (FontTweak, ab_glyph::FontArc)>,
 
+    #[cfg(feature = "color_glyphs")]
+    face_indices: BTreeMap<String, u32>,
+
+    #[cfg(feature = "text_shaping")]
+    face_data: BTreeMap<String, Arc<super::shaping::FaceData>>,
+
     /// Map font pixel sizes and names to the cached [`FontImpl`].
     cache: ahash::HashMap<(u32, String), Arc<FontImpl>>,
 }
@@ -739,31 +820,48 @@ impl FontImplCache {
             })
             .collect();
 
+        #[cfg(feature = "color_glyphs")]
+        let face_indices = font_data
+            .iter()
+            .map(|(name, font_data)| (name.clone(), font_data.index))
+            .collect();
+
+        #[cfg(feature = "text_shaping")]
+        let face_data = font_data
+            .iter()
+            .map(|(name, font_data)| {
+                let face_data = super::shaping::FaceData::new(font_data);
+                (name.clone(), Arc::new(face_data))
+            })
+            .collect();
+
         Self {
             atlas,
             pixels_per_point,
             ab_glyph_fonts,
+            #[cfg(feature = "color_glyphs")]
+            face_indices,
+            #[cfg(feature = "text_shaping")]
+            face_data,
             cache: Default::default(),
         }
     }
 
     pub fn font_impl(&mut self, scale_in_points: f32, font_name: &str) -> Arc<FontImpl> {
-        use ab_glyph::Font as _;
-
         let (tweak, ab_glyph_font) = self
             .ab_glyph_fonts
             .get(font_name)
             .unwrap_or_else(|| panic!("No font data found for {font_name:?}"))
             .clone();
 
-        let scale_in_pixels = self.pixels_per_point * scale_in_points;
-
-        // Scale the font properly (see https://github.com/emilk/egui/issues/2068).
-        let units_per_em = ab_glyph_font.units_per_em().unwrap_or_else(|| {
+        let scale_in_pixels = scale_in_pixels(
+            self.pixels_per_point,
+            scale_in_points,
+            &ab_glyph_font,
+        )
+        .unwrap_or_else(|| {
             panic!("The font unit size of {font_name:?} exceeds the expected range (16..=16384)")
         });
-        let font_scaling = ab_glyph_font.height_unscaled() / units_per_em;
-        let scale_in_pixels = scale_in_pixels * font_scaling;
 
         self.cache
             .entry((
@@ -771,14 +869,25 @@ impl FontImplCache {
                 font_name.to_owned(),
             ))
             .or_insert_with(|| {
-                Arc::new(FontImpl::new(
+                let font_impl = FontImpl::new(
                     self.atlas.clone(),
                     self.pixels_per_point,
                     font_name.to_owned(),
                     ab_glyph_font,
                     scale_in_pixels,
                     tweak,
-                ))
+                );
+                #[cfg(feature = "color_glyphs")]
+                let font_impl = match self.face_indices.get(font_name) {
+                    Some(&face_index) => font_impl.with_face_index(face_index),
+                    None => font_impl,
+                };
+                #[cfg(feature = "text_shaping")]
+                let font_impl = match self.face_data.get(font_name) {
+                    Some(face_data) => font_impl.with_face_data(face_data.clone()),
+                    None => font_impl,
+                };
+                Arc::new(font_impl)
             })
             .clone()
     }
@@ -1,8 +1,13 @@
 //! Everything related to text, fonts, text layout, cursors etc.
 
+#[cfg(feature = "color_glyphs")]
+mod color_glyph;
 pub mod cursor;
 mod font;
 mod fonts;
+#[cfg(feature = "text_shaping")]
+mod shaping;
+mod system_fonts;
 mod text_layout;
 mod text_layout_types;
 
@@ -10,7 +15,11 @@ mod text_layout_types;
 pub const TAB_SIZE: usize = 4;
 
 pub use {
-    fonts::{FontData, FontDefinitions, FontFamily, FontId, FontTweak, Fonts, FontsImpl},
+    fonts::{
+        FontData, FontDefinitions, FontFamily, FontId, FontTweak, Fonts, FontsImpl,
+        GlyphRasterization,
+    },
+    system_fonts::SystemFonts,
     text_layout::layout,
     text_layout_types::*,
 };
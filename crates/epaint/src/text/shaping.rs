@@ -0,0 +1,118 @@
+//! Text shaping with [`rustybuzz`] and bidirectional text with [`unicode_bidi`].
+//!
+//! Only used with the `text_shaping` feature.
+
+use std::borrow::Cow;
+
+use emath::{vec2, Vec2};
+
+use super::FontData;
+
+/// The font file of a [`super::font::FontImpl`], kept around for shaping.
+pub(crate) struct FaceData {
+    bytes: Cow<'static, [u8]>,
+    index: u32,
+}
+
+impl FaceData {
+    pub fn new(font_data: &FontData) -> Self {
+        Self {
+            bytes: font_data.font.clone(),
+            index: font_data.index,
+        }
+    }
+
+    /// Parsing the face is cheap compared to the shaping itself,
+    /// and galleys are cached, so we don't bother caching the face.
+    fn face(&self) -> Option<rustybuzz::Face<'_>> {
+        rustybuzz::Face::from_slice(&self.bytes, self.index)
+    }
+}
+
+/// Glyphs that belong together and can't be split by a line break or a cursor,
+/// e.g. a ligature, or a letter with its combining marks.
+pub(crate) struct Cluster {
+    /// Byte offset of the first character of the cluster in the shaped text.
+    pub byte_offset: usize,
+
+    /// Total advance width of the glyphs, in font units.
+    pub advance: f32,
+
+    /// Glyphs, left to right, with their offset from the left edge of the cluster
+    /// and the baseline, in font units.
+    pub glyphs: Vec<(ab_glyph::GlyphId, Vec2)>,
+}
+
+/// Shape a run of text using a single font and direction.
+///
+/// Returns the clusters in logical order, i.e. in the order of the text.
+pub(crate) fn shape(face_data: &FaceData, text: &str, rtl: bool) -> Vec<Cluster> {
+    crate::profile_function!();
+
+    let Some(face) = face_data.face() else {
+        // ab_glyph already parsed this font successfully, so this shouldn't happen.
+        return vec![];
+    };
+
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.set_direction(if rtl {
+        rustybuzz::Direction::RightToLeft
+    } else {
+        rustybuzz::Direction::LeftToRight
+    });
+    buffer.guess_segment_properties();
+    let glyph_buffer = rustybuzz::shape(&face, &[], buffer);
+
+    // The glyphs come in visual order, so the clusters of right-to-left text are reversed.
+    let mut clusters: Vec<Cluster> = vec![];
+    for (info, pos) in glyph_buffer
+        .glyph_infos()
+        .iter()
+        .zip(glyph_buffer.glyph_positions())
+    {
+        let byte_offset = info.cluster as usize;
+        if clusters
+            .last()
+            .map_or(true, |cluster| cluster.byte_offset != byte_offset)
+        {
+            clusters.push(Cluster {
+                byte_offset,
+                advance: 0.0,
+                glyphs: vec![],
+            });
+        }
+        let cluster = clusters.last_mut().unwrap();
+        let offset = vec2(
+            cluster.advance + pos.x_offset as f32,
+            -pos.y_offset as f32, // font units are y-up
+        );
+        cluster
+            .glyphs
+            .push((ab_glyph::GlyphId(info.glyph_id as u16), offset));
+        cluster.advance += pos.x_advance as f32;
+    }
+
+    if rtl {
+        clusters.reverse();
+    }
+    clusters
+}
+
+/// The bidirectional embedding level of each byte of the text.
+///
+/// Odd levels are right-to-left.
+pub(crate) fn bidi_levels(text: &str) -> Vec<u8> {
+    crate::profile_function!();
+
+    if text.is_ascii() {
+        return vec![0; text.len()]; // Fast path: no right-to-left characters
+    }
+
+    let bidi_info = unicode_bidi::BidiInfo::new(text, None);
+    bidi_info
+        .levels
+        .iter()
+        .map(|level| level.number())
+        .collect()
+}
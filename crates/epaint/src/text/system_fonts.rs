@@ -0,0 +1,426 @@
+//! Fonts installed on the system, used as a fallback for characters
+//! that none of the fonts in a [`super::FontFamily`] support.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::{mutex::Mutex, text::font::FontImpl, TextureAtlas};
+
+use super::FontTweak;
+
+/// Font files installed on the system.
+///
+/// When set in [`super::FontDefinitions::system_fonts`], these are searched
+/// for characters that none of the fonts of a [`super::FontFamily`] has a glyph for,
+/// e.g. CJK characters or emojis that the default fonts don't cover.
+///
+/// Only the paths are stored here. When a character is missing, the files are read in order
+/// until one supports it, and the font chosen for a character is then reused for the nearby characters.
+/// Which characters each file supports is remembered for as long as the program runs.
+///
+/// ```no_run
+/// # use epaint::text::{FontDefinitions, SystemFonts};
+/// let mut fonts = FontDefinitions::default();
+/// fonts.system_fonts = SystemFonts::load();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SystemFonts {
+    /// `.ttf`, `.otf`, `.ttc` and `.otc` files, in order of priority.
+    pub files: Vec<PathBuf>,
+}
+
+impl SystemFonts {
+    /// Find the fonts in the standard font directories of Linux and other freedesktop systems.
+    ///
+    /// These are the same directories fontconfig searches by default:
+    /// `$XDG_DATA_HOME/fonts`, `~/.fonts` and `fonts` in each of the `$XDG_DATA_DIRS`
+    /// (`/usr/local/share/fonts` and `/usr/share/fonts` if unset).
+    ///
+    /// Returns no fonts on other platforms, or if the directories don't exist.
+    pub fn load() -> Self {
+        crate::profile_function!();
+        Self::from_dirs(Self::standard_dirs())
+    }
+
+    /// Find the font files in these directories and their subdirectories.
+    ///
+    /// Directories that don't exist are ignored.
+    pub fn from_dirs(dirs: impl IntoIterator<Item = impl AsRef<Path>>) -> Self {
+        let mut files = vec![];
+        for dir in dirs {
+            let mut dir_files = vec![];
+            find_font_files(dir.as_ref(), 0, &mut dir_files);
+            dir_files.sort(); // Be deterministic
+            for file in dir_files {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// The directories searched by [`Self::load`].
+    pub fn standard_dirs() -> Vec<PathBuf> {
+        if !cfg!(all(unix, not(target_os = "macos"), not(target_os = "ios"))) {
+            return vec![];
+        }
+
+        let env_path = |name: &str| {
+            std::env::var_os(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+        let home = env_path("HOME");
+
+        let mut dirs = vec![];
+        if let Some(data_home) = env_path("XDG_DATA_HOME")
+            .or_else(|| home.as_ref().map(|home| home.join(".local/share")))
+        {
+            dirs.push(data_home.join("fonts"));
+        }
+        if let Some(home) = &home {
+            dirs.push(home.join(".fonts"));
+        }
+        let data_dirs = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_owned());
+        for data_dir in data_dirs.split(':').filter(|dir| !dir.is_empty()) {
+            dirs.push(Path::new(data_dir).join("fonts"));
+        }
+        dirs
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+fn find_font_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    // Guard against symlink cycles.
+    const MAX_DEPTH: usize = 8;
+    if MAX_DEPTH < depth {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_font_files(&path, depth + 1, files);
+        } else if is_font_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| {
+            ["ttf", "otf", "ttc", "otc"]
+                .iter()
+                .any(|supported| extension.eq_ignore_ascii_case(supported))
+        })
+}
+
+// ----------------------------------------------------------------------------
+
+/// Characters are grouped into ranges of this many code points,
+/// and we try to use the same system font for all characters in a range,
+/// so that e.g. a line of CJK text isn't a patchwork of different fonts.
+const CHARS_PER_RANGE: u32 = 128;
+
+/// Collections (`.ttc`) rarely contain more faces than this.
+const MAX_FACES_PER_FILE: u32 = 64;
+
+/// Sorted, non-overlapping, inclusive ranges of the code points supported by a font face.
+type Coverage = Arc<[(u32, u32)]>;
+
+/// One font face in a system font file.
+struct SystemFace {
+    path: PathBuf,
+    index: u32,
+    coverage: Coverage,
+
+    /// Loaded when first used for a character.
+    font: Option<ab_glyph::FontArc>,
+}
+
+impl SystemFace {
+    fn covers(&self, c: char) -> bool {
+        let c = c as u32;
+        let index = self.coverage.partition_point(|&(_, last)| last < c);
+        self.coverage
+            .get(index)
+            .map_or(false, |&(first, _)| first <= c)
+    }
+
+    fn name(&self) -> String {
+        format!("{}#{}", self.path.display(), self.index)
+    }
+
+    fn load(&mut self) -> Option<ab_glyph::FontArc> {
+        if self.font.is_none() {
+            let font = std::fs::read(&self.path).ok().and_then(|bytes| {
+                ab_glyph::FontVec::try_from_vec_and_index(bytes, self.index).ok()
+            });
+            if let Some(font) = font {
+                self.font = Some(ab_glyph::FontArc::from(font));
+            } else {
+                #[cfg(feature = "log")]
+                log::warn!("Failed to load system font {:?}", self.name());
+                self.coverage = Arc::from([]); // Don't try again
+            }
+        }
+        self.font.clone()
+    }
+}
+
+fn coverage(font: &impl ab_glyph::Font) -> Coverage {
+    let mut code_points: Vec<u32> = font
+        .codepoint_ids()
+        .filter(|(glyph_id, _)| glyph_id.0 != 0)
+        .map(|(_, c)| c as u32)
+        .collect();
+    code_points.sort_unstable();
+
+    let mut coverage: Vec<(u32, u32)> = vec![];
+    for c in code_points {
+        match coverage.last_mut() {
+            Some((_, last)) if c <= *last + 1 => *last = (*last).max(c),
+            _ => coverage.push((c, c)),
+        }
+    }
+    coverage.into()
+}
+
+/// Picks and loads system fonts for characters that are missing from a [`super::font::Font`].
+///
+/// Shared by all fonts of a [`super::FontsImpl`].
+pub(crate) struct SystemFontFallback {
+    atlas: Arc<Mutex<TextureAtlas>>,
+    pixels_per_point: f32,
+    files: Vec<PathBuf>,
+
+    /// The faces of the first [`Self::num_indexed_files`] files, in order of priority.
+    faces: Vec<SystemFace>,
+
+    /// Files are only indexed until one supports a missing character, see [`index_file`].
+    num_indexed_files: usize,
+
+    /// The face first chosen for a character in each range of [`CHARS_PER_RANGE`] code points.
+    face_by_range: ahash::HashMap<u32, usize>,
+
+    /// Characters that no system font supports.
+    unsupported: ahash::HashSet<char>,
+
+    /// Map face index and font pixel size to the cached [`FontImpl`].
+    cache: ahash::HashMap<(usize, u32), Arc<FontImpl>>,
+}
+
+impl SystemFontFallback {
+    pub fn new(
+        atlas: Arc<Mutex<TextureAtlas>>,
+        pixels_per_point: f32,
+        system_fonts: &SystemFonts,
+    ) -> Self {
+        Self {
+            atlas,
+            pixels_per_point,
+            files: system_fonts.files.clone(),
+            faces: vec![],
+            num_indexed_files: 0,
+            face_by_range: Default::default(),
+            unsupported: Default::default(),
+            cache: Default::default(),
+        }
+    }
+
+    /// A system font supporting this character, at the given size.
+    pub fn font_impl(&mut self, scale_in_points: f32, c: char) -> Option<Arc<FontImpl>> {
+        let face_index = self.face_for(c)?;
+        let face = &mut self.faces[face_index];
+        let ab_glyph_font = face.load()?;
+
+        let name = face.name();
+        let scale_in_pixels =
+            super::fonts::scale_in_pixels(self.pixels_per_point, scale_in_points, &ab_glyph_font)?;
+
+        let font_impl = self
+            .cache
+            .entry((face_index, scale_in_pixels.round() as u32))
+            .or_insert_with(|| {
+                let font_impl = FontImpl::new(
+                    self.atlas.clone(),
+                    self.pixels_per_point,
+                    name,
+                    ab_glyph_font,
+                    scale_in_pixels,
+                    FontTweak::default(),
+                );
+                #[cfg(feature = "color_glyphs")]
+                let font_impl = font_impl.with_face_index(face.index);
+                Arc::new(font_impl)
+            });
+        Some(font_impl.clone())
+    }
+
+    fn face_for(&mut self, c: char) -> Option<usize> {
+        let range = c as u32 / CHARS_PER_RANGE;
+
+        if let Some(&face_index) = self.face_by_range.get(&range) {
+            if self.faces[face_index].covers(c) {
+                return Some(face_index);
+            }
+        }
+
+        if self.unsupported.contains(&c) {
+            return None;
+        }
+
+        let mut first_unchecked = 0;
+        loop {
+            let Some(face_index) = self.faces[first_unchecked..]
+                .iter()
+                .position(|face| face.covers(c))
+                .map(|i| first_unchecked + i)
+            else {
+                first_unchecked = self.faces.len();
+                if self.index_next_file() {
+                    continue;
+                }
+                self.unsupported.insert(c);
+                return None;
+            };
+            if self.faces[face_index].load().is_some() {
+                self.face_by_range.entry(range).or_insert(face_index);
+                return Some(face_index);
+            }
+            // The file could not be loaded, and its coverage has been cleared, so try the next one.
+            first_unchecked = face_index + 1;
+        }
+    }
+
+    /// Add the faces of the next file, if there are files left.
+    fn index_next_file(&mut self) -> bool {
+        let Some(path) = self.files.get(self.num_indexed_files) else {
+            return false;
+        };
+        self.num_indexed_files += 1;
+        for (index, coverage) in index_file(path).into_iter().enumerate() {
+            self.faces.push(SystemFace {
+                path: path.clone(),
+                index: index as u32,
+                coverage,
+                font: None,
+            });
+        }
+        true
+    }
+}
+
+/// Which characters each face of a font file supports.
+///
+/// Reading a file is slow, so this is remembered for as long as the program runs,
+/// and reused when the fonts are recreated, e.g. for a new `pixels_per_point`.
+fn index_file(path: &Path) -> Vec<Coverage> {
+    static INDEX: OnceLock<Mutex<ahash::HashMap<PathBuf, Vec<Coverage>>>> = OnceLock::new();
+    let index = INDEX.get_or_init(Default::default);
+
+    if let Some(faces) = index.lock().get(path) {
+        return faces.clone();
+    }
+
+    crate::profile_function!();
+    let mut faces = vec![];
+    if let Ok(bytes) = std::fs::read(path) {
+        for index in 0..MAX_FACES_PER_FILE {
+            let Ok(font) = ab_glyph::FontRef::try_from_slice_and_index(&bytes, index) else {
+                break; // No more faces in this file
+            };
+            faces.push(coverage(&font));
+        }
+    }
+    index.lock().insert(path.to_owned(), faces.clone());
+    faces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{FontData, FontDefinitions, FontFamily, FontId, FontsImpl};
+
+    fn bundled_fonts_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts")
+    }
+
+    #[test]
+    fn test_find_font_files() {
+        let system_fonts = SystemFonts::from_dirs([bundled_fonts_dir(), "/does/not/exist".into()]);
+        let names: Vec<_> = system_fonts
+            .files
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Hack-Regular.ttf",
+                "NotoEmoji-Regular.ttf",
+                "Ubuntu-Light.ttf",
+                "emoji-icon-font.ttf",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_system_font_fallback() {
+        let mut definitions = FontDefinitions::empty();
+        definitions.font_data.insert(
+            "Hack".to_owned(),
+            FontData::from_static(include_bytes!("../../fonts/Hack-Regular.ttf")),
+        );
+        definitions
+            .families
+            .insert(FontFamily::Proportional, vec!["Hack".to_owned()]);
+
+        let font_id = FontId::proportional(14.0);
+        let emoji = '🔥';
+
+        let mut fonts = FontsImpl::new(1.0, 1024, definitions.clone());
+        assert!(!fonts.has_glyph(&font_id, emoji));
+
+        definitions.system_fonts = SystemFonts::from_dirs([bundled_fonts_dir()]);
+        let mut fonts = FontsImpl::new(1.0, 1024, definitions);
+        assert!(fonts.has_glyph(&font_id, 'a'));
+        assert!(fonts.has_glyph(&font_id, emoji));
+        assert!(!fonts.has_glyph(&font_id, '\u{10FFFD}')); // private use
+    }
+
+    #[test]
+    fn test_files_are_indexed_lazily() {
+        let system_fonts = SystemFonts::from_dirs([bundled_fonts_dir()]);
+        let atlas = Arc::new(Mutex::new(TextureAtlas::new([1024, 64])));
+        let mut fallback = SystemFontFallback::new(atlas, 1.0, &system_fonts);
+        assert_eq!(fallback.num_indexed_files, 0);
+
+        assert!(fallback.font_impl(14.0, 'a').is_some());
+        assert_eq!(fallback.num_indexed_files, 1, "Hack supports 'a'");
+
+        assert!(fallback.font_impl(14.0, '🔥').is_some());
+        assert_eq!(fallback.num_indexed_files, 2, "Noto Emoji comes second");
+
+        assert!(fallback.font_impl(14.0, '\u{10FFFD}').is_none());
+        assert_eq!(fallback.num_indexed_files, system_fonts.files.len());
+
+        // The files are only read once:
+        let path = &system_fonts.files[0];
+        assert!(Arc::ptr_eq(&index_file(path)[0], &index_file(path)[0]));
+    }
+}
@@ -1,11 +1,15 @@
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
 use emath::*;
 
-use crate::{stroke::PathStroke, text::font::Font, Color32, Mesh, Stroke, Vertex};
+use crate::{
+    stroke::PathStroke,
+    text::font::{Font, UvRect},
+    Color32, Mesh, Stroke, Vertex,
+};
 
-use super::{FontsImpl, Galley, Glyph, LayoutJob, LayoutSection, Row, RowVisuals};
+use super::{FontsImpl, Galley, Glyph, LayoutJob, LayoutSection, Row, RowVisuals, ShapedGlyph};
 
 // ----------------------------------------------------------------------------
 
@@ -50,6 +54,9 @@ struct Paragraph {
 
     pub glyphs: Vec<Glyph>,
 
+    /// See [`Row::shaped_glyphs`].
+    pub shaped_glyphs: Vec<ShapedGlyph>,
+
     /// In case of an empty paragraph ("\n"), use this as height.
     pub empty_paragraph_height: f32,
 }
@@ -60,9 +67,33 @@ impl Paragraph {
             cursor_x: 0.0,
             section_index_at_start,
             glyphs: vec![],
+            shaped_glyphs: vec![],
             empty_paragraph_height: 0.0,
         }
     }
+
+    /// The shaped glyphs of the given characters, with indices relative to the first of them.
+    fn shaped_glyphs_in(&self, char_range: Range<usize>) -> Vec<ShapedGlyph> {
+        self.shaped_glyphs
+            .iter()
+            .filter(|glyph| char_range.contains(&glyph.char_index))
+            .map(|glyph| ShapedGlyph {
+                char_index: glyph.char_index - char_range.start,
+                ..*glyph
+            })
+            .collect()
+    }
+
+    /// For each character: is it in the middle of a shaped cluster, so we can't break the row before it?
+    fn continues_cluster(&self) -> Vec<bool> {
+        let mut continues_cluster = vec![false; self.glyphs.len()];
+        for glyph in &self.shaped_glyphs {
+            let cluster_end = (glyph.char_index + glyph.cluster_len).at_most(self.glyphs.len());
+            let cluster_start = (glyph.char_index + 1).at_most(cluster_end);
+            continues_cluster[cluster_start..cluster_end].fill(true);
+        }
+        continues_cluster
+    }
 }
 
 /// Layout text into a [`Galley`].
@@ -87,10 +118,27 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
     // For most of this we ignore the y coordinate:
 
     let mut paragraphs = vec![Paragraph::from_section_index(0)];
+
+    #[cfg(not(feature = "text_shaping"))]
     for (section_index, section) in job.sections.iter().enumerate() {
         layout_section(fonts, &job, section_index as u32, section, &mut paragraphs);
     }
 
+    #[cfg(feature = "text_shaping")]
+    {
+        let bidi_levels = super::shaping::bidi_levels(&job.text);
+        for (section_index, section) in job.sections.iter().enumerate() {
+            layout_section_shaped(
+                fonts,
+                &job,
+                &bidi_levels,
+                section_index as u32,
+                section,
+                &mut paragraphs,
+            );
+        }
+    }
+
     let point_scale = PointScale::new(fonts.pixels_per_point());
 
     let mut elided = false;
@@ -98,6 +146,18 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
     if elided {
         if let Some(last_row) = rows.last_mut() {
             replace_last_glyph_with_overflow_character(fonts, &job, last_row);
+
+            // Shaped glyphs of characters that were replaced by the overflow character:
+            let num_kept_chars = last_row.glyphs.len() - 1;
+            last_row
+                .shaped_glyphs
+                .retain(|glyph| glyph.char_index + glyph.cluster_len <= num_kept_chars);
+        }
+    }
+
+    for row in &mut rows {
+        if row.has_rtl() {
+            reorder_bidi_row(row);
         }
     }
 
@@ -123,6 +183,7 @@ pub fn layout(fonts: &mut FontsImpl, job: Arc<LayoutJob>) -> Galley {
 }
 
 // Ignores the Y coordinate.
+#[cfg(not(feature = "text_shaping"))]
 fn layout_section(
     fonts: &mut FontsImpl,
     job: &LayoutJob,
@@ -172,6 +233,7 @@ fn layout_section(
                 ascent: font_impl.map_or(0.0, |font| font.ascent()), // Failure to find the font here would be weird
                 uv_rect: glyph_info.uv_rect,
                 section_index,
+                bidi_level: 0,
             });
 
             paragraph.cursor_x += glyph_info.advance_width;
@@ -181,6 +243,192 @@ fn layout_section(
     }
 }
 
+/// Consecutive characters of a section that are laid out together.
+#[cfg(feature = "text_shaping")]
+enum ShapingItem {
+    Newline,
+
+    /// Characters with the same font and bidi level, shaped together.
+    Run {
+        font_impl: Arc<super::font::FontImpl>,
+        bidi_level: u8,
+        byte_range: Range<usize>,
+    },
+
+    /// A character that isn't shaped, e.g. `\t` or a character that no font supports.
+    Char {
+        chr: char,
+        bidi_level: u8,
+    },
+}
+
+/// Like [`layout_section`], but shapes the text with `rustybuzz`.
+///
+/// Ignores the Y coordinate.
+#[cfg(feature = "text_shaping")]
+fn layout_section_shaped(
+    fonts: &mut FontsImpl,
+    job: &LayoutJob,
+    bidi_levels: &[u8],
+    section_index: u32,
+    section: &LayoutSection,
+    out_paragraphs: &mut Vec<Paragraph>,
+) {
+    let LayoutSection {
+        leading_space,
+        byte_range,
+        format,
+    } = section;
+    let font = fonts.font(&format.font_id);
+    let line_height = section
+        .format
+        .line_height
+        .unwrap_or_else(|| font.row_height());
+    let extra_letter_spacing = section.format.extra_letter_spacing;
+
+    let mut items: Vec<ShapingItem> = vec![];
+    for (offset, chr) in job.text[byte_range.clone()].char_indices() {
+        let start = byte_range.start + offset;
+        let end = start + chr.len_utf8();
+        let bidi_level = bidi_levels[start];
+
+        if job.break_on_newline && chr == '\n' {
+            items.push(ShapingItem::Newline);
+            continue;
+        }
+
+        if chr != '\t' {
+            // Prefer to keep using the same font, e.g. for combining marks:
+            if let Some(ShapingItem::Run {
+                font_impl,
+                bidi_level: run_bidi_level,
+                byte_range,
+            }) = items.last_mut()
+            {
+                if *run_bidi_level == bidi_level && font_impl.glyph_info(chr).is_some() {
+                    byte_range.end = end;
+                    continue;
+                }
+            }
+
+            if let Some(font_impl) = font.shaping_font_impl(chr) {
+                items.push(ShapingItem::Run {
+                    font_impl,
+                    bidi_level,
+                    byte_range: start..end,
+                });
+                continue;
+            }
+        }
+
+        items.push(ShapingItem::Char { chr, bidi_level });
+    }
+
+    let mut paragraph = out_paragraphs.last_mut().unwrap();
+    if paragraph.glyphs.is_empty() {
+        paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+    }
+
+    paragraph.cursor_x += leading_space;
+
+    let mut is_first_in_section = true;
+
+    for item in items {
+        match item {
+            ShapingItem::Newline => {
+                out_paragraphs.push(Paragraph::from_section_index(section_index));
+                paragraph = out_paragraphs.last_mut().unwrap();
+                paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+            }
+
+            ShapingItem::Char { chr, bidi_level } => {
+                if !is_first_in_section {
+                    paragraph.cursor_x += extra_letter_spacing;
+                }
+                is_first_in_section = false;
+
+                let (font_impl, glyph_info) = font.font_impl_and_glyph_info(chr);
+                paragraph.glyphs.push(Glyph {
+                    chr,
+                    pos: pos2(paragraph.cursor_x, f32::NAN),
+                    size: vec2(glyph_info.advance_width, line_height),
+                    ascent: font_impl.map_or(0.0, |font| font.ascent()), // Failure to find the font here would be weird
+                    uv_rect: glyph_info.uv_rect,
+                    section_index,
+                    bidi_level,
+                });
+
+                paragraph.cursor_x += glyph_info.advance_width;
+                paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
+            }
+
+            ShapingItem::Run {
+                font_impl,
+                bidi_level,
+                byte_range,
+            } => {
+                let text = &job.text[byte_range];
+                let clusters = font_impl.shape(text, bidi_level % 2 == 1);
+
+                // Characters before the first cluster should not happen, but every character needs a glyph:
+                let first_cluster_offset = clusters.first().map_or(text.len(), |c| c.byte_offset);
+                let unshaped = std::iter::once((&text[..first_cluster_offset], 0.0, &[][..]));
+                let clusters = clusters.iter().enumerate().map(|(i, cluster)| {
+                    let cluster_end = clusters
+                        .get(i + 1)
+                        .map_or(text.len(), |next| next.byte_offset)
+                        .at_least(cluster.byte_offset);
+                    let cluster_text = &text[cluster.byte_offset..cluster_end];
+                    (cluster_text, cluster.advance, cluster.glyphs.as_slice())
+                });
+
+                for (cluster_text, advance, glyphs) in unshaped.chain(clusters) {
+                    let cluster_len = cluster_text.chars().count();
+                    if cluster_len == 0 {
+                        continue;
+                    }
+
+                    if !is_first_in_section {
+                        paragraph.cursor_x += extra_letter_spacing;
+                    }
+                    is_first_in_section = false;
+
+                    // We can put a cursor inside a ligature, so we split the width evenly:
+                    let char_index = paragraph.glyphs.len();
+                    let char_width = advance / cluster_len as f32;
+                    for (i, chr) in cluster_text.chars().enumerate() {
+                        paragraph.glyphs.push(Glyph {
+                            chr,
+                            pos: pos2(paragraph.cursor_x + i as f32 * char_width, f32::NAN),
+                            size: vec2(char_width, line_height),
+                            ascent: font_impl.ascent(),
+                            uv_rect: UvRect::default(),
+                            section_index,
+                            bidi_level,
+                        });
+                    }
+
+                    for &(uv_rect, offset) in glyphs {
+                        if !uv_rect.is_nothing() {
+                            paragraph.shaped_glyphs.push(ShapedGlyph {
+                                char_index,
+                                cluster_len,
+                                offset,
+                                uv_rect,
+                            });
+                        }
+                    }
+
+                    paragraph.cursor_x += advance;
+                }
+
+                // Only round between runs, so we don't break up cursive scripts:
+                paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
+            }
+        }
+    }
+}
+
 /// We ignore y at this stage
 fn rect_from_x_range(x_range: RangeInclusive<f32>) -> Rect {
     Rect::from_x_y_ranges(x_range, 0.0..=0.0)
@@ -208,6 +456,7 @@ fn rows_from_paragraphs(
             rows.push(Row {
                 section_index_at_start: paragraph.section_index_at_start,
                 glyphs: vec![],
+                shaped_glyphs: vec![],
                 visuals: Default::default(),
                 rect: Rect::from_min_size(
                     pos2(paragraph.cursor_x, 0.0),
@@ -223,6 +472,7 @@ fn rows_from_paragraphs(
                 rows.push(Row {
                     section_index_at_start: paragraph.section_index_at_start,
                     glyphs: paragraph.glyphs,
+                    shaped_glyphs: paragraph.shaped_glyphs,
                     visuals: Default::default(),
                     rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                     ends_with_newline: !is_last_paragraph,
@@ -240,6 +490,7 @@ fn rows_from_paragraphs(
 fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>, elided: &mut bool) {
     // Keeps track of good places to insert row break if we exceed `wrap_width`.
     let mut row_break_candidates = RowBreakCandidates::default();
+    let continues_cluster = paragraph.continues_cluster();
 
     let mut first_row_indentation = paragraph.glyphs[0].pos.x;
     let mut row_start_x = 0.0;
@@ -264,6 +515,7 @@ fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>, e
                 out_rows.push(Row {
                     section_index_at_start: paragraph.section_index_at_start,
                     glyphs: vec![],
+                    shaped_glyphs: vec![],
                     visuals: Default::default(),
                     rect: rect_from_x_range(first_row_indentation..=first_row_indentation),
                     ends_with_newline: false,
@@ -288,6 +540,7 @@ fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>, e
                 out_rows.push(Row {
                     section_index_at_start,
                     glyphs,
+                    shaped_glyphs: paragraph.shaped_glyphs_in(row_start_idx..last_kept_index + 1),
                     visuals: Default::default(),
                     rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                     ends_with_newline: false,
@@ -302,7 +555,9 @@ fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>, e
             }
         }
 
-        row_break_candidates.add(i, &paragraph.glyphs[i..]);
+        if !continues_cluster.get(i + 1).copied().unwrap_or(false) {
+            row_break_candidates.add(i, &paragraph.glyphs[i..]);
+        }
     }
 
     if row_start_idx < paragraph.glyphs.len() {
@@ -327,6 +582,7 @@ fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>, e
             out_rows.push(Row {
                 section_index_at_start,
                 glyphs,
+                shaped_glyphs: paragraph.shaped_glyphs_in(row_start_idx..paragraph.glyphs.len()),
                 visuals: Default::default(),
                 rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                 ends_with_newline: false,
@@ -365,6 +621,7 @@ fn replace_last_glyph_with_overflow_character(
     // We always try to just append the character first:
     if let Some(last_glyph) = row.glyphs.last() {
         let section_index = last_glyph.section_index;
+        let bidi_level = last_glyph.bidi_level;
         let section = &job.sections[section_index as usize];
         let font = fonts.font(&section.format.font_id);
         let line_height = row_height(section, font);
@@ -390,6 +647,7 @@ fn replace_last_glyph_with_overflow_character(
             ascent: font_impl.map_or(0.0, |font| font.ascent()), // Failure to find the font here would be weird
             uv_rect: replacement_glyph_info.uv_rect,
             section_index,
+            bidi_level,
         });
     } else {
         let section_index = row.section_index_at_start;
@@ -408,6 +666,7 @@ fn replace_last_glyph_with_overflow_character(
             ascent: font_impl.map_or(0.0, |font| font.ascent()), // Failure to find the font here would be weird
             uv_rect: replacement_glyph_info.uv_rect,
             section_index,
+            bidi_level: 0,
         });
     }
 
@@ -489,6 +748,11 @@ fn halign_and_justify_row(
         return;
     }
 
+    if row.has_rtl() {
+        align_bidi_row(point_scale, row, halign);
+        return;
+    }
+
     let num_leading_spaces = row
         .glyphs
         .iter()
@@ -568,6 +832,86 @@ fn halign_and_justify_row(
     row.rect.max.x = target_max_x;
 }
 
+/// Horizontally align a row with right-to-left text.
+///
+/// The glyphs are not sorted by x, so we only move them, and don't justify.
+fn align_bidi_row(point_scale: PointScale, row: &mut Row, halign: Align) {
+    let min_x = row
+        .glyphs
+        .iter()
+        .map(|glyph| glyph.pos.x)
+        .fold(f32::INFINITY, f32::min);
+    let max_x = row
+        .glyphs
+        .iter()
+        .map(|glyph| glyph.max_x())
+        .fold(f32::NEG_INFINITY, f32::max);
+    let width = max_x - min_x;
+
+    let target_min_x = match halign {
+        Align::LEFT => 0.0,
+        Align::Center => -width / 2.0,
+        Align::RIGHT => -width,
+    };
+
+    // Move by whole pixels, so we keep the relative positions of shaped glyphs:
+    let translate_x = point_scale.round_to_pixel(target_min_x - min_x);
+    for glyph in &mut row.glyphs {
+        glyph.pos.x += translate_x;
+    }
+
+    row.rect.min.x = target_min_x;
+    row.rect.max.x = target_min_x + width;
+}
+
+/// Lay out the glyphs of a row with right-to-left text in visual order,
+/// following rule L2 of the Unicode Bidirectional Algorithm.
+///
+/// The glyphs are kept in logical order; only their x coordinates change.
+fn reorder_bidi_row(row: &mut Row) {
+    let levels: Vec<u8> = row.glyphs.iter().map(|glyph| glyph.bidi_level).collect();
+    let Some(&highest_level) = levels.iter().max() else {
+        return;
+    };
+    let Some(lowest_odd_level) = levels.iter().copied().filter(|level| level % 2 == 1).min() else {
+        return; // Nothing to reverse
+    };
+
+    // From the highest level to the lowest odd level,
+    // reverse any contiguous sequence of characters that are at that level or higher:
+    let mut visual_order: Vec<usize> = (0..levels.len()).collect();
+    for level in (lowest_odd_level..=highest_level).rev() {
+        let mut i = 0;
+        while i < visual_order.len() {
+            if levels[visual_order[i]] < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < visual_order.len() && level <= levels[visual_order[i]] {
+                i += 1;
+            }
+            visual_order[start..i].reverse();
+        }
+    }
+
+    // Keep any extra space after each glyph (e.g. letter spacing):
+    let spacing: Vec<f32> = row
+        .glyphs
+        .iter()
+        .zip(row.glyphs.iter().skip(1))
+        .map(|(glyph, next)| next.pos.x - glyph.max_x())
+        .chain(std::iter::once(0.0))
+        .collect();
+
+    let mut x = row.glyphs[0].pos.x;
+    for i in visual_order {
+        let glyph = &mut row.glyphs[i];
+        glyph.pos.x = x;
+        x += glyph.size.x + spacing[i];
+    }
+}
+
 /// Calculate the Y positions and tessellate the text.
 fn galley_from_rows(
     point_scale: PointScale,
@@ -676,16 +1020,27 @@ fn tessellate_row(
     mesh.reserve_triangles(row.glyphs.len() * 2);
     mesh.reserve_vertices(row.glyphs.len() * 4);
 
+    // Backgrounds and lines are added left to right:
+    let visual_glyphs = if row.has_rtl() {
+        let mut glyphs = row.glyphs.clone();
+        glyphs.sort_by(|a, b| a.pos.x.total_cmp(&b.pos.x));
+        std::borrow::Cow::Owned(glyphs)
+    } else {
+        std::borrow::Cow::Borrowed(&row.glyphs)
+    };
+
     if format_summary.any_background {
-        add_row_backgrounds(job, row, &mut mesh);
+        add_row_backgrounds(job, &visual_glyphs, &mut mesh);
     }
 
     let glyph_vertex_start = mesh.vertices.len();
-    tessellate_glyphs(point_scale, job, row, &mut mesh);
+    tessellate_glyphs(point_scale, job, row, false, &mut mesh);
     let glyph_vertex_end = mesh.vertices.len();
+    tessellate_glyphs(point_scale, job, row, true, &mut mesh);
+    let color_glyph_vertex_end = mesh.vertices.len();
 
     if format_summary.any_underline {
-        add_row_hline(point_scale, row, &mut mesh, |glyph| {
+        add_row_hline(point_scale, &visual_glyphs, &mut mesh, |glyph| {
             let format = &job.sections[glyph.section_index as usize].format;
             let stroke = format.underline;
             let y = glyph.logical_rect().bottom();
@@ -694,7 +1049,7 @@ fn tessellate_row(
     }
 
     if format_summary.any_strikethrough {
-        add_row_hline(point_scale, row, &mut mesh, |glyph| {
+        add_row_hline(point_scale, &visual_glyphs, &mut mesh, |glyph| {
             let format = &job.sections[glyph.section_index as usize].format;
             let stroke = format.strikethrough;
             let y = glyph.logical_rect().center().y;
@@ -708,13 +1063,14 @@ fn tessellate_row(
         mesh,
         mesh_bounds,
         glyph_vertex_range: glyph_vertex_start..glyph_vertex_end,
+        color_glyph_vertex_range: glyph_vertex_end..color_glyph_vertex_end,
     }
 }
 
 /// Create background for glyphs that have them.
 /// Creates as few rectangular regions as possible.
-fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
-    if row.glyphs.is_empty() {
+fn add_row_backgrounds(job: &LayoutJob, glyphs: &[Glyph], mesh: &mut Mesh) {
+    if glyphs.is_empty() {
         return;
     }
 
@@ -729,7 +1085,7 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     let mut run_start = None;
     let mut last_rect = Rect::NAN;
 
-    for glyph in &row.glyphs {
+    for glyph in glyphs {
         let format = &job.sections[glyph.section_index as usize].format;
         let color = format.background;
         let rect = glyph.logical_rect();
@@ -756,62 +1112,136 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     end_run(run_start.take(), last_rect.right());
 }
 
-fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
+/// Tessellate either the full-color glyphs, or all the others.
+///
+/// The parts of full-color glyphs that are in the text color are tessellated with the others,
+/// so they are tinted like them.
+fn tessellate_glyphs(
+    point_scale: PointScale,
+    job: &LayoutJob,
+    row: &Row,
+    color_glyphs: bool,
+    mesh: &mut Mesh,
+) {
+    let uv_rect_to_paint = |uv_rect: UvRect| {
+        if color_glyphs {
+            uv_rect.is_color.then_some(uv_rect)
+        } else if uv_rect.is_color {
+            uv_rect.foreground()
+        } else {
+            Some(uv_rect)
+        }
+    };
+
     for glyph in &row.glyphs {
-        let uv_rect = glyph.uv_rect;
-        if !uv_rect.is_nothing() {
-            let mut left_top = glyph.pos + uv_rect.offset;
-            left_top.x = point_scale.round_to_pixel(left_top.x);
-            left_top.y = point_scale.round_to_pixel(left_top.y);
-
-            let rect = Rect::from_min_max(left_top, left_top + uv_rect.size);
-            let uv = Rect::from_min_max(
-                pos2(uv_rect.min[0] as f32, uv_rect.min[1] as f32),
-                pos2(uv_rect.max[0] as f32, uv_rect.max[1] as f32),
-            );
+        let Some(uv_rect) = uv_rect_to_paint(glyph.uv_rect) else {
+            continue;
+        };
+        tessellate_glyph(
+            point_scale,
+            job,
+            glyph.pos,
+            uv_rect,
+            glyph.section_index,
+            mesh,
+        );
+    }
 
-            let format = &job.sections[glyph.section_index as usize].format;
+    for shaped_glyph in &row.shaped_glyphs {
+        let Some(uv_rect) = uv_rect_to_paint(shaped_glyph.uv_rect) else {
+            continue;
+        };
+        let cluster_end =
+            (shaped_glyph.char_index + shaped_glyph.cluster_len).at_most(row.glyphs.len());
+        let Some(cluster) = row.glyphs.get(shaped_glyph.char_index..cluster_end) else {
+            continue;
+        };
+        let Some(first_glyph) = cluster.first() else {
+            continue;
+        };
+        let cluster_min_x = cluster
+            .iter()
+            .map(|glyph| glyph.pos.x)
+            .fold(f32::INFINITY, f32::min);
+        tessellate_glyph(
+            point_scale,
+            job,
+            pos2(cluster_min_x, first_glyph.pos.y) + shaped_glyph.offset,
+            uv_rect,
+            first_glyph.section_index,
+            mesh,
+        );
+    }
+}
 
-            let color = format.color;
+fn tessellate_glyph(
+    point_scale: PointScale,
+    job: &LayoutJob,
+    pos: Pos2,
+    uv_rect: UvRect,
+    section_index: u32,
+    mesh: &mut Mesh,
+) {
+    if uv_rect.is_nothing() {
+        return;
+    }
 
-            if format.italics {
-                let idx = mesh.vertices.len() as u32;
-                mesh.add_triangle(idx, idx + 1, idx + 2);
-                mesh.add_triangle(idx + 2, idx + 1, idx + 3);
+    let mut left_top = pos + uv_rect.offset;
+    left_top.x = point_scale.round_to_pixel(left_top.x);
+    left_top.y = point_scale.round_to_pixel(left_top.y);
 
-                let top_offset = rect.height() * 0.25 * Vec2::X;
+    let rect = Rect::from_min_max(left_top, left_top + uv_rect.size);
+    let uv = Rect::from_min_max(
+        pos2(uv_rect.min[0] as f32, uv_rect.min[1] as f32),
+        pos2(uv_rect.max[0] as f32, uv_rect.max[1] as f32),
+    );
 
-                mesh.vertices.push(Vertex {
-                    pos: rect.left_top() + top_offset,
-                    uv: uv.left_top(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.right_top() + top_offset,
-                    uv: uv.right_top(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.left_bottom(),
-                    uv: uv.left_bottom(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.right_bottom(),
-                    uv: uv.right_bottom(),
-                    color,
-                });
-            } else {
-                mesh.add_rect_with_uv(rect, uv, color);
-            }
-        }
+    let format = &job.sections[section_index as usize].format;
+
+    let color = if uv_rect.is_color && format.color != Color32::PLACEHOLDER {
+        // Don't tint full-color glyphs, but fade them like the text:
+        let a = format.color.a();
+        Color32::from_rgba_premultiplied(a, a, a, a)
+    } else {
+        format.color
+    };
+
+    if format.italics {
+        let idx = mesh.vertices.len() as u32;
+        mesh.add_triangle(idx, idx + 1, idx + 2);
+        mesh.add_triangle(idx + 2, idx + 1, idx + 3);
+
+        let top_offset = rect.height() * 0.25 * Vec2::X;
+
+        mesh.vertices.push(Vertex {
+            pos: rect.left_top() + top_offset,
+            uv: uv.left_top(),
+            color,
+        });
+        mesh.vertices.push(Vertex {
+            pos: rect.right_top() + top_offset,
+            uv: uv.right_top(),
+            color,
+        });
+        mesh.vertices.push(Vertex {
+            pos: rect.left_bottom(),
+            uv: uv.left_bottom(),
+            color,
+        });
+        mesh.vertices.push(Vertex {
+            pos: rect.right_bottom(),
+            uv: uv.right_bottom(),
+            color,
+        });
+    } else {
+        mesh.add_rect_with_uv(rect, uv, color);
     }
 }
 
 /// Add a horizontal line over a row of glyphs with a stroke and y decided by a callback.
 fn add_row_hline(
     point_scale: PointScale,
-    row: &Row,
+    glyphs: &[Glyph],
     mesh: &mut Mesh,
     stroke_and_y: impl Fn(&Glyph) -> (Stroke, f32),
 ) {
@@ -824,7 +1254,7 @@ fn add_row_hline(
     let mut line_start = None;
     let mut last_right_x = f32::NAN;
 
-    for glyph in &row.glyphs {
+    for glyph in glyphs {
         let (stroke, y) = stroke_and_y(glyph);
 
         if stroke == Stroke::NONE {
@@ -1090,4 +1520,128 @@ mod tests {
             vec!["日本語とEnglish", "の混在した文章"]
         );
     }
+
+    #[test]
+    fn test_bidi_row() {
+        // "ab" followed by the Hebrew "אבג", which is shown right-to-left:
+        let glyphs = [('a', 0), ('b', 0), ('א', 1), ('ב', 1), ('ג', 1)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (chr, bidi_level))| Glyph {
+                chr,
+                pos: pos2(10.0 * i as f32, 0.0),
+                ascent: 10.0,
+                size: vec2(10.0, 10.0),
+                uv_rect: Default::default(),
+                section_index: 0,
+                bidi_level,
+            })
+            .collect();
+        let mut row = Row {
+            section_index_at_start: 0,
+            glyphs,
+            shaped_glyphs: vec![],
+            rect: Rect::from_min_max(pos2(0.0, 0.0), pos2(50.0, 10.0)),
+            visuals: Default::default(),
+            ends_with_newline: false,
+        };
+
+        reorder_bidi_row(&mut row);
+        let xs: Vec<f32> = row.glyphs.iter().map(|glyph| glyph.pos.x).collect();
+        assert_eq!(xs, vec![0.0, 10.0, 40.0, 30.0, 20.0]);
+
+        // The cursor before a right-to-left character is on its right side:
+        assert_eq!(row.x_offset(1), 10.0);
+        assert_eq!(row.x_offset(2), 50.0);
+        assert_eq!(row.x_offset(3), 40.0);
+        assert_eq!(row.x_offset(5), 20.0);
+
+        assert_eq!(row.char_at(-5.0), 0);
+        assert_eq!(row.char_at(12.0), 1);
+        assert_eq!(row.char_at(18.0), 2);
+        assert_eq!(row.char_at(22.0), 5);
+        assert_eq!(row.char_at(48.0), 2);
+        assert_eq!(row.char_at(55.0), 2);
+    }
+
+    #[cfg(feature = "text_shaping")]
+    #[test]
+    fn test_ligature_and_kerning() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        let mut row = |text: &str| {
+            let job = LayoutJob::simple_singleline(
+                text.into(),
+                FontId::proportional(100.0),
+                Color32::WHITE,
+            );
+            layout(&mut fonts, job.into()).rows[0].clone()
+        };
+
+        // The default font has a ligature for "fi", which is one glyph for two characters:
+        let fi = row("fi");
+        assert_eq!(fi.glyphs.len(), 2);
+        assert_eq!(fi.shaped_glyphs.len(), 1);
+        assert_eq!(fi.shaped_glyphs[0].cluster_len, 2);
+        assert_eq!(row("fo").shaped_glyphs.len(), 2);
+
+        // The cursor can go inside the ligature:
+        assert_eq!(fi.glyphs[0].size.x, fi.glyphs[1].size.x);
+        assert_eq!(fi.glyphs[1].pos.x, fi.glyphs[0].max_x());
+
+        // "A" and "V" are kerned closer together:
+        let av = row("AV");
+        assert_eq!(av.shaped_glyphs.len(), 2);
+        let separate_width = row("A").rect.width() + row("V").rect.width();
+        assert!(
+            av.rect.width() < separate_width - 1.0,
+            "{} vs {separate_width}",
+            av.rect.width()
+        );
+        assert_eq!(av.glyphs[1].pos.x, av.glyphs[0].max_x());
+    }
+
+    #[cfg(feature = "text_shaping")]
+    #[test]
+    fn test_bidi_cursor_positions() {
+        let mut fonts = FontsImpl::new(1.0, 1024, FontDefinitions::default());
+        // The Hebrew "אבג" between "ab" and "cd" is shown right-to-left:
+        let job = LayoutJob::simple_singleline(
+            "abאבגcd".into(),
+            FontId::proportional(20.0),
+            Color32::WHITE,
+        );
+        let galley = layout(&mut fonts, job.into());
+        let row = &galley.rows[0];
+        let levels: Vec<u8> = row.glyphs.iter().map(|glyph| glyph.bidi_level).collect();
+        assert_eq!(levels, [0, 0, 1, 1, 1, 0, 0]);
+
+        // Shown as "abגבאcd", with each run of text rounded to whole pixels:
+        let visual_order = [0, 1, 4, 3, 2, 5, 6];
+        for pair in visual_order.windows(2) {
+            let (left, right) = (&row.glyphs[pair[0]], &row.glyphs[pair[1]]);
+            let gap = right.pos.x - left.max_x();
+            assert!(gap.abs() < 1.0, "{:?}", (left.chr, right.chr));
+        }
+
+        // The cursor before a right-to-left character is on its right side:
+        let cursor_x = |index| galley.pos_from_ccursor(cursor::CCursor::new(index)).min.x;
+        assert_eq!(cursor_x(1), row.glyphs[1].pos.x);
+        assert_eq!(cursor_x(2), row.glyphs[2].max_x());
+        assert_eq!(cursor_x(4), row.glyphs[4].max_x());
+        assert_eq!(cursor_x(5), row.glyphs[5].pos.x);
+        assert_eq!(cursor_x(7), row.glyphs[6].max_x());
+
+        // Clicking a character puts the cursor on the side of it that was clicked:
+        let y = row.rect.center().y;
+        for (i, glyph) in row.glyphs.iter().enumerate() {
+            let cursor_at = |x: f32| galley.cursor_from_pos(vec2(x, y)).ccursor.index;
+            let left = cursor_at(glyph.pos.x + 1.0);
+            let right = cursor_at(glyph.max_x() - 1.0);
+            if glyph.is_rtl() {
+                assert_eq!((left, right), (i + 1, i), "{:?}", glyph.chr);
+            } else {
+                assert_eq!((left, right), (i, i + 1), "{:?}", glyph.chr);
+            }
+        }
+    }
 }
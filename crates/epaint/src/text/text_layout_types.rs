@@ -481,9 +481,16 @@ pub struct Row {
     /// This is included in case there are no glyphs
     pub section_index_at_start: u32,
 
-    /// One for each `char`.
+    /// One for each `char`, in logical order (the order of the text).
+    ///
+    /// For right-to-left text this is not the order they are shown in, see [`Glyph::bidi_level`].
     pub glyphs: Vec<Glyph>,
 
+    /// Glyphs from text shaping, painted in addition to the [`Glyph::uv_rect`]s.
+    ///
+    /// Always empty unless the `text_shaping` feature is enabled.
+    pub shaped_glyphs: Vec<ShapedGlyph>,
+
     /// Logical bounding rectangle based on font heights etc.
     /// Use this when drawing a selection or similar!
     /// Includes leading and trailing whitespace.
@@ -515,7 +522,17 @@ pub struct RowVisuals {
     /// The range of vertices in the mesh that contain glyphs (as opposed to background, underlines, strikethorugh, etc).
     ///
     /// The glyph vertices comes before backgrounds (if any), and after any underlines and strikethrough.
+    ///
+    /// This does not include full-color glyphs, see [`Self::color_glyph_vertex_range`],
+    /// except for their parts in the text color, see [`UvRect::foreground`].
     pub glyph_vertex_range: Range<usize>,
+
+    /// The range of vertices in the mesh that contain full-color glyphs, e.g. color emojis.
+    ///
+    /// These are white, so that the texture colors come through untinted,
+    /// and only use the alpha of the text color.
+    /// They come directly after [`Self::glyph_vertex_range`].
+    pub color_glyph_vertex_range: Range<usize>,
 }
 
 impl Default for RowVisuals {
@@ -524,6 +541,7 @@ impl Default for RowVisuals {
             mesh: Default::default(),
             mesh_bounds: Rect::NOTHING,
             glyph_vertex_range: 0..0,
+            color_glyph_vertex_range: 0..0,
         }
     }
 }
@@ -551,6 +569,12 @@ pub struct Glyph {
 
     /// Index into [`LayoutJob::sections`]. Decides color etc.
     pub section_index: u32,
+
+    /// The embedding level from the Unicode Bidirectional Algorithm.
+    ///
+    /// Odd levels are right-to-left (e.g. Arabic or Hebrew).
+    /// Always `0` unless the `text_shaping` feature is enabled.
+    pub bidi_level: u8,
 }
 
 impl Glyph {
@@ -558,6 +582,14 @@ impl Glyph {
         self.pos.x + self.size.x
     }
 
+    /// Is this character part of right-to-left text?
+    ///
+    /// If so, the cursor before it is on its right side.
+    #[inline]
+    pub fn is_rtl(&self) -> bool {
+        self.bidi_level % 2 == 1
+    }
+
     /// Same y range for all characters with the same [`TextFormat`].
     #[inline]
     pub fn logical_rect(&self) -> Rect {
@@ -565,6 +597,28 @@ impl Glyph {
     }
 }
 
+/// A glyph produced by text shaping.
+///
+/// Shaping can turn several characters into one glyph (e.g. a ligature),
+/// or one character into several glyphs, so these are kept apart from the [`Glyph`]s.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ShapedGlyph {
+    /// Index into [`Row::glyphs`] of the first character of the cluster this glyph belongs to.
+    ///
+    /// Decides color etc.
+    pub char_index: usize,
+
+    /// Number of characters in the cluster.
+    pub cluster_len: usize,
+
+    /// Offset from the left edge of the cluster and the baseline.
+    pub offset: Vec2,
+
+    /// Position and size of the glyph in the font texture, in texels.
+    pub uv_rect: UvRect,
+}
+
 // ----------------------------------------------------------------------------
 
 impl Row {
@@ -600,9 +654,20 @@ impl Row {
         self.rect.height()
     }
 
+    /// Does this row contain any right-to-left text?
+    ///
+    /// If so, the [`Self::glyphs`] are not sorted by x.
+    pub fn has_rtl(&self) -> bool {
+        self.glyphs.iter().any(|glyph| glyph.is_rtl())
+    }
+
     /// Closest char at the desired x coordinate.
     /// Returns something in the range `[0, char_count_excluding_newline()]`.
     pub fn char_at(&self, desired_x: f32) -> usize {
+        if self.has_rtl() {
+            return self.char_at_bidi(desired_x);
+        }
+
         for (i, glyph) in self.glyphs.iter().enumerate() {
             if desired_x < glyph.logical_rect().center().x {
                 return i;
@@ -611,9 +676,40 @@ impl Row {
         self.char_count_excluding_newline()
     }
 
+    fn char_at_bidi(&self, desired_x: f32) -> usize {
+        let distance = |glyph: &Glyph| {
+            if desired_x < glyph.pos.x {
+                glyph.pos.x - desired_x
+            } else {
+                (desired_x - glyph.max_x()).at_least(0.0)
+            }
+        };
+        let Some((i, glyph)) = self
+            .glyphs
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+        else {
+            return 0;
+        };
+
+        let left_half = desired_x < glyph.logical_rect().center().x;
+        if left_half != glyph.is_rtl() {
+            i
+        } else {
+            i + 1
+        }
+    }
+
     pub fn x_offset(&self, column: usize) -> f32 {
         if let Some(glyph) = self.glyphs.get(column) {
-            glyph.pos.x
+            if glyph.is_rtl() {
+                glyph.max_x()
+            } else {
+                glyph.pos.x
+            }
+        } else if let Some(last_glyph) = self.glyphs.last().filter(|glyph| glyph.is_rtl()) {
+            last_glyph.pos.x
         } else {
             self.rect.right()
         }
@@ -1,6 +1,6 @@
 use emath::{remap_clamp, Rect};
 
-use crate::{FontImage, ImageDelta};
+use crate::{text::GlyphRasterization, Color32, FontImage, ImageDelta};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct Rectu {
@@ -72,18 +72,31 @@ pub struct TextureAtlas {
 
     /// pre-rasterized discs of radii `2^i`, where `i` is the index.
     discs: Vec<PrerasterizedDisc>,
+
+    rasterization: GlyphRasterization,
 }
 
 impl TextureAtlas {
     pub fn new(size: [usize; 2]) -> Self {
+        Self::with_rasterization(size, GlyphRasterization::Coverage)
+    }
+
+    /// If `rasterization` is [`GlyphRasterization::Sdf`], the whole atlas,
+    /// including the discs, is a signed distance field.
+    pub fn with_rasterization(size: [usize; 2], rasterization: GlyphRasterization) -> Self {
         assert!(size[0] >= 1024, "Tiny texture atlas");
+        let sdf = rasterization == GlyphRasterization::Sdf;
         let mut atlas = Self {
-            image: FontImage::new(size),
+            image: FontImage {
+                sdf,
+                ..FontImage::new(size)
+            },
             dirty: Rectu::EVERYTHING,
             cursor: (0, 0),
             row_height: 0,
             overflowed: false,
             discs: vec![], // will be filled in below
+            rasterization,
         };
 
         // Make the top left pixel fully white for `WHITE_UV`, i.e. painting something with solid color:
@@ -109,10 +122,12 @@ impl TextureAtlas {
             for dx in -hw..=hw {
                 for dy in -hw..=hw {
                     let distance_to_center = ((dx * dx + dy * dy) as f32).sqrt();
-                    let coverage =
-                        remap_clamp(distance_to_center, (r - 0.5)..=(r + 0.5), 1.0..=0.0);
-                    image[((x as i32 + hw + dx) as usize, (y as i32 + hw + dy) as usize)] =
-                        coverage;
+                    let value = if sdf {
+                        crate::sdf::encode(distance_to_center - r)
+                    } else {
+                        remap_clamp(distance_to_center, (r - 0.5)..=(r + 0.5), 1.0..=0.0)
+                    };
+                    image[((x as i32 + hw + dx) as usize, (y as i32 + hw + dy) as usize)] = value;
                 }
             }
             atlas.discs.push(PrerasterizedDisc {
@@ -133,6 +148,12 @@ impl TextureAtlas {
         self.image.size
     }
 
+    /// How the glyphs are stored in this atlas.
+    #[inline]
+    pub fn rasterization(&self) -> GlyphRasterization {
+        self.rasterization
+    }
+
     /// Returns the locations and sizes of pre-rasterized discs (filled circles) in this atlas.
     pub fn prepared_discs(&self) -> Vec<PreparedDisc> {
         let size = self.size();
@@ -246,6 +267,15 @@ impl TextureAtlas {
         self.dirty.max_x = self.dirty.max_x.max(pos.0 + w);
         self.dirty.max_y = self.dirty.max_y.max(pos.1 + h);
 
+        if !self.image.colors.is_empty() {
+            // The space may be reused after an overflow, so clear any old color glyph:
+            let width = self.image.width();
+            for y in pos.1..pos.1 + h {
+                self.image.colors[y * width + pos.0..y * width + pos.0 + w]
+                    .fill(Color32::TRANSPARENT);
+            }
+        }
+
         (pos, &mut self.image)
     }
 }
@@ -257,6 +287,11 @@ fn resize_to_min_height(image: &mut FontImage, required_height: usize) -> bool {
 
     if image.width() * image.height() > image.pixels.len() {
         image.pixels.resize(image.width() * image.height(), 0.0);
+        if !image.colors.is_empty() {
+            image
+                .colors
+                .resize(image.pixels.len(), Color32::TRANSPARENT);
+        }
         true
     } else {
         false